@@ -0,0 +1,57 @@
+use crate::models::VmHost;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Remote configuration files copied over SFTP into a dated local archive,
+/// as a lightweight config backup alongside the inventory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchiveConfig {
+    pub enabled: bool,
+    /// Files land in `<dir>/<YYYY-MM-DD>/<host>/<remote path>`.
+    pub dir: String,
+    /// Remote paths to fetch; shell globs are expanded on the host.
+    pub files: Vec<String>,
+    /// Read files SFTP is denied through `sudo cat` over SSH instead.
+    pub sudo_fallback: bool,
+    /// Host names, groups and tags to archive; all empty means every host.
+    pub hosts: Vec<String>,
+    pub groups: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: "~/SecurePenguin/config-archive".to_string(),
+            files: vec![
+                "/etc/ssh/sshd_config".to_string(),
+                "/etc/wireguard/*.conf".to_string(),
+                "/etc/traefik/*.yml".to_string(),
+                "/etc/traefik/*.toml".to_string(),
+                "/opt/*/docker-compose.yml".to_string(),
+                "/opt/*/compose.yaml".to_string(),
+            ],
+            sudo_fallback: true,
+            hosts: Vec::new(),
+            groups: Vec::new(),
+            tags: Vec::new(),
+        }
+    }
+}
+
+impl ArchiveConfig {
+    pub fn applies_to(&self, host: &VmHost) -> bool {
+        self.enabled && !self.files.is_empty() && host.in_scope(&self.hosts, &self.groups, &self.tags)
+    }
+
+    /// Local copy of `remote` for `host` in today's archive.
+    pub fn local_path(&self, host: &str, remote: &str) -> PathBuf {
+        PathBuf::from(shellexpand::tilde(&self.dir).to_string())
+            .join(Local::now().format("%Y-%m-%d").to_string())
+            .join(host)
+            .join(remote.trim_start_matches('/'))
+    }
+}
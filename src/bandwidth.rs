@@ -0,0 +1,51 @@
+use crate::models::{BandwidthResult, VmStatus};
+use crate::ssh_config::wildcard_match;
+use serde::{Deserialize, Serialize};
+
+/// `[bandwidth]` section: iperf3 throughput between every pair of hosts over
+/// their VPN addresses. Each pair runs in turn for `duration_secs`, so the
+/// test is off by default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BandwidthConfig {
+    pub enabled: bool,
+    /// Hosts taking part, with `*` and `?` wildcards; all hosts with a VPN
+    /// address when empty.
+    pub hosts: Vec<String>,
+    pub port: u16,
+    pub duration_secs: u64,
+    /// Throughput below this many Mbit/s is a warning.
+    pub min_mbps: Option<f64>,
+}
+
+impl Default for BandwidthConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hosts: Vec::new(),
+            port: 5201,
+            duration_secs: 5,
+            min_mbps: None,
+        }
+    }
+}
+
+impl BandwidthConfig {
+    /// Reachable hosts to test, with the VPN address their server listens on.
+    pub fn participants<'a>(&self, vms: &'a [VmStatus]) -> Vec<(&'a VmStatus, &'a str)> {
+        vms.iter()
+            .filter(|vm| vm.reachable)
+            .filter(|vm| self.hosts.is_empty() || self.hosts.iter().any(|pattern| wildcard_match(pattern, &vm.host.name)))
+            .filter_map(|vm| Some((vm, vm.host.vpn_ip.as_deref()?)))
+            .collect()
+    }
+
+    /// Warning for a failed or slow measurement.
+    pub fn issue(&self, result: &BandwidthResult) -> Option<String> {
+        if let Some(ref error) = result.error {
+            return Some(format!("{} → {}: bandwidth test failed - {}", result.from, result.to, error));
+        }
+        let (mbps, min) = (result.mbps?, self.min_mbps?);
+        (mbps < min).then(|| format!("{} → {}: {:.0} Mbit/s over the VPN, below {:.0}", result.from, result.to, mbps, min))
+    }
+}
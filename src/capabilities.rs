@@ -0,0 +1,44 @@
+use crate::models::{Capabilities, InitSystem};
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Caching of what each host can run. Probing costs a round trip, and init
+/// systems and tools rarely change, so the result is kept between scans.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CapabilitiesConfig {
+    /// Cache file in the state directory.
+    pub cache_file: String,
+    /// Hosts are probed again once their entry is older than this.
+    pub max_age_hours: i64,
+}
+
+impl Default for CapabilitiesConfig {
+    fn default() -> Self {
+        Self {
+            cache_file: "capabilities.json".to_string(),
+            max_age_hours: 24,
+        }
+    }
+}
+
+impl CapabilitiesConfig {
+    pub fn is_fresh(&self, capabilities: &Capabilities) -> bool {
+        Utc::now() - capabilities.probed_at < Duration::hours(self.max_age_hours)
+    }
+}
+
+/// Probed capabilities by host name.
+pub type CapabilityCache = BTreeMap<String, Capabilities>;
+
+/// Why `check` can't run on a host with `capabilities`, or `None` when it can.
+pub fn missing(capabilities: &Capabilities, check: &str) -> Option<&'static str> {
+    match check {
+        "services" if capabilities.init == InitSystem::Other => Some("no systemd, OpenRC or rc.d"),
+        "containers" | "volumes" if !capabilities.docker && !capabilities.podman => Some("no docker or podman"),
+        "ports" if !capabilities.ss && !capabilities.netstat && !capabilities.sockstat => Some("no ss, netstat or sockstat"),
+        "logs" if !capabilities.journald && !capabilities.syslog => Some("no journald or syslog file"),
+        _ => None,
+    }
+}
@@ -0,0 +1,171 @@
+use crate::i18n::Lang;
+use crate::logging::LogFormat;
+use crate::models::{InventoryReport, VmHost};
+use crate::profile::{Check, ScanProfile};
+use crate::ssh_config;
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+use std::time::Duration;
+
+/// SecurePenguin infrastructure inventory scanner.
+#[derive(Debug, Parser)]
+#[command(version, about, after_help = EXIT_CODES)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Deadline for the whole scan in seconds; hosts not reached by then are skipped
+    #[arg(long, value_name = "SECS")]
+    pub timeout: Option<u64>,
+
+    /// Exit non-zero when the scan finds these conditions (comma-separated)
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "CONDITION")]
+    pub fail_on: Vec<FailOn>,
+
+    /// Re-run the scan in a loop, redrawing the summary and highlighting changes
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Time between scans in watch mode (e.g. 60s, 5m)
+    #[arg(long, value_parser = humantime::parse_duration, default_value = "60s", requires = "watch")]
+    pub interval: Duration,
+
+    /// Only scan these hosts (comma-separated or repeated; `*` and `?` wildcards)
+    #[arg(long, value_delimiter = ',', value_name = "NAME")]
+    pub host: Vec<String>,
+
+    /// Only scan hosts in these groups (comma-separated or repeated)
+    #[arg(long, value_delimiter = ',')]
+    pub group: Vec<String>,
+
+    /// Only scan hosts carrying any of these tags (comma-separated or repeated)
+    #[arg(long, value_delimiter = ',')]
+    pub tag: Vec<String>,
+
+    /// Report formats to write (comma-separated); JUnit goes next to the report as .xml
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "markdown")]
+    pub format: Vec<ReportFormat>,
+
+    /// Take the monitored files as found by this scan as the new integrity baseline
+    #[arg(long)]
+    pub accept_integrity: bool,
+
+    /// Also write how long each check took on each host to this JSON file
+    #[arg(long, value_name = "PATH")]
+    pub profile_output: Option<String>,
+
+    /// Replace public IPs, public keys, SSH usernames and identity files with stable pseudonyms in the reports and the Atom feed, keyed with ~/.config/securepenguin/redact.key
+    #[arg(long)]
+    pub redact: bool,
+
+    /// Render the markdown report with the Tera templates in this directory (entry: report.md)
+    #[arg(long, value_name = "DIR")]
+    pub template_dir: Option<String>,
+
+    /// Checks to run: quick (reachability and web), standard or full (overrides `profile` in the config)
+    #[arg(long, value_enum)]
+    pub profile: Option<ScanProfile>,
+
+    /// Run only these checks, e.g. `--only containers,wireguard` after a deploy
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "CHECK")]
+    pub only: Vec<Check>,
+
+    /// Leave these checks out, e.g. `--skip logs,ports`
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "CHECK")]
+    pub skip: Vec<Check>,
+
+    /// Language of the markdown report (overrides `language` in the config)
+    #[arg(long, value_enum)]
+    pub lang: Option<Lang>,
+
+    /// More detailed logs (-v debug, -vv trace)
+    #[arg(short, long, action = ArgAction::Count, conflicts_with = "quiet")]
+    pub verbose: u8,
+
+    /// Only log warnings and errors, and skip the banner and summary
+    #[arg(short, long)]
+    pub quiet: bool,
+
+    /// Log output format
+    #[arg(long, value_enum, default_value_t = LogFormat::Text)]
+    pub log_format: LogFormat,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Interactive terminal UI: live host list, host details and per-host rescans
+    Tui,
+}
+
+impl Cli {
+    /// Whether `--host`/`--group`/`--tag` select the host; all given must match.
+    pub fn selects(&self, host: &VmHost) -> bool {
+        let named = self.host.is_empty() || self.host.iter().any(|pattern| ssh_config::wildcard_match(pattern, &host.name));
+        let in_group = self.group.is_empty() || host.group.as_ref().is_some_and(|g| self.group.contains(g));
+        let tagged = self.tag.is_empty() || host.tags.iter().any(|t| self.tag.contains(t));
+        named && in_group && tagged
+    }
+
+    pub fn verbosity(&self) -> i8 {
+        if self.quiet {
+            -1
+        } else {
+            self.verbose.min(2) as i8
+        }
+    }
+}
+
+const EXIT_CODES: &str = "Exit codes:
+  0  scan finished and no --fail-on condition matched
+  1  the scan itself failed
+  2  critical issues found (--fail-on critical)
+  3  hosts unreachable (--fail-on unreachable)
+  4  warnings or critical issues found (--fail-on warning)";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    Markdown,
+    Junit,
+}
+
+/// Scan outcomes that turn into a failing exit status for cron/CI gates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FailOn {
+    /// Critical issues found (exit code 2)
+    Critical,
+    /// Warnings or critical issues found (exit code 4)
+    Warning,
+    /// Hosts unreachable (exit code 3)
+    Unreachable,
+}
+
+impl FailOn {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            FailOn::Critical => 2,
+            FailOn::Unreachable => 3,
+            FailOn::Warning => 4,
+        }
+    }
+
+    pub fn matches(self, report: &InventoryReport) -> bool {
+        match self {
+            FailOn::Critical => !report.critical_issues.is_empty(),
+            // Warning or worse, so a scan with only critical issues still fails
+            FailOn::Warning => !report.warnings.is_empty() || !report.critical_issues.is_empty(),
+            // Hosts declared as expected-unreachable don't count
+            FailOn::Unreachable => report.vms.iter().any(|vm| {
+                !vm.reachable && vm.host.external.as_ref().is_none_or(|ext| ext.expect_reachable)
+            }),
+        }
+    }
+}
+
+/// Exit status for `report`: the most severe matching condition wins.
+pub fn exit_code(fail_on: &[FailOn], report: &InventoryReport) -> i32 {
+    fail_on
+        .iter()
+        .filter(|condition| condition.matches(report))
+        .map(|condition| condition.exit_code())
+        .min()
+        .unwrap_or(0)
+}
@@ -0,0 +1,381 @@
+use crate::archive::ArchiveConfig;
+use crate::bandwidth::BandwidthConfig;
+use crate::container_audit::ContainerAuditConfig;
+use crate::container_storage::ContainerStorageConfig;
+use crate::custom_checks::CustomCheckConfig;
+use crate::dependencies::Dependency;
+use crate::disk::DiskConfig;
+use crate::events::EventsConfig;
+use crate::file_audit::FileAuditConfig;
+use crate::capabilities::CapabilitiesConfig;
+use crate::i18n::Lang;
+use crate::incidents::github::GitHubConfig;
+use crate::incidents::opsgenie::OpsgenieConfig;
+use crate::integrity::IntegrityConfig;
+use crate::incidents::pagerduty::PagerDutyConfig;
+use crate::log_scan::LogConfig;
+use crate::maintenance::MaintenanceWindow;
+use crate::models::{ExternalHost, Severity, VmHost};
+use crate::mqtt::MqttConfig;
+use crate::nagios::NagiosConfig;
+use crate::timeseries::{InfluxConfig, TimescaleConfig};
+use crate::n8n::N8nConfig;
+use crate::netbox::NetboxConfig;
+use crate::notifier::gotify::GotifyConfig;
+use crate::notifier::matrix::MatrixConfig;
+use crate::notifier::ntfy::NtfyConfig;
+use crate::obsidian::ObsidianConfig;
+use crate::os_release::OsConfig;
+use crate::node_exporter::NodeExporterConfig;
+use crate::domains::DomainConfig;
+use crate::ct_log::CtConfig;
+use crate::rbl::RblConfig;
+use crate::secrets;
+use crate::ssh_config;
+use crate::state::StateConfig;
+use crate::pinger::PingConfig;
+use crate::policy::PolicyConfig;
+use crate::port_scanner::ExposureConfig;
+use crate::processes::ProcessConfig;
+use crate::profile::{CheckSelection, ScanProfile};
+use crate::providers::gcp::GcpConfig;
+use crate::providers::hetzner::HetznerConfig;
+use crate::providers::oci::OciConfig;
+use crate::proxmox::ProxmoxConfig;
+use crate::reporter::ReportConfig;
+use crate::scripting::ScriptingConfig;
+use crate::sensors::SensorConfig;
+use crate::ssh_client::{SshSettings, TimeoutConfig, TraefikConfig};
+use crate::suppressions::SuppressionsConfig;
+use crate::telemetry::TelemetryConfig;
+use crate::terraform::TerraformConfig;
+use crate::traceroute::TracerouteConfig;
+use crate::web_scanner::{default_web_services, TlsConfig, WebServiceConfig};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+const DEFAULT_CONFIG_PATH: &str = "~/.config/securepenguin/inventory.toml";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub ssh_config: String,
+    pub ssh: SshSettings,
+    pub timeouts: TimeoutConfig,
+    pub traefik: TraefikConfig,
+    pub output_path: String,
+    /// Language of the markdown report.
+    pub language: Lang,
+    /// Which checks a scan runs: quick, standard or full.
+    pub profile: ScanProfile,
+    /// Checks to run or leave out on top of the profile.
+    pub checks: CheckSelection,
+    pub report: ReportConfig,
+    pub external_hosts: Vec<ExternalHostConfig>,
+    /// Group, tags and roles for hosts from the SSH config, keyed by host alias.
+    pub hosts: HashMap<String, HostMetaConfig>,
+    pub host_filter: HostFilterConfig,
+    /// Services each role requires, keyed by role name (e.g. `dns-server = ["pdns"]`).
+    pub roles: HashMap<String, Vec<String>>,
+    pub policy: PolicyConfig,
+    pub custom_checks: Vec<CustomCheckConfig>,
+    pub scripting: ScriptingConfig,
+    pub suppressions: SuppressionsConfig,
+    pub maintenance: Vec<MaintenanceWindow>,
+    /// What each host check and web service relies on, to tell root causes from their impact.
+    pub dependencies: Vec<Dependency>,
+    /// Directory of the files kept between scans.
+    pub state: StateConfig,
+    pub archive: ArchiveConfig,
+    pub integrity: IntegrityConfig,
+    pub file_audit: FileAuditConfig,
+    pub capabilities: CapabilitiesConfig,
+    pub processes: ProcessConfig,
+    pub os: OsConfig,
+    pub disk: DiskConfig,
+    pub node_exporter: NodeExporterConfig,
+    pub sensors: SensorConfig,
+    pub logs: LogConfig,
+    pub container_audit: ContainerAuditConfig,
+    pub container_storage: ContainerStorageConfig,
+    pub web_services: Vec<WebServiceConfig>,
+    pub tls: TlsConfig,
+    pub domains: DomainConfig,
+    pub certificate_transparency: CtConfig,
+    pub exposure: ExposureConfig,
+    pub rbl: RblConfig,
+    pub ping: PingConfig,
+    pub traceroute: TracerouteConfig,
+    pub bandwidth: BandwidthConfig,
+    pub gcp: GcpConfig,
+    pub hetzner: HetznerConfig,
+    pub oci: OciConfig,
+    pub proxmox: ProxmoxConfig,
+    pub terraform: TerraformConfig,
+    pub netbox: NetboxConfig,
+    pub obsidian: ObsidianConfig,
+    pub n8n: N8nConfig,
+    pub mqtt: MqttConfig,
+    pub influxdb: InfluxConfig,
+    pub timescale: TimescaleConfig,
+    pub nagios: NagiosConfig,
+    pub ntfy: NtfyConfig,
+    pub gotify: GotifyConfig,
+    pub matrix: MatrixConfig,
+    pub pagerduty: PagerDutyConfig,
+    pub opsgenie: OpsgenieConfig,
+    pub github: GitHubConfig,
+    pub events: EventsConfig,
+    pub telemetry: TelemetryConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            ssh_config: "/home/jnovoas/.ssh/config".to_string(),
+            ssh: SshSettings::default(),
+            timeouts: TimeoutConfig::default(),
+            traefik: TraefikConfig::default(),
+            output_path: "/home/jnovoas/SecurePenguin/INVENTARIO_STATUS_AUTO.md".to_string(),
+            language: Lang::default(),
+            profile: ScanProfile::default(),
+            checks: CheckSelection::default(),
+            report: ReportConfig::default(),
+            external_hosts: Vec::new(),
+            hosts: HashMap::new(),
+            host_filter: HostFilterConfig::default(),
+            roles: HashMap::new(),
+            policy: PolicyConfig::default(),
+            custom_checks: Vec::new(),
+            scripting: ScriptingConfig::default(),
+            suppressions: SuppressionsConfig::default(),
+            maintenance: Vec::new(),
+            dependencies: Vec::new(),
+            archive: ArchiveConfig::default(),
+            integrity: IntegrityConfig::default(),
+            file_audit: FileAuditConfig::default(),
+            capabilities: CapabilitiesConfig::default(),
+            state: StateConfig::default(),
+            processes: ProcessConfig::default(),
+            os: OsConfig::default(),
+            disk: DiskConfig::default(),
+            node_exporter: NodeExporterConfig::default(),
+            sensors: SensorConfig::default(),
+            logs: LogConfig::default(),
+            container_audit: ContainerAuditConfig::default(),
+            container_storage: ContainerStorageConfig::default(),
+            web_services: default_web_services(),
+            tls: TlsConfig::default(),
+            domains: DomainConfig::default(),
+            certificate_transparency: CtConfig::default(),
+            exposure: ExposureConfig::default(),
+            rbl: RblConfig::default(),
+            ping: PingConfig::default(),
+            traceroute: TracerouteConfig::default(),
+            bandwidth: BandwidthConfig::default(),
+            gcp: GcpConfig::default(),
+            hetzner: HetznerConfig::default(),
+            oci: OciConfig::default(),
+            proxmox: ProxmoxConfig::default(),
+            terraform: TerraformConfig::default(),
+            netbox: NetboxConfig::default(),
+            obsidian: ObsidianConfig::default(),
+            n8n: N8nConfig::default(),
+            mqtt: MqttConfig::default(),
+            influxdb: InfluxConfig::default(),
+            timescale: TimescaleConfig::default(),
+            nagios: NagiosConfig::default(),
+            ntfy: NtfyConfig::default(),
+            gotify: GotifyConfig::default(),
+            matrix: MatrixConfig::default(),
+            pagerduty: PagerDutyConfig::default(),
+            opsgenie: OpsgenieConfig::default(),
+            github: GitHubConfig::default(),
+            events: EventsConfig::default(),
+            telemetry: TelemetryConfig::default(),
+        }
+    }
+}
+
+/// A host that lives outside the SSH config (e.g. a cloud VM only
+/// reachable through the VPN) and is declared directly in the config file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalHostConfig {
+    pub name: String,
+    pub ip: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    pub user: String,
+    pub identity_file: String,
+    pub vpn_ip: Option<String>,
+    pub provider: String,
+    pub region: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Addresses tried in order when `ip` does not answer.
+    #[serde(default)]
+    pub alternate_ips: Vec<String>,
+    /// Whether the scanner is expected to reach this host at all.
+    #[serde(default = "default_true")]
+    pub expect_reachable: bool,
+    /// Overrides the severity used when the host cannot be reached.
+    pub unreachable_severity: Option<Severity>,
+    pub note: Option<String>,
+    pub group: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    /// Containers that must be running, by name or image pattern.
+    #[serde(default)]
+    pub containers: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HostMetaConfig {
+    /// Off leaves the host out of every scan.
+    pub enabled: bool,
+    pub group: Option<String>,
+    pub tags: Vec<String>,
+    pub roles: Vec<String>,
+    /// Containers that must be running, by name or image pattern with `*`
+    /// and `?` wildcards (e.g. `traefik` or `postgres:16*`). Any other
+    /// container on the host is reported as unexpected.
+    pub containers: Vec<String>,
+}
+
+impl Default for HostMetaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            group: None,
+            tags: Vec::new(),
+            roles: Vec::new(),
+            containers: Vec::new(),
+        }
+    }
+}
+
+/// Which hosts are scanned, by name, with ssh-style `*` and `?` wildcards.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HostFilterConfig {
+    /// Hosts to scan; empty means every host not excluded.
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl Default for HostFilterConfig {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            // Backup copies of VMs share their keys and services
+            exclude: vec!["*-bkp".to_string()],
+        }
+    }
+}
+
+fn default_port() -> u16 {
+    22
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl ExternalHostConfig {
+    pub fn to_vm_host(&self) -> VmHost {
+        let default_severity = if self.expect_reachable {
+            Severity::Critical
+        } else {
+            Severity::Info
+        };
+
+        VmHost {
+            name: self.name.clone(),
+            ip: self.ip.clone(),
+            port: self.port,
+            user: self.user.clone(),
+            identity_file: shellexpand::tilde(&self.identity_file).to_string(),
+            vpn_ip: self.vpn_ip.clone(),
+            external: Some(ExternalHost {
+                provider: self.provider.clone(),
+                region: self.region.clone(),
+                alternate_ips: self.alternate_ips.clone(),
+                expect_reachable: self.expect_reachable,
+                unreachable_severity: self.unreachable_severity.unwrap_or(default_severity),
+                note: self.note.clone(),
+            }),
+            group: self.group.clone(),
+            tags: self.tags.clone(),
+            roles: self.roles.clone(),
+            expected_containers: self.containers.clone(),
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config from `SP_INVENTORY_CONFIG` or the default location,
+    /// falling back to built-in defaults when no file exists.
+    pub fn load() -> Result<Self> {
+        let path = std::env::var("SP_INVENTORY_CONFIG")
+            .unwrap_or_else(|_| DEFAULT_CONFIG_PATH.to_string());
+        let path = shellexpand::tilde(&path).to_string();
+
+        if !Path::new(&path).exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(&path)
+            .context(format!("Failed to read config file: {}", path))?;
+
+        // Parsed as Config first for errors pointing at the offending line
+        toml::from_str::<Self>(&content).context(format!("Failed to parse config file: {}", path))?;
+        let mut value: toml::Value = toml::from_str(&content).context(format!("Failed to parse config file: {}", path))?;
+        secrets::resolve(&mut value)?;
+        value.try_into().context(format!("Failed to parse config file: {}", path))
+    }
+
+    /// Applies `[hosts.<name>]` metadata: its group wins, its tags, roles and
+    /// expected containers are added.
+    pub fn apply_host_meta(&self, host: &mut VmHost) {
+        if let Some(meta) = self.hosts.get(&host.name) {
+            if meta.group.is_some() {
+                host.group = meta.group.clone();
+            }
+            for tag in &meta.tags {
+                if !host.tags.contains(tag) {
+                    host.tags.push(tag.clone());
+                }
+            }
+            for role in &meta.roles {
+                if !host.roles.contains(role) {
+                    host.roles.push(role.clone());
+                }
+            }
+            for container in &meta.containers {
+                if !host.expected_containers.contains(container) {
+                    host.expected_containers.push(container.clone());
+                }
+            }
+        }
+    }
+
+    /// Whether the host named `name` is scanned: not disabled in
+    /// `[hosts.<name>]`, matching `[host_filter]` include patterns when there
+    /// are any, and none of its exclude patterns.
+    pub fn scans_host(&self, name: &str) -> bool {
+        let filter = &self.host_filter;
+        let matches = |patterns: &[String]| patterns.iter().any(|pattern| ssh_config::wildcard_match(pattern, name));
+        self.hosts.get(name).is_none_or(|meta| meta.enabled)
+            && (filter.include.is_empty() || matches(&filter.include))
+            && !matches(&filter.exclude)
+    }
+
+    pub fn is_external(&self, name: &str) -> bool {
+        self.external_hosts.iter().any(|h| h.name == name)
+    }
+}
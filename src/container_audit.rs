@@ -0,0 +1,112 @@
+use crate::models::Container;
+use serde::{Deserialize, Serialize};
+
+/// Container settings that hand the host to whoever controls the container.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContainerAuditConfig {
+    /// Host paths a container should not mount, nor any directory above them.
+    pub sensitive_paths: Vec<String>,
+    /// Containers never flagged, e.g. a reverse proxy that needs the Docker socket.
+    pub allow: Vec<String>,
+    /// Environment variable names holding credentials (case-insensitive
+    /// substrings). `*_FILE` variables point at mounted secrets instead.
+    pub secret_env: Vec<String>,
+    /// Variables matching `secret_env` that hold no secret, e.g. `PASSWORD_MIN_LENGTH`.
+    pub allowed_env: Vec<String>,
+}
+
+impl Default for ContainerAuditConfig {
+    fn default() -> Self {
+        Self {
+            sensitive_paths: [
+                "/etc/shadow", "/etc/sudoers", "/etc/ssh", "/root", "/boot", "/proc", "/sys", "/dev", "/var/lib/docker",
+                "/home",
+            ]
+            .iter()
+            .map(|p| p.to_string())
+            .collect(),
+            allow: Vec::new(),
+            secret_env: ["PASSWORD", "PASSWD", "TOKEN", "SECRET", "API_KEY", "APIKEY", "PRIVATE_KEY", "ACCESS_KEY", "CREDENTIAL"]
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            allowed_env: Vec::new(),
+        }
+    }
+}
+
+/// Sockets of the container runtimes, which amount to root on the host.
+const RUNTIME_SOCKETS: [&str; 4] = [
+    "/var/run/docker.sock",
+    "/run/docker.sock",
+    "/var/run/podman/podman.sock",
+    "/run/podman/podman.sock",
+];
+
+impl ContainerAuditConfig {
+    /// Every way `container` is exposed to its host, as issue messages
+    /// without the host.
+    pub fn exposures(&self, container: &Container) -> Vec<String> {
+        let Some(ref runtime) = container.runtime else {
+            return Vec::new();
+        };
+        if self.allow.contains(&container.name) {
+            return Vec::new();
+        }
+
+        let mut exposures = Vec::new();
+        if runtime.privileged {
+            exposures.push(format!("container {} runs privileged", container.name));
+        }
+        if runtime.network_mode == "host" {
+            exposures.push(format!("container {} shares the host network", container.name));
+        }
+        for mount in &runtime.mounts {
+            let access = if mount.read_only { "read-only" } else { "read-write" };
+            if self.sensitive_paths.iter().any(|path| covers(&mount.source, path)) {
+                exposures.push(format!(
+                    "container {} mounts host path {} at {} ({})",
+                    container.name, mount.source, mount.destination, access
+                ));
+            } else if mount.source.ends_with("/docker.sock")
+                || mount.source.ends_with("/podman.sock")
+                || RUNTIME_SOCKETS.iter().any(|socket| covers(&mount.source, socket))
+            {
+                exposures.push(format!(
+                    "container {} mounts the container runtime socket ({} at {})",
+                    container.name, mount.source, mount.destination
+                ));
+            }
+        }
+        exposures
+    }
+
+    /// Credentials passed to `container` in plain text through its
+    /// environment, redacted, as an issue message without the host.
+    pub fn plaintext_secrets(&self, container: &Container) -> Option<String> {
+        let runtime = container.runtime.as_ref()?;
+        if self.allow.contains(&container.name) {
+            return None;
+        }
+
+        let secrets: Vec<String> = runtime
+            .env
+            .iter()
+            .filter(|name| !name.ends_with("_FILE") && !self.allowed_env.contains(name))
+            .filter(|name| {
+                let upper = name.to_uppercase();
+                self.secret_env.iter().any(|pattern| upper.contains(&pattern.to_uppercase()))
+            })
+            .map(|name| format!("{}=***", name))
+            .collect();
+        (!secrets.is_empty()).then(|| {
+            format!("container {} has plaintext secrets in its environment ({})", container.name, secrets.join(", "))
+        })
+    }
+}
+
+/// Whether mounting `source` exposes `path`: the same path or a directory above it.
+fn covers(source: &str, path: &str) -> bool {
+    path == source || path.starts_with(&format!("{}/", source.trim_end_matches('/')))
+}
@@ -0,0 +1,48 @@
+use crate::models::ContainerStorage;
+use serde::{Deserialize, Serialize};
+
+/// When space held by unused container images and orphan volumes is worth
+/// a warning.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContainerStorageConfig {
+    /// Usage of the filesystem holding the runtime data from which any
+    /// reclaimable space is reported.
+    pub disk_warning_percent: u8,
+    /// Reclaimable space reported regardless of disk usage.
+    pub reclaimable_warning_gb: f64,
+}
+
+impl Default for ContainerStorageConfig {
+    fn default() -> Self {
+        Self {
+            disk_warning_percent: 85,
+            reclaimable_warning_gb: 10.0,
+        }
+    }
+}
+
+const GB: f64 = 1_000_000_000.0;
+
+impl ContainerStorageConfig {
+    /// Space pruning would free when it matters, as an issue message
+    /// without the host.
+    pub fn issue(&self, storage: &ContainerStorage) -> Option<String> {
+        let reclaimable = storage.reclaimable_bytes() as f64 / GB;
+        let disk_full = storage.data_root_used_percent.is_some_and(|used| used >= self.disk_warning_percent);
+        if reclaimable <= 0.0 || (!disk_full && reclaimable < self.reclaimable_warning_gb) {
+            return None;
+        }
+
+        let mut issue = format!(
+            "{:.1} GB reclaimable from {} unused images and {} orphan volumes",
+            reclaimable,
+            storage.unused_images().count(),
+            storage.orphan_volumes().count()
+        );
+        if let (Some(root), Some(used)) = (&storage.data_root, storage.data_root_used_percent) {
+            issue.push_str(&format!(" ({} on a filesystem {}% full)", root, used));
+        }
+        Some(issue)
+    }
+}
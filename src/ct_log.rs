@@ -0,0 +1,135 @@
+use crate::models::{CtCertificate, Severity};
+use anyhow::{Context, Result};
+use chrono::{NaiveDateTime, Utc};
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+/// Certificate Transparency monitoring through crt.sh: every certificate
+/// issued for the domains must name a known host and come from a known CA,
+/// which catches misissuance and subdomains nobody remembers setting up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CtConfig {
+    pub domains: Vec<String>,
+    /// Names expected in certificates besides the domains themselves and the
+    /// hosts of `[[web_services]]`, e.g. `*.secure-penguin.com`.
+    pub known_names: Vec<String>,
+    /// Substrings of the issuer DN of the CAs meant to issue certificates.
+    pub issuers: Vec<String>,
+}
+
+impl Default for CtConfig {
+    fn default() -> Self {
+        Self {
+            domains: vec!["secure-penguin.com".to_string()],
+            known_names: Vec::new(),
+            issuers: vec!["Let's Encrypt".to_string()],
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CrtShEntry {
+    id: u64,
+    issuer_name: String,
+    name_value: String,
+    not_before: String,
+    not_after: String,
+    serial_number: String,
+}
+
+impl CtConfig {
+    /// Unexpired certificates logged for the domains that name an unknown
+    /// host or come from an unknown issuer. `known` holds the hostnames of
+    /// the probed web services.
+    pub async fn unexpected(&self, known: &[String]) -> Result<Vec<CtCertificate>> {
+        let client = Client::builder().timeout(Duration::from_secs(60)).build()?;
+        let known: BTreeSet<String> = self
+            .domains
+            .iter()
+            .chain(&self.known_names)
+            .chain(known)
+            .map(|name| name.to_lowercase())
+            .collect();
+
+        let mut certificates = Vec::new();
+        let mut serials = BTreeSet::new();
+        for domain in &self.domains {
+            let url = Url::parse_with_params("https://crt.sh/", &[("q", format!("%.{}", domain).as_str()), ("output", "json")])?;
+            let entries: Vec<CrtShEntry> = client
+                .get(url)
+                .send()
+                .await
+                .context("Failed to reach crt.sh")?
+                .error_for_status()
+                .context("crt.sh query failed")?
+                .json()
+                .await
+                .context("Failed to parse crt.sh response")?;
+
+            for entry in entries {
+                let parse = |date: &str| NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S").map(|d| d.and_utc()).ok();
+                let (Some(not_before), Some(not_after)) = (parse(&entry.not_before), parse(&entry.not_after)) else {
+                    continue;
+                };
+                // Precertificates and the final certificate share a serial
+                if not_after < Utc::now() || !serials.insert(entry.serial_number.clone()) {
+                    continue;
+                }
+
+                let names: Vec<String> = entry.name_value.lines().map(|name| name.trim().to_lowercase()).collect();
+                let unknown_names: Vec<String> = names.iter().filter(|name| !known.contains(*name)).cloned().collect();
+                let unknown_issuer = !self.issuers.iter().any(|issuer| entry.issuer_name.contains(issuer.as_str()));
+                if unknown_names.is_empty() && !unknown_issuer {
+                    continue;
+                }
+
+                certificates.push(CtCertificate {
+                    id: entry.id,
+                    names,
+                    unknown_names,
+                    issuer: entry.issuer_name,
+                    unknown_issuer,
+                    not_before,
+                    not_after,
+                });
+            }
+        }
+
+        certificates.sort_by_key(|certificate| std::cmp::Reverse(certificate.not_before));
+        Ok(certificates)
+    }
+
+    /// Issue messages for an unexpected certificate. One from an unknown CA
+    /// may be misissued, so it is critical.
+    pub fn issues(&self, certificate: &CtCertificate) -> Vec<(Severity, String)> {
+        let mut issues = Vec::new();
+        let issued = certificate.not_before.format("%Y-%m-%d");
+        if !certificate.unknown_names.is_empty() {
+            issues.push((
+                Severity::Warning,
+                format!(
+                    "certificate for unexpected {} issued {} (crt.sh id {})",
+                    certificate.unknown_names.join(", "),
+                    issued,
+                    certificate.id
+                ),
+            ));
+        }
+        if certificate.unknown_issuer {
+            issues.push((
+                Severity::Critical,
+                format!(
+                    "certificate for {} issued {} by unknown CA {} (crt.sh id {})",
+                    certificate.names.join(", "),
+                    issued,
+                    certificate.issuer,
+                    certificate.id
+                ),
+            ));
+        }
+        issues
+    }
+}
@@ -0,0 +1,67 @@
+use crate::models::{CustomCheck, CustomCheckStatus, VmHost};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// A user-defined check: a script run on each host in scope, which prints a
+/// [`CustomCheck`] as JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomCheckConfig {
+    pub name: String,
+    /// Inline shell script run on the host.
+    pub command: Option<String>,
+    /// Local script file whose contents are run on the host instead of `command`.
+    pub script: Option<String>,
+    #[serde(default)]
+    pub sudo: bool,
+    #[serde(default)]
+    pub hosts: Vec<String>,
+    #[serde(default)]
+    pub groups: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl CustomCheckConfig {
+    pub fn applies_to(&self, host: &VmHost) -> bool {
+        host.in_scope(&self.hosts, &self.groups, &self.tags)
+    }
+
+    /// Shell source to run remotely.
+    pub fn source(&self) -> Result<String> {
+        match (&self.script, &self.command) {
+            (Some(path), _) => {
+                let path = shellexpand::tilde(path).to_string();
+                std::fs::read_to_string(&path).context(format!("Failed to read check script: {}", path))
+            }
+            (None, Some(command)) => Ok(command.clone()),
+            (None, None) => anyhow::bail!("custom check '{}' has neither command nor script", self.name),
+        }
+    }
+}
+
+/// Reads the first JSON document the script printed; anything else makes
+/// the check unknown rather than silently passing.
+pub fn parse(name: &str, output: &str) -> CustomCheck {
+    let start = output.find('{').unwrap_or(output.len());
+    let parsed = serde_json::Deserializer::from_str(&output[start..])
+        .into_iter::<CustomCheck>()
+        .next();
+
+    match parsed {
+        Some(Ok(mut check)) => {
+            check.name = name.to_string();
+            check
+        }
+        Some(Err(e)) => unknown(name, &format!("invalid output: {}", e)),
+        None => unknown(name, "no output"),
+    }
+}
+
+pub fn unknown(name: &str, message: &str) -> CustomCheck {
+    CustomCheck {
+        name: name.to_string(),
+        status: CustomCheckStatus::Unknown,
+        message: message.to_string(),
+        metrics: Default::default(),
+    }
+}
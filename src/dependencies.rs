@@ -0,0 +1,139 @@
+use crate::models::{CheckResult, Impact, InventoryReport, Issue, WebService};
+use crate::ssh_config::wildcard_match;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// `component` only works while everything in `depends_on` does. Components
+/// are a host (`kingu`, reachable over SSH), one of its checks
+/// (`kingu/containers`) or a web service (`web:Guacamole`), and `*` or `?`
+/// name several at once. A host's checks always depend on the host.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dependency {
+    pub component: String,
+    pub depends_on: Vec<String>,
+}
+
+/// Every component of the report, and whether it is failing.
+fn components(report: &InventoryReport) -> BTreeMap<String, bool> {
+    let mut components = BTreeMap::new();
+    for vm in &report.vms {
+        components.insert(vm.host.name.clone(), !vm.reachable);
+        for check in &vm.checks {
+            components.insert(format!("{}/{}", vm.host.name, check.name), check.result == CheckResult::Failed);
+        }
+    }
+    for service in &report.web_services {
+        components.insert(format!("web:{}", service.name), web_failing(service));
+    }
+    components
+}
+
+fn web_failing(service: &WebService) -> bool {
+    service.error.is_some()
+        || service.content_error.is_some()
+        || service.unexpected_status
+        || service.scenario.iter().any(|step| step.error.is_some())
+        || service
+            .health
+            .as_ref()
+            .is_some_and(|health| health.error.is_some() || health.fields.iter().any(|field| !field.ok))
+}
+
+/// The component an issue is about: the web service its message names, or
+/// the host check that raised it.
+fn component_of(issue: &Issue, report: &InventoryReport) -> Vec<String> {
+    match issue.host {
+        Some(ref host) if issue.check == "connectivity" => vec![host.clone()],
+        Some(ref host) => vec![format!("{}/{}", host, issue.check), host.clone()],
+        None if issue.check == "web" => report
+            .web_services
+            .iter()
+            .filter(|service| {
+                issue
+                    .message
+                    .strip_prefix(service.name.as_str())
+                    .is_some_and(|rest| rest.starts_with([':', ' ']))
+            })
+            .max_by_key(|service| service.name.len())
+            .map(|service| vec![format!("web:{}", service.name)])
+            .unwrap_or_default(),
+        None => Vec::new(),
+    }
+}
+
+/// Failing components that `component` fails because of: the failing
+/// dependencies reached through other failing components whose own
+/// dependencies work. Empty when `component` is a root cause.
+fn root_causes<'a>(component: &'a str, edges: &BTreeMap<&'a str, BTreeSet<&'a str>>, failing: &BTreeSet<&'a str>) -> BTreeSet<&'a str> {
+    let failing_dependencies =
+        |name: &'a str| edges.get(name).into_iter().flatten().copied().filter(|d| failing.contains(d)).collect::<Vec<_>>();
+
+    let mut roots = BTreeSet::new();
+    let mut seen = BTreeSet::from([component]);
+    let mut pending = failing_dependencies(component);
+    while let Some(name) = pending.pop() {
+        if !seen.insert(name) {
+            continue;
+        }
+        let next = failing_dependencies(name);
+        if next.is_empty() {
+            roots.insert(name);
+        }
+        pending.extend(next);
+    }
+    roots
+}
+
+/// Separates root causes from downstream impact: every failing component
+/// that depends on another failing component is attributed to the root
+/// causes behind it, and its issues are marked with them.
+pub fn apply(dependencies: &[Dependency], report: &mut InventoryReport) {
+    let components = components(report);
+    let failing: BTreeSet<&str> = components.iter().filter(|(_, failing)| **failing).map(|(name, _)| name.as_str()).collect();
+
+    let mut edges: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for name in components.keys() {
+        if name.starts_with("web:") {
+            continue;
+        }
+        if let Some((host, _)) = name.split_once('/') {
+            if let Some((host, _)) = components.get_key_value(host) {
+                edges.entry(name).or_default().insert(host);
+            }
+        }
+    }
+    for dependency in dependencies {
+        for component in components.keys().filter(|name| wildcard_match(&dependency.component, name)) {
+            for pattern in &dependency.depends_on {
+                let targets = components.keys().filter(|name| *name != component && wildcard_match(pattern, name));
+                edges.entry(component).or_default().extend(targets.map(String::as_str));
+            }
+        }
+    }
+
+    let mut caused_by: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut affected: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for component in &failing {
+        let roots = root_causes(component, &edges, &failing);
+        for root in &roots {
+            affected.entry(root.to_string()).or_default().push(component.to_string());
+        }
+        if !roots.is_empty() {
+            caused_by.insert(component.to_string(), roots.into_iter().map(str::to_string).collect());
+        }
+    }
+
+    let mut lists = [
+        std::mem::take(&mut report.critical_issues),
+        std::mem::take(&mut report.warnings),
+        std::mem::take(&mut report.notices),
+    ];
+    for issue in lists.iter_mut().flatten() {
+        if let Some(roots) = component_of(issue, report).iter().find_map(|c| caused_by.get(c)) {
+            issue.caused_by = roots.clone();
+        }
+    }
+    [report.critical_issues, report.warnings, report.notices] = lists;
+
+    report.impacts = affected.into_iter().map(|(root, affected)| Impact { root, affected }).collect();
+}
@@ -0,0 +1,73 @@
+use crate::models::{Filesystem, Severity, Swap};
+use serde::{Deserialize, Serialize};
+
+/// Thresholds for filesystem space, inodes and swap. Small VMs tend to run
+/// out of inodes (mail queues, caches) or swap themselves to a crawl long
+/// before disk space looks alarming.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiskConfig {
+    pub warning_percent: u8,
+    pub critical_percent: u8,
+    pub inode_warning_percent: u8,
+    pub inode_critical_percent: u8,
+    /// Mountpoints never flagged, e.g. a backup disk meant to fill up.
+    pub ignore_mounts: Vec<String>,
+    pub swap_warning_percent: u8,
+    /// Pages swapped in and out per second that count as thrashing.
+    pub swap_io_warning_pages: u64,
+}
+
+impl Default for DiskConfig {
+    fn default() -> Self {
+        Self {
+            warning_percent: 85,
+            critical_percent: 95,
+            inode_warning_percent: 85,
+            inode_critical_percent: 95,
+            ignore_mounts: Vec::new(),
+            swap_warning_percent: 80,
+            swap_io_warning_pages: 100,
+        }
+    }
+}
+
+impl DiskConfig {
+    /// Filesystems short of space or inodes, as issue messages without the host.
+    pub fn filesystem_issues(&self, filesystems: &[Filesystem]) -> Vec<(Severity, String)> {
+        let level = |used: u8, warning: u8, critical: u8| {
+            if used >= critical {
+                Some(Severity::Critical)
+            } else if used >= warning {
+                Some(Severity::Warning)
+            } else {
+                None
+            }
+        };
+
+        let mut issues = Vec::new();
+        for fs in filesystems.iter().filter(|fs| !self.ignore_mounts.contains(&fs.mountpoint)) {
+            if let Some(severity) = level(fs.used_percent, self.warning_percent, self.critical_percent) {
+                issues.push((severity, format!("{} is {}% full ({})", fs.mountpoint, fs.used_percent, fs.device)));
+            }
+            if let Some(inodes) = fs.inodes_used_percent {
+                if let Some(severity) = level(inodes, self.inode_warning_percent, self.inode_critical_percent) {
+                    issues.push((severity, format!("{} has used {}% of its inodes ({})", fs.mountpoint, inodes, fs.device)));
+                }
+            }
+        }
+        issues
+    }
+
+    /// Swap nearly full or in heavy use, as issue messages without the host.
+    pub fn swap_issues(&self, swap: &Swap) -> Vec<String> {
+        let mut issues = Vec::new();
+        if let Some(used) = swap.used_percent().filter(|used| *used >= self.swap_warning_percent) {
+            issues.push(format!("swap {}% used", used));
+        }
+        if let Some(pages) = swap.pages_per_sec.filter(|pages| *pages >= self.swap_io_warning_pages) {
+            issues.push(format!("swapping actively ({} pages/s)", pages));
+        }
+        issues
+    }
+}
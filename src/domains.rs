@@ -0,0 +1,144 @@
+use crate::models::{DomainRegistration, Severity};
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::future::join_all;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::process::Command;
+
+/// Registered domains whose expiry is tracked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DomainConfig {
+    pub names: Vec<String>,
+    pub warning_days: i64,
+    /// RDAP endpoint the domain name is appended to; rdap.org redirects to
+    /// the registry serving the TLD.
+    pub rdap_url: String,
+}
+
+impl Default for DomainConfig {
+    fn default() -> Self {
+        Self {
+            names: vec!["secure-penguin.com".to_string()],
+            warning_days: 30,
+            rdap_url: "https://rdap.org/domain/".to_string(),
+        }
+    }
+}
+
+impl DomainConfig {
+    /// Looks up the registration of every configured domain.
+    pub async fn registrations(&self) -> Vec<DomainRegistration> {
+        let client = Client::builder().timeout(Duration::from_secs(15)).build().unwrap_or_default();
+        join_all(self.names.iter().map(|domain| self.registration(&client, domain))).await
+    }
+
+    /// Asks RDAP first and falls back to `whois`, since some ccTLD registries
+    /// have no RDAP service.
+    async fn registration(&self, client: &Client, domain: &str) -> DomainRegistration {
+        let mut registration = DomainRegistration {
+            domain: domain.to_string(),
+            expires: None,
+            registrar: None,
+            error: None,
+        };
+
+        match self.rdap(client, domain).await {
+            Ok((expires, registrar)) if expires.is_some() => {
+                registration.expires = expires;
+                registration.registrar = registrar;
+            }
+            rdap => match whois_expiry(domain).await {
+                Ok(expires) => registration.expires = Some(expires),
+                Err(e) => {
+                    registration.error = Some(match rdap {
+                        Err(rdap) => format!("{:#}; {:#}", rdap, e),
+                        Ok(_) => format!("RDAP response has no expiration; {:#}", e),
+                    })
+                }
+            },
+        }
+        registration
+    }
+
+    async fn rdap(&self, client: &Client, domain: &str) -> Result<(Option<DateTime<Utc>>, Option<String>)> {
+        let response: serde_json::Value = client
+            .get(format!("{}{}", self.rdap_url, domain))
+            .header("Accept", "application/rdap+json")
+            .send()
+            .await
+            .context("RDAP lookup failed")?
+            .error_for_status()
+            .context("RDAP lookup failed")?
+            .json()
+            .await
+            .context("Failed to parse RDAP response")?;
+
+        let expires = response
+            .get("events")
+            .and_then(|e| e.as_array())
+            .into_iter()
+            .flatten()
+            .find(|event| event.get("eventAction").and_then(|a| a.as_str()) == Some("expiration"))
+            .and_then(|event| event.get("eventDate")?.as_str()?.parse().ok());
+
+        // The registrar's name sits in the vCard of its entity
+        let registrar = response
+            .get("entities")
+            .and_then(|e| e.as_array())
+            .into_iter()
+            .flatten()
+            .find(|entity| {
+                entity.get("roles").and_then(|r| r.as_array()).is_some_and(|roles| roles.iter().any(|r| r == "registrar"))
+            })
+            .and_then(|entity| {
+                entity
+                    .pointer("/vcardArray/1")?
+                    .as_array()?
+                    .iter()
+                    .find(|field| field.get(0).and_then(|f| f.as_str()) == Some("fn"))?
+                    .get(3)?
+                    .as_str()
+                    .map(|name| name.to_string())
+            });
+
+        Ok((expires, registrar))
+    }
+
+    /// Expired or soon to expire registrations, as issue messages.
+    pub fn issue(&self, registration: &DomainRegistration) -> Option<(Severity, String)> {
+        let expires = registration.expires?;
+        let days_left = (expires - Utc::now()).num_days();
+        if expires < Utc::now() {
+            Some((Severity::Critical, format!("{} registration expired on {}", registration.domain, expires.format("%Y-%m-%d"))))
+        } else if days_left <= self.warning_days {
+            Some((
+                Severity::Warning,
+                format!("{} registration expires on {} ({} days left)", registration.domain, expires.format("%Y-%m-%d"), days_left),
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// Reads the expiry date out of `whois`, whose field name varies by registry.
+async fn whois_expiry(domain: &str) -> Result<DateTime<Utc>> {
+    let output = Command::new("whois").arg(domain).output().await.context("whois not available")?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.trim().split_once(':'))
+        .filter(|(field, _)| {
+            let field = field.to_lowercase();
+            field.contains("expir") || field == "paid-till"
+        })
+        .find_map(|(_, value)| {
+            // 2026-03-14T04:00:00Z, 2026-03-14 00:00:00 CLST or 2026.03.14
+            let date = value.trim().get(..10)?.replace('.', "-");
+            NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok()
+        })
+        .map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+        .context("whois shows no expiry date")
+}
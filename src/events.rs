@@ -0,0 +1,100 @@
+use crate::feed::Feed;
+use anyhow::{Context, Result};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::http::header;
+use axum::routing::get;
+use axum::Router;
+use futures::stream::{self, Stream};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{info, warn};
+
+/// `[events]` section: serve live scan progress as server-sent events, and
+/// recent scans as an Atom feed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventsConfig {
+    pub enabled: bool,
+    pub listen: String,
+    /// Entries kept in the feed, scan summaries and new issues alike.
+    pub feed_entries: usize,
+}
+
+impl Default for EventsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen: "127.0.0.1:8089".to_string(),
+            feed_entries: 50,
+        }
+    }
+}
+
+/// Progress of a scan as it happens, for dashboards that shouldn't wait for
+/// the final report.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ScanEvent {
+    ScanStarted { hosts: usize },
+    Connecting { host: String },
+    Collected { host: String, services: usize, containers: usize },
+    Finished { host: String, reachable: bool, failed_checks: usize },
+    Failed { host: String, error: String },
+    ScanFinished { partial: bool, critical_issues: usize, warnings: usize },
+}
+
+impl ScanEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            ScanEvent::ScanStarted { .. } => "scan_started",
+            ScanEvent::Connecting { .. } => "connecting",
+            ScanEvent::Collected { .. } => "collected",
+            ScanEvent::Finished { .. } => "finished",
+            ScanEvent::Failed { .. } => "failed",
+            ScanEvent::ScanFinished { .. } => "scan_finished",
+        }
+    }
+}
+
+/// Serves `GET /events` and `GET /feed.atom` until the process exits. Each
+/// event subscriber gets the events emitted after it connected.
+pub async fn serve(config: EventsConfig, events: broadcast::Sender<ScanEvent>, feed: Arc<Feed>) -> Result<()> {
+    let app = Router::new()
+        .route(
+            "/events",
+            get(move || {
+                let receiver = events.subscribe();
+                async move { Sse::new(event_stream(receiver)).keep_alive(KeepAlive::default()) }
+            }),
+        )
+        .route(
+            "/feed.atom",
+            get(move || async move { ([(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")], feed.atom()) }),
+        );
+
+    let listener = tokio::net::TcpListener::bind(&config.listen)
+        .await
+        .context(format!("Failed to listen on {}", config.listen))?;
+    info!("Streaming scan events on http://{0}/events, feed on http://{0}/feed.atom", config.listen);
+    axum::serve(listener, app).await.context("Event server failed")
+}
+
+fn event_stream(receiver: broadcast::Receiver<ScanEvent>) -> impl Stream<Item = Result<Event, Infallible>> {
+    stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    let sse = Event::default()
+                        .event(event.name())
+                        .json_data(&event)
+                        .unwrap_or_else(|_| Event::default().comment("unserializable event"));
+                    return Some((Ok(sse), receiver));
+                }
+                Err(RecvError::Lagged(missed)) => warn!("Event subscriber lagging, dropped {} events", missed),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+}
@@ -0,0 +1,112 @@
+use crate::models::{InventoryReport, Issue};
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::collections::{BTreeSet, VecDeque};
+use std::sync::Mutex;
+
+/// Tag URI authority the feed and entry IDs live under.
+const TAG: &str = "tag:secure-penguin.com,2024";
+
+struct Entry {
+    id: String,
+    title: String,
+    updated: DateTime<Utc>,
+    /// One line per list item.
+    lines: Vec<String>,
+}
+
+/// Recent scans and the issues they raised, served as an Atom feed. Each
+/// scan adds a summary entry plus one entry per issue the previous scan
+/// didn't have.
+pub struct Feed {
+    entries: Mutex<VecDeque<Entry>>,
+    max_entries: usize,
+}
+
+impl Feed {
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::new()),
+            max_entries,
+        }
+    }
+
+    pub fn record(&self, report: &InventoryReport, previous: Option<&InventoryReport>) {
+        let before: Option<BTreeSet<String>> =
+            previous.map(|p| p.critical_issues.iter().chain(&p.warnings).map(Issue::key).collect());
+        let now: BTreeSet<String> = report.critical_issues.iter().chain(&report.warnings).map(Issue::key).collect();
+        let is_new = |issue: &&Issue| before.as_ref().is_none_or(|before| !before.contains(&issue.key()));
+        let new_critical: Vec<&Issue> = report.critical_issues.iter().filter(is_new).collect();
+        let new_warnings: Vec<&Issue> = report.warnings.iter().filter(is_new).collect();
+        let resolved: Vec<&Issue> = previous
+            .into_iter()
+            .flat_map(|p| p.critical_issues.iter().chain(&p.warnings))
+            .filter(|issue| !now.contains(&issue.key()))
+            .collect();
+
+        let stamp = report.timestamp.timestamp();
+        let mut added = Vec::new();
+        let mut lines = vec![format!("{}/{} VMs accesibles", report.summary.reachable_vms, report.summary.total_vms)];
+        lines.extend(new_critical.iter().map(|i| format!("❌ Nuevo: {}", i)));
+        lines.extend(new_warnings.iter().map(|i| format!("⚠️ Nuevo: {}", i)));
+        lines.extend(resolved.iter().map(|i| format!("✅ Resuelto: {}", i)));
+        added.push(Entry {
+            id: format!("{}:scan/{}", TAG, stamp),
+            title: format!(
+                "Escaneo{}: {} críticos, {} warnings",
+                if report.partial { " parcial" } else { "" },
+                report.critical_issues.len(),
+                report.warnings.len()
+            ),
+            updated: report.timestamp,
+            lines,
+        });
+
+        let new_issues = new_critical.iter().map(|i| ("❌", i)).chain(new_warnings.iter().map(|i| ("⚠️", i)));
+        for (n, (icon, issue)) in new_issues.enumerate() {
+            added.push(Entry {
+                id: format!("{}:scan/{}/issue/{}", TAG, stamp, n + 1),
+                title: format!("{} {}", icon, issue),
+                updated: report.timestamp,
+                lines: vec![
+                    format!("Host: {}", issue.host.as_deref().unwrap_or("-")),
+                    format!("Check: {}", issue.check),
+                ],
+            });
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        // Newest first, the summary ahead of its issues
+        for entry in added.into_iter().rev() {
+            entries.push_front(entry);
+        }
+        entries.truncate(self.max_entries);
+    }
+
+    pub fn atom(&self) -> String {
+        let entries = self.entries.lock().unwrap();
+        let time = |t: &DateTime<Utc>| t.to_rfc3339_opts(SecondsFormat::Secs, true);
+        let updated = entries.front().map(|e| e.updated).unwrap_or_else(Utc::now);
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+        xml.push_str(&format!("  <id>{}:feed</id>\n", TAG));
+        xml.push_str("  <title>SecurePenguin Inventory</title>\n");
+        xml.push_str("  <author><name>sp-inventory</name></author>\n");
+        xml.push_str(&format!("  <updated>{}</updated>\n", time(&updated)));
+        for entry in entries.iter() {
+            let items: Vec<String> = entry.lines.iter().map(|line| format!("<li>{}</li>", escape(line))).collect();
+            xml.push_str("  <entry>\n");
+            xml.push_str(&format!("    <id>{}</id>\n", escape(&entry.id)));
+            xml.push_str(&format!("    <title>{}</title>\n", escape(&entry.title)));
+            xml.push_str(&format!("    <updated>{}</updated>\n", time(&entry.updated)));
+            // The HTML is escaped once more to travel as text inside the XML
+            xml.push_str(&format!("    <content type=\"html\">{}</content>\n", escape(&format!("<ul>{}</ul>", items.concat()))));
+            xml.push_str("  </entry>\n");
+        }
+        xml.push_str("</feed>\n");
+        xml
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -0,0 +1,67 @@
+use crate::models::{SpecialFile, SpecialFileKind, VmHost};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Deep audit for SUID/SGID binaries and world-writable files, reporting the
+/// ones that appeared since the previous scan. Walking the filesystem is
+/// slow, so it is off by default and only part of the full profile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FileAuditConfig {
+    pub enabled: bool,
+    /// Directories searched for SUID/SGID binaries (one filesystem each).
+    pub setuid_paths: Vec<String>,
+    /// Sensitive directories where nothing should be world-writable.
+    pub writable_paths: Vec<String>,
+    /// Files found by the previous scan, per host, in the state directory.
+    pub state_file: String,
+    /// Host names, groups and tags to audit; all empty means every host.
+    pub hosts: Vec<String>,
+    pub groups: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+impl Default for FileAuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            setuid_paths: ["/usr", "/bin", "/sbin", "/opt", "/home", "/var", "/tmp"]
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            writable_paths: ["/etc", "/usr", "/bin", "/sbin", "/opt", "/root", "/var/www"]
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            state_file: "file_audit.json".to_string(),
+            hosts: Vec::new(),
+            groups: Vec::new(),
+            tags: Vec::new(),
+        }
+    }
+}
+
+impl FileAuditConfig {
+    pub fn applies_to(&self, host: &VmHost) -> bool {
+        self.enabled && host.in_scope(&self.hosts, &self.groups, &self.tags)
+    }
+}
+
+/// Files found by the previous scan, by host name, as [`key`]s.
+pub type AuditState = BTreeMap<String, BTreeSet<String>>;
+
+/// Identifies a file by kind and path, so a binary that gains SUID shows up
+/// as new even if it was world-writable before.
+pub fn key(file: &SpecialFile) -> String {
+    format!("{:?} {}", file.kind, file.path)
+}
+
+/// What a new special file means, as an issue message without the host.
+pub fn describe(file: &SpecialFile) -> String {
+    let kind = match file.kind {
+        SpecialFileKind::Setuid => "new SUID binary",
+        SpecialFileKind::Setgid => "new SGID binary",
+        SpecialFileKind::WorldWritable => "new world-writable file",
+    };
+    format!("{} {} (mode {}, owner {})", kind, file.path, file.mode, file.owner)
+}
@@ -0,0 +1,84 @@
+use crate::i18n::Lang;
+use crate::models::{InventoryReport, VmStatus};
+use crate::reporter::{HostReportFormat, MarkdownReporter, ReportConfig};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+use tracing::info;
+
+/// Entry of `index.json`, pointing at each VM's JSON file.
+#[derive(Serialize)]
+struct IndexEntry<'a> {
+    name: &'a str,
+    ip: &'a str,
+    reachable: bool,
+    file: String,
+}
+
+/// Writes one file per VM (e.g. `reports/kingu.md`) plus an index in each
+/// requested format. Does nothing unless `per_host_dir` is set.
+pub fn save(report: &InventoryReport, config: &ReportConfig, lang: Lang) -> Result<()> {
+    let Some(ref dir) = config.per_host_dir else {
+        return Ok(());
+    };
+    let dir = shellexpand::tilde(dir).to_string();
+    let dir = Path::new(&dir);
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create per-host report directory: {}", dir.display()))?;
+
+    let formats = if config.per_host_formats.is_empty() {
+        &[HostReportFormat::Markdown][..]
+    } else {
+        &config.per_host_formats[..]
+    };
+
+    for format in formats {
+        let extension = match format {
+            HostReportFormat::Markdown => "md",
+            HostReportFormat::Json => "json",
+        };
+        let file_name = |vm: &VmStatus| format!("{}.{}", file_stem(&vm.host.name), extension);
+
+        for vm in &report.vms {
+            let contents = match format {
+                HostReportFormat::Markdown => MarkdownReporter::host_page(vm, report, lang),
+                HostReportFormat::Json => serde_json::to_string_pretty(vm)?,
+            };
+            write(&dir.join(file_name(vm)), &contents)?;
+        }
+
+        let index = match format {
+            HostReportFormat::Markdown => MarkdownReporter::host_index(report, lang, file_name),
+            HostReportFormat::Json => {
+                let entries: Vec<IndexEntry> = report
+                    .vms
+                    .iter()
+                    .map(|vm| IndexEntry {
+                        name: &vm.host.name,
+                        ip: &vm.host.ip,
+                        reachable: vm.reachable,
+                        file: file_name(vm),
+                    })
+                    .collect();
+                serde_json::to_string_pretty(&entries)?
+            }
+        };
+        write(&dir.join(format!("index.{}", extension)), &index)?;
+    }
+
+    info!("Reportes por host guardados en: {}", dir.display());
+    Ok(())
+}
+
+fn write(path: &Path, contents: &str) -> Result<()> {
+    std::fs::write(path, contents).with_context(|| format!("Failed to write report file: {}", path.display()))
+}
+
+/// Host names come from the SSH config, so keep them from escaping the directory.
+fn file_stem(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect::<String>()
+        .trim_start_matches('.')
+        .to_string()
+}
@@ -0,0 +1,511 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// Language the markdown report is written in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Lang {
+    #[default]
+    Es,
+    En,
+}
+
+impl Lang {
+    pub fn strings(self) -> &'static Strings {
+        match self {
+            Lang::Es => &ES,
+            Lang::En => &EN,
+        }
+    }
+}
+
+/// Every piece of fixed text the markdown report uses. Values coming from the
+/// scan itself (issue messages, service names, errors) are not translated.
+pub struct Strings {
+    pub title: &'static str,
+    pub date: &'static str,
+    pub time: &'static str,
+    pub partial_report: &'static str,
+    pub profile: &'static str,
+    pub executive_summary: &'static str,
+    pub vms_audited: &'static str,
+    pub vms_reachable: &'static str,
+    pub services_total: &'static str,
+    pub services_running: &'static str,
+    pub services_failing: &'static str,
+    pub containers_total: &'static str,
+    pub containers_running: &'static str,
+    pub status_per_vm: &'static str,
+    pub status: &'static str,
+    pub role: &'static str,
+    pub tags: &'static str,
+    pub ungrouped: &'static str,
+    pub operational: &'static str,
+    pub unreachable: &'static str,
+    pub incomplete_checks: &'static str,
+    pub received: &'static str,
+    pub loss: &'static str,
+    pub avg_rtt: &'static str,
+    pub network_paths: &'static str,
+    pub public_ip: &'static str,
+    pub exposed_ports: &'static str,
+    pub provider: &'static str,
+    pub alternate_ips: &'static str,
+    pub expected_reachability: &'static str,
+    pub not_reachable_from_scanner: &'static str,
+    pub connected_via: &'static str,
+    pub services: &'static str,
+    pub none_detected: &'static str,
+    pub ports: &'static str,
+    pub containers: &'static str,
+    pub interface: &'static str,
+    pub public_key: &'static str,
+    pub listening_port: &'static str,
+    pub connected_peers: &'static str,
+    pub storage: &'static str,
+    pub affected: &'static str,
+    pub certificates: &'static str,
+    pub no_auto_renew: &'static str,
+    pub expires: &'static str,
+    pub unknown: &'static str,
+    pub services_lower: &'static str,
+    pub backends_up: &'static str,
+    pub databases: &'static str,
+    pub no_connection: &'static str,
+    pub connections: &'static str,
+    pub oldest_transaction: &'static str,
+    pub replica: &'static str,
+    pub lag: &'static str,
+    pub stopped: &'static str,
+    pub data_stores: &'static str,
+    pub no_response: &'static str,
+    pub memory: &'static str,
+    pub keys: &'static str,
+    pub replication: &'static str,
+    pub configured_interfaces: &'static str,
+    pub all: &'static str,
+    pub listening_on: &'static str,
+    pub unbound_interfaces: &'static str,
+    pub anonymous_listing: &'static str,
+    pub shares_visible: &'static str,
+    pub recent_logs: &'static str,
+    pub kernel_events: &'static str,
+    pub external_web_services: &'static str,
+    pub topology: &'static str,
+    pub bandwidth: &'static str,
+    pub bandwidth_corner: &'static str,
+    pub web_services_columns: [&'static str; 7],
+    pub content: &'static str,
+    pub redirect_chains: &'static str,
+    pub dns_records: &'static str,
+    pub tls_protocols: &'static str,
+    pub scenarios: &'static str,
+    pub health_endpoints: &'static str,
+    pub health_field_parts: [&'static str; 2],
+    pub accepts: &'static str,
+    pub proxmox_node_columns: [&'static str; 4],
+    pub proxmox_guest_columns: [&'static str; 9],
+    pub cores_of: &'static str,
+    pub cores: &'static str,
+    pub terraform: &'static str,
+    pub terraform_columns: [&'static str; 5],
+    pub responds: &'static str,
+    pub not_responding: &'static str,
+    pub not_in_inventory: &'static str,
+    pub unmanaged_hosts: &'static str,
+    pub cloud_instances: &'static str,
+    pub domains: &'static str,
+    pub domain_columns: [&'static str; 4],
+    pub ct_certificates: &'static str,
+    pub ct_columns: [&'static str; 4],
+    pub cloud_columns: [&'static str; 8],
+    pub not_inventoried: &'static str,
+    pub critical_issues: &'static str,
+    pub no_critical_issues: &'static str,
+    pub warnings: &'static str,
+    pub no_warnings: &'static str,
+    pub notices: &'static str,
+    pub impact_analysis: &'static str,
+    pub caused_by: &'static str,
+    pub generated_by: &'static str,
+    pub checks: &'static str,
+    pub host_index: &'static str,
+    pub host_index_columns: [&'static str; 4],
+    pub load: &'static str,
+    pub memory_usage: &'static str,
+    pub os: &'static str,
+    pub hardware: &'static str,
+    pub disks: &'static str,
+    pub bare_metal: &'static str,
+    pub gpus: &'static str,
+    pub gpu_columns: [&'static str; 4],
+    pub end_of_life: &'static str,
+    pub blacklisted: &'static str,
+    pub uptime: &'static str,
+    pub filesystems: &'static str,
+    pub filesystem_columns: [&'static str; 5],
+    pub swap: &'static str,
+    pub temperatures: &'static str,
+    /// Throttling now, since boot and CPU throttle events.
+    pub throttling: [&'static str; 3],
+    pub policy_violations: &'static str,
+    pub policy_columns: [&'static str; 5],
+    pub custom_checks: &'static str,
+    pub archived_files: &'static str,
+    pub scheduled_jobs: &'static str,
+    pub container_storage: &'static str,
+    /// Images, unused, volumes, orphan and reclaimable.
+    pub container_storage_parts: [&'static str; 5],
+    pub listeners: &'static str,
+    /// Loopback, VPN, LAN and world-exposed listeners.
+    pub listener_classes: [&'static str; 4],
+    pub suppressions: &'static str,
+    pub maintenance: &'static str,
+    pub suppression_columns: [&'static str; 4],
+    pub timings: &'static str,
+    pub scan_duration: &'static str,
+    pub timing_host_columns: [&'static str; 3],
+    pub timing_check_columns: [&'static str; 4],
+}
+
+const ES: Strings = Strings {
+    title: "INVENTARIO STATUS SECUREPENGUIN",
+    date: "Fecha",
+    time: "Hora",
+    partial_report: "**REPORTE PARCIAL:** el escaneo fue interrumpido, faltan hosts o chequeos",
+    profile: "Perfil de escaneo",
+    executive_summary: "RESUMEN EJECUTIVO",
+    vms_audited: "VMs auditadas",
+    vms_reachable: "VMs accesibles",
+    services_total: "Servicios totales",
+    services_running: "Servicios corriendo",
+    services_failing: "Servicios con problemas",
+    containers_total: "Contenedores totales",
+    containers_running: "Contenedores activos",
+    status_per_vm: "ESTADO POR VM",
+    status: "Estado",
+    role: "Rol",
+    tags: "Etiquetas",
+    ungrouped: "Sin grupo",
+    operational: "Operativa",
+    unreachable: "Inaccesible",
+    incomplete_checks: "Chequeos incompletos",
+    received: "recibidos",
+    loss: "pérdida",
+    avg_rtt: "rtt promedio",
+    network_paths: "Rutas de red",
+    public_ip: "IP pública",
+    exposed_ports: "Puertos expuestos (desde el escáner)",
+    provider: "Proveedor",
+    alternate_ips: "IPs alternativas",
+    expected_reachability: "Accesibilidad esperada",
+    not_reachable_from_scanner: "no accesible desde el escáner",
+    connected_via: "Conectado vía",
+    services: "Servicios",
+    none_detected: "Ninguno detectado",
+    ports: "puertos",
+    containers: "Contenedores",
+    interface: "Interfaz",
+    public_key: "Clave pública",
+    listening_port: "Puerto de escucha",
+    connected_peers: "Peers conectados",
+    storage: "Almacenamiento (RAID/ZFS)",
+    affected: "afectados",
+    certificates: "Certificados",
+    no_auto_renew: "sin renovación automática",
+    expires: "expira",
+    unknown: "desconocido",
+    services_lower: "servicios",
+    backends_up: "backends activos",
+    databases: "Bases de datos",
+    no_connection: "sin conexión",
+    connections: "conexiones",
+    oldest_transaction: "transacción más antigua",
+    replica: "réplica",
+    lag: "retraso",
+    stopped: "detenida",
+    data_stores: "Almacenes de datos",
+    no_response: "sin respuesta",
+    memory: "memoria",
+    keys: "claves",
+    replication: "replicación",
+    configured_interfaces: "Interfaces configuradas",
+    all: "todas",
+    listening_on: "escuchando en",
+    unbound_interfaces: "Interfaces sin bind",
+    anonymous_listing: "smbclient -L anónimo",
+    shares_visible: "shares visibles",
+    recent_logs: "Logs recientes",
+    kernel_events: "Eventos del kernel",
+    external_web_services: "SERVICIOS WEB EXTERNOS",
+    topology: "TOPOLOGÍA DE RED",
+    bandwidth: "ANCHO DE BANDA ENTRE HOSTS (Mbit/s)",
+    bandwidth_corner: "Origen → Destino",
+    web_services_columns: [
+        "Servicio",
+        "URL",
+        "HTTP Status",
+        "Latencia min/med/p95",
+        "Errores",
+        "HTTPS forzado",
+        "Backend",
+    ],
+    content: "contenido",
+    redirect_chains: "Cadenas de redirección",
+    dns_records: "Registros DNS",
+    tls_protocols: "Protocolos TLS",
+    scenarios: "Escenarios",
+    health_endpoints: "Endpoints de salud",
+    health_field_parts: ["esperado", "ausente"],
+    accepts: "acepta",
+    proxmox_node_columns: ["Nodo", "Estado", "CPU", "Memoria"],
+    proxmox_guest_columns: [
+        "VMID",
+        "Nombre",
+        "Tipo",
+        "Nodo",
+        "Estado",
+        "vCPU",
+        "Memoria",
+        "Disco",
+        "Inventario",
+    ],
+    cores_of: "de",
+    cores: "cores",
+    terraform: "TERRAFORM (ESPERADO VS REAL)",
+    terraform_columns: ["Recurso", "Nombre", "IPs", "Host", "Estado"],
+    responds: "responde",
+    not_responding: "no responde",
+    not_in_inventory: "fuera del inventario",
+    unmanaged_hosts: "Hosts no gestionados por Terraform",
+    cloud_instances: "INSTANCIAS CLOUD",
+    domains: "DOMINIOS",
+    domain_columns: ["Dominio", "Registrador", "Vence", "Días restantes"],
+    ct_certificates: "CERTIFICADOS INESPERADOS (CERTIFICATE TRANSPARENCY)",
+    ct_columns: ["crt.sh", "Nombres", "Emisor", "Validez"],
+    cloud_columns: [
+        "Proveedor",
+        "Instancia",
+        "Ubicación",
+        "Tipo",
+        "Estado",
+        "IPs públicas",
+        "IPs privadas",
+        "Inventario",
+    ],
+    not_inventoried: "no inventariada",
+    critical_issues: "ISSUES CRÍTICOS",
+    no_critical_issues: "No se encontraron issues críticos",
+    warnings: "ADVERTENCIAS",
+    no_warnings: "Sin advertencias",
+    notices: "NOTAS",
+    impact_analysis: "ANÁLISIS DE IMPACTO",
+    caused_by: "consecuencia de",
+    generated_by: "Generado por",
+    checks: "Chequeos",
+    host_index: "ÍNDICE DE HOSTS",
+    host_index_columns: ["Host", "IP", "Estado", "Chequeos con problemas"],
+    load: "Carga (1/5/15 min)",
+    memory_usage: "Memoria en uso",
+    os: "Sistema operativo",
+    hardware: "Hardware",
+    disks: "Discos",
+    bare_metal: "físico",
+    gpus: "GPUs",
+    gpu_columns: ["Modelo", "VRAM", "Uso", "Temperatura"],
+    end_of_life: "fin de soporte",
+    blacklisted: "En listas negras",
+    uptime: "Encendido desde",
+    filesystems: "Sistemas de archivos",
+    filesystem_columns: ["Montaje", "Dispositivo", "Tamaño", "Uso", "Inodos"],
+    swap: "Swap",
+    temperatures: "Temperaturas",
+    throttling: ["throttling ahora", "desde el arranque", "eventos de throttling de CPU"],
+    policy_violations: "VIOLACIONES DE POLÍTICA",
+    policy_columns: ["Regla", "Severidad", "Host", "Descripción", "Detalle"],
+    custom_checks: "Chequeos personalizados",
+    archived_files: "Configuración archivada",
+    scheduled_jobs: "Tareas programadas",
+    container_storage: "Imágenes y volúmenes",
+    container_storage_parts: ["imágenes", "sin usar", "volúmenes", "huérfanos", "recuperables"],
+    listeners: "Sockets en escucha",
+    listener_classes: ["loopback", "VPN", "LAN", "expuestos"],
+    suppressions: "SUPRESIONES ACTIVAS",
+    maintenance: "En mantenimiento",
+    suppression_columns: ["Criterio", "Motivo", "Expira", "Issues suprimidos"],
+    timings: "APÉNDICE: TIEMPOS DE ESCANEO",
+    scan_duration: "Duración total",
+    timing_host_columns: ["Host", "Duración (s)", "Chequeos más lentos"],
+    timing_check_columns: ["Chequeo", "Total (s)", "Máx. (s)", "Host más lento"],
+};
+
+const EN: Strings = Strings {
+    title: "SECUREPENGUIN INVENTORY STATUS",
+    date: "Date",
+    time: "Time",
+    partial_report: "**PARTIAL REPORT:** the scan was interrupted, hosts or checks are missing",
+    profile: "Scan profile",
+    executive_summary: "EXECUTIVE SUMMARY",
+    vms_audited: "VMs audited",
+    vms_reachable: "VMs reachable",
+    services_total: "Total services",
+    services_running: "Running services",
+    services_failing: "Services with problems",
+    containers_total: "Total containers",
+    containers_running: "Running containers",
+    status_per_vm: "STATUS PER VM",
+    status: "Status",
+    role: "Role",
+    tags: "Tags",
+    ungrouped: "Ungrouped",
+    operational: "Operational",
+    unreachable: "Unreachable",
+    incomplete_checks: "Incomplete checks",
+    received: "received",
+    loss: "loss",
+    avg_rtt: "avg rtt",
+    network_paths: "Network paths",
+    public_ip: "Public IP",
+    exposed_ports: "Exposed ports (from the scanner)",
+    provider: "Provider",
+    alternate_ips: "Alternate IPs",
+    expected_reachability: "Expected reachability",
+    not_reachable_from_scanner: "not reachable from the scanner",
+    connected_via: "Connected via",
+    services: "Services",
+    none_detected: "None detected",
+    ports: "ports",
+    containers: "Containers",
+    interface: "Interface",
+    public_key: "Public key",
+    listening_port: "Listening port",
+    connected_peers: "Connected peers",
+    storage: "Storage (RAID/ZFS)",
+    affected: "affected",
+    certificates: "Certificates",
+    no_auto_renew: "no automatic renewal",
+    expires: "expires",
+    unknown: "unknown",
+    services_lower: "services",
+    backends_up: "backends up",
+    databases: "Databases",
+    no_connection: "no connection",
+    connections: "connections",
+    oldest_transaction: "oldest transaction",
+    replica: "replica",
+    lag: "lag",
+    stopped: "stopped",
+    data_stores: "Data stores",
+    no_response: "no response",
+    memory: "memory",
+    keys: "keys",
+    replication: "replication",
+    configured_interfaces: "Configured interfaces",
+    all: "all",
+    listening_on: "listening on",
+    unbound_interfaces: "Unbound interfaces",
+    anonymous_listing: "anonymous smbclient -L",
+    shares_visible: "shares visible",
+    recent_logs: "Recent logs",
+    kernel_events: "Kernel events",
+    external_web_services: "EXTERNAL WEB SERVICES",
+    topology: "NETWORK TOPOLOGY",
+    bandwidth: "BANDWIDTH BETWEEN HOSTS (Mbit/s)",
+    bandwidth_corner: "From → To",
+    web_services_columns: [
+        "Service",
+        "URL",
+        "HTTP Status",
+        "Latency min/med/p95",
+        "Errors",
+        "HTTPS enforced",
+        "Backend",
+    ],
+    content: "content",
+    redirect_chains: "Redirect chains",
+    dns_records: "DNS records",
+    tls_protocols: "TLS protocols",
+    scenarios: "Scenarios",
+    health_endpoints: "Health endpoints",
+    health_field_parts: ["expected", "missing"],
+    accepts: "accepts",
+    proxmox_node_columns: ["Node", "Status", "CPU", "Memory"],
+    proxmox_guest_columns: [
+        "VMID",
+        "Name",
+        "Type",
+        "Node",
+        "Status",
+        "vCPU",
+        "Memory",
+        "Disk",
+        "Inventory",
+    ],
+    cores_of: "of",
+    cores: "cores",
+    terraform: "TERRAFORM (EXPECTED VS ACTUAL)",
+    terraform_columns: ["Resource", "Name", "IPs", "Host", "Status"],
+    responds: "responds",
+    not_responding: "not responding",
+    not_in_inventory: "not in inventory",
+    unmanaged_hosts: "Hosts not managed by Terraform",
+    cloud_instances: "CLOUD INSTANCES",
+    domains: "DOMAINS",
+    domain_columns: ["Domain", "Registrar", "Expires", "Days left"],
+    ct_certificates: "UNEXPECTED CERTIFICATES (CERTIFICATE TRANSPARENCY)",
+    ct_columns: ["crt.sh", "Names", "Issuer", "Validity"],
+    cloud_columns: [
+        "Provider",
+        "Instance",
+        "Location",
+        "Type",
+        "Status",
+        "Public IPs",
+        "Private IPs",
+        "Inventory",
+    ],
+    not_inventoried: "not inventoried",
+    critical_issues: "CRITICAL ISSUES",
+    no_critical_issues: "No critical issues found",
+    warnings: "WARNINGS",
+    no_warnings: "No warnings",
+    notices: "NOTES",
+    impact_analysis: "IMPACT ANALYSIS",
+    caused_by: "caused by",
+    generated_by: "Generated by",
+    checks: "Checks",
+    host_index: "HOST INDEX",
+    host_index_columns: ["Host", "IP", "Status", "Checks with problems"],
+    load: "Load (1/5/15 min)",
+    memory_usage: "Memory in use",
+    os: "Operating system",
+    hardware: "Hardware",
+    disks: "Disks",
+    bare_metal: "bare metal",
+    gpus: "GPUs",
+    gpu_columns: ["Model", "VRAM", "Utilization", "Temperature"],
+    end_of_life: "end of life",
+    blacklisted: "Blacklisted",
+    uptime: "Up since",
+    filesystems: "Filesystems",
+    filesystem_columns: ["Mount", "Device", "Size", "Used", "Inodes"],
+    swap: "Swap",
+    temperatures: "Temperatures",
+    throttling: ["throttling now", "since boot", "CPU throttling events"],
+    policy_violations: "POLICY VIOLATIONS",
+    policy_columns: ["Rule", "Severity", "Host", "Description", "Detail"],
+    custom_checks: "Custom checks",
+    archived_files: "Archived configuration",
+    scheduled_jobs: "Scheduled jobs",
+    container_storage: "Images and volumes",
+    container_storage_parts: ["images", "unused", "volumes", "orphan", "reclaimable"],
+    listeners: "Listening sockets",
+    listener_classes: ["loopback", "VPN", "LAN", "world-exposed"],
+    suppressions: "ACTIVE SUPPRESSIONS",
+    maintenance: "In maintenance",
+    suppression_columns: ["Criteria", "Reason", "Expires", "Suppressed issues"],
+    timings: "APPENDIX: SCAN TIMINGS",
+    scan_duration: "Total duration",
+    timing_host_columns: ["Host", "Duration (s)", "Slowest checks"],
+    timing_check_columns: ["Check", "Total (s)", "Max (s)", "Slowest host"],
+};
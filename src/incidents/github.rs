@@ -0,0 +1,212 @@
+use super::{Incident, IncidentBackend};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GitHubConfig {
+    pub enabled: bool,
+    /// Repository as `owner/name`.
+    pub repo: String,
+    /// Token with write access to the repository's issues.
+    pub token: String,
+    /// `https://<host>/api/v3` for GitHub Enterprise Server.
+    pub api_url: String,
+    /// Added to every issue; the first one also finds the issues we opened.
+    pub labels: Vec<String>,
+}
+
+impl Default for GitHubConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            repo: String::new(),
+            token: String::new(),
+            api_url: "https://api.github.com".to_string(),
+            labels: vec!["sp-inventory".to_string()],
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GitHubIssue {
+    number: u64,
+    #[serde(default)]
+    body: Option<String>,
+    /// Set on pull requests, which the issues API lists too.
+    #[serde(default)]
+    pull_request: Option<serde_json::Value>,
+}
+
+/// Opens a GitHub issue per failing check and closes it once the check
+/// passes. The dedup key sits in an HTML comment of the issue body, so an
+/// issue is found again across scans and runs.
+pub struct GitHubBackend {
+    config: GitHubConfig,
+    client: Client,
+    /// Open issues as (number, body), listed once per sync.
+    open_issues: Mutex<Option<Vec<(u64, String)>>>,
+}
+
+/// URL of the next page from a `Link` response header.
+fn next_page(link: &str) -> Option<String> {
+    link.split(',').find(|part| part.contains("rel=\"next\"")).and_then(|part| {
+        let (_, rest) = part.split_once('<')?;
+        Some(rest.split_once('>')?.0.to_string())
+    })
+}
+
+impl GitHubBackend {
+    pub fn new(config: GitHubConfig) -> Self {
+        Self {
+            config,
+            client: Client::new(),
+            open_issues: Mutex::new(None),
+        }
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> RequestBuilder {
+        let url = format!("{}/repos/{}/issues{}", self.config.api_url.trim_end_matches('/'), self.config.repo, path);
+        self.authorized(self.client.request(method, url))
+    }
+
+    fn authorized(&self, request: RequestBuilder) -> RequestBuilder {
+        request
+            .header("Authorization", format!("Bearer {}", self.config.token))
+            .header("Accept", "application/vnd.github+json")
+            .header("User-Agent", "sp-inventory")
+    }
+
+    fn marker(dedup_key: &str) -> String {
+        format!("<!-- {} -->", dedup_key)
+    }
+
+    /// Every open issue with our label, following the pagination links.
+    /// Pull requests are left out.
+    async fn list_open(&self) -> Result<Vec<(u64, String)>> {
+        let mut query = vec![("state", "open".to_string()), ("per_page", "100".to_string())];
+        if let Some(label) = self.config.labels.first() {
+            query.push(("labels", label.clone()));
+        }
+        let mut request = self.request(reqwest::Method::GET, "").query(&query);
+        let mut open = Vec::new();
+        loop {
+            let response = request
+                .send()
+                .await
+                .context("Failed to reach GitHub")?
+                .error_for_status()
+                .context("GitHub rejected the issue listing")?;
+            let next = response.headers().get("link").and_then(|link| link.to_str().ok()).and_then(next_page);
+            let issues: Vec<GitHubIssue> = response.json().await.context("Failed to parse GitHub issues")?;
+            open.extend(
+                issues
+                    .into_iter()
+                    .filter(|issue| issue.pull_request.is_none())
+                    .map(|issue| (issue.number, issue.body.unwrap_or_default())),
+            );
+            let Some(next) = next else { break };
+            request = self.authorized(self.client.get(next));
+        }
+        Ok(open)
+    }
+
+    /// The open issue carrying the dedup key, if any, from the listing made
+    /// at the start of the sync.
+    async fn find_open(&self, dedup_key: &str) -> Result<Option<u64>> {
+        if self.open_issues.lock().unwrap().is_none() {
+            let listed = self.list_open().await?;
+            *self.open_issues.lock().unwrap() = Some(listed);
+        }
+        let marker = Self::marker(dedup_key);
+        Ok(self
+            .open_issues
+            .lock()
+            .unwrap()
+            .iter()
+            .flatten()
+            .find(|(_, body)| body.contains(&marker))
+            .map(|(number, _)| *number))
+    }
+}
+
+#[async_trait]
+impl IncidentBackend for GitHubBackend {
+    fn name(&self) -> &str {
+        "github"
+    }
+
+    async fn prepare(&self) -> Result<()> {
+        let listed = self.list_open().await?;
+        *self.open_issues.lock().unwrap() = Some(listed);
+        Ok(())
+    }
+
+    /// Opens an issue unless one is already open; an open issue is left as
+    /// is rather than commented on every scan.
+    async fn trigger(&self, incident: &Incident) -> Result<()> {
+        if self.find_open(&incident.dedup_key).await?.is_some() {
+            return Ok(());
+        }
+
+        let details: Vec<String> = incident.details.iter().map(|d| format!("- {}", d)).collect();
+        let body = format!(
+            "`{}` failed on **{}**.\n\n{}\n\nClosed automatically once a scan shows the check passing.\n\n{}",
+            incident.check,
+            incident.host,
+            details.join("\n"),
+            Self::marker(&incident.dedup_key)
+        );
+        // GitHub caps titles at 256 characters
+        let title: String = incident.summary.chars().take(256).collect();
+        let created: GitHubIssue = self
+            .request(reqwest::Method::POST, "")
+            .json(&json!({
+                "title": title,
+                "body": body,
+                "labels": self.config.labels,
+            }))
+            .send()
+            .await
+            .context("Failed to reach GitHub")?
+            .error_for_status()
+            .context("GitHub rejected the issue")?
+            .json()
+            .await
+            .context("Failed to parse the created GitHub issue")?;
+        if let Some(ref mut open) = *self.open_issues.lock().unwrap() {
+            open.push((created.number, body));
+        }
+        Ok(())
+    }
+
+    async fn resolve(&self, dedup_key: &str) -> Result<()> {
+        // Already closed by hand
+        let Some(number) = self.find_open(dedup_key).await? else {
+            return Ok(());
+        };
+
+        self.request(reqwest::Method::POST, &format!("/{}/comments", number))
+            .json(&json!({ "body": "The check passes again, closing." }))
+            .send()
+            .await
+            .context("Failed to reach GitHub")?
+            .error_for_status()
+            .context("GitHub rejected the comment")?;
+        self.request(reqwest::Method::PATCH, &format!("/{}", number))
+            .json(&json!({ "state": "closed", "state_reason": "completed" }))
+            .send()
+            .await
+            .context("Failed to reach GitHub")?
+            .error_for_status()
+            .context("GitHub rejected closing the issue")?;
+        if let Some(ref mut open) = *self.open_issues.lock().unwrap() {
+            open.retain(|(open_number, _)| *open_number != number);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+pub mod github;
+pub mod opsgenie;
+pub mod pagerduty;
+
+use crate::config::Config;
+use crate::models::{CheckResult, InventoryReport};
+use crate::state::{self, StateConfig};
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::{error, info, warn};
+
+/// Incidents we triggered and haven't resolved yet, per backend, so a later
+/// scan knows what to resolve.
+const STATE_FILE: &str = "incidents.json";
+
+/// A failed check on a host, deduplicated on `host/check`. Scan-wide checks
+/// (web services, Terraform drift) use `-` as the host.
+#[derive(Debug, Clone)]
+pub struct Incident {
+    pub dedup_key: String,
+    pub host: String,
+    pub check: String,
+    pub summary: String,
+    pub details: Vec<String>,
+}
+
+/// An incident management service.
+#[async_trait]
+pub trait IncidentBackend: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Called once per sync before any incident is triggered or resolved.
+    async fn prepare(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Opens the incident, or updates it if it is already open.
+    async fn trigger(&self, incident: &Incident) -> Result<()>;
+
+    async fn resolve(&self, dedup_key: &str) -> Result<()>;
+}
+
+pub fn enabled_backends(config: &Config) -> Vec<Box<dyn IncidentBackend>> {
+    let mut backends: Vec<Box<dyn IncidentBackend>> = Vec::new();
+
+    if config.pagerduty.enabled {
+        backends.push(Box::new(pagerduty::PagerDutyBackend::new(config.pagerduty.clone())));
+    }
+    if config.opsgenie.enabled {
+        backends.push(Box::new(opsgenie::OpsgenieBackend::new(config.opsgenie.clone())));
+    }
+    if config.github.enabled {
+        backends.push(Box::new(github::GitHubBackend::new(config.github.clone())));
+    }
+
+    backends
+}
+
+/// Triggers an incident for every failed check and resolves the ones a
+/// previous scan opened once their check passes again. Checks that were
+/// skipped (host unreachable, scan interrupted) keep their incident open.
+pub async fn sync(backends: &[Box<dyn IncidentBackend>], report: &InventoryReport, state_config: &StateConfig) {
+    if backends.is_empty() {
+        return;
+    }
+
+    let failing = failing_checks(report);
+    let cleared = cleared_checks(report);
+    let state_path = state_config.path(STATE_FILE);
+    let mut state: BTreeMap<String, BTreeSet<String>> = state::load(&state_path, "incident state").unwrap_or_else(|e| {
+        warn!("{:#}, open incidents won't be resolved", e);
+        BTreeMap::new()
+    });
+
+    for backend in backends {
+        if let Err(e) = backend.prepare().await {
+            error!("{}: {:#}", backend.name(), e);
+            continue;
+        }
+        let open = state.entry(backend.name().to_string()).or_default();
+
+        for incident in &failing {
+            match backend.trigger(incident).await {
+                Ok(()) => {
+                    open.insert(incident.dedup_key.clone());
+                }
+                Err(e) => error!("{}: failed to trigger {}: {:#}", backend.name(), incident.dedup_key, e),
+            }
+        }
+
+        let to_resolve: Vec<String> = open.intersection(&cleared).cloned().collect();
+        for key in to_resolve {
+            match backend.resolve(&key).await {
+                Ok(()) => {
+                    info!("{}: incidente resuelto {}", backend.name(), key);
+                    open.remove(&key);
+                }
+                Err(e) => error!("{}: failed to resolve {}: {:#}", backend.name(), key, e),
+            }
+        }
+    }
+
+    if let Err(e) = state::save(&state_path, "incident state", &state) {
+        error!("{:#}", e);
+    }
+}
+
+/// Host of the incidents raised by scan-wide checks.
+const SCAN_WIDE: &str = "-";
+
+fn dedup_key(host: &str, check: &str) -> String {
+    format!("sp-inventory/{}/{}", host, check)
+}
+
+fn failing_checks(report: &InventoryReport) -> Vec<Incident> {
+    report
+        .vms
+        .iter()
+        .flat_map(|vm| {
+            vm.checks.iter().filter(|c| c.result == CheckResult::Failed).map(move |check| Incident {
+                dedup_key: dedup_key(&vm.host.name, &check.name),
+                host: vm.host.name.clone(),
+                check: check.name.clone(),
+                summary: check
+                    .messages
+                    .first()
+                    .cloned()
+                    .unwrap_or_else(|| format!("{}: {} failed", vm.host.name, check.name)),
+                details: check.messages.clone(),
+            })
+        })
+        .chain(scan_wide_failures(report).into_iter().map(|(check, messages)| Incident {
+            dedup_key: dedup_key(SCAN_WIDE, check),
+            host: SCAN_WIDE.to_string(),
+            check: check.to_string(),
+            summary: messages[0].clone(),
+            details: messages,
+        }))
+        .collect()
+}
+
+/// Critical issues raised by scan-wide checks, grouped by check.
+fn scan_wide_failures(report: &InventoryReport) -> BTreeMap<&str, Vec<String>> {
+    let mut failures: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for issue in report.critical_issues.iter().filter(|issue| issue.host.is_none()) {
+        failures.entry(issue.check.as_str()).or_default().push(issue.message.clone());
+    }
+    failures
+}
+
+/// Checks known to be fine now: passed, or only raising warnings. A
+/// scan-wide check is fine when it ran (it has a timing) without critical
+/// issues.
+fn cleared_checks(report: &InventoryReport) -> BTreeSet<String> {
+    let scan_wide_failures = scan_wide_failures(report);
+    report
+        .vms
+        .iter()
+        .flat_map(|vm| {
+            vm.checks
+                .iter()
+                .filter(|c| matches!(c.result, CheckResult::Passed | CheckResult::Warning))
+                .map(move |c| dedup_key(&vm.host.name, &c.name))
+        })
+        .chain(
+            report
+                .timings
+                .keys()
+                .filter(|check| !scan_wide_failures.contains_key(check.as_str()))
+                .map(|check| dedup_key(SCAN_WIDE, check)),
+        )
+        .collect()
+}
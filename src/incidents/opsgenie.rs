@@ -0,0 +1,97 @@
+use super::{Incident, IncidentBackend};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpsgenieConfig {
+    pub enabled: bool,
+    /// API key of an "API" integration.
+    pub api_key: String,
+    /// `https://api.eu.opsgenie.com` for EU accounts.
+    pub api_url: String,
+    pub priority: String,
+}
+
+impl Default for OpsgenieConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            api_key: String::new(),
+            api_url: "https://api.opsgenie.com".to_string(),
+            priority: "P1".to_string(),
+        }
+    }
+}
+
+/// Opens and closes Opsgenie alerts, using the dedup key as the alert alias.
+pub struct OpsgenieBackend {
+    config: OpsgenieConfig,
+    client: Client,
+}
+
+impl OpsgenieBackend {
+    pub fn new(config: OpsgenieConfig) -> Self {
+        Self {
+            config,
+            client: Client::new(),
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/v2/alerts{}", self.config.api_url.trim_end_matches('/'), path)
+    }
+}
+
+#[async_trait]
+impl IncidentBackend for OpsgenieBackend {
+    fn name(&self) -> &str {
+        "opsgenie"
+    }
+
+    async fn trigger(&self, incident: &Incident) -> Result<()> {
+        // Opsgenie's message limit is 130 characters
+        let message: String = incident.summary.chars().take(130).collect();
+        self.client
+            .post(self.url(""))
+            .header("Authorization", format!("GenieKey {}", self.config.api_key))
+            .json(&json!({
+                "message": message,
+                "alias": incident.dedup_key,
+                "description": incident.details.join("\n"),
+                "entity": incident.host,
+                "source": "sp-inventory",
+                "tags": [incident.check],
+                "priority": self.config.priority,
+            }))
+            .send()
+            .await?
+            .error_for_status()
+            .context("Opsgenie rejected the alert")?;
+        Ok(())
+    }
+
+    async fn resolve(&self, dedup_key: &str) -> Result<()> {
+        let mut url = reqwest::Url::parse(&self.url("")).context("Invalid Opsgenie URL")?;
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.push(dedup_key).push("close");
+        }
+        let response = self
+            .client
+            .post(url)
+            .query(&[("identifierType", "alias")])
+            .header("Authorization", format!("GenieKey {}", self.config.api_key))
+            .json(&json!({ "source": "sp-inventory" }))
+            .send()
+            .await?;
+        // Already closed or deleted by hand
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        response.error_for_status().context("Opsgenie rejected the close request")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use super::{Incident, IncidentBackend};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+const EVENTS_API_URL: &str = "https://events.pagerduty.com/v2/enqueue";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PagerDutyConfig {
+    pub enabled: bool,
+    /// Integration key of an Events API v2 integration on the service.
+    pub routing_key: String,
+}
+
+/// Opens and resolves incidents through the PagerDuty Events API v2.
+pub struct PagerDutyBackend {
+    config: PagerDutyConfig,
+    client: Client,
+}
+
+impl PagerDutyBackend {
+    pub fn new(config: PagerDutyConfig) -> Self {
+        Self {
+            config,
+            client: Client::new(),
+        }
+    }
+
+    async fn enqueue(&self, event: Value) -> Result<()> {
+        self.client
+            .post(EVENTS_API_URL)
+            .json(&event)
+            .send()
+            .await?
+            .error_for_status()
+            .context("PagerDuty rejected the event")?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl IncidentBackend for PagerDutyBackend {
+    fn name(&self) -> &str {
+        "pagerduty"
+    }
+
+    async fn trigger(&self, incident: &Incident) -> Result<()> {
+        self.enqueue(json!({
+            "routing_key": self.config.routing_key,
+            "event_action": "trigger",
+            "dedup_key": incident.dedup_key,
+            "payload": {
+                "summary": incident.summary,
+                "source": incident.host,
+                "component": incident.check,
+                "severity": "critical",
+                "custom_details": { "issues": incident.details },
+            },
+        }))
+        .await
+    }
+
+    async fn resolve(&self, dedup_key: &str) -> Result<()> {
+        self.enqueue(json!({
+            "routing_key": self.config.routing_key,
+            "event_action": "resolve",
+            "dedup_key": dedup_key,
+        }))
+        .await
+    }
+}
@@ -0,0 +1,102 @@
+use crate::models::{FileFingerprint, VmHost};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// File integrity monitoring: critical files are hashed on every scan and
+/// compared with the baseline recorded by earlier scans.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IntegrityConfig {
+    pub enabled: bool,
+    /// Remote files to monitor; shell globs are expanded on the host.
+    pub files: Vec<String>,
+    /// Hashes, modes and owners of the monitored files, per host, in the
+    /// state directory.
+    pub baseline_file: String,
+    /// Adopt changes into the baseline right after reporting them, so each
+    /// change is reported once (`--accept-integrity` does it for one run).
+    /// Otherwise a change keeps being reported until accepted.
+    pub accept_changes: bool,
+    /// Host names, groups and tags to monitor; all empty means every host.
+    pub hosts: Vec<String>,
+    pub groups: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+impl Default for IntegrityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            files: vec![
+                "/etc/ssh/sshd_config".to_string(),
+                "/etc/sudoers".to_string(),
+                "/etc/sudoers.d/*".to_string(),
+                "/etc/wireguard/*.conf".to_string(),
+                "/etc/wireguard/*.key".to_string(),
+                "/root/.ssh/authorized_keys".to_string(),
+                "/home/*/.ssh/authorized_keys".to_string(),
+            ],
+            baseline_file: "integrity.json".to_string(),
+            accept_changes: false,
+            hosts: Vec::new(),
+            groups: Vec::new(),
+            tags: Vec::new(),
+        }
+    }
+}
+
+impl IntegrityConfig {
+    pub fn applies_to(&self, host: &VmHost) -> bool {
+        self.enabled && !self.files.is_empty() && host.in_scope(&self.hosts, &self.groups, &self.tags)
+    }
+}
+
+/// Monitored files by host name, then by path.
+pub type Baseline = BTreeMap<String, BTreeMap<String, FileFingerprint>>;
+
+/// How the files of a host differ from their baseline, one line per change.
+pub fn changes(known: &BTreeMap<String, FileFingerprint>, current: &[FileFingerprint]) -> Vec<String> {
+    let mut changes = Vec::new();
+
+    for file in current {
+        let Some(before) = known.get(&file.path) else {
+            continue;
+        };
+        if before.sha256 != file.sha256 {
+            changes.push(format!(
+                "{} content changed (sha256 {} -> {})",
+                file.path,
+                short(before.sha256.as_deref()),
+                short(file.sha256.as_deref())
+            ));
+        }
+        if before.mode != file.mode || before.owner != file.owner {
+            changes.push(format!(
+                "{} permissions changed ({} {} -> {} {})",
+                file.path, before.mode, before.owner, file.mode, file.owner
+            ));
+        }
+    }
+
+    for path in known.keys().filter(|path| !current.iter().any(|f| &f.path == *path)) {
+        changes.push(format!("{} was removed", path));
+    }
+
+    changes
+}
+
+fn short(hash: Option<&str>) -> &str {
+    match hash {
+        Some(hash) => &hash[..hash.len().min(12)],
+        None => "unreadable",
+    }
+}
+
+/// WireGuard configs and keys hold private keys, so only their owner may read them.
+pub fn exposed_private_keys(current: &[FileFingerprint]) -> Vec<String> {
+    current
+        .iter()
+        .filter(|file| file.path.starts_with("/etc/wireguard/") && !file.mode.ends_with("00"))
+        .map(|file| format!("{} is readable by group or others (mode {})", file.path, file.mode))
+        .collect()
+}
@@ -0,0 +1,131 @@
+use crate::models::*;
+use anyhow::{Context, Result};
+use std::fmt::Write as _;
+use tracing::info;
+
+/// Renders the scan as JUnit XML: one test suite per host with a test case
+/// per check, plus a suite for the external web services.
+pub struct JunitReporter;
+
+impl JunitReporter {
+    pub fn generate_report(report: &InventoryReport) -> String {
+        let suites: Vec<String> = report
+            .vms
+            .iter()
+            .map(|vm| Self::host_suite(report, vm))
+            .chain(std::iter::once(Self::web_suite(report)))
+            .collect();
+
+        let (tests, failures, skipped) = report.vms.iter().flat_map(|vm| &vm.checks).fold(
+            (report.web_services.len(), Self::web_failures(report), 0),
+            |(tests, failures, skipped), check| match check.result {
+                CheckResult::Failed => (tests + 1, failures + 1, skipped),
+                CheckResult::Skipped => (tests + 1, failures, skipped + 1),
+                _ => (tests + 1, failures, skipped),
+            },
+        );
+
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+            <testsuites name=\"securepenguin-inventory\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" timestamp=\"{}\">\n{}</testsuites>\n",
+            tests,
+            failures,
+            skipped,
+            report.timestamp.format("%Y-%m-%dT%H:%M:%S"),
+            suites.concat()
+        )
+    }
+
+    pub fn save_report(report: &InventoryReport, output_path: &str) -> Result<()> {
+        std::fs::write(output_path, Self::generate_report(report))
+            .context(format!("Failed to write JUnit report: {}", output_path))?;
+
+        info!("Reporte JUnit guardado en: {}", output_path);
+        Ok(())
+    }
+
+    fn host_suite(report: &InventoryReport, vm: &VmStatus) -> String {
+        let count = |result: CheckResult| vm.checks.iter().filter(|c| c.result == result).count();
+        let mut suite = format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" timestamp=\"{}\">\n",
+            escape(&vm.host.name),
+            vm.checks.len(),
+            count(CheckResult::Failed),
+            count(CheckResult::Skipped),
+            report.timestamp.format("%Y-%m-%dT%H:%M:%S"),
+        );
+
+        for check in &vm.checks {
+            let messages = check.messages.join("\n");
+            let _ = write!(suite, "    <testcase name=\"{}\" classname=\"{}\"", escape(&check.name), escape(&vm.host.name));
+            match check.result {
+                CheckResult::Passed => suite.push_str("/>\n"),
+                CheckResult::Warning => {
+                    let _ = write!(suite, ">\n      <system-out>{}</system-out>\n    </testcase>\n", escape(&messages));
+                }
+                CheckResult::Failed => {
+                    let _ = write!(
+                        suite,
+                        ">\n      <failure type=\"critical\" message=\"{}\">{}</failure>\n    </testcase>\n",
+                        escape(check.messages.first().map(String::as_str).unwrap_or_default()),
+                        escape(&messages)
+                    );
+                }
+                CheckResult::Skipped => {
+                    let _ = write!(suite, ">\n      <skipped message=\"{}\"/>\n    </testcase>\n", escape(&messages));
+                }
+            }
+        }
+
+        suite.push_str("  </testsuite>\n");
+        suite
+    }
+
+    fn web_suite(report: &InventoryReport) -> String {
+        let mut suite = format!(
+            "  <testsuite name=\"web_services\" tests=\"{}\" failures=\"{}\" skipped=\"0\">\n",
+            report.web_services.len(),
+            Self::web_failures(report)
+        );
+
+        for service in &report.web_services {
+            let _ = write!(suite, "    <testcase name=\"{}\" classname=\"web_services\"", escape(&service.name));
+            match Self::web_failure(service) {
+                Some(reason) => {
+                    let _ = write!(
+                        suite,
+                        ">\n      <failure type=\"critical\" message=\"{}\">{}</failure>\n    </testcase>\n",
+                        escape(&reason),
+                        escape(&service.url)
+                    );
+                }
+                None => suite.push_str("/>\n"),
+            }
+        }
+
+        suite.push_str("  </testsuite>\n");
+        suite
+    }
+
+    fn web_failures(report: &InventoryReport) -> usize {
+        report.web_services.iter().filter(|s| Self::web_failure(s).is_some()).count()
+    }
+
+    fn web_failure(service: &WebService) -> Option<String> {
+        if let Some(ref error) = service.error {
+            return Some(error.clone());
+        }
+        if service.unexpected_status {
+            return Some(format!("unexpected HTTP status {}", service.http_status.unwrap_or_default()));
+        }
+        service.content_error.clone()
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -0,0 +1,89 @@
+use crate::models::{KernelEvent, KernelEventKind};
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Kernel messages worth a look, as an extended regex for the remote grep
+/// that keeps the rest of the kernel log off the wire.
+pub const PREFILTER: &str =
+    "out of memory|Killed process|blocked for more than|EXT4-fs error|XFS .*(error|corrupt)|BTRFS (error|critical)|Remounting filesystem read-only|I/O error";
+
+struct Patterns {
+    oom: Regex,
+    hung_task: Regex,
+    filesystem: Regex,
+    io: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| Patterns {
+        // Out of memory: Killed process 1234 (java) total-vm:...
+        oom: Regex::new(r"(?i)out of memory: killed process (\d+) \(([^)]+)\)").unwrap(),
+        // INFO: task kworker/0:1:123 blocked for more than 120 seconds.
+        hung_task: Regex::new(r"task (\S+):(\d+) blocked for more than (\d+) seconds").unwrap(),
+        filesystem: Regex::new(r"EXT4-fs error|XFS .*(error|corrupt)|BTRFS (error|critical)|Remounting filesystem read-only").unwrap(),
+        io: Regex::new(r"I/O error").unwrap(),
+    })
+}
+
+/// The kernel event `message` reports, if any.
+pub fn classify(timestamp: DateTime<Utc>, message: &str) -> Option<KernelEvent> {
+    let patterns = patterns();
+    let (kind, process) = if let Some(captures) = patterns.oom.captures(message) {
+        (KernelEventKind::OomKill, Some(format!("{} (pid {})", &captures[2], &captures[1])))
+    } else if let Some(captures) = patterns.hung_task.captures(message) {
+        (KernelEventKind::HungTask, Some(format!("{} (pid {})", &captures[1], &captures[2])))
+    } else if patterns.filesystem.is_match(message) {
+        (KernelEventKind::FilesystemError, None)
+    } else if patterns.io.is_match(message) {
+        (KernelEventKind::IoError, None)
+    } else {
+        return None;
+    };
+
+    Some(KernelEvent { timestamp, kind, process, message: message.trim().to_string() })
+}
+
+/// Issue messages for the events of a host, without the host: one per
+/// killed or hung process, one per kind for filesystem and I/O errors.
+pub fn issues(events: &[KernelEvent]) -> Vec<String> {
+    let mut grouped: Vec<(&KernelEventKind, Option<&str>, &KernelEvent, usize)> = Vec::new();
+    for event in events {
+        // Pids change on restart; the process name is what matters
+        let subject = event.process.as_deref().map(|p| p.split(" (pid").next().unwrap_or(p));
+        match grouped.iter_mut().find(|(kind, s, _, _)| **kind == event.kind && *s == subject) {
+            Some((_, _, latest, count)) => {
+                *count += 1;
+                if event.timestamp > latest.timestamp {
+                    *latest = event;
+                }
+            }
+            None => grouped.push((&event.kind, subject, event, 1)),
+        }
+    }
+
+    grouped
+        .into_iter()
+        .map(|(kind, _, latest, count)| {
+            let mut issue = match kind {
+                KernelEventKind::OomKill => format!(
+                    "OOM killer killed {} at {}",
+                    latest.process.as_deref().unwrap_or("a process"),
+                    latest.timestamp.format("%Y-%m-%d %H:%M")
+                ),
+                KernelEventKind::HungTask => format!(
+                    "hung task {} at {}",
+                    latest.process.as_deref().unwrap_or("unknown"),
+                    latest.timestamp.format("%Y-%m-%d %H:%M")
+                ),
+                KernelEventKind::FilesystemError => format!("filesystem error - {}", latest.message),
+                KernelEventKind::IoError => format!("I/O error - {}", latest.message),
+            };
+            if count > 1 {
+                issue.push_str(&format!(" ({} times)", count));
+            }
+            issue
+        })
+        .collect()
+}
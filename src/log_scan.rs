@@ -0,0 +1,162 @@
+use crate::models::{LogEntry, Severity, VmHost};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::warn;
+
+/// What `journalctl` reads on each host and which entries raise issues.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogConfig {
+    /// How far back to read, e.g. `24h` or `3days`.
+    pub window: String,
+    /// Least severe priority read: `emerg` to `debug`, `0` to `7`, or a range like `warning..err`.
+    pub priority: String,
+    /// Newest entries kept per host.
+    pub max_entries: usize,
+    /// Window and priority for some hosts; the first matching override wins.
+    pub overrides: Vec<LogOverride>,
+    /// Entries raising an issue when their message matches.
+    pub patterns: Vec<LogPattern>,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            window: "24h".to_string(),
+            priority: "err".to_string(),
+            max_entries: 50,
+            overrides: Vec::new(),
+            patterns: vec![LogPattern {
+                description: "Port binding error".to_string(),
+                regex: "NT_STATUS_ADDRESS_ALREADY_ASSOCIATED|Failed to bind|port.*already".to_string(),
+                severity: Severity::Critical,
+                services: Vec::new(),
+                hosts: Vec::new(),
+                groups: Vec::new(),
+                tags: Vec::new(),
+            }],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogOverride {
+    #[serde(default)]
+    pub hosts: Vec<String>,
+    #[serde(default)]
+    pub groups: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub window: Option<String>,
+    pub priority: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogPattern {
+    /// Issue title, e.g. `Filesystem error`.
+    pub description: String,
+    pub regex: String,
+    #[serde(default = "default_severity")]
+    pub severity: Severity,
+    /// Units or syslog identifiers the pattern applies to (`nginx` matches
+    /// `nginx.service`); empty means every entry.
+    #[serde(default)]
+    pub services: Vec<String>,
+    /// Host names, groups and tags the pattern applies to; all empty means every host.
+    #[serde(default)]
+    pub hosts: Vec<String>,
+    #[serde(default)]
+    pub groups: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+fn default_severity() -> Severity {
+    Severity::Critical
+}
+
+impl LogPattern {
+    fn applies_to(&self, entry: &LogEntry) -> bool {
+        self.services.is_empty()
+            || self
+                .services
+                .iter()
+                .any(|service| *service == entry.service || entry.service.strip_suffix(".service") == Some(service))
+    }
+}
+
+/// The `journalctl` query run on one host.
+#[derive(Debug, Clone)]
+pub struct LogQuery {
+    pub window: Duration,
+    pub priority: String,
+    pub max_entries: usize,
+}
+
+const DEFAULT_WINDOW: Duration = Duration::from_secs(24 * 3600);
+
+/// Log settings with the patterns compiled once per scanner.
+pub struct LogScanner {
+    config: LogConfig,
+    patterns: Vec<(LogPattern, Regex)>,
+}
+
+impl LogScanner {
+    pub fn new(config: LogConfig) -> Self {
+        let patterns = config
+            .patterns
+            .iter()
+            .filter_map(|pattern| match Regex::new(&pattern.regex) {
+                Ok(regex) => Some((pattern.clone(), regex)),
+                Err(e) => {
+                    warn!("Ignoring log pattern {:?}: {}", pattern.description, e);
+                    None
+                }
+            })
+            .collect();
+        Self { config, patterns }
+    }
+
+    pub fn query(&self, host: &VmHost) -> LogQuery {
+        let matching = self
+            .config
+            .overrides
+            .iter()
+            .find(|o| host.in_scope(&o.hosts, &o.groups, &o.tags));
+        let window = matching.and_then(|o| o.window.as_ref()).unwrap_or(&self.config.window);
+        let priority = matching.and_then(|o| o.priority.as_ref()).unwrap_or(&self.config.priority);
+
+        LogQuery {
+            window: humantime::parse_duration(window).unwrap_or_else(|e| {
+                warn!("Invalid log window {:?}: {}", window, e);
+                DEFAULT_WINDOW
+            }),
+            // Ends up in a remote shell command
+            priority: if !priority.is_empty() && priority.chars().all(|c| c.is_ascii_alphanumeric() || c == '.') {
+                priority.clone()
+            } else {
+                warn!("Invalid log priority {:?}", priority);
+                "err".to_string()
+            },
+            max_entries: self.config.max_entries,
+        }
+    }
+
+    /// Entries matching each pattern that applies to `host`, for the
+    /// patterns that matched any.
+    pub fn matches<'a>(&self, host: &VmHost, entries: &'a [LogEntry]) -> Vec<(&LogPattern, Vec<&'a LogEntry>)> {
+        self.patterns
+            .iter()
+            .filter(|(pattern, _)| host.in_scope(&pattern.hosts, &pattern.groups, &pattern.tags))
+            .map(|(pattern, regex)| {
+                let matched = entries
+                    .iter()
+                    .filter(|entry| pattern.applies_to(entry) && regex.is_match(&entry.message))
+                    .collect::<Vec<_>>();
+                (pattern, matched)
+            })
+            .filter(|(_, matched)| !matched.is_empty())
+            .collect()
+    }
+}
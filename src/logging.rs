@@ -0,0 +1,54 @@
+use crate::progress;
+use clap::ValueEnum;
+use opentelemetry_sdk::trace::SdkTracer;
+use std::io::Write;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human readable lines on stderr
+    Text,
+    /// One JSON object per event, for log collectors
+    Json,
+}
+
+/// Installs the global subscriber. `verbosity` is 0 for the default `info`
+/// level, positive for each `-v` and negative for `--quiet`; `RUST_LOG`
+/// overrides it when set. With a `tracer`, spans are also exported to OpenTelemetry.
+pub fn init(verbosity: i8, format: LogFormat, tracer: Option<SdkTracer>) {
+    let level = match verbosity {
+        i8::MIN..=-1 => "warn",
+        0 => "info",
+        1 => "debug",
+        _ => "trace",
+    };
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(format!("{}={}", env!("CARGO_CRATE_NAME"), level)));
+
+    let fmt = tracing_subscriber::fmt::layer().with_writer(|| ProgressAwareStderr);
+    let fmt = match format {
+        LogFormat::Text => fmt.with_target(false).without_time().boxed(),
+        LogFormat::Json => fmt.json().with_current_span(true).with_span_list(false).boxed(),
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt)
+        .with(tracer.map(|tracer| tracing_opentelemetry::layer().with_tracer(tracer)))
+        .init();
+}
+
+/// stderr writer that clears the progress bars while a log line is written.
+struct ProgressAwareStderr;
+
+impl Write for ProgressAwareStderr {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        progress::suspend(|| std::io::stderr().write(buf))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        std::io::stderr().flush()
+    }
+}
@@ -1,95 +1,376 @@
+mod cli;
+mod config;
+mod logging;
+mod telemetry;
+mod i18n;
 mod models;
 mod ssh_client;
+mod ssh_agent;
+mod ssh_config;
+mod state;
 mod web_scanner;
+mod domains;
+mod ct_log;
+mod rbl;
+mod port_scanner;
+mod pinger;
+mod traceroute;
+mod bandwidth;
+mod progress;
+mod profile;
+mod providers;
+mod proxmox;
+mod terraform;
+mod policy;
+mod custom_checks;
+mod archive;
+mod integrity;
+mod file_audit;
+mod capabilities;
+mod processes;
+mod sensors;
+mod disk;
+mod node_exporter;
+mod reboots;
+mod os_release;
+mod log_scan;
+mod kernel_events;
+mod container_audit;
+mod container_storage;
+mod scheduled_jobs;
+mod scripting;
+mod suppressions;
+mod timing;
+mod maintenance;
+mod dependencies;
+mod netbox;
+mod obsidian;
+mod n8n;
+mod mqtt;
+mod timeseries;
+mod nagios;
+mod notifier;
+mod incidents;
 mod scanner;
+mod secrets;
+mod events;
+mod feed;
+mod redact;
 mod reporter;
+mod host_reports;
+mod report_git;
+mod report_signing;
+mod junit;
+mod topology;
+mod template_reporter;
+mod tui;
 
 use anyhow::{Context, Result};
+use clap::Parser;
+use std::borrow::Cow;
+use std::io::IsTerminal;
 use colored::*;
-use models::VmHost;
+use config::Config;
+use models::{Issue, VmHost};
+use tokio::signal::unix::{signal, SignalKind};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    println!("\n{}", "╔══════════════════════════════════════════╗".cyan());
-    println!("{}", "║  SECUREPENGUIN INVENTORY SCANNER           ║".cyan());
-    println!("{}\n", "╚══════════════════════════════════════════╝".cyan());
+    let cli = cli::Cli::parse();
+    let tui = matches!(cli.command, Some(cli::Command::Tui));
+    let mut config = Config::load()?;
+    let telemetry = telemetry::Telemetry::init(&config.telemetry)?;
+    // Log lines would tear through the TUI's alternate screen
+    if !tui {
+        logging::init(cli.verbosity(), cli.log_format, telemetry.as_ref().map(|t| t.tracer()));
+    }
+    progress::init(
+        !cli.quiet
+            && !tui
+            && cli.log_format == logging::LogFormat::Text
+            && std::io::stdout().is_terminal()
+            && std::io::stderr().is_terminal(),
+    );
 
-    let hosts = load_ssh_config()?;
-    
-    println!("{} Loaded {} VMs from SSH config", 
-        "[✓]".green().bold(), hosts.len());
+    if !cli.quiet && !tui {
+        println!("\n{}", "╔══════════════════════════════════════════╗".cyan());
+        println!("{}", "║  SECUREPENGUIN INVENTORY SCANNER           ║".cyan());
+        println!("{}\n", "╚══════════════════════════════════════════╝".cyan());
+    }
 
-    let inventory_scanner = scanner::InventoryScanner::new(hosts);
+    if let Some(secs) = cli.timeout {
+        config.timeouts.scan_secs = Some(secs);
+    }
+    if let Some(lang) = cli.lang {
+        config.language = lang;
+    }
+    if let Some(profile) = cli.profile {
+        config.profile = profile;
+    }
+    if cli.accept_integrity {
+        config.integrity.accept_changes = true;
+    }
+    if !cli.only.is_empty() {
+        config.checks.only = cli.only.clone();
+    }
+    if !cli.skip.is_empty() {
+        config.checks.skip = cli.skip.clone();
+    }
+    let mut hosts = load_ssh_config(&config)?;
+    if !cli.host.is_empty() || !cli.group.is_empty() || !cli.tag.is_empty() {
+        hosts.retain(|host| cli.selects(host));
+        if hosts.is_empty() {
+            anyhow::bail!("No hosts match the given --host/--group/--tag");
+        }
+    }
     
-    println!("{} Starting inventory scan...", 
-        "[→]".blue().bold());
+    info!("Loaded {} VMs from SSH config", hosts.len());
 
-    let report = inventory_scanner.scan()
-        .await
-        .context("Failed to complete inventory scan")?;
+    // Unlock passphrase-protected keys into a private agent kept alive for the whole scan
+    let identity_files: Vec<String> = hosts.iter().map(|h| h.identity_file.clone()).collect();
+    let agent = ssh_agent::AgentSession::start(&config.ssh, &identity_files)
+        .context("Failed to prepare ssh-agent")?;
+    if let Some(ref agent) = agent {
+        info!("SSH keys loaded into a private ssh-agent");
+        config.ssh.agent_socket = Some(agent.socket().to_string());
+    }
 
-    let output_path = "/home/jnovoas/SecurePenguin/INVENTARIO_STATUS_AUTO.md";
-    
-    reporter::MarkdownReporter::save_report(&report, &output_path)?;
+    // Load templates up front so a broken template fails before the scan, not after
+    let templates = cli
+        .template_dir
+        .as_deref()
+        .map(template_reporter::TemplateReporter::new)
+        .transpose()?;
 
-    print_summary(&report);
+    let output_path = shellexpand::tilde(&config.output_path).to_string();
+    let netbox_config = config.netbox.clone();
+    let obsidian_config = config.obsidian.clone();
+    let n8n_config = config.n8n.clone();
+    let mqtt_config = config.mqtt.clone();
+    let influx_config = config.influxdb.clone();
+    let timescale_config = config.timescale.clone();
+    let nagios_config = config.nagios.clone();
+    let notifiers = notifier::enabled_notifiers(&config);
+    let incident_backends = incidents::enabled_backends(&config);
+    let language = config.language;
+    let state_config = config.state.clone();
+    let report_config = config.report.clone();
+    let events_config = config.events.clone();
+    let inventory_scanner = scanner::InventoryScanner::new(hosts, config);
+    if tui {
+        return tui::run(inventory_scanner).await;
+    }
+    let cancel = inventory_scanner.cancellation_token();
+    tokio::spawn(cancel_on_signal(cancel.clone()));
 
-    Ok(())
-}
+    let feed = std::sync::Arc::new(feed::Feed::new(events_config.feed_entries));
+    if events_config.enabled {
+        let events = inventory_scanner.events();
+        let feed = feed.clone();
+        tokio::spawn(async move {
+            if let Err(e) = events::serve(events_config, events, feed).await {
+                error!("{:#}", e);
+            }
+        });
+    }
 
-fn load_ssh_config() -> Result<Vec<VmHost>> {
-    // Parse ~/.ssh/config to extract VM hosts
-    let ssh_config_path = "/home/jnovoas/.ssh/config";
-    
-    let config_content = std::fs::read_to_string(&ssh_config_path)
-        .context("Failed to read SSH config")?;
+    let mut previous: Option<models::InventoryReport> = None;
+    let mut previous_shared: Option<models::InventoryReport> = None;
+    loop {
+        info!("Starting inventory scan...");
 
-    let mut hosts = Vec::new();
-    let mut current_host: Option<VmHost> = None;
-
-    for line in config_content.lines() {
-        let line = line.trim();
-        
-        if line.starts_with("Host ") {
-            // Save previous host if exists
-            if let Some(host) = current_host.take() {
-                hosts.push(host);
+        let report = inventory_scanner.scan()
+            .await
+            .context("Failed to complete inventory scan")?;
+        // Reports get the redacted copy; integrations keep the real values
+        let shared = if cli.redact { Cow::Owned(redact::redact(&report)?) } else { Cow::Borrowed(&report) };
+
+        let mut written = vec![std::path::PathBuf::from(&output_path)];
+        for format in &cli.format {
+            match format {
+                cli::ReportFormat::Markdown => match templates {
+                    Some(ref templates) => templates.save_report(&shared, &output_path)?,
+                    None => reporter::MarkdownReporter::save_report(&shared, &output_path, language)?,
+                },
+                cli::ReportFormat::Junit => {
+                    let junit_path = std::path::Path::new(&output_path).with_extension("xml");
+                    junit::JunitReporter::save_report(&shared, &junit_path.to_string_lossy())?;
+                    written.push(junit_path);
+                }
+            }
+        }
+
+        if let Some(ref path) = cli.profile_output {
+            timing::TimingProfile::from_report(&shared).save(&shellexpand::tilde(path))?;
+        }
+
+        host_reports::save(&shared, &report_config, language)?;
+        if let Some(ref dir) = report_config.per_host_dir {
+            written.push(shellexpand::tilde(dir).to_string().into());
+        }
+        if let Some(ref path) = report_config.topology_dot {
+            let path = shellexpand::tilde(path).to_string();
+            topology::Topology::from_report(&shared).save_dot(&path)?;
+            written.push(path.into());
+        }
+
+        // Before the git commit, so the signatures are versioned with the reports
+        if report_config.signing.enabled {
+            match report_config.signing.sign(&written) {
+                Ok(signatures) => written.extend(signatures),
+                Err(e) => error!("Report signing failed: {:#}", e),
+            }
+        }
+
+        if report_config.git.enabled {
+            if report.partial {
+                warn!("Partial report, skipping git commit");
+            } else if let Err(e) = report_git::ReportRepo::new(report_config.git.clone()).commit(&shared, &written) {
+                error!("Report git commit failed: {:#}", e);
+            }
+        }
+
+        if netbox_config.enabled && !report.partial {
+            sync_netbox(netbox_config.clone(), &report).await;
+        }
+
+        if obsidian_config.enabled {
+            let markdown = match templates {
+                Some(ref templates) => templates.generate_report(&shared)?,
+                None => reporter::MarkdownReporter::generate_report(&shared, language)?,
+            };
+            publish_obsidian(obsidian_config.clone(), &markdown).await;
+        }
+
+        if n8n_config.enabled {
+            trigger_n8n(n8n_config.clone(), &report).await;
+        }
+
+        if mqtt_config.enabled {
+            match mqtt::MqttPublisher::new(mqtt_config.clone()).publish(&report).await {
+                Ok(count) => info!("Estado publicado en MQTT ({} mensajes)", count),
+                Err(e) => error!("MQTT publish failed: {:#}", e),
             }
-            
-            let name = line[5..].trim().to_string();
-            // Filter out backup hosts
-            if !name.ends_with("-bkp") && name != "pirex" {
-                current_host = Some(VmHost {
-                    name: name.clone(),
-                    ip: String::new(),
-                    port: 22,
-                    user: String::new(),
-                    identity_file: String::new(),
-                    vpn_ip: None,
-                });
+        }
+
+        // A partial scan would draw false dips in the dashboards
+        if influx_config.enabled && !report.partial {
+            match timeseries::InfluxWriter::new(influx_config.clone()) {
+                Ok(writer) => match writer.write(&report).await {
+                    Ok(count) => info!("Métricas escritas en InfluxDB ({} puntos)", count),
+                    Err(e) => error!("InfluxDB write failed: {:#}", e),
+                },
+                Err(e) => error!("InfluxDB write failed: {:#}", e),
+            }
+        }
+
+        if timescale_config.enabled && !report.partial {
+            match timeseries::TimescaleWriter::new(timescale_config.clone()).write(&report).await {
+                Ok(count) => info!("Métricas escritas en TimescaleDB ({} filas)", count),
+                Err(e) => error!("Timescale write failed: {:#}", e),
             }
-        } else if let Some(ref mut host) = current_host {
-            if line.starts_with("HostName ") {
-                host.ip = line[9..].trim().to_string();
-            } else if line.starts_with("Port ") {
-                host.port = line[5..].trim().parse().unwrap_or(22);
-            } else if line.starts_with("User ") {
-                host.user = line[5..].trim().to_string();
-            } else if line.starts_with("IdentityFile ") {
-                host.identity_file = line[12..].trim().to_string();
+        }
+
+        // Hosts never reached in a partial scan would turn DOWN
+        if nagios_config.enabled && !report.partial {
+            match nagios_config.submit(&report).await {
+                Ok(count) => info!("Resultados enviados a Nagios/Icinga ({} checks)", count),
+                Err(e) => error!("Nagios/Icinga submission failed: {:#}", e),
+            }
+        }
+
+        // The feed is published like the reports, so it compares redacted copies
+        feed.record(&shared, if cli.redact { previous_shared.as_ref() } else { previous.as_ref() });
+        notifier::notify_all(&notifiers, &report).await;
+        incidents::sync(&incident_backends, &report, &state_config).await;
+
+        if !cli.quiet {
+            if cli.watch {
+                // Redraw in place: clear the screen and move the cursor home
+                print!("\x1b[2J\x1b[H");
             }
+            print_summary(&report, previous.as_ref());
+        }
+
+        if !cli.watch || cancel.is_cancelled() {
+            let code = cli::exit_code(&cli.fail_on, &report);
+            if code != 0 {
+                // exit() skips destructors, so flush telemetry first
+                drop(telemetry);
+                std::process::exit(code);
+            }
+            break;
+        }
+
+        if !cli.quiet {
+            println!("Próximo escaneo en {} (Ctrl+C para salir)", humantime::format_duration(cli.interval));
+        }
+        if cli.redact {
+            previous_shared = Some(shared.into_owned());
+        }
+        previous = Some(report);
+        tokio::select! {
+            _ = tokio::time::sleep(cli.interval) => {}
+            _ = cancel.cancelled() => break,
         }
     }
 
-    // Add pirex separately (different SSH config pattern)
-    hosts.push(VmHost {
-        name: "pirex".to_string(),
-        ip: "34.176.56.176".to_string(),
-        port: 22,
-        user: "jnovoas".to_string(),
-        identity_file: "/home/jnovoas/.ssh/id_oracle".to_string(),
-        vpn_ip: Some("10.10.10.7".to_string()),
-    });
+    Ok(())
+}
+
+/// Cancels the scan on the first SIGINT/SIGTERM so a partial report can be
+/// written; a second signal exits immediately.
+async fn cancel_on_signal(cancel: CancellationToken) {
+    let Ok(mut terminate) = signal(SignalKind::terminate()) else {
+        return;
+    };
+
+    for attempt in 0.. {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        }
+
+        if attempt > 0 {
+            std::process::exit(130);
+        }
+        warn!("Interrupted, finishing with a partial report (press Ctrl+C again to abort)");
+        cancel.cancel();
+    }
+}
+
+fn load_ssh_config(config: &Config) -> Result<Vec<VmHost>> {
+    // Parse ~/.ssh/config (including Include files and Match blocks) to extract VM hosts
+    let ssh_config = ssh_config::SshConfig::load(&config.ssh_config)?;
+
+    let mut hosts = Vec::new();
+
+    for name in ssh_config.host_aliases() {
+        // Hosts declared as external come from the config instead
+        if config.is_external(&name) {
+            continue;
+        }
+
+        let options = ssh_config.resolve(&name);
+        hosts.push(VmHost {
+            ip: options.get("hostname").cloned().unwrap_or_else(|| name.clone()),
+            port: options.get("port").and_then(|p| p.parse().ok()).unwrap_or(22),
+            user: options.get("user").cloned().unwrap_or_default(),
+            identity_file: options
+                .get("identityfile")
+                .map(|f| shellexpand::tilde(f).to_string())
+                .unwrap_or_default(),
+            vpn_ip: None,
+            external: None,
+            group: None,
+            tags: Vec::new(),
+            roles: Vec::new(),
+            expected_containers: Vec::new(),
+            name,
+        });
+    }
 
     // Manually add kingu, sentinel, centurion VPN IPs
     hosts.iter_mut().for_each(|host| {
@@ -101,24 +382,78 @@ fn load_ssh_config() -> Result<Vec<VmHost>> {
         };
     });
 
+    // Externally hosted VMs come from the config, not the SSH config
+    hosts.extend(config.external_hosts.iter().filter(|h| h.enabled).map(|h| h.to_vm_host()));
+    hosts.iter_mut().for_each(|host| config.apply_host_meta(host));
+    hosts.retain(|host| config.scans_host(&host.name));
+
     Ok(hosts)
 }
 
-fn print_summary(report: &models::InventoryReport) {
+async fn sync_netbox(config: netbox::NetboxConfig, report: &models::InventoryReport) {
+    let result = match netbox::NetboxClient::new(config) {
+        Ok(client) => client.sync(report).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(summary) => info!(
+            "NetBox sincronizado: {} devices, {} IPs, {} servicios",
+            summary.devices,
+            summary.ip_addresses,
+            summary.services
+        ),
+        Err(e) => error!("NetBox sync failed: {:#}", e),
+    }
+}
+
+async fn publish_obsidian(config: obsidian::ObsidianConfig, markdown: &str) {
+    let note_path = config.note_path.clone();
+    let result = match obsidian::ObsidianPublisher::new(config) {
+        Ok(publisher) => publisher.publish(markdown).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(()) => info!("Reporte publicado en Obsidian: {}", note_path),
+        Err(e) => error!("Obsidian publish failed: {:#}", e),
+    }
+}
+
+async fn trigger_n8n(config: n8n::N8nConfig, report: &models::InventoryReport) {
+    let result = match n8n::N8nWebhook::new(config) {
+        Ok(webhook) => webhook.trigger(report).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(()) => info!("Webhook de n8n notificado"),
+        Err(e) => error!("n8n webhook failed: {:#}", e),
+    }
+}
+
+/// Prints the end-of-scan summary. With a previous report (watch mode),
+/// counts that changed and issues that are new get highlighted.
+fn print_summary(report: &models::InventoryReport, previous: Option<&models::InventoryReport>) {
     println!("\n{}", "══════════════════════════════════════════".cyan());
     println!("{}", "SCAN SUMMARY".cyan());
     println!("{}\n", "══════════════════════════════════════════".cyan());
 
-    println!("VMs totales:        {}", report.summary.total_vms.to_string().white().bold());
-    println!("VMs accesibles:     {}", report.summary.reachable_vms.to_string().green().bold());
-    println!("Servicios corriendo: {}", report.summary.running_services.to_string().green().bold());
-    println!("Contenedores activos: {}", report.summary.running_containers.to_string().green().bold());
+    if report.partial {
+        println!("{} Reporte parcial: el escaneo fue interrumpido\n", "⚠️".yellow().bold());
+    }
+
+    let before = previous.map(|p| &p.summary);
+    println!("VMs totales:        {}", count(report.summary.total_vms, before.map(|s| s.total_vms), Color::White));
+    println!("VMs accesibles:     {}", count(report.summary.reachable_vms, before.map(|s| s.reachable_vms), Color::Green));
+    println!("Servicios corriendo: {}", count(report.summary.running_services, before.map(|s| s.running_services), Color::Green));
+    println!("Contenedores activos: {}", count(report.summary.running_containers, before.map(|s| s.running_containers), Color::Green));
     
     if !report.critical_issues.is_empty() {
         println!("\n{} Issues críticos: {}", 
             "❌".red().bold(), report.critical_issues.len());
         for issue in &report.critical_issues {
-            println!("  - {}", issue.red());
+            println!("  - {}{}", issue.message.red(), new_marker(issue, previous.map(|p| &p.critical_issues)));
         }
     }
 
@@ -126,13 +461,44 @@ fn print_summary(report: &models::InventoryReport) {
         println!("\n{} Warnings: {}", 
             "⚠️".yellow().bold(), report.warnings.len());
         for warning in &report.warnings {
-            println!("  - {}", warning.yellow());
+            println!("  - {}{}", warning.message.yellow(), new_marker(warning, previous.map(|p| &p.warnings)));
         }
     }
 
+    if !report.notices.is_empty() {
+        println!("\n{} Notas: {}", 
+            "ℹ️".blue().bold(), report.notices.len());
+        for notice in &report.notices {
+            println!("  - {}{}", notice, new_marker(notice, previous.map(|p| &p.notices)));
+        }
+    }
+
+    let suppressed: usize = report.suppressions.iter().map(|s| s.suppressed.len()).sum();
+    if suppressed > 0 {
+        println!("\n{} Suprimidos: {}", "🔕".dimmed(), suppressed);
+    }
+
     if report.critical_issues.is_empty() && report.warnings.is_empty() {
         println!("\n{}", "✅ Todos los sistemas operativos!".green().bold());
     }
 
     println!("\n{}", "══════════════════════════════════════════\n".cyan());
 }
+
+/// A summary count, reversed and with the delta when it changed since the previous scan.
+fn count(value: usize, previous: Option<usize>, color: Color) -> ColoredString {
+    match previous {
+        Some(before) if before != value => {
+            let delta = value as i64 - before as i64;
+            format!("{} ({:+})", value, delta).color(color).bold().reversed()
+        }
+        _ => value.to_string().color(color).bold(),
+    }
+}
+
+fn new_marker(issue: &Issue, previous: Option<&Vec<Issue>>) -> ColoredString {
+    match previous {
+        Some(before) if !before.iter().any(|b| b.key() == issue.key()) => " (nuevo)".bold().reversed(),
+        _ => "".normal(),
+    }
+}
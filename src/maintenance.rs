@@ -0,0 +1,130 @@
+use crate::models::{CheckResult, InventoryReport, Issue, Severity};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use croner::Cron;
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+/// A planned maintenance period for some hosts, either a one-off range
+/// (`start`/`end`) or a recurring cron `schedule` lasting `duration`.
+/// Times without an offset, and cron schedules, are in local time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    #[serde(default)]
+    pub hosts: Vec<String>,
+    #[serde(default)]
+    pub groups: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default, deserialize_with = "toml_datetime")]
+    pub start: Option<DateTime<Local>>,
+    #[serde(default, deserialize_with = "toml_datetime")]
+    pub end: Option<DateTime<Local>>,
+    /// Five-field cron expression for when the window opens, e.g. `0 3 * * SUN`.
+    pub schedule: Option<String>,
+    /// How long a scheduled window stays open, e.g. `2h`.
+    pub duration: Option<String>,
+    pub reason: Option<String>,
+}
+
+fn toml_datetime<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<DateTime<Local>>, D::Error> {
+    let Some(value) = Option::<toml::value::Datetime>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    let value = value.to_string();
+    if let Ok(time) = DateTime::parse_from_rfc3339(&value) {
+        return Ok(Some(time.with_timezone(&Local)));
+    }
+    let naive = NaiveDateTime::parse_from_str(&value, "%Y-%m-%dT%H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(&value, "%Y-%m-%dT%H:%M"))
+        .map_err(de::Error::custom)?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(Some)
+        .ok_or_else(|| de::Error::custom(format!("{} does not exist in local time", value)))
+}
+
+impl MaintenanceWindow {
+    /// Whether the window is open at `now`.
+    pub fn is_active(&self, now: DateTime<Local>) -> Result<bool> {
+        if let Some(ref schedule) = self.schedule {
+            let duration = self.duration.as_deref().context("a scheduled window needs a duration")?;
+            let duration = chrono::Duration::from_std(humantime::parse_duration(duration)?)?;
+            let cron = Cron::new(schedule)
+                .parse()
+                .map_err(|e| anyhow::anyhow!("invalid schedule '{}': {}", schedule, e))?;
+            // The latest opening at or before now is the one that could still be open
+            let opened = cron
+                .find_next_occurrence(&(now - duration), false)
+                .map_err(|e| anyhow::anyhow!("invalid schedule '{}': {}", schedule, e))?;
+            return Ok(opened <= now);
+        }
+
+        match (self.start, self.end) {
+            (Some(start), Some(end)) => Ok(start <= now && now < end),
+            _ => anyhow::bail!("a window needs start and end, or schedule and duration"),
+        }
+    }
+
+    fn describe(&self) -> String {
+        self.reason.clone().unwrap_or_else(|| match (&self.schedule, self.end) {
+            (Some(schedule), _) => schedule.clone(),
+            (None, Some(end)) => format!("until {}", end.format("%Y-%m-%d %H:%M")),
+            (None, None) => "maintenance".to_string(),
+        })
+    }
+}
+
+/// Downgrades the findings of hosts inside an open maintenance window to
+/// notices, and marks their failing checks as skipped so nobody gets paged
+/// for a planned reboot.
+pub fn apply(windows: &[MaintenanceWindow], report: &mut InventoryReport) {
+    let now = Local::now();
+
+    let mut open = Vec::new();
+    for window in windows {
+        match window.is_active(now) {
+            Ok(true) => open.push(window),
+            Ok(false) => {}
+            Err(e) => report.warnings.push(Issue::new(
+                Severity::Warning,
+                None,
+                "maintenance",
+                format!("Maintenance window ignored: {:#}", e),
+            )),
+        }
+    }
+
+    for vm in &mut report.vms {
+        let Some(window) = open.iter().find(|w| vm.host.in_scope(&w.hosts, &w.groups, &w.tags)) else {
+            continue;
+        };
+        let reason = window.describe();
+
+        let mut downgraded = 0;
+        for list in [&mut report.critical_issues, &mut report.warnings] {
+            let (matching, kept): (Vec<Issue>, Vec<Issue>) =
+                list.drain(..).partition(|issue| issue.host.as_ref() == Some(&vm.host.name));
+            *list = kept;
+            downgraded += matching.len();
+            report
+                .notices
+                .extend(matching.into_iter().map(|issue| Issue { severity: Severity::Info, ..issue }));
+        }
+
+        for check in &mut vm.checks {
+            if matches!(check.result, CheckResult::Failed | CheckResult::Warning) {
+                check.result = CheckResult::Skipped;
+                check.messages.push(format!("maintenance window: {}", reason));
+            }
+        }
+
+        report.notices.push(Issue::new(
+            Severity::Info,
+            Some(&vm.host.name),
+            "maintenance",
+            format!("{}: in maintenance ({}), {} findings downgraded", vm.host.name, reason, downgraded),
+        ));
+        vm.maintenance = Some(reason);
+    }
+}
@@ -1,5 +1,10 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use crate::suppressions::ActiveSuppression;
+use crate::profile::{CheckSelection, ScanProfile};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VmHost {
@@ -9,17 +14,423 @@ pub struct VmHost {
     pub user: String,
     pub identity_file: String,
     pub vpn_ip: Option<String>,
+    pub external: Option<ExternalHost>,
+    /// Group the host belongs to (e.g. "cloud"), used to scope scans and the report.
+    #[serde(default)]
+    pub group: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Roles from `[roles]`; their services are expected to be running.
+    #[serde(default)]
+    pub roles: Vec<String>,
+    /// Name or image patterns of the containers the host must run; when
+    /// set, any other container is unexpected.
+    #[serde(default)]
+    pub expected_containers: Vec<String>,
+}
+
+impl VmHost {
+    /// Every address the host is known by: primary, VPN and alternates.
+    pub fn addresses(&self) -> Vec<&String> {
+        let mut addresses = vec![&self.ip];
+        addresses.extend(self.vpn_ip.as_ref());
+        if let Some(ref ext) = self.external {
+            addresses.extend(ext.alternate_ips.iter());
+        }
+        addresses
+    }
+
+    /// Whether the host is named in `hosts`, or belongs to one of `groups` or
+    /// `tags`. Empty lists on all three select every host.
+    pub fn in_scope(&self, hosts: &[String], groups: &[String], tags: &[String]) -> bool {
+        if hosts.is_empty() && groups.is_empty() && tags.is_empty() {
+            return true;
+        }
+
+        hosts.contains(&self.name)
+            || self.group.as_ref().is_some_and(|g| groups.contains(g))
+            || self.tags.iter().any(|t| tags.contains(t))
+    }
+}
+
+/// `address` as the host part of a URL or `host:port`: IPv6 literals go in
+/// brackets so their colons aren't taken for the port separator.
+pub fn url_host(address: &str) -> String {
+    if address.contains(':') && !address.starts_with('[') {
+        format!("[{}]", address)
+    } else {
+        address.to_string()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalHost {
+    pub provider: String,
+    pub region: Option<String>,
+    pub alternate_ips: Vec<String>,
+    pub expect_reachable: bool,
+    pub unreachable_severity: Severity,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Critical,
+    Warning,
+    Info,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VmStatus {
     pub host: VmHost,
     pub reachable: bool,
+    pub connected_ip: Option<String>,
     pub services: Vec<Service>,
     pub containers: Vec<Container>,
     pub wireguard: Option<WireGuardStatus>,
     pub open_ports: Vec<Port>,
+    /// DNS blacklists naming a public address of the host.
+    #[serde(default)]
+    pub blacklistings: Vec<BlacklistListing>,
+    #[serde(default)]
+    pub load: Option<LoadAverage>,
+    /// Only collected from node_exporter.
+    #[serde(default)]
+    pub memory: Option<Memory>,
+    #[serde(default)]
+    pub os: Option<OsRelease>,
+    #[serde(default)]
+    pub hardware: Option<Hardware>,
+    #[serde(default)]
+    pub gpus: Vec<Gpu>,
+    /// Seconds since boot, from `/proc/uptime`.
+    #[serde(default)]
+    pub uptime_secs: Option<u64>,
+    /// When the host booted, as of the moment its uptime was read.
+    #[serde(default)]
+    pub booted_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub filesystems: Vec<Filesystem>,
+    #[serde(default)]
+    pub swap: Option<Swap>,
+    /// Unset when the host exposes no sensors, as with most VMs.
+    #[serde(default)]
+    pub thermal: Option<Thermal>,
     pub recent_errors: Vec<LogEntry>,
+    /// Kernel events within the log window.
+    #[serde(default)]
+    pub kernel_events: Vec<KernelEvent>,
+    pub storage_arrays: Vec<StorageArray>,
+    pub certificates: Vec<ManagedCertificate>,
+    pub traefik: Option<TraefikInventory>,
+    pub databases: Vec<DatabaseStatus>,
+    pub data_stores: Vec<DataStoreStatus>,
+    pub samba: Option<SambaStatus>,
+    pub exposed_ports: Vec<ExposedPort>,
+    pub ping: Vec<PingResult>,
+    /// Reachability over the public and VPN addresses, for hosts with both.
+    #[serde(default)]
+    pub paths: Vec<NetworkPath>,
+    pub traceroute: Vec<TraceHop>,
+    /// Checks that timed out or were skipped, so empty results aren't mistaken for clean ones.
+    pub incomplete_checks: Vec<String>,
+    /// Per-check verdicts, for reports that list checks individually (e.g. JUnit).
+    pub checks: Vec<CheckOutcome>,
+    /// Results of the user-defined scripts from `[[custom_checks]]`.
+    #[serde(default)]
+    pub custom_checks: Vec<CustomCheck>,
+    /// Reason of the maintenance window the host was in during the scan.
+    #[serde(default)]
+    pub maintenance: Option<String>,
+    /// Configuration files copied into the local archive.
+    #[serde(default)]
+    pub archived_files: Vec<ArchivedFile>,
+    #[serde(default)]
+    pub processes: Vec<Process>,
+    /// Monitored files as found by this scan; unset when not collected.
+    #[serde(default)]
+    pub file_integrity: Option<Vec<FileFingerprint>>,
+    /// SUID/SGID binaries and world-writable files; unset when not audited.
+    #[serde(default)]
+    pub special_files: Option<Vec<SpecialFile>>,
+    /// Container images and volumes; unset when not collected or no runtime.
+    #[serde(default)]
+    pub container_storage: Option<ContainerStorage>,
+    /// Cron entries and systemd timers; unset when not collected.
+    #[serde(default)]
+    pub scheduled_jobs: Option<Vec<ScheduledJob>>,
+    /// Init system and tools found on the host; unset when not probed.
+    #[serde(default)]
+    pub capabilities: Option<Capabilities>,
+    /// Seconds spent on each check, keyed by check name.
+    #[serde(default)]
+    pub timings: BTreeMap<String, f64>,
+    /// Seconds the whole host took.
+    #[serde(default)]
+    pub duration_secs: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomCheckStatus {
+    Ok,
+    Warning,
+    Critical,
+    Unknown,
+}
+
+/// Output contract of a custom check script, printed as JSON on stdout:
+/// `{"status": "ok", "message": "...", "metrics": {"queue": 3}}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomCheck {
+    #[serde(default)]
+    pub name: String,
+    pub status: CustomCheckStatus,
+    #[serde(default)]
+    pub message: String,
+    #[serde(default)]
+    pub metrics: BTreeMap<String, f64>,
+}
+
+/// Content hash and permissions of a monitored file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileFingerprint {
+    pub path: String,
+    /// Unset when the file exists but could not be read.
+    pub sha256: Option<String>,
+    /// Octal mode, e.g. `600`.
+    pub mode: String,
+    /// `user:group`.
+    pub owner: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Process {
+    pub pid: u32,
+    pub user: String,
+    /// Executable path, with ` (deleted)` when it was removed from disk.
+    pub exe: String,
+    pub command: String,
+}
+
+/// A file whose permissions could let a local user escalate privileges.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpecialFile {
+    pub path: String,
+    pub kind: SpecialFileKind,
+    /// Octal mode, e.g. `4755`.
+    pub mode: String,
+    /// `user:group`.
+    pub owner: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpecialFileKind {
+    Setuid,
+    Setgid,
+    WorldWritable,
+}
+
+/// Images and volumes kept by the container runtime, and the filesystem
+/// holding them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerStorage {
+    pub images: Vec<ContainerImage>,
+    pub volumes: Vec<ContainerVolume>,
+    /// Runtime data directory, e.g. `/var/lib/docker`.
+    pub data_root: Option<String>,
+    /// Usage of the filesystem holding `data_root`, in percent.
+    pub data_root_used_percent: Option<u8>,
+}
+
+impl ContainerStorage {
+    /// Dangling or unused images, which `image prune -a` would remove.
+    pub fn unused_images(&self) -> impl Iterator<Item = &ContainerImage> {
+        self.images.iter().filter(|image| !image.in_use)
+    }
+
+    /// Volumes no container references, which `volume prune` would remove.
+    pub fn orphan_volumes(&self) -> impl Iterator<Item = &ContainerVolume> {
+        self.volumes.iter().filter(|volume| volume.orphan)
+    }
+
+    /// Bytes pruning would free, at most: images may share layers.
+    pub fn reclaimable_bytes(&self) -> u64 {
+        self.unused_images().map(|image| image.size_bytes).sum::<u64>()
+            + self.orphan_volumes().filter_map(|volume| volume.size_bytes).sum::<u64>()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerImage {
+    pub id: String,
+    /// Empty for dangling images.
+    pub tags: Vec<String>,
+    pub size_bytes: u64,
+    /// Some container, running or stopped, was created from it.
+    pub in_use: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerVolume {
+    pub name: String,
+    pub size_bytes: Option<u64>,
+    pub orphan: bool,
+}
+
+/// A cron entry or systemd timer that runs something on a schedule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledJob {
+    pub kind: ScheduledJobKind,
+    /// Crontab file or timer unit the job comes from.
+    pub source: String,
+    /// User the job runs as, when the source says.
+    pub user: Option<String>,
+    /// Cron expression, or the timer's `OnCalendar=`/`OnBootSec=` settings.
+    pub schedule: String,
+    pub command: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduledJobKind {
+    Cron,
+    Timer,
+}
+
+/// A remote file copied into the config archive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedFile {
+    pub path: String,
+    pub bytes: u64,
+    /// SFTP was denied and the file was read with sudo.
+    pub via_sudo: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckResult {
+    Passed,
+    Warning,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckOutcome {
+    pub name: String,
+    pub result: CheckResult,
+    pub messages: Vec<String>,
+}
+
+impl CheckOutcome {
+    /// Verdict from the issues a check raised: critical issues fail it,
+    /// warnings alone only flag it.
+    pub fn from_issues(name: &str, critical: Vec<String>, warnings: Vec<String>) -> Self {
+        let result = if !critical.is_empty() {
+            CheckResult::Failed
+        } else if !warnings.is_empty() {
+            CheckResult::Warning
+        } else {
+            CheckResult::Passed
+        };
+
+        Self {
+            name: name.to_string(),
+            result,
+            messages: critical.into_iter().chain(warnings).collect(),
+        }
+    }
+
+    pub fn skipped(name: &str, reason: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            result: CheckResult::Skipped,
+            messages: vec![reason.to_string()],
+        }
+    }
+}
+
+impl VmStatus {
+    /// Status for a host that was never contacted.
+    pub fn not_scanned(host: VmHost, reason: &str) -> Self {
+        Self {
+            host,
+            reachable: false,
+            connected_ip: None,
+            services: Vec::new(),
+            containers: Vec::new(),
+            wireguard: None,
+            open_ports: Vec::new(),
+            blacklistings: Vec::new(),
+            load: None,
+            memory: None,
+            os: None,
+            hardware: None,
+            gpus: Vec::new(),
+            uptime_secs: None,
+            booted_at: None,
+            filesystems: Vec::new(),
+            swap: None,
+            thermal: None,
+            recent_errors: Vec::new(),
+            kernel_events: Vec::new(),
+            storage_arrays: Vec::new(),
+            certificates: Vec::new(),
+            traefik: None,
+            databases: Vec::new(),
+            data_stores: Vec::new(),
+            samba: None,
+            exposed_ports: Vec::new(),
+            ping: Vec::new(),
+            paths: Vec::new(),
+            traceroute: Vec::new(),
+            incomplete_checks: vec![reason.to_string()],
+            checks: vec![CheckOutcome::skipped("connectivity", reason)],
+            custom_checks: Vec::new(),
+            maintenance: None,
+            archived_files: Vec::new(),
+            processes: Vec::new(),
+            file_integrity: None,
+            special_files: None,
+            container_storage: None,
+            scheduled_jobs: None,
+            capabilities: None,
+            timings: BTreeMap::new(),
+            duration_secs: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InitSystem {
+    Systemd,
+    OpenRc,
+    /// FreeBSD's rc.d, managed with `service`.
+    BsdRc,
+    Other,
+}
+
+/// What a host can be asked: its init system, the tools checks rely on and
+/// whether sudo works, so checks it can't run are reported as skipped
+/// instead of coming back empty.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Capabilities {
+    pub init: InitSystem,
+    pub docker: bool,
+    pub podman: bool,
+    pub ss: bool,
+    pub netstat: bool,
+    pub sockstat: bool,
+    pub journald: bool,
+    /// `/var/log/messages` or `/var/log/syslog` exists.
+    pub syslog: bool,
+    /// Sudo as configured for the host works without prompting.
+    pub sudo: bool,
+    pub probed_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,7 +452,34 @@ pub enum ServiceStatus {
 pub struct Container {
     pub name: String,
     pub status: String,
+    #[serde(default)]
+    pub image: String,
     pub ports: String,
+    /// Settings from `docker inspect`; unset when the container was not inspected.
+    #[serde(default)]
+    pub runtime: Option<ContainerRuntime>,
+}
+
+/// How a running container is isolated from its host.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerRuntime {
+    pub image: String,
+    pub privileged: bool,
+    /// `bridge`, `host`, `none` or a user-defined network.
+    pub network_mode: String,
+    pub mounts: Vec<ContainerMount>,
+    /// Environment variables set to a literal value, by name only: the
+    /// values never leave the host.
+    #[serde(default)]
+    pub env: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerMount {
+    /// Host path, or the volume name for named volumes.
+    pub source: String,
+    pub destination: String,
+    pub read_only: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,33 +505,708 @@ pub struct Port {
     pub port: u16,
     pub protocol: String,
     pub process: String,
+    /// Local address the socket is bound to (`0.0.0.0`, `::`, `*` for all).
+    #[serde(default)]
+    pub address: String,
+    /// Interface the socket is bound to, when `ss` shows one (`127.0.0.53%lo`).
+    #[serde(default)]
+    pub interface: Option<String>,
+    /// Set by the scanner from the bind address and the VPN settings.
+    #[serde(default)]
+    pub exposure: Option<SocketExposure>,
+}
+
+/// Who can reach a listening socket, judged by where it is bound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SocketExposure {
+    Loopback,
+    /// Bound to a VPN interface or address only.
+    Vpn,
+    /// Bound to a private LAN address.
+    Private,
+    /// Bound to every interface or to a public address.
+    World,
+}
+
+impl Port {
+    /// The bind address, with IPv4-mapped IPv6 (`::ffff:127.0.0.1`, as
+    /// dual-stack sockets report it) turned back into IPv4.
+    pub fn ip(&self) -> Option<std::net::IpAddr> {
+        self.address.parse::<std::net::IpAddr>().ok().map(|ip| ip.to_canonical())
+    }
+
+    /// Bound to every interface or to a globally routable address.
+    pub fn is_public(&self) -> bool {
+        match self.address.as_str() {
+            "0.0.0.0" | "::" | "*" | "" => true,
+            _ => match self.ip() {
+                Some(std::net::IpAddr::V4(ip)) => !(ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified()),
+                Some(std::net::IpAddr::V6(ip)) => {
+                    !(ip.is_loopback() || (ip.segments()[0] & 0xfe00) == 0xfc00 || (ip.segments()[0] & 0xffc0) == 0xfe80)
+                }
+                None => false,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlacklistListing {
+    pub list: String,
+    pub ip: String,
+    /// The 127.0.0.x answer, whose meaning depends on the list.
+    pub response: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct LoadAverage {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+    pub cores: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Memory {
+    pub total_bytes: u64,
+    /// Memory available to new processes without swapping, caches included.
+    pub available_bytes: u64,
+}
+
+impl Memory {
+    pub fn used_percent(&self) -> u8 {
+        if self.total_bytes == 0 {
+            return 0;
+        }
+        (self.total_bytes.saturating_sub(self.available_bytes) * 100 / self.total_bytes) as u8
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExposedPort {
+    pub port: u16,
+    pub process: Option<String>,
+    pub expected_public: bool,
+    /// Exposure of the TCP socket listening on the port according to `ss`;
+    /// `None` when no such socket was seen.
+    #[serde(default)]
+    pub exposure: Option<SocketExposure>,
+}
+
+impl ExposedPort {
+    /// Reachable from outside although the socket is bound to loopback, a
+    /// VPN or the LAN: something forwards it, like docker-proxy or a DNAT rule.
+    pub fn is_forwarded(&self) -> bool {
+        self.exposure.is_some_and(|e| e != SocketExposure::World)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PingResult {
+    pub target: String,
+    pub transmitted: u32,
+    pub received: u32,
+    pub loss_percent: f64,
+    pub min_ms: Option<f64>,
+    pub avg_ms: Option<f64>,
+    pub max_ms: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PathKind {
+    /// The host's primary `ip`.
+    Public,
+    Vpn,
+}
+
+/// How the host answers over one of its addresses.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkPath {
+    pub kind: PathKind,
+    pub address: String,
+    /// Milliseconds a TCP connection to the SSH port took; unset when it
+    /// was refused or timed out.
+    pub ssh_connect_ms: Option<f64>,
+    pub ping_avg_ms: Option<f64>,
+    pub ping_loss_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceHop {
+    pub ttl: u8,
+    pub address: Option<String>,
+    pub rtt_ms: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LogEntry {
-    pub timestamp: String,
+    pub timestamp: DateTime<Utc>,
+    /// systemd unit the entry came from, or its syslog identifier (e.g. `kernel`).
     pub service: String,
-    pub level: String,
+    /// Syslog priority, from 0 (emerg) to 7 (debug).
+    pub priority: u8,
+    pub message: String,
+}
+
+impl LogEntry {
+    pub fn level(&self) -> &'static str {
+        match self.priority {
+            0 => "emerg",
+            1 => "alert",
+            2 => "crit",
+            3 => "err",
+            4 => "warning",
+            5 => "notice",
+            6 => "info",
+            _ => "debug",
+        }
+    }
+}
+
+/// Distribution and kernel, from `/etc/os-release` and `uname -r`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsRelease {
+    /// e.g. `debian`, `ubuntu`, `rocky`.
+    pub id: String,
+    /// e.g. `12`, `22.04`, `9.3`.
+    pub version_id: String,
+    pub pretty_name: String,
+    pub kernel: String,
+}
+
+/// CPU, memory, disks and virtualization of a host. Fields the host
+/// doesn't report are unset.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hardware {
+    pub cpu_model: Option<String>,
+    pub cores: Option<u32>,
+    pub memory_bytes: Option<u64>,
+    pub disks: Vec<DiskDevice>,
+    /// As named by `systemd-detect-virt` (`kvm`, `lxc`, `microsoft`, ...),
+    /// or `none` on bare metal.
+    pub virtualization: Option<String>,
+}
+
+/// A whole block device, as listed by `lsblk -d`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskDevice {
+    pub name: String,
+    pub size_bytes: u64,
+    pub model: Option<String>,
+    pub rotational: bool,
+}
+
+/// An NVIDIA or AMD GPU as reported by `nvidia-smi` or `rocm-smi`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Gpu {
+    /// `nvidia` or `amd`.
+    pub vendor: String,
+    pub model: String,
+    pub memory_total_bytes: Option<u64>,
+    pub memory_used_bytes: Option<u64>,
+    pub utilization_percent: Option<u8>,
+    pub temperature_celsius: Option<f64>,
+}
+
+/// A mounted filesystem with its space and inode usage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Filesystem {
+    pub mountpoint: String,
+    pub device: String,
+    pub size_bytes: u64,
+    pub used_percent: u8,
+    /// Unset on filesystems without a fixed inode table, such as btrfs.
+    pub inodes_used_percent: Option<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Swap {
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    /// Pages swapped in plus out per second, sampled over one second.
+    pub pages_per_sec: Option<u64>,
+}
+
+impl Swap {
+    /// `None` when the host has no swap.
+    pub fn used_percent(&self) -> Option<u8> {
+        (self.total_bytes > 0).then(|| (self.used_bytes * 100 / self.total_bytes) as u8)
+    }
+}
+
+/// Temperatures and throttling, from whichever of lm-sensors,
+/// `/sys/class/thermal` and `vcgencmd` the host has.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Thermal {
+    pub readings: Vec<TemperatureReading>,
+    /// Throttling conditions in effect (Raspberry Pi), e.g. `under-voltage`.
+    pub throttling: Vec<String>,
+    /// Throttling conditions seen since boot (Raspberry Pi).
+    pub throttled_since_boot: Vec<String>,
+    /// Thermal throttling events since boot, summed over CPU cores (x86).
+    pub throttle_count: Option<u64>,
+}
+
+impl Thermal {
+    pub fn hottest(&self) -> Option<&TemperatureReading> {
+        self.readings.iter().max_by(|a, b| a.celsius.total_cmp(&b.celsius))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemperatureReading {
+    /// Sensor, e.g. `coretemp/Package id 0` or `x86_pkg_temp`.
+    pub name: String,
+    pub celsius: f64,
+}
+
+/// An OOM kill, hung task or filesystem/disk error from the kernel log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KernelEvent {
+    pub timestamp: DateTime<Utc>,
+    pub kind: KernelEventKind,
+    /// Killed or hung process, as `name (pid N)`.
+    pub process: Option<String>,
     pub message: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KernelEventKind {
+    OomKill,
+    HungTask,
+    FilesystemError,
+    IoError,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageArray {
+    pub name: String,
+    pub kind: String,
+    pub level: Option<String>,
+    pub state: String,
+    pub degraded: bool,
+    pub failed_devices: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagedCertificate {
+    pub name: String,
+    pub source: String,
+    pub subject: String,
+    pub expires: Option<DateTime<Utc>>,
+    pub auto_renew: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraefikInventory {
+    pub routers: Vec<TraefikRouter>,
+    pub services: Vec<TraefikService>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraefikRouter {
+    pub name: String,
+    pub rule: String,
+    pub domains: Vec<String>,
+    pub service: String,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraefikService {
+    pub name: String,
+    pub status: String,
+    pub servers: Vec<TraefikServer>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraefikServer {
+    pub url: String,
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteAttribution {
+    pub vm: String,
+    pub router: String,
+    pub service: String,
+    pub servers: Vec<TraefikServer>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseStatus {
+    pub engine: String,
+    pub connected: bool,
+    pub error: Option<String>,
+    pub is_replica: bool,
+    pub replication_lag_secs: Option<f64>,
+    /// Whether a replica is receiving from its primary; `None` on a primary.
+    #[serde(default)]
+    pub replicating: Option<bool>,
+    pub connections: Option<u64>,
+    pub oldest_transaction_secs: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataStoreStatus {
+    pub engine: String,
+    pub healthy: bool,
+    pub error: Option<String>,
+    pub memory_used: Option<String>,
+    pub keys: Option<u64>,
+    pub replication: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SambaStatus {
+    pub shares: Vec<SambaShare>,
+    pub interfaces: Vec<String>,
+    pub bind_interfaces_only: bool,
+    pub smbd_bindings: Vec<String>,
+    pub nmbd_bindings: Vec<String>,
+    pub unbound_interfaces: Vec<String>,
+    pub anonymous_listing: Option<SambaListing>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SambaShare {
+    pub name: String,
+    pub path: Option<String>,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SambaListing {
+    pub reachable: bool,
+    pub shares: Vec<String>,
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebService {
     pub name: String,
     pub url: String,
     pub http_status: Option<u16>,
+    pub unexpected_status: bool,
     pub response_time: Option<f64>,
+    pub latency: Option<LatencyStats>,
+    pub error: Option<String>,
+    pub content_error: Option<String>,
+    pub redirect_chain: Vec<String>,
+    pub redirect_error: Option<String>,
+    pub https_enforced: Option<bool>,
+    pub route: Option<RouteAttribution>,
+    pub dns: Option<DnsRecords>,
+    /// Why the DNS records do not point at the host serving the service.
+    pub dns_error: Option<String>,
+    pub tls: Option<TlsAudit>,
+    /// Steps of the service's scenario that ran, up to the first failure.
+    #[serde(default)]
+    pub scenario: Vec<ScenarioStepResult>,
+    pub health: Option<HealthReport>,
+}
+
+/// What a service's JSON health endpoint reported.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub url: String,
+    pub http_status: Option<u16>,
+    pub fields: Vec<HealthField>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthField {
+    pub path: String,
+    /// The field as JSON, unset when the response lacks it.
+    pub value: Option<String>,
+    /// The asserted value as JSON, for asserted fields.
+    pub expected: Option<String>,
+    pub ok: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioStepResult {
+    pub name: String,
+    pub http_status: Option<u16>,
+    /// Seconds the step took, redirects included.
+    pub duration: f64,
     pub error: Option<String>,
 }
 
+/// How an HTTPS endpoint negotiates TLS.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsAudit {
+    pub protocol: Option<String>,
+    pub cipher: Option<String>,
+    /// Protocols below the configured minimum that the endpoint accepts.
+    pub legacy_protocols: Vec<String>,
+    /// A weak cipher the endpoint agreed to, if any.
+    pub weak_cipher: Option<String>,
+    pub error: Option<String>,
+}
+
+/// What the domain of a web service resolves to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DnsRecords {
+    /// CNAME chain in resolution order; empty when `dig` is not available.
+    pub cnames: Vec<String>,
+    /// A and AAAA records.
+    pub addresses: Vec<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyStats {
+    pub samples: usize,
+    pub min: Option<f64>,
+    pub median: Option<f64>,
+    pub p95: Option<f64>,
+    pub error_rate: f64,
+}
+
+impl LatencyStats {
+    /// Builds the stats from successful response times in seconds, using
+    /// nearest-rank percentiles.
+    pub fn from_samples(mut samples: Vec<f64>, error_rate: f64) -> Self {
+        samples.sort_by(|a, b| a.total_cmp(b));
+        let percentile = |p: f64| {
+            if samples.is_empty() {
+                return None;
+            }
+            let rank = (p * samples.len() as f64).ceil() as usize;
+            samples.get(rank.saturating_sub(1)).copied()
+        };
+
+        Self {
+            samples: samples.len(),
+            min: samples.first().copied(),
+            median: percentile(0.5),
+            p95: percentile(0.95),
+            error_rate,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloudInstance {
+    pub provider: String,
+    pub name: String,
+    pub location: Option<String>,
+    pub machine_type: Option<String>,
+    pub status: String,
+    pub public_ips: Vec<String>,
+    pub private_ips: Vec<String>,
+    /// Inventory host this instance was matched to by name or IP.
+    pub matched_host: Option<String>,
+}
+
+impl CloudInstance {
+    pub fn matches(&self, host: &VmHost) -> bool {
+        let host_ips = host.addresses();
+
+        self.name == host.name
+            || self
+                .public_ips
+                .iter()
+                .chain(self.private_ips.iter())
+                .any(|ip| host_ips.contains(&ip))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxmoxInventory {
+    pub nodes: Vec<ProxmoxNode>,
+    pub guests: Vec<ProxmoxGuest>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxmoxNode {
+    pub name: String,
+    pub status: String,
+    pub cpu_usage: Option<f64>,
+    pub max_cpu: Option<u64>,
+    pub mem_used: Option<u64>,
+    pub max_mem: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxmoxGuest {
+    pub vmid: u64,
+    pub name: String,
+    /// `qemu` for VMs, `lxc` for containers.
+    pub kind: String,
+    pub node: String,
+    pub status: String,
+    pub max_cpu: Option<u64>,
+    pub max_mem: Option<u64>,
+    pub max_disk: Option<u64>,
+    pub matched_host: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerraformResource {
+    pub address: String,
+    pub kind: String,
+    pub name: String,
+    pub ips: Vec<String>,
+    pub matched_host: Option<String>,
+    pub reachable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerraformComparison {
+    pub resources: Vec<TerraformResource>,
+    /// Inventory hosts with no matching resource in the state.
+    pub unmanaged_hosts: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InventoryReport {
     pub timestamp: DateTime<Utc>,
     pub vms: Vec<VmStatus>,
     pub web_services: Vec<WebService>,
+    pub cloud_instances: Vec<CloudInstance>,
+    pub proxmox: Option<ProxmoxInventory>,
+    pub terraform: Option<TerraformComparison>,
     pub summary: Summary,
-    pub critical_issues: Vec<String>,
-    pub warnings: Vec<String>,
+    pub critical_issues: Vec<Issue>,
+    pub warnings: Vec<Issue>,
+    pub notices: Vec<Issue>,
+    /// The scan was interrupted before every host was checked.
+    pub partial: bool,
+    /// Scan profile, which decides the checks the report covers.
+    #[serde(default)]
+    pub profile: ScanProfile,
+    #[serde(default)]
+    pub checks: CheckSelection,
+    /// Seconds the whole scan took.
+    #[serde(default)]
+    pub duration_secs: f64,
+    /// Seconds spent on each scan-wide check (web, cloud, proxmox, terraform, policy).
+    #[serde(default)]
+    pub timings: BTreeMap<String, f64>,
+    #[serde(default)]
+    pub policy_violations: Vec<PolicyViolation>,
+    #[serde(default)]
+    pub domains: Vec<DomainRegistration>,
+    /// Certificates in CT logs naming an unknown host or from an unknown CA.
+    #[serde(default)]
+    pub ct_certificates: Vec<CtCertificate>,
+    /// Suppressions in effect and the issues they hid.
+    #[serde(default)]
+    pub suppressions: Vec<ActiveSuppression>,
+    /// Failing components and what failed because of them.
+    #[serde(default)]
+    pub impacts: Vec<Impact>,
+    /// iperf3 throughput between hosts, when `[bandwidth]` is enabled.
+    #[serde(default)]
+    pub bandwidth: Vec<BandwidthResult>,
+}
+
+/// Throughput from `from` to the VPN address of `to`, measured with iperf3.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BandwidthResult {
+    pub from: String,
+    pub to: String,
+    pub mbps: Option<f64>,
+    pub error: Option<String>,
+}
+
+/// A failing component whose dependencies all work, and the failing
+/// components that depend on it. Components are named `host`,
+/// `host/check` or `web:service`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Impact {
+    pub root: String,
+    pub affected: Vec<String>,
+}
+
+/// Registration of a domain as reported by RDAP or whois.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DomainRegistration {
+    pub domain: String,
+    pub expires: Option<DateTime<Utc>>,
+    pub registrar: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CtCertificate {
+    /// crt.sh certificate id.
+    pub id: u64,
+    pub names: Vec<String>,
+    pub unknown_names: Vec<String>,
+    pub issuer: String,
+    pub unknown_issuer: bool,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+}
+
+/// A finding raised by a scan. `host` is unset for findings about the
+/// environment as a whole (web services, cloud, Terraform).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Issue {
+    pub severity: Severity,
+    pub host: Option<String>,
+    pub check: String,
+    pub message: String,
+    /// When this issue was first raised by consecutive scans of this process.
+    pub first_seen: DateTime<Utc>,
+    /// Failing components this issue follows from, when it is downstream impact.
+    #[serde(default)]
+    pub caused_by: Vec<String>,
+}
+
+impl Issue {
+    pub fn new(severity: Severity, host: Option<&str>, check: &str, message: String) -> Self {
+        Self {
+            severity,
+            host: host.map(|h| h.to_string()),
+            check: check.to_string(),
+            message,
+            first_seen: Utc::now(),
+            caused_by: Vec::new(),
+        }
+    }
+
+    /// Identity used to deduplicate an issue across scans.
+    pub fn key(&self) -> String {
+        format!("{}/{}/{}", self.host.as_deref().unwrap_or("-"), self.check, self.message)
+    }
+
+    /// Short hash of [`Issue::key`], shown in the report so the issue can be suppressed.
+    pub fn fingerprint(&self) -> String {
+        let mut hasher = DefaultHasher::new();
+        self.key().hash(&mut hasher);
+        format!("{:016x}", hasher.finish())[..12].to_string()
+    }
+}
+
+impl std::fmt::Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// A host breaking a rule from the policy file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyViolation {
+    pub rule_id: String,
+    pub description: Option<String>,
+    pub severity: Severity,
+    pub host: String,
+    pub message: String,
+}
+
+impl PolicyViolation {
+    /// One-line form used in the issue lists, e.g. `[NET-001] kingu: port 445 ...`.
+    pub fn issue(&self) -> String {
+        format!("[{}] {}: {}", self.rule_id, self.host, self.message)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
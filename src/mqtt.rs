@@ -0,0 +1,177 @@
+use crate::models::{InventoryReport, ServiceStatus, VmStatus};
+use anyhow::{Context, Result};
+use rumqttc::{AsyncClient, Event, MqttOptions, Outgoing, Packet, QoS};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+
+/// `[mqtt]` section: per-host and per-service state for homelab dashboards
+/// and automations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttConfig {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub client_id: String,
+    /// Topics are `<prefix>/<host>/reachable` and `<prefix>/<host>/service/<name>`.
+    pub topic_prefix: String,
+    /// Retained messages let late subscribers see the last known state.
+    pub retain: bool,
+    /// Also publish Home Assistant MQTT discovery configs.
+    pub home_assistant: bool,
+    pub discovery_prefix: String,
+    pub timeout_secs: u64,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: "localhost".to_string(),
+            port: 1883,
+            username: None,
+            password: None,
+            client_id: "sp-inventory".to_string(),
+            topic_prefix: "securepenguin".to_string(),
+            retain: true,
+            home_assistant: false,
+            discovery_prefix: "homeassistant".to_string(),
+            timeout_secs: 30,
+        }
+    }
+}
+
+pub struct MqttPublisher {
+    config: MqttConfig,
+}
+
+impl MqttPublisher {
+    pub fn new(config: MqttConfig) -> Self {
+        Self { config }
+    }
+
+    /// Publishes every message for the report, then disconnects. Returns the
+    /// number of messages sent.
+    pub async fn publish(&self, report: &InventoryReport) -> Result<usize> {
+        let messages = self.messages(report);
+        let count = messages.len();
+
+        let mut options = MqttOptions::new(&self.config.client_id, &self.config.host, self.config.port);
+        options.set_keep_alive(Duration::from_secs(30));
+        if let Some(ref username) = self.config.username {
+            options.set_credentials(username, self.config.password.clone().unwrap_or_default());
+        }
+        let (client, mut eventloop) = AsyncClient::new(options, 64);
+
+        let send = async {
+            for (topic, payload) in messages {
+                client.publish(topic, QoS::AtLeastOnce, self.config.retain, payload).await?;
+            }
+            Ok::<_, anyhow::Error>(())
+        };
+        // Disconnect only once the broker acknowledged everything
+        let drive = async {
+            let mut acked = 0;
+            if count == 0 {
+                client.disconnect().await?;
+            }
+            loop {
+                match eventloop.poll().await {
+                    Ok(Event::Incoming(Packet::PubAck(_))) => {
+                        acked += 1;
+                        if acked == count {
+                            client.disconnect().await?;
+                        }
+                    }
+                    Ok(Event::Outgoing(Outgoing::Disconnect)) => return Ok(()),
+                    Ok(_) => {}
+                    Err(e) => return Err(anyhow::Error::new(e)),
+                }
+            }
+        };
+
+        tokio::time::timeout(Duration::from_secs(self.config.timeout_secs), async { tokio::try_join!(send, drive) })
+            .await
+            .context("Timed out publishing to MQTT")?
+            .context(format!("Failed to publish to MQTT broker {}:{}", self.config.host, self.config.port))?;
+
+        Ok(count)
+    }
+
+    fn messages(&self, report: &InventoryReport) -> Vec<(String, String)> {
+        let mut messages = Vec::new();
+
+        for vm in &report.vms {
+            let host = topic_segment(&vm.host.name);
+            let base = format!("{}/{}", self.config.topic_prefix, host);
+
+            messages.push((
+                format!("{}/reachable", base),
+                if vm.reachable { "online" } else { "offline" }.to_string(),
+            ));
+            if self.config.home_assistant {
+                messages.push(self.discovery(vm, "reachable", "connectivity", format!("{}/reachable", base), "online"));
+            }
+
+            // An unreachable host says nothing about its services; leave the last known state
+            if !vm.reachable {
+                continue;
+            }
+            for service in &vm.services {
+                let topic = format!("{}/service/{}", base, topic_segment(&service.name));
+                messages.push((topic.clone(), service_state(&service.status).to_string()));
+                if self.config.home_assistant {
+                    messages.push(self.discovery(vm, &service.name, "running", topic, "running"));
+                }
+            }
+        }
+
+        messages
+    }
+
+    /// Home Assistant discovery config for a binary sensor on `state_topic`.
+    fn discovery(&self, vm: &VmStatus, name: &str, device_class: &str, state_topic: String, on: &str) -> (String, String) {
+        let device_id = format!("sp_{}", object_id(&vm.host.name));
+        let unique_id = format!("{}_{}", device_id, object_id(name));
+        let config = json!({
+            "name": name,
+            "unique_id": unique_id,
+            "state_topic": state_topic,
+            "value_template": format!("{{{{ 'ON' if value == '{}' else 'OFF' }}}}", on),
+            "device_class": device_class,
+            "device": {
+                "identifiers": [device_id],
+                "name": vm.host.name,
+                "manufacturer": "SecurePenguin",
+            },
+        });
+        (
+            format!("{}/binary_sensor/{}/config", self.config.discovery_prefix, unique_id),
+            config.to_string(),
+        )
+    }
+}
+
+fn service_state(status: &ServiceStatus) -> &'static str {
+    match status {
+        ServiceStatus::Running => "running",
+        ServiceStatus::Stopped => "stopped",
+        ServiceStatus::Failed => "failed",
+        ServiceStatus::NotFound => "not_found",
+    }
+}
+
+/// MQTT wildcards and level separators can't appear inside a topic level.
+fn topic_segment(name: &str) -> String {
+    name.replace(['/', '+', '#'], "_")
+}
+
+/// Home Assistant object ids only allow `[a-zA-Z0-9_-]`.
+fn object_id(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect()
+}
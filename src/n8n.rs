@@ -0,0 +1,96 @@
+use crate::models::InventoryReport;
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct N8nConfig {
+    pub enabled: bool,
+    /// Production URL of the workflow's Webhook node.
+    pub webhook_url: String,
+    pub payload: WebhookPayload,
+    /// Optional header for the Webhook node's "Header Auth".
+    pub auth_header: Option<String>,
+    pub auth_value: Option<String>,
+    pub timeout_secs: u64,
+}
+
+impl Default for N8nConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            webhook_url: String::new(),
+            payload: WebhookPayload::Summary,
+            auth_header: None,
+            auth_value: None,
+            timeout_secs: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookPayload {
+    /// Counts, issues and unreachable hosts only.
+    Summary,
+    /// The whole inventory report as JSON.
+    Report,
+}
+
+/// Notifies an n8n workflow after each scan so routing, ticketing and
+/// alerting can live in n8n instead of here.
+pub struct N8nWebhook {
+    config: N8nConfig,
+    client: Client,
+}
+
+impl N8nWebhook {
+    pub fn new(config: N8nConfig) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()?;
+        Ok(Self { config, client })
+    }
+
+    pub async fn trigger(&self, report: &InventoryReport) -> Result<()> {
+        let body = match self.config.payload {
+            WebhookPayload::Summary => Self::summary(report),
+            WebhookPayload::Report => serde_json::to_value(report)?,
+        };
+
+        let mut request = self.client.post(&self.config.webhook_url).json(&body);
+        if let (Some(header), Some(value)) = (&self.config.auth_header, &self.config.auth_value) {
+            request = request.header(header, value);
+        }
+
+        request
+            .send()
+            .await?
+            .error_for_status()
+            .context("n8n webhook rejected the request")?;
+
+        Ok(())
+    }
+
+    fn summary(report: &InventoryReport) -> Value {
+        let unreachable: Vec<&str> = report
+            .vms
+            .iter()
+            .filter(|vm| !vm.reachable)
+            .map(|vm| vm.host.name.as_str())
+            .collect();
+
+        json!({
+            "timestamp": report.timestamp,
+            "partial": report.partial,
+            "summary": report.summary,
+            "unreachable_hosts": unreachable,
+            "critical_issues": report.critical_issues,
+            "warnings": report.warnings,
+            "notices": report.notices,
+        })
+    }
+}
@@ -0,0 +1,245 @@
+use crate::models::{CheckResult, InventoryReport, Severity};
+use anyhow::{Context, Result};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tracing::debug;
+
+/// Checks that run once per scan rather than per host; their results are
+/// submitted as services of `scan_host`.
+const SCAN_CHECKS: [&str; 6] = ["web", "domains", "ct", "cloud", "proxmox", "terraform"];
+
+/// `[nagios]` section: passive check results for an existing Nagios or
+/// Icinga setup. Hosts and services must be defined there as passive
+/// checks; results for unknown ones are dropped by the monitoring side.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NagiosConfig {
+    pub enabled: bool,
+    /// External command file (named pipe) of Nagios or Icinga 1.x, e.g.
+    /// `/var/lib/nagios4/rw/nagios.cmd`.
+    pub command_file: Option<String>,
+    /// Icinga 2 API base URL, e.g. `https://icinga.lan:5665`.
+    pub icinga_url: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Icinga 2 signs its API certificate with its own CA.
+    pub accept_invalid_certs: bool,
+    /// Host the scan-wide checks and web services are reported on.
+    pub scan_host: String,
+    /// Prepended to every service name, e.g. `sp-` for `sp-disk`.
+    pub service_prefix: String,
+}
+
+impl Default for NagiosConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            command_file: None,
+            icinga_url: None,
+            username: None,
+            password: None,
+            accept_invalid_certs: false,
+            scan_host: "securepenguin".to_string(),
+            service_prefix: String::new(),
+        }
+    }
+}
+
+/// One passive result: a host check when `service` is `None`.
+#[derive(Debug, Clone)]
+pub struct PassiveResult {
+    pub host: String,
+    pub service: Option<String>,
+    /// 0 OK/UP, 1 WARNING/DOWN, 2 CRITICAL, 3 UNKNOWN.
+    pub code: u8,
+    pub output: String,
+    pub perfdata: Option<String>,
+}
+
+impl NagiosConfig {
+    /// Results for the report: a host check per host from its connectivity,
+    /// a service per host check, one per web service and one per scan-wide
+    /// check that ran. Skipped checks are left out so they go stale instead
+    /// of flapping to UNKNOWN.
+    pub fn results(&self, report: &InventoryReport) -> Vec<PassiveResult> {
+        let mut results = Vec::new();
+        let service = |name: &str| Some(format!("{}{}", self.service_prefix, name));
+
+        for vm in &report.vms {
+            let connectivity = vm.checks.iter().find(|c| c.name == "connectivity");
+            let down = !vm.reachable && connectivity.is_some_and(|c| c.result == CheckResult::Failed);
+            results.push(PassiveResult {
+                host: vm.host.name.clone(),
+                service: None,
+                code: u8::from(down),
+                output: match connectivity {
+                    Some(c) if !c.messages.is_empty() => summarize(&c.messages),
+                    _ if vm.reachable => format!("reachable via {}", vm.connected_ip.as_deref().unwrap_or(&vm.host.ip)),
+                    _ => "not reachable".to_string(),
+                },
+                perfdata: None,
+            });
+
+            for check in vm.checks.iter().filter(|c| c.name != "connectivity") {
+                let code = match check.result {
+                    CheckResult::Passed => 0,
+                    CheckResult::Warning => 1,
+                    CheckResult::Failed => 2,
+                    CheckResult::Skipped => continue,
+                };
+                results.push(PassiveResult {
+                    host: vm.host.name.clone(),
+                    service: service(&check.name),
+                    code,
+                    output: if check.messages.is_empty() { "OK".to_string() } else { summarize(&check.messages) },
+                    perfdata: None,
+                });
+            }
+        }
+
+        for web in &report.web_services {
+            let failure = web.error.as_deref().or(web.content_error.as_deref());
+            let (code, output) = match failure {
+                Some(error) => (2, summarize(&[error.to_string()])),
+                None if web.unexpected_status => {
+                    (2, format!("unexpected HTTP {}", web.http_status.map(|s| s.to_string()).unwrap_or_default()))
+                }
+                None => (0, format!("HTTP {}", web.http_status.map(|s| s.to_string()).unwrap_or_default())),
+            };
+            results.push(PassiveResult {
+                host: self.scan_host.clone(),
+                service: service(&web.name),
+                code,
+                output,
+                perfdata: web.response_time.map(|secs| format!("time={:.3}s", secs)),
+            });
+        }
+
+        for check in SCAN_CHECKS.iter().filter(|check| report.timings.contains_key(**check)) {
+            let issues: Vec<_> = report
+                .critical_issues
+                .iter()
+                .chain(&report.warnings)
+                .filter(|issue| issue.host.is_none() && issue.check == *check)
+                .collect();
+            let code = if issues.iter().any(|i| i.severity == Severity::Critical) {
+                2
+            } else {
+                u8::from(!issues.is_empty())
+            };
+            let messages: Vec<String> = issues.iter().map(|i| i.message.clone()).collect();
+            results.push(PassiveResult {
+                host: self.scan_host.clone(),
+                service: service(check),
+                code,
+                output: if messages.is_empty() { "OK".to_string() } else { summarize(&messages) },
+                perfdata: None,
+            });
+        }
+
+        results
+    }
+
+    /// Submits the report's results to every configured target and returns
+    /// how many were sent.
+    pub async fn submit(&self, report: &InventoryReport) -> Result<usize> {
+        let results = self.results(report);
+        if self.command_file.is_none() && self.icinga_url.is_none() {
+            anyhow::bail!("Neither command_file nor icinga_url is set");
+        }
+        if let Some(ref path) = self.command_file {
+            self.write_command_file(path, &results, report.timestamp.timestamp()).await?;
+        }
+        if let Some(ref url) = self.icinga_url {
+            self.post_icinga(url, &results).await?;
+        }
+        Ok(results.len())
+    }
+
+    /// Appends PROCESS_HOST_CHECK_RESULT and PROCESS_SERVICE_CHECK_RESULT
+    /// commands. The file is a FIFO, so it is opened for writing only and
+    /// never created.
+    async fn write_command_file(&self, path: &str, results: &[PassiveResult], timestamp: i64) -> Result<()> {
+        let mut commands = String::new();
+        for result in results {
+            let output = match result.perfdata {
+                Some(ref perfdata) => format!("{}|{}", result.output, perfdata),
+                None => result.output.clone(),
+            };
+            match result.service {
+                Some(ref service) => commands.push_str(&format!(
+                    "[{}] PROCESS_SERVICE_CHECK_RESULT;{};{};{};{}\n",
+                    timestamp, result.host, service, result.code, output
+                )),
+                None => commands.push_str(&format!(
+                    "[{}] PROCESS_HOST_CHECK_RESULT;{};{};{}\n",
+                    timestamp, result.host, result.code, output
+                )),
+            }
+        }
+
+        let path = shellexpand::tilde(path).to_string();
+        let mut file = tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .await
+            .context(format!("Failed to open command file {}", path))?;
+        file.write_all(commands.as_bytes()).await.context(format!("Failed to write to {}", path))?;
+        Ok(())
+    }
+
+    /// Calls `/v1/actions/process-check-result` once per result. Objects not
+    /// defined in Icinga are skipped, like the command file would drop them.
+    async fn post_icinga(&self, url: &str, results: &[PassiveResult]) -> Result<()> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(15))
+            .danger_accept_invalid_certs(self.accept_invalid_certs)
+            .build()?;
+        let endpoint = format!("{}/v1/actions/process-check-result", url.trim_end_matches('/'));
+
+        let mut failed = Vec::new();
+        for result in results {
+            let mut body = match result.service {
+                Some(ref service) => json!({"type": "Service", "service": format!("{}!{}", result.host, service)}),
+                None => json!({"type": "Host", "host": result.host}),
+            };
+            body["exit_status"] = json!(result.code);
+            body["plugin_output"] = json!(result.output);
+            if let Some(ref perfdata) = result.perfdata {
+                body["performance_data"] = json!([perfdata]);
+            }
+
+            let mut request = client.post(&endpoint).header("Accept", "application/json").json(&body);
+            if let Some(ref username) = self.username {
+                request = request.basic_auth(username, self.password.as_ref());
+            }
+            let response = request.send().await.context(format!("Failed to reach Icinga API at {}", url))?;
+            let object = match result.service {
+                Some(ref service) => format!("{}!{}", result.host, service),
+                None => result.host.clone(),
+            };
+            if response.status() == StatusCode::NOT_FOUND {
+                debug!("{} is not defined in Icinga, result dropped", object);
+            } else if !response.status().is_success() {
+                failed.push(format!("{} ({})", object, response.status()));
+            }
+        }
+
+        if !failed.is_empty() {
+            anyhow::bail!("Icinga rejected {} of {} results: {}", failed.len(), results.len(), failed.join(", "));
+        }
+        Ok(())
+    }
+}
+
+/// Plugin output is a single line: the first message, then how many more.
+fn summarize(messages: &[String]) -> String {
+    let first = messages[0].replace(['\n', '\r'], " ");
+    match messages.len() {
+        1 => first,
+        n => format!("{} (+{} more)", first, n - 1),
+    }
+}
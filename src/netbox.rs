@@ -0,0 +1,129 @@
+use crate::models::InventoryReport;
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetboxConfig {
+    pub enabled: bool,
+    pub url: String,
+    pub token: String,
+    /// NetBox requires these on every device; they are used when creating new ones.
+    pub site_id: u64,
+    pub role_id: u64,
+    pub device_type_id: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct SyncSummary {
+    pub devices: usize,
+    pub ip_addresses: usize,
+    pub services: usize,
+}
+
+/// Pushes scan results into NetBox so it stays the IPAM/DCIM source of truth.
+pub struct NetboxClient {
+    config: NetboxConfig,
+    client: Client,
+}
+
+impl NetboxClient {
+    pub fn new(config: NetboxConfig) -> Result<Self> {
+        let client = Client::builder().timeout(Duration::from_secs(15)).build()?;
+        Ok(Self { config, client })
+    }
+
+    pub async fn sync(&self, report: &InventoryReport) -> Result<SyncSummary> {
+        let mut summary = SyncSummary::default();
+
+        for vm in &report.vms {
+            let device_id = self
+                .upsert(
+                    "dcim/devices",
+                    &[("name", vm.host.name.clone())],
+                    json!({
+                        "name": vm.host.name,
+                        "site": self.config.site_id,
+                        "role": self.config.role_id,
+                        "device_type": self.config.device_type_id,
+                        "status": if vm.reachable { "active" } else { "offline" },
+                    }),
+                )
+                .await
+                .context(format!("Failed to sync device {}", vm.host.name))?;
+            summary.devices += 1;
+
+            for ip in vm.host.addresses() {
+                let address = if ip.contains(':') { format!("{}/128", ip) } else { format!("{}/32", ip) };
+                self.upsert(
+                    "ipam/ip-addresses",
+                    &[("address", address.clone())],
+                    json!({
+                        "address": address,
+                        "status": "active",
+                        "description": vm.host.name,
+                    }),
+                )
+                .await
+                .context(format!("Failed to sync IP {}", ip))?;
+                summary.ip_addresses += 1;
+            }
+
+            for port in &vm.open_ports {
+                self.upsert(
+                    "ipam/services",
+                    &[("device_id", device_id.to_string()), ("port", port.port.to_string())],
+                    json!({
+                        "device": device_id,
+                        "name": port.process,
+                        "protocol": if port.protocol.starts_with("udp") { "udp" } else { "tcp" },
+                        "ports": [port.port],
+                    }),
+                )
+                .await
+                .context(format!("Failed to sync service {}:{}", vm.host.name, port.port))?;
+                summary.services += 1;
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Updates the first object matching `query`, or creates it. Returns its id.
+    async fn upsert(&self, endpoint: &str, query: &[(&str, String)], body: Value) -> Result<u64> {
+        let url = format!("{}/api/{}/", self.config.url.trim_end_matches('/'), endpoint);
+
+        let existing: Value = self
+            .client
+            .get(&url)
+            .query(query)
+            .header("Authorization", format!("Token {}", self.config.token))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let request = match existing.pointer("/results/0/id").and_then(|id| id.as_u64()) {
+            Some(id) => self.client.patch(format!("{}{}/", url, id)),
+            None => self.client.post(&url),
+        };
+
+        let saved: Value = request
+            .header("Authorization", format!("Token {}", self.config.token))
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        saved
+            .get("id")
+            .and_then(|id| id.as_u64())
+            .context("NetBox response has no id")
+    }
+}
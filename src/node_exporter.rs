@@ -0,0 +1,151 @@
+use crate::models::{url_host, Filesystem, LoadAverage, Memory, VmHost};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+use tracing::debug;
+
+/// Prometheus node_exporter on the hosts, scraped over the VPN when it
+/// answers. Its load, memory and filesystem metrics replace the SSH
+/// commands that would collect the same.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NodeExporterConfig {
+    pub enabled: bool,
+    pub port: u16,
+    pub timeout_ms: u64,
+}
+
+impl Default for NodeExporterConfig {
+    fn default() -> Self {
+        Self { enabled: true, port: 9100, timeout_ms: 2000 }
+    }
+}
+
+/// Pseudo and image filesystems are always full; the same ones `df` skips.
+const IGNORED_FSTYPES: [&str; 5] = ["tmpfs", "devtmpfs", "overlay", "squashfs", "efivarfs"];
+
+/// What a node_exporter scrape provided.
+pub struct NodeMetrics {
+    pub load: Option<LoadAverage>,
+    pub memory: Option<Memory>,
+    pub filesystems: Vec<Filesystem>,
+}
+
+/// One sample of the text exposition format.
+struct Sample<'a> {
+    name: &'a str,
+    labels: BTreeMap<&'a str, &'a str>,
+    value: f64,
+}
+
+impl NodeExporterConfig {
+    /// Scrapes the host's exporter, on the VPN address first. `None` when
+    /// no exporter answers.
+    pub async fn scrape(&self, host: &VmHost) -> Option<NodeMetrics> {
+        if !self.enabled {
+            return None;
+        }
+        let client = Client::builder().timeout(Duration::from_millis(self.timeout_ms)).build().ok()?;
+
+        for address in host.vpn_ip.iter().chain(std::iter::once(&host.ip)) {
+            let url = format!("http://{}:{}/metrics", url_host(address), self.port);
+            let body = match client.get(&url).send().await.and_then(|r| r.error_for_status()) {
+                Ok(response) => match response.text().await {
+                    Ok(body) => body,
+                    Err(e) => {
+                        debug!("node_exporter at {} unreadable: {}", url, e);
+                        continue;
+                    }
+                },
+                Err(e) => {
+                    debug!("No node_exporter at {}: {}", url, e);
+                    continue;
+                }
+            };
+            return Some(parse_metrics(&body));
+        }
+        None
+    }
+}
+
+fn parse_metrics(body: &str) -> NodeMetrics {
+    let samples: Vec<Sample> = body.lines().filter(|line| !line.starts_with('#')).filter_map(parse_sample).collect();
+    let value = |name: &str| samples.iter().find(|s| s.name == name).map(|s| s.value);
+
+    let cores: BTreeSet<&str> = samples
+        .iter()
+        .filter(|s| s.name == "node_cpu_seconds_total" && s.labels.get("mode") == Some(&"idle"))
+        .filter_map(|s| s.labels.get("cpu").copied())
+        .collect();
+    let load = match (value("node_load1"), value("node_load5"), value("node_load15")) {
+        (Some(one), Some(five), Some(fifteen)) => Some(LoadAverage { one, five, fifteen, cores: cores.len() as u32 }),
+        _ => None,
+    };
+
+    let memory = match (value("node_memory_MemTotal_bytes"), value("node_memory_MemAvailable_bytes")) {
+        (Some(total), Some(available)) => Some(Memory { total_bytes: total as u64, available_bytes: available as u64 }),
+        _ => None,
+    };
+
+    // node_filesystem_*{device, fstype, mountpoint}, one sample per metric
+    let filesystem_value = |name: &str, mountpoint: &str| {
+        samples.iter().find(|s| s.name == name && s.labels.get("mountpoint") == Some(&mountpoint)).map(|s| s.value)
+    };
+    let mut filesystems: Vec<Filesystem> = Vec::new();
+    for sample in samples.iter().filter(|s| s.name == "node_filesystem_size_bytes") {
+        let (Some(mountpoint), Some(device)) = (sample.labels.get("mountpoint"), sample.labels.get("device")) else {
+            continue;
+        };
+        let fstype = sample.labels.get("fstype").copied().unwrap_or_default();
+        if IGNORED_FSTYPES.contains(&fstype) || sample.value <= 0.0 || filesystems.iter().any(|fs| fs.mountpoint == *mountpoint) {
+            continue;
+        }
+        // Used over used plus available, rounded up, as df computes Use%
+        let percent = |used: f64, available: f64| {
+            (used + available > 0.0).then(|| (used * 100.0 / (used + available)).ceil().min(100.0) as u8)
+        };
+        let free = filesystem_value("node_filesystem_free_bytes", mountpoint).unwrap_or(0.0);
+        let available = filesystem_value("node_filesystem_avail_bytes", mountpoint).unwrap_or(free);
+        let inodes = filesystem_value("node_filesystem_files", mountpoint).unwrap_or(0.0);
+        let inodes_free = filesystem_value("node_filesystem_files_free", mountpoint).unwrap_or(0.0);
+
+        filesystems.push(Filesystem {
+            mountpoint: mountpoint.to_string(),
+            device: device.to_string(),
+            size_bytes: sample.value as u64,
+            used_percent: percent(sample.value - free, available).unwrap_or(0),
+            inodes_used_percent: if inodes > 0.0 { percent(inodes - inodes_free, inodes_free) } else { None },
+        });
+    }
+
+    NodeMetrics { load, memory, filesystems }
+}
+
+/// Parses `name{label="value",...} 1.5`, ignoring an optional timestamp.
+fn parse_sample(line: &str) -> Option<Sample<'_>> {
+    let (series, rest) = match line.find('{') {
+        Some(_) => {
+            let close = line.rfind('}')?;
+            (&line[..close + 1], &line[close + 1..])
+        }
+        None => line.split_once(' ')?,
+    };
+    let value = rest.split_whitespace().next()?.parse().ok()?;
+
+    let (name, labels) = match series.split_once('{') {
+        Some((name, labels)) => {
+            let labels = labels
+                .trim_end_matches('}')
+                .split("\",")
+                .filter_map(|pair| {
+                    let (key, value) = pair.split_once("=\"")?;
+                    Some((key.trim(), value.trim_end_matches('"')))
+                })
+                .collect();
+            (name, labels)
+        }
+        None => (series, BTreeMap::new()),
+    };
+    Some(Sample { name, labels, value })
+}
@@ -0,0 +1,77 @@
+use super::{Notification, Notifier};
+use crate::models::Severity;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GotifyConfig {
+    pub enabled: bool,
+    pub url: String,
+    /// Application token created in Gotify for this scanner.
+    pub app_token: String,
+    pub min_severity: Severity,
+}
+
+impl Default for GotifyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: String::new(),
+            app_token: String::new(),
+            min_severity: Severity::Warning,
+        }
+    }
+}
+
+/// Pushes messages to a Gotify server as an application.
+pub struct GotifyNotifier {
+    config: GotifyConfig,
+    client: Client,
+}
+
+impl GotifyNotifier {
+    pub fn new(config: GotifyConfig) -> Self {
+        Self {
+            config,
+            client: Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for GotifyNotifier {
+    fn name(&self) -> &str {
+        "gotify"
+    }
+
+    fn min_severity(&self) -> Severity {
+        self.config.min_severity
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        // Gotify clients pop up and ring from priority 8, stay silent below 4
+        let priority = match notification.severity {
+            Severity::Critical => 8,
+            Severity::Warning => 5,
+            Severity::Info => 2,
+        };
+
+        self.client
+            .post(format!("{}/message", self.config.url.trim_end_matches('/')))
+            .header("X-Gotify-Key", &self.config.app_token)
+            .json(&json!({
+                "title": notification.title,
+                "message": notification.message,
+                "priority": priority,
+            }))
+            .send()
+            .await?
+            .error_for_status()
+            .context("Gotify rejected the message")?;
+        Ok(())
+    }
+}
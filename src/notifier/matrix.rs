@@ -0,0 +1,89 @@
+use super::{Notification, Notifier};
+use crate::models::Severity;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatrixConfig {
+    pub enabled: bool,
+    pub homeserver: String,
+    /// Access token of the bot account, which must have joined the room.
+    pub access_token: String,
+    /// Internal room ID (`!abc123:example.org`), not an alias.
+    pub room_id: String,
+    pub min_severity: Severity,
+}
+
+impl Default for MatrixConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            homeserver: "https://matrix.org".to_string(),
+            access_token: String::new(),
+            room_id: String::new(),
+            min_severity: Severity::Warning,
+        }
+    }
+}
+
+/// Posts to a Matrix room through the client-server API.
+pub struct MatrixNotifier {
+    config: MatrixConfig,
+    client: Client,
+}
+
+impl MatrixNotifier {
+    pub fn new(config: MatrixConfig) -> Self {
+        Self {
+            config,
+            client: Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for MatrixNotifier {
+    fn name(&self) -> &str {
+        "matrix"
+    }
+
+    fn min_severity(&self) -> Severity {
+        self.config.min_severity
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let escape = |s: &str| s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+        let items: Vec<String> = notification.message.lines().map(|line| format!("<li>{}</li>", escape(line))).collect();
+        let html = format!("<strong>{}</strong><ul>{}</ul>", escape(&notification.title), items.concat());
+        // Notices are meant for bots and don't ping; critical findings should
+        let msgtype = if notification.severity == Severity::Critical { "m.text" } else { "m.notice" };
+
+        // The transaction ID makes a retried request idempotent
+        let txn_id = format!("sp-inventory-{}", Utc::now().timestamp_nanos_opt().unwrap_or_default());
+        let mut url = Url::parse(&self.config.homeserver).context("Invalid Matrix homeserver URL")?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("Invalid Matrix homeserver URL"))?
+            .pop_if_empty()
+            .extend(["_matrix", "client", "v3", "rooms", &self.config.room_id, "send", "m.room.message", &txn_id]);
+
+        self.client
+            .put(url)
+            .bearer_auth(&self.config.access_token)
+            .json(&json!({
+                "msgtype": msgtype,
+                "body": format!("{}\n{}", notification.title, notification.message),
+                "format": "org.matrix.custom.html",
+                "formatted_body": html,
+            }))
+            .send()
+            .await?
+            .error_for_status()
+            .context("Matrix homeserver rejected the message")?;
+        Ok(())
+    }
+}
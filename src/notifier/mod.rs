@@ -0,0 +1,116 @@
+pub mod gotify;
+pub mod matrix;
+pub mod ntfy;
+
+use crate::config::Config;
+use crate::models::{InventoryReport, Issue, Severity};
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::{error, info};
+
+/// Maximum issues listed in one push notification; phones truncate anyway.
+const MAX_LISTED_ISSUES: usize = 15;
+
+/// A push message summarizing a scan, at the severity of its worst issue.
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub title: String,
+    pub message: String,
+    pub severity: Severity,
+}
+
+impl Notification {
+    /// Builds the notification for a report, or `None` when nothing at or
+    /// above `min_severity` was found.
+    pub fn from_report(report: &InventoryReport, min_severity: Severity) -> Option<Self> {
+        let mut issues: Vec<(&str, &Issue)> = report.critical_issues.iter().map(|i| ("❌", i)).collect();
+        if rank(min_severity) <= rank(Severity::Warning) {
+            issues.extend(report.warnings.iter().map(|w| ("⚠️", w)));
+        }
+        if rank(min_severity) <= rank(Severity::Info) {
+            issues.extend(report.notices.iter().map(|n| ("ℹ️", n)));
+        }
+        if issues.is_empty() {
+            return None;
+        }
+
+        let severity = if !report.critical_issues.is_empty() {
+            Severity::Critical
+        } else if !report.warnings.is_empty() && rank(min_severity) <= rank(Severity::Warning) {
+            Severity::Warning
+        } else {
+            Severity::Info
+        };
+
+        let mut message: Vec<String> = issues
+            .iter()
+            .take(MAX_LISTED_ISSUES)
+            .map(|(icon, issue)| format!("{} {}", icon, issue))
+            .collect();
+        if issues.len() > MAX_LISTED_ISSUES {
+            message.push(format!("... y {} más", issues.len() - MAX_LISTED_ISSUES));
+        }
+
+        Some(Self {
+            title: format!(
+                "SecurePenguin: {} críticos, {} warnings ({}/{} VMs)",
+                report.critical_issues.len(),
+                report.warnings.len(),
+                report.summary.reachable_vms,
+                report.summary.total_vms
+            ),
+            message: message.join("\n"),
+            severity,
+        })
+    }
+}
+
+/// Lower is more severe.
+fn rank(severity: Severity) -> u8 {
+    match severity {
+        Severity::Critical => 0,
+        Severity::Warning => 1,
+        Severity::Info => 2,
+    }
+}
+
+/// A push notification backend.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Severity below which this backend stays silent.
+    fn min_severity(&self) -> Severity;
+
+    async fn send(&self, notification: &Notification) -> Result<()>;
+}
+
+pub fn enabled_notifiers(config: &Config) -> Vec<Box<dyn Notifier>> {
+    let mut notifiers: Vec<Box<dyn Notifier>> = Vec::new();
+
+    if config.ntfy.enabled {
+        notifiers.push(Box::new(ntfy::NtfyNotifier::new(config.ntfy.clone())));
+    }
+    if config.gotify.enabled {
+        notifiers.push(Box::new(gotify::GotifyNotifier::new(config.gotify.clone())));
+    }
+    if config.matrix.enabled {
+        notifiers.push(Box::new(matrix::MatrixNotifier::new(config.matrix.clone())));
+    }
+
+    notifiers
+}
+
+/// Sends the report's notification through every backend; failures are
+/// logged so one broken backend doesn't silence the others.
+pub async fn notify_all(notifiers: &[Box<dyn Notifier>], report: &InventoryReport) {
+    for notifier in notifiers {
+        let Some(notification) = Notification::from_report(report, notifier.min_severity()) else {
+            continue;
+        };
+        match notifier.send(&notification).await {
+            Ok(()) => info!("Notificación enviada por {}", notifier.name()),
+            Err(e) => error!("{} notification failed: {:#}", notifier.name(), e),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use super::{Notification, Notifier};
+use crate::models::Severity;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NtfyConfig {
+    pub enabled: bool,
+    pub server: String,
+    pub topic: String,
+    /// Access token for protected topics.
+    pub token: Option<String>,
+    pub min_severity: Severity,
+}
+
+impl Default for NtfyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            server: "https://ntfy.sh".to_string(),
+            topic: String::new(),
+            token: None,
+            min_severity: Severity::Warning,
+        }
+    }
+}
+
+/// Publishes to an ntfy topic (ntfy.sh or self-hosted).
+pub struct NtfyNotifier {
+    config: NtfyConfig,
+    client: Client,
+}
+
+impl NtfyNotifier {
+    pub fn new(config: NtfyConfig) -> Self {
+        Self {
+            config,
+            client: Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for NtfyNotifier {
+    fn name(&self) -> &str {
+        "ntfy"
+    }
+
+    fn min_severity(&self) -> Severity {
+        self.config.min_severity
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        // ntfy priorities: 1 min .. 5 max/urgent
+        let (priority, tags) = match notification.severity {
+            Severity::Critical => ("5", "rotating_light"),
+            Severity::Warning => ("3", "warning"),
+            Severity::Info => ("2", "information_source"),
+        };
+
+        let mut request = self
+            .client
+            .post(format!("{}/{}", self.config.server.trim_end_matches('/'), self.config.topic))
+            // Query parameters rather than headers: titles aren't ASCII
+            .query(&[("title", notification.title.as_str()), ("priority", priority), ("tags", tags)])
+            .body(notification.message.clone());
+        if let Some(ref token) = self.config.token {
+            request = request.bearer_auth(token);
+        }
+
+        request
+            .send()
+            .await?
+            .error_for_status()
+            .context("ntfy rejected the message")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use anyhow::{Context, Result};
+use reqwest::{Client, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+/// Obsidian LiveSync keeps note contents in separate "leaf" chunk documents;
+/// this stays well under CouchDB's document size limits.
+const CHUNK_CHARS: usize = 32 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ObsidianConfig {
+    pub enabled: bool,
+    /// CouchDB server used by the Self-hosted LiveSync plugin.
+    pub url: String,
+    pub database: String,
+    pub username: String,
+    pub password: String,
+    /// Vault path of the note the report is written to.
+    pub note_path: String,
+    /// Must match LiveSync's "Handle files as Case-Sensitive" setting.
+    pub case_sensitive: bool,
+}
+
+impl Default for ObsidianConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: "http://localhost:5984".to_string(),
+            database: "obsidian".to_string(),
+            username: String::new(),
+            password: String::new(),
+            note_path: "SecurePenguin/Inventario.md".to_string(),
+            case_sensitive: false,
+        }
+    }
+}
+
+/// Writes the markdown report into the LiveSync database so it shows up as a
+/// note in the vault. End-to-end encryption and path obfuscation are not
+/// supported: the plugin would not be able to read what we write.
+pub struct ObsidianPublisher {
+    config: ObsidianConfig,
+    client: Client,
+    database: Url,
+}
+
+impl ObsidianPublisher {
+    pub fn new(config: ObsidianConfig) -> Result<Self> {
+        let client = Client::builder().timeout(Duration::from_secs(15)).build()?;
+        let mut database = Url::parse(&config.url).context("Invalid CouchDB URL")?;
+        database
+            .path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("Invalid CouchDB URL: {}", config.url))?
+            .pop_if_empty()
+            .push(&config.database);
+        Ok(Self { config, client, database })
+    }
+
+    pub async fn publish(&self, markdown: &str) -> Result<()> {
+        let mut children = Vec::new();
+        for chunk in chunks(markdown) {
+            children.push(self.put_chunk(&chunk).await?);
+        }
+
+        let id = self.note_id();
+        let existing = self.get(&id).await?;
+        let now = chrono::Utc::now().timestamp_millis();
+
+        let mut note = json!({
+            "_id": id,
+            "path": self.config.note_path,
+            "children": children,
+            "ctime": existing.as_ref().and_then(|doc| doc.get("ctime")).cloned().unwrap_or(json!(now)),
+            "mtime": now,
+            "size": markdown.len(),
+            "type": "plain",
+            "eden": {},
+        });
+        if let Some(rev) = existing.as_ref().and_then(|doc| doc.get("_rev")) {
+            note["_rev"] = rev.clone();
+        }
+
+        self.request(self.client.put(self.document_url(&id)))
+            .json(&note)
+            .send()
+            .await?
+            .error_for_status()
+            .context(format!("Failed to write note {}", self.config.note_path))?;
+
+        Ok(())
+    }
+
+    /// Stores a content-addressed chunk. Chunks are immutable, so an existing
+    /// one (409 Conflict) already holds the same data.
+    async fn put_chunk(&self, data: &str) -> Result<String> {
+        let mut hasher = DefaultHasher::new();
+        data.hash(&mut hasher);
+        let id = format!("h:{:016x}{:x}", hasher.finish(), data.len());
+
+        let response = self
+            .request(self.client.put(self.document_url(&id)))
+            .json(&json!({ "_id": id, "data": data, "type": "leaf" }))
+            .send()
+            .await?;
+        if response.status() != StatusCode::CONFLICT {
+            response.error_for_status().context("Failed to write note chunk")?;
+        }
+
+        Ok(id)
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<Value>> {
+        let response = self.request(self.client.get(self.document_url(id))).send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(response.error_for_status()?.json().await?))
+    }
+
+    /// Document id LiveSync derives from a vault path.
+    fn note_id(&self) -> String {
+        let path = if self.config.case_sensitive {
+            self.config.note_path.clone()
+        } else {
+            self.config.note_path.to_lowercase()
+        };
+        // Ids starting with "_" are reserved by CouchDB
+        if path.starts_with('_') {
+            format!("/{}", path)
+        } else {
+            path
+        }
+    }
+
+    fn document_url(&self, id: &str) -> Url {
+        let mut url = self.database.clone();
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.push(id);
+        }
+        url
+    }
+
+    fn request(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        if self.config.username.is_empty() {
+            request
+        } else {
+            request.basic_auth(&self.config.username, Some(&self.config.password))
+        }
+    }
+}
+
+fn chunks(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.is_empty() {
+        return vec![String::new()];
+    }
+    chars.chunks(CHUNK_CHARS).map(|c| c.iter().collect()).collect()
+}
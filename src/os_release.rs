@@ -0,0 +1,87 @@
+use crate::models::{OsRelease, Severity};
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How early to warn about a distribution approaching its end of life.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OsConfig {
+    pub eol_warning_days: i64,
+}
+
+impl Default for OsConfig {
+    fn default() -> Self {
+        Self { eol_warning_days: 180 }
+    }
+}
+
+/// End of security support by `/etc/os-release` ID and version, counting
+/// extended/LTS support where the distribution offers it for free. Versions
+/// match on their leading components, so `8` covers `8.9`.
+const END_OF_LIFE: &[(&str, &str, &str)] = &[
+    ("debian", "9", "2022-06-30"),
+    ("debian", "10", "2024-06-30"),
+    ("debian", "11", "2026-08-31"),
+    ("debian", "12", "2028-06-30"),
+    ("debian", "13", "2030-06-30"),
+    ("raspbian", "10", "2024-06-30"),
+    ("raspbian", "11", "2026-08-31"),
+    ("raspbian", "12", "2028-06-30"),
+    ("ubuntu", "16.04", "2021-04-30"),
+    ("ubuntu", "18.04", "2023-05-31"),
+    ("ubuntu", "20.04", "2025-05-29"),
+    ("ubuntu", "22.04", "2027-04-01"),
+    ("ubuntu", "23.04", "2024-01-25"),
+    ("ubuntu", "23.10", "2024-07-11"),
+    ("ubuntu", "24.04", "2029-04-25"),
+    ("ubuntu", "24.10", "2025-07-10"),
+    ("ubuntu", "25.04", "2026-01-15"),
+    ("centos", "7", "2024-06-30"),
+    ("centos", "8", "2021-12-31"),
+    ("centos", "9", "2027-05-31"),
+    ("rhel", "7", "2024-06-30"),
+    ("rhel", "8", "2029-05-31"),
+    ("rhel", "9", "2032-05-31"),
+    ("rocky", "8", "2029-05-31"),
+    ("rocky", "9", "2032-05-31"),
+    ("almalinux", "8", "2029-05-31"),
+    ("almalinux", "9", "2032-05-31"),
+    ("fedora", "39", "2024-11-26"),
+    ("fedora", "40", "2025-05-13"),
+    ("fedora", "41", "2025-12-15"),
+    ("fedora", "42", "2026-05-13"),
+    ("alpine", "3.17", "2024-11-22"),
+    ("alpine", "3.18", "2025-05-09"),
+    ("alpine", "3.19", "2025-11-01"),
+    ("alpine", "3.20", "2026-04-01"),
+    ("alpine", "3.21", "2026-11-01"),
+    ("alpine", "3.22", "2027-05-01"),
+    ("opensuse-leap", "15.5", "2024-12-31"),
+    ("opensuse-leap", "15.6", "2025-12-31"),
+];
+
+/// End of support of `os`, when the table knows its release.
+pub fn end_of_life(os: &OsRelease) -> Option<NaiveDate> {
+    END_OF_LIFE
+        .iter()
+        .find(|(id, version, _)| {
+            *id == os.id && (os.version_id == *version || os.version_id.starts_with(&format!("{}.", version)))
+        })
+        .and_then(|(_, _, date)| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+}
+
+impl OsConfig {
+    /// Whether `os` is past or near its end of life, as an issue message
+    /// without the host.
+    pub fn eol_issue(&self, os: &OsRelease) -> Option<(Severity, String)> {
+        let eol = end_of_life(os)?;
+        let days_left = (eol - Utc::now().date_naive()).num_days();
+        if days_left < 0 {
+            Some((Severity::Critical, format!("{} reached end of life on {}", os.pretty_name, eol)))
+        } else if days_left <= self.eol_warning_days {
+            Some((Severity::Warning, format!("{} reaches end of life on {} ({} days left)", os.pretty_name, eol, days_left)))
+        } else {
+            None
+        }
+    }
+}
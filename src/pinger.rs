@@ -0,0 +1,102 @@
+use crate::models::PingResult;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio::process::Command;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PingConfig {
+    pub enabled: bool,
+    pub count: u32,
+    pub timeout_secs: u32,
+}
+
+impl Default for PingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            count: 4,
+            timeout_secs: 2,
+        }
+    }
+}
+
+pub struct Pinger {
+    config: PingConfig,
+}
+
+impl Pinger {
+    pub fn new(config: PingConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Milliseconds a TCP connection to `address` on `port` takes, or `None`
+    /// when it is refused or doesn't complete within the ping timeout.
+    pub async fn connect(&self, address: &str, port: u16) -> Option<f64> {
+        let started = Instant::now();
+        let timeout = Duration::from_secs(self.config.timeout_secs.into());
+        match tokio::time::timeout(timeout, TcpStream::connect((address, port))).await {
+            Ok(Ok(_)) => Some(started.elapsed().as_secs_f64() * 1000.0),
+            _ => None,
+        }
+    }
+
+    /// Pings `target` with the system `ping` and parses the summary lines.
+    pub async fn ping(&self, target: &str) -> PingResult {
+        let mut result = PingResult {
+            target: target.to_string(),
+            transmitted: self.config.count,
+            received: 0,
+            loss_percent: 100.0,
+            min_ms: None,
+            avg_ms: None,
+            max_ms: None,
+        };
+
+        let output = Command::new("ping")
+            .args([
+                "-c", &self.config.count.to_string(),
+                "-W", &self.config.timeout_secs.to_string(),
+                "-q",
+                target,
+            ])
+            .output()
+            .await;
+
+        let Ok(output) = output else {
+            return result;
+        };
+
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            // 4 packets transmitted, 3 received, 25% packet loss, time 3004ms
+            if line.contains("packets transmitted") {
+                for part in line.split(',').map(|p| p.trim()) {
+                    if let Some(n) = part.strip_suffix(" packets transmitted") {
+                        result.transmitted = n.parse().unwrap_or(result.transmitted);
+                    } else if let Some(n) = part.strip_suffix(" received") {
+                        result.received = n.parse().unwrap_or(0);
+                    } else if let Some(n) = part.strip_suffix("% packet loss") {
+                        result.loss_percent = n.parse().unwrap_or(100.0);
+                    }
+                }
+            // rtt min/avg/max/mdev = 0.040/0.050/0.060/0.010 ms
+            } else if let Some(values) = line.split(" = ").nth(1).filter(|_| line.starts_with("rtt") || line.starts_with("round-trip")) {
+                let times: Vec<f64> = values
+                    .trim_end_matches(" ms")
+                    .split('/')
+                    .filter_map(|v| v.parse().ok())
+                    .collect();
+                result.min_ms = times.first().copied();
+                result.avg_ms = times.get(1).copied();
+                result.max_ms = times.get(2).copied();
+            }
+        }
+
+        result
+    }
+}
@@ -0,0 +1,125 @@
+use crate::models::{PolicyViolation, ServiceStatus, Severity, SocketExposure, VmStatus};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PolicyConfig {
+    pub enabled: bool,
+    /// YAML file with the rules, re-read on every scan.
+    pub rules_file: String,
+}
+
+impl Default for PolicyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rules_file: "~/.config/securepenguin/policy.yaml".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RulesFile {
+    rules: Vec<Rule>,
+}
+
+/// An assertion about hosts, e.g. "port 445 must not listen on public interfaces".
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    pub id: String,
+    pub description: Option<String>,
+    pub severity: Severity,
+    /// Host names, groups and tags the rule is scoped to; all empty means every host.
+    #[serde(default)]
+    pub hosts: Vec<String>,
+    #[serde(default)]
+    pub groups: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(flatten)]
+    pub condition: Condition,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    /// Port must not be bound to all interfaces or a public address, nor reachable from the scanner.
+    PortNotPublic(u16),
+    PortNotListening(u16),
+    ContainerExists(String),
+    ServiceRunning(String),
+    /// Five-minute load average must stay below this many times the core count.
+    MaxLoadPerCore(f64),
+}
+
+pub fn load(config: &PolicyConfig) -> Result<Vec<Rule>> {
+    let path = shellexpand::tilde(&config.rules_file).to_string();
+    let content = std::fs::read_to_string(&path).context(format!("Failed to read policy rules: {}", path))?;
+    let file: RulesFile = serde_yaml::from_str(&content).context(format!("Failed to parse policy rules: {}", path))?;
+    Ok(file.rules)
+}
+
+impl Rule {
+    /// What the host does wrong, if it breaks the rule.
+    fn violation(&self, vm: &VmStatus) -> Option<String> {
+        match self.condition {
+            Condition::PortNotPublic(port) => {
+                let bound: Vec<&str> = vm
+                    .open_ports
+                    .iter()
+                    .filter(|p| p.port == port && p.exposure.map_or(p.is_public(), |e| e == SocketExposure::World))
+                    .map(|p| p.address.as_str())
+                    .collect();
+                if !bound.is_empty() {
+                    Some(format!("port {} listens on a public interface ({})", port, bound.join(", ")))
+                } else if vm.exposed_ports.iter().any(|p| p.port == port) {
+                    Some(format!("port {} is reachable from the scanner", port))
+                } else {
+                    None
+                }
+            }
+            Condition::PortNotListening(port) => vm
+                .open_ports
+                .iter()
+                .find(|p| p.port == port)
+                .map(|p| format!("port {} is listening ({})", port, p.process)),
+            Condition::ContainerExists(ref name) => (!vm.containers.iter().any(|c| &c.name == name))
+                .then(|| format!("container {} does not exist", name)),
+            Condition::ServiceRunning(ref name) => (!vm
+                .services
+                .iter()
+                .any(|s| s.name.contains(name.as_str()) && s.status == ServiceStatus::Running))
+            .then(|| format!("service {} is not running", name)),
+            Condition::MaxLoadPerCore(factor) => {
+                let load = vm.load?;
+                let limit = factor * load.cores as f64;
+                (load.five >= limit).then(|| {
+                    format!("load average {:.2} exceeds {} x {} cores", load.five, factor, load.cores)
+                })
+            }
+        }
+    }
+}
+
+/// Evaluates every rule against the hosts that were reached; an unreachable
+/// host has no data to assert on and is already reported as such.
+pub fn evaluate(rules: &[Rule], vms: &[VmStatus]) -> Vec<PolicyViolation> {
+    let mut violations = Vec::new();
+
+    for vm in vms.iter().filter(|vm| vm.reachable) {
+        for rule in rules.iter().filter(|rule| vm.host.in_scope(&rule.hosts, &rule.groups, &rule.tags)) {
+            if let Some(message) = rule.violation(vm) {
+                violations.push(PolicyViolation {
+                    rule_id: rule.id.clone(),
+                    description: rule.description.clone(),
+                    severity: rule.severity,
+                    host: vm.host.name.clone(),
+                    message,
+                });
+            }
+        }
+    }
+
+    violations
+}
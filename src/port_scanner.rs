@@ -0,0 +1,140 @@
+use crate::models::{ExposedPort, Port, SocketExposure};
+use futures::future::join_all;
+use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tracing::warn;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExposureConfig {
+    pub enabled: bool,
+    /// TCP ports probed from the scanner machine against each host's IP.
+    pub ports: Vec<u16>,
+    /// Ports that are meant to be reachable from the internet.
+    pub public_ports: Vec<u16>,
+    pub timeout_ms: u64,
+    /// Interfaces whose listeners only VPN peers reach; a trailing `*` matches any suffix.
+    pub vpn_interfaces: Vec<String>,
+    /// Address ranges of the VPNs, in CIDR notation.
+    pub vpn_networks: Vec<String>,
+}
+
+impl Default for ExposureConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ports: vec![
+                21, 22, 23, 25, 53, 80, 111, 139, 443, 445, 2049, 3000, 3306, 3389, 5432, 5678,
+                5984, 6379, 8000, 8080, 8443, 9000, 9001, 9090, 9100, 27017,
+            ],
+            public_ports: vec![22, 80, 443],
+            timeout_ms: 1500,
+            vpn_interfaces: vec!["wg*".to_string(), "tun*".to_string(), "tailscale*".to_string()],
+            vpn_networks: vec!["100.64.0.0/10".to_string(), "fd7a:115c:a1e0::/48".to_string()],
+        }
+    }
+}
+
+pub struct PortScanner {
+    config: ExposureConfig,
+    vpn_networks: Vec<IpNet>,
+}
+
+impl PortScanner {
+    pub fn new(config: ExposureConfig) -> Self {
+        let vpn_networks = config
+            .vpn_networks
+            .iter()
+            .filter_map(|network| match network.parse() {
+                Ok(network) => Some(network),
+                Err(e) => {
+                    warn!("Ignoring VPN network {}: {}", network, e);
+                    None
+                }
+            })
+            .collect();
+        Self { config, vpn_networks }
+    }
+
+    /// Classifies a listening socket by its bind address and interface.
+    pub fn classify(&self, port: &Port) -> SocketExposure {
+        let ip = port.ip();
+        let on_vpn_interface = port.interface.as_deref().is_some_and(|interface| {
+            self.config.vpn_interfaces.iter().any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => interface.starts_with(prefix),
+                None => interface == pattern,
+            })
+        });
+
+        if ip.is_some_and(|ip| ip.is_loopback()) || port.interface.as_deref() == Some("lo") {
+            SocketExposure::Loopback
+        } else if on_vpn_interface || ip.is_some_and(|ip| self.vpn_networks.iter().any(|n| n.contains(&ip))) {
+            SocketExposure::Vpn
+        } else if port.is_public() {
+            SocketExposure::World
+        } else {
+            SocketExposure::Private
+        }
+    }
+
+    /// Ports that are meant to be reachable from the internet.
+    pub fn is_expected_public(&self, port: u16) -> bool {
+        self.config.public_ports.contains(&port)
+    }
+
+    /// Returns the configured ports that accept a TCP connection on `ip`,
+    /// annotated with the process and exposure of the TCP socket listening on
+    /// them according to `ss`. When both a world-exposed and a narrower
+    /// socket share the port, the world-exposed one is the one reached.
+    pub async fn scan(&self, ip: &str, internal_ports: &[Port]) -> Vec<ExposedPort> {
+        // Only public addresses tell us anything about internet exposure
+        if !self.config.enabled || Self::is_private(ip) {
+            return Vec::new();
+        }
+
+        let probes = self.config.ports.iter().map(|port| self.probe(ip, *port));
+        let results = join_all(probes).await;
+
+        self.config
+            .ports
+            .iter()
+            .zip(results)
+            .filter(|(_, open)| *open)
+            .map(|(port, _)| {
+                let listener = internal_ports
+                    .iter()
+                    .filter(|p| p.port == *port && p.protocol == "tcp")
+                    .map(|p| (p, self.classify(p)))
+                    .max_by_key(|(_, exposure)| *exposure == SocketExposure::World);
+                ExposedPort {
+                    port: *port,
+                    process: listener.map(|(p, _)| p.process.clone()),
+                    expected_public: self.config.public_ports.contains(port),
+                    exposure: listener.map(|(_, exposure)| exposure),
+                }
+            })
+            .collect()
+    }
+
+    fn is_private(ip: &str) -> bool {
+        match ip.parse::<IpAddr>() {
+            Ok(IpAddr::V4(v4)) => v4.is_private() || v4.is_loopback() || v4.is_link_local(),
+            // Unique local (fc00::/7) and link-local (fe80::/10)
+            Ok(IpAddr::V6(v6)) => {
+                v6.is_loopback() || (v6.segments()[0] & 0xfe00) == 0xfc00 || (v6.segments()[0] & 0xffc0) == 0xfe80
+            }
+            Err(_) => false,
+        }
+    }
+
+    async fn probe(&self, ip: &str, port: u16) -> bool {
+        let timeout = Duration::from_millis(self.config.timeout_ms);
+        matches!(
+            tokio::time::timeout(timeout, TcpStream::connect((ip, port))).await,
+            Ok(Ok(_))
+        )
+    }
+}
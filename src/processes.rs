@@ -0,0 +1,55 @@
+use crate::models::{Process, Severity};
+use serde::{Deserialize, Serialize};
+
+/// Heuristics flagging processes that look like a compromise.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProcessConfig {
+    /// Executable names of known cryptominers.
+    pub miner_names: Vec<String>,
+    /// Executables never flagged, e.g. a legitimate tool run from /tmp.
+    pub allow: Vec<String>,
+}
+
+impl Default for ProcessConfig {
+    fn default() -> Self {
+        Self {
+            miner_names: [
+                "xmrig", "xmr-stak", "minerd", "cpuminer", "kdevtmpfsi", "kinsing", "ethminer", "nbminer", "t-rex",
+                "lolminer", "phoenixminer", "cgminer", "bfgminer", "nanominer",
+            ]
+            .iter()
+            .map(|n| n.to_string())
+            .collect(),
+            allow: Vec::new(),
+        }
+    }
+}
+
+/// Directories no long-running program should execute from.
+const SCRATCH_DIRS: [&str; 3] = ["/tmp/", "/var/tmp/", "/dev/shm/"];
+
+impl ProcessConfig {
+    /// Why `process` looks suspicious, and how much.
+    pub fn suspicion(&self, process: &Process) -> Option<(Severity, String)> {
+        let (exe, deleted) = match process.exe.strip_suffix(" (deleted)") {
+            Some(exe) => (exe, true),
+            None => (process.exe.as_str(), false),
+        };
+        if self.allow.iter().any(|allowed| allowed == exe) {
+            return None;
+        }
+
+        let name = exe.rsplit('/').next().unwrap_or(exe).to_lowercase();
+        if self.miner_names.iter().any(|miner| name == miner.to_lowercase()) {
+            Some((Severity::Critical, "known cryptominer".to_string()))
+        } else if SCRATCH_DIRS.iter().any(|dir| exe.starts_with(dir)) {
+            Some((Severity::Critical, "running from a world-writable scratch directory".to_string()))
+        } else if deleted {
+            // Usually a service not restarted after an upgrade, but also how droppers hide
+            Some((Severity::Warning, "executable deleted from disk".to_string()))
+        } else {
+            None
+        }
+    }
+}
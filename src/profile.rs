@@ -0,0 +1,147 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// How much a scan collects, from a quick health check to a full audit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ScanProfile {
+    /// Reachability (ping and SSH connect) and web services only.
+    Quick,
+    /// Everything except log scanning, the exposure port scan, traceroutes,
+    /// bandwidth tests and the SUID/world-writable file audit.
+    Standard,
+    /// Every check.
+    #[default]
+    Full,
+}
+
+impl ScanProfile {
+    /// Whether the profile runs `check`, named as in `[timeouts.checks]`
+    /// (`services`, `containers`, `logs`, `exposure`, `web`, ...).
+    pub fn includes(self, check: &str) -> bool {
+        match self {
+            ScanProfile::Quick => matches!(check, "ping" | "web"),
+            ScanProfile::Standard => !matches!(check, "logs" | "exposure" | "traceroute" | "bandwidth" | "suid"),
+            ScanProfile::Full => true,
+        }
+    }
+}
+
+impl std::fmt::Display for ScanProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ScanProfile::Quick => "quick",
+            ScanProfile::Standard => "standard",
+            ScanProfile::Full => "full",
+        })
+    }
+}
+
+/// A check that `--only` and `--skip` can select, named as the scanner and
+/// `[timeouts.checks]` name it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Check {
+    Ping,
+    Services,
+    Containers,
+    /// Container images and volumes, with the space pruning would free.
+    Volumes,
+    Wireguard,
+    Ports,
+    Load,
+    /// Distribution and kernel, and their end of life.
+    Os,
+    /// CPU model and cores, memory, disks and virtualization type.
+    Hardware,
+    /// NVIDIA and AMD GPUs with their memory, utilization and temperature.
+    Gpu,
+    /// Time since boot, and reboots since the previous scan.
+    Uptime,
+    /// Filesystem space and inodes.
+    Disk,
+    /// Swap usage and activity.
+    Swap,
+    /// Temperatures and thermal throttling.
+    Sensors,
+    Processes,
+    /// Crontabs and systemd timers, new jobs compared with the previous scan.
+    Cron,
+    Logs,
+    /// OOM kills, hung tasks and filesystem or I/O errors in the kernel log.
+    Kernel,
+    Storage,
+    Certificates,
+    /// Traefik, databases, data stores and Samba.
+    Apps,
+    Exposure,
+    /// Public addresses listed on DNS blacklists.
+    Rbl,
+    Traceroute,
+    /// iperf3 throughput between hosts over the VPN, when `[bandwidth]` is enabled.
+    Bandwidth,
+    Custom,
+    /// Copying configuration files into the local archive.
+    Archive,
+    /// Hashes of monitored files, compared with the baseline.
+    Integrity,
+    /// SUID/SGID binaries and world-writable files new since the previous scan.
+    Suid,
+    Policy,
+    Web,
+    /// Registration expiry of the configured domains.
+    Domains,
+    /// Certificates logged for the domains, checked for unknown hosts and CAs.
+    Ct,
+    Cloud,
+    Proxmox,
+    Terraform,
+}
+
+impl Check {
+    fn name(self) -> String {
+        self.to_possible_value().map(|v| v.get_name().to_string()).unwrap_or_default()
+    }
+}
+
+/// Narrows the checks of a profile, e.g. to re-check only containers after a deploy.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CheckSelection {
+    /// Run just these checks, whatever the profile.
+    pub only: Vec<Check>,
+    /// Never run these checks.
+    pub skip: Vec<Check>,
+}
+
+impl CheckSelection {
+    /// Whether `check` runs under `profile`. Connecting to the host is never
+    /// skipped, since every other host check depends on it.
+    pub fn includes(&self, profile: ScanProfile, check: &str) -> bool {
+        if matches!(check, "hostname" | "connectivity") {
+            return true;
+        }
+        let selected = if self.only.is_empty() {
+            profile.includes(check)
+        } else {
+            self.only.iter().any(|c| c.name() == check)
+        };
+        selected && !self.skip.iter().any(|c| c.name() == check)
+    }
+
+    /// Whether every check runs, so the report is a complete picture.
+    pub fn is_complete(&self, profile: ScanProfile) -> bool {
+        profile == ScanProfile::Full && self.only.is_empty() && self.skip.is_empty()
+    }
+
+    /// Short description for the report header, e.g. `only containers; skip logs`.
+    pub fn describe(&self) -> Option<String> {
+        let list = |checks: &[Check]| checks.iter().map(|c| c.name()).collect::<Vec<_>>().join(", ");
+        let parts: Vec<String> = [("only", &self.only), ("skip", &self.skip)]
+            .into_iter()
+            .filter(|(_, checks)| !checks.is_empty())
+            .map(|(label, checks)| format!("{} {}", label, list(checks)))
+            .collect();
+        (!parts.is_empty()).then(|| parts.join("; "))
+    }
+}
@@ -0,0 +1,116 @@
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Collection stages shown on a host's bar, in scan order. "checks" stands
+/// for the checks collected in parallel, one step each.
+const HOST_STAGES: &[&str] = &[
+    "ping", "node_exporter", "connect", "capabilities", "collect", "checks", "apps", "exposure", "archive", "integrity", "suid", "custom",
+];
+
+fn checks_stage() -> usize {
+    HOST_STAGES.iter().position(|s| *s == "checks").unwrap_or_default()
+}
+
+static MULTI: OnceLock<MultiProgress> = OnceLock::new();
+
+/// Enables progress bars. When disabled (not a TTY, quiet or JSON logs) the
+/// bars are hidden and every call below is a no-op.
+pub fn init(enabled: bool) {
+    let target = if enabled {
+        ProgressDrawTarget::stderr()
+    } else {
+        ProgressDrawTarget::hidden()
+    };
+    let _ = MULTI.set(MultiProgress::with_draw_target(target));
+}
+
+fn multi() -> &'static MultiProgress {
+    MULTI.get_or_init(|| MultiProgress::with_draw_target(ProgressDrawTarget::hidden()))
+}
+
+/// Runs `f` with the bars temporarily cleared, so log lines don't get
+/// drawn over.
+pub fn suspend<R>(f: impl FnOnce() -> R) -> R {
+    multi().suspend(f)
+}
+
+/// Overall bar across all hosts, with ETA.
+pub struct ScanProgress {
+    overall: ProgressBar,
+}
+
+impl ScanProgress {
+    pub fn new(hosts: usize) -> Self {
+        let overall = multi().add(ProgressBar::new(hosts as u64));
+        overall.set_style(
+            ProgressStyle::with_template("{prefix:.bold} [{elapsed_precise}] {bar:30.cyan/blue} {pos}/{len} hosts (ETA {eta})")
+                .unwrap_or_else(|_| ProgressStyle::default_bar())
+                .progress_chars("█▉▊▋▌▍▎▏ "),
+        );
+        overall.set_prefix("Scan");
+        Self { overall }
+    }
+
+    pub fn host(&self, name: &str) -> HostProgress {
+        let bar = multi().insert_before(&self.overall, ProgressBar::new(HOST_STAGES.len() as u64 - 1));
+        bar.set_style(
+            ProgressStyle::with_template("  {spinner:.green} {prefix:<14.cyan} {bar:20} {msg}")
+                .unwrap_or_else(|_| ProgressStyle::default_bar()),
+        );
+        bar.set_prefix(name.to_string());
+        bar.enable_steady_tick(Duration::from_millis(120));
+        HostProgress {
+            bar,
+            overall: self.overall.clone(),
+            checks: AtomicU64::new(0),
+        }
+    }
+
+    pub fn finish(&self) {
+        self.overall.finish_and_clear();
+    }
+}
+
+/// Bar for the host currently being scanned.
+pub struct HostProgress {
+    bar: ProgressBar,
+    overall: ProgressBar,
+    /// Steps of the "checks" stage, which come before the stages after it.
+    checks: AtomicU64,
+}
+
+impl HostProgress {
+    pub fn stage(&self, stage: &str) {
+        if let Some(position) = HOST_STAGES.iter().position(|s| *s == stage) {
+            let position = position as u64;
+            let checks_stage = checks_stage() as u64;
+            if position > checks_stage {
+                self.bar.set_position(position - 1 + self.checks.load(Ordering::Relaxed));
+            } else {
+                self.bar.set_position(position);
+            }
+        }
+        self.bar.set_message(stage.to_string());
+    }
+
+    /// Adds a step to the "checks" stage.
+    pub fn add_check(&self) {
+        self.checks.fetch_add(1, Ordering::Relaxed);
+        self.bar.inc_length(1);
+    }
+
+    /// Advances the bar once a check of the "checks" stage is done.
+    pub fn check_done(&self, check: &str) {
+        self.bar.inc(1);
+        self.bar.set_message(check.to_string());
+    }
+}
+
+impl Drop for HostProgress {
+    fn drop(&mut self) {
+        self.bar.finish_and_clear();
+        self.overall.inc(1);
+    }
+}
@@ -0,0 +1,108 @@
+use super::HostProvider;
+use crate::models::CloudInstance;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GcpConfig {
+    pub enabled: bool,
+    /// Projects to list instances from. Empty means the gcloud default project.
+    pub projects: Vec<String>,
+}
+
+/// Lists Compute Engine instances through the `gcloud` CLI, relying on
+/// whatever credentials gcloud is already authenticated with.
+pub struct GcpProvider {
+    config: GcpConfig,
+}
+
+impl GcpProvider {
+    pub fn new(config: GcpConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl HostProvider for GcpProvider {
+    fn name(&self) -> &str {
+        "GCP"
+    }
+
+    async fn discover(&self) -> Result<Vec<CloudInstance>> {
+        let projects: Vec<Option<&String>> = if self.config.projects.is_empty() {
+            vec![None]
+        } else {
+            self.config.projects.iter().map(Some).collect()
+        };
+
+        let mut instances = Vec::new();
+        for project in projects {
+            let mut command = Command::new("gcloud");
+            command.args(["compute", "instances", "list", "--format=json"]);
+            if let Some(project) = project {
+                command.arg(format!("--project={}", project));
+            }
+
+            let output = command.output().await.context("Failed to execute gcloud")?;
+            if !output.status.success() {
+                anyhow::bail!(
+                    "gcloud compute instances list failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+            }
+
+            let listed: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout)
+                .context("Failed to parse gcloud output")?;
+            instances.extend(listed.iter().map(Self::to_instance));
+        }
+
+        Ok(instances)
+    }
+}
+
+impl GcpProvider {
+    fn to_instance(value: &serde_json::Value) -> CloudInstance {
+        // zone and machineType are full resource URLs, keep the last segment
+        let last_segment = |key: &str| {
+            value
+                .get(key)
+                .and_then(|v| v.as_str())
+                .and_then(|v| v.rsplit('/').next())
+                .map(|v| v.to_string())
+        };
+
+        let interfaces = value
+            .get("networkInterfaces")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        let private_ips = interfaces
+            .iter()
+            .filter_map(|i| i.get("networkIP").and_then(|ip| ip.as_str()))
+            .map(|ip| ip.to_string())
+            .collect();
+
+        let public_ips = interfaces
+            .iter()
+            .filter_map(|i| i.get("accessConfigs").and_then(|a| a.as_array()))
+            .flatten()
+            .filter_map(|a| a.get("natIP").and_then(|ip| ip.as_str()))
+            .map(|ip| ip.to_string())
+            .collect();
+
+        CloudInstance {
+            provider: "gcp".to_string(),
+            name: value.get("name").and_then(|v| v.as_str()).unwrap_or("unknown").to_string(),
+            location: last_segment("zone"),
+            machine_type: last_segment("machineType"),
+            status: value.get("status").and_then(|v| v.as_str()).unwrap_or("UNKNOWN").to_string(),
+            public_ips,
+            private_ips,
+            matched_host: None,
+        }
+    }
+}
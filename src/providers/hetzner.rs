@@ -0,0 +1,98 @@
+use super::HostProvider;
+use crate::models::CloudInstance;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+const HETZNER_API_URL: &str = "https://api.hetzner.cloud/v1";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HetznerConfig {
+    pub enabled: bool,
+    pub api_token: String,
+}
+
+/// Lists servers from the Hetzner Cloud API using a project API token.
+pub struct HetznerProvider {
+    config: HetznerConfig,
+    client: Client,
+}
+
+impl HetznerProvider {
+    pub fn new(config: HetznerConfig) -> Self {
+        Self {
+            config,
+            client: Client::new(),
+        }
+    }
+
+    fn to_instance(server: &serde_json::Value) -> CloudInstance {
+        let str_at = |pointer: &str| server.pointer(pointer).and_then(|v| v.as_str()).map(|v| v.to_string());
+
+        let public_ips = [str_at("/public_net/ipv4/ip"), str_at("/public_net/ipv6/ip")]
+            .into_iter()
+            .flatten()
+            .collect();
+
+        let private_ips = server
+            .get("private_net")
+            .and_then(|v| v.as_array())
+            .map(|nets| {
+                nets.iter()
+                    .filter_map(|n| n.get("ip").and_then(|ip| ip.as_str()))
+                    .map(|ip| ip.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        CloudInstance {
+            provider: "hetzner".to_string(),
+            name: str_at("/name").unwrap_or_else(|| "unknown".to_string()),
+            location: str_at("/datacenter/location/name"),
+            machine_type: str_at("/server_type/name"),
+            status: str_at("/status").unwrap_or_else(|| "unknown".to_string()),
+            public_ips,
+            private_ips,
+            matched_host: None,
+        }
+    }
+}
+
+#[async_trait]
+impl HostProvider for HetznerProvider {
+    fn name(&self) -> &str {
+        "Hetzner"
+    }
+
+    async fn discover(&self) -> Result<Vec<CloudInstance>> {
+        let mut instances = Vec::new();
+        let mut page: Option<u64> = Some(1);
+
+        while let Some(current) = page {
+            let response: serde_json::Value = self
+                .client
+                .get(format!("{}/servers", HETZNER_API_URL))
+                .query(&[("page", current), ("per_page", 50)])
+                .bearer_auth(&self.config.api_token)
+                .send()
+                .await
+                .context("Failed to reach Hetzner API")?
+                .error_for_status()?
+                .json()
+                .await
+                .context("Failed to parse Hetzner API response")?;
+
+            if let Some(servers) = response.get("servers").and_then(|s| s.as_array()) {
+                instances.extend(servers.iter().map(Self::to_instance));
+            }
+
+            page = response
+                .pointer("/meta/pagination/next_page")
+                .and_then(|p| p.as_u64());
+        }
+
+        Ok(instances)
+    }
+}
@@ -0,0 +1,32 @@
+pub mod gcp;
+pub mod hetzner;
+pub mod oci;
+
+use crate::config::Config;
+use crate::models::CloudInstance;
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// A source of hosts outside the SSH config, typically a cloud provider API.
+#[async_trait]
+pub trait HostProvider: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn discover(&self) -> Result<Vec<CloudInstance>>;
+}
+
+pub fn enabled_providers(config: &Config) -> Vec<Box<dyn HostProvider>> {
+    let mut providers: Vec<Box<dyn HostProvider>> = Vec::new();
+
+    if config.gcp.enabled {
+        providers.push(Box::new(gcp::GcpProvider::new(config.gcp.clone())));
+    }
+    if config.hetzner.enabled {
+        providers.push(Box::new(hetzner::HetznerProvider::new(config.hetzner.clone())));
+    }
+    if config.oci.enabled {
+        providers.push(Box::new(oci::OciProvider::new(config.oci.clone())));
+    }
+
+    providers
+}
@@ -0,0 +1,93 @@
+use super::HostProvider;
+use crate::models::CloudInstance;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OciConfig {
+    pub enabled: bool,
+    /// Profile in ~/.oci/config holding the API signing key.
+    pub profile: Option<String>,
+    pub compartment_ids: Vec<String>,
+}
+
+/// Lists compute instances through the `oci` CLI. OCI requests must be
+/// signed with the API key from the CLI config, so the CLI does the auth.
+pub struct OciProvider {
+    config: OciConfig,
+}
+
+impl OciProvider {
+    pub fn new(config: OciConfig) -> Self {
+        Self { config }
+    }
+
+    async fn oci(&self, args: &[&str]) -> Result<serde_json::Value> {
+        let mut command = Command::new("oci");
+        command.args(args).args(["--output", "json"]);
+        if let Some(ref profile) = self.config.profile {
+            command.args(["--profile", profile]);
+        }
+
+        let output = command.output().await.context("Failed to execute oci")?;
+        if !output.status.success() {
+            anyhow::bail!("oci {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+        }
+
+        // The CLI prints nothing at all for empty results
+        if output.stdout.iter().all(|b| b.is_ascii_whitespace()) {
+            return Ok(serde_json::json!({ "data": [] }));
+        }
+
+        serde_json::from_slice(&output.stdout).context("Failed to parse oci output")
+    }
+}
+
+#[async_trait]
+impl HostProvider for OciProvider {
+    fn name(&self) -> &str {
+        "OCI"
+    }
+
+    async fn discover(&self) -> Result<Vec<CloudInstance>> {
+        let mut instances = Vec::new();
+
+        for compartment in &self.config.compartment_ids {
+            let listed = self
+                .oci(&["compute", "instance", "list", "--compartment-id", compartment, "--all"])
+                .await?;
+
+            for instance in listed.get("data").and_then(|d| d.as_array()).into_iter().flatten() {
+                let str_at = |key: &str| instance.get(key).and_then(|v| v.as_str()).map(|v| v.to_string());
+
+                let (mut public_ips, mut private_ips) = (Vec::new(), Vec::new());
+                if let Some(id) = str_at("id") {
+                    let vnics = self
+                        .oci(&["compute", "instance", "list-vnics", "--instance-id", &id, "--compartment-id", compartment])
+                        .await
+                        .unwrap_or_default();
+                    for vnic in vnics.get("data").and_then(|d| d.as_array()).into_iter().flatten() {
+                        public_ips.extend(vnic.get("public-ip").and_then(|ip| ip.as_str()).map(|ip| ip.to_string()));
+                        private_ips.extend(vnic.get("private-ip").and_then(|ip| ip.as_str()).map(|ip| ip.to_string()));
+                    }
+                }
+
+                instances.push(CloudInstance {
+                    provider: "oci".to_string(),
+                    name: str_at("display-name").unwrap_or_else(|| "unknown".to_string()),
+                    location: str_at("availability-domain"),
+                    machine_type: str_at("shape"),
+                    status: str_at("lifecycle-state").unwrap_or_else(|| "UNKNOWN".to_string()),
+                    public_ips,
+                    private_ips,
+                    matched_host: None,
+                });
+            }
+        }
+
+        Ok(instances)
+    }
+}
@@ -0,0 +1,92 @@
+use crate::models::{ProxmoxGuest, ProxmoxInventory, ProxmoxNode};
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProxmoxConfig {
+    pub enabled: bool,
+    /// Base URL of the API, e.g. `https://pve.lan:8006`.
+    pub url: String,
+    /// API token id in the form `user@realm!tokenid`.
+    pub token_id: String,
+    pub token_secret: String,
+    /// Proxmox ships a self-signed certificate by default.
+    pub accept_invalid_certs: bool,
+}
+
+pub struct ProxmoxClient {
+    config: ProxmoxConfig,
+    client: Client,
+}
+
+impl ProxmoxClient {
+    pub fn new(config: ProxmoxConfig) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(15))
+            .danger_accept_invalid_certs(config.accept_invalid_certs)
+            .build()?;
+
+        Ok(Self { config, client })
+    }
+
+    async fn get(&self, path: &str) -> Result<Vec<serde_json::Value>> {
+        let response: serde_json::Value = self
+            .client
+            .get(format!("{}/api2/json{}", self.config.url.trim_end_matches('/'), path))
+            .header(
+                "Authorization",
+                format!("PVEAPIToken={}={}", self.config.token_id, self.config.token_secret),
+            )
+            .send()
+            .await
+            .context("Failed to reach Proxmox API")?
+            .error_for_status()?
+            .json()
+            .await
+            .context("Failed to parse Proxmox API response")?;
+
+        Ok(response.get("data").and_then(|d| d.as_array()).cloned().unwrap_or_default())
+    }
+
+    pub async fn inventory(&self) -> Result<ProxmoxInventory> {
+        let nodes = self
+            .get("/nodes")
+            .await?
+            .iter()
+            .map(|n| ProxmoxNode {
+                name: json_str(n, "node"),
+                status: json_str(n, "status"),
+                cpu_usage: n.get("cpu").and_then(|v| v.as_f64()),
+                max_cpu: n.get("maxcpu").and_then(|v| v.as_u64()),
+                mem_used: n.get("mem").and_then(|v| v.as_u64()),
+                max_mem: n.get("maxmem").and_then(|v| v.as_u64()),
+            })
+            .collect();
+
+        let guests = self
+            .get("/cluster/resources?type=vm")
+            .await?
+            .iter()
+            .map(|g| ProxmoxGuest {
+                vmid: g.get("vmid").and_then(|v| v.as_u64()).unwrap_or(0),
+                name: json_str(g, "name"),
+                kind: json_str(g, "type"),
+                node: json_str(g, "node"),
+                status: json_str(g, "status"),
+                max_cpu: g.get("maxcpu").and_then(|v| v.as_u64()),
+                max_mem: g.get("maxmem").and_then(|v| v.as_u64()),
+                max_disk: g.get("maxdisk").and_then(|v| v.as_u64()),
+                matched_host: None,
+            })
+            .collect();
+
+        Ok(ProxmoxInventory { nodes, guests })
+    }
+}
+
+fn json_str(value: &serde_json::Value, key: &str) -> String {
+    value.get(key).and_then(|v| v.as_str()).unwrap_or("unknown").to_string()
+}
@@ -0,0 +1,74 @@
+use crate::models::BlacklistListing;
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use tracing::debug;
+
+/// DNS blacklists the public addresses of every host are looked up in. A
+/// listing gets mail rejected and may hint at a compromised host.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RblConfig {
+    pub lists: Vec<String>,
+}
+
+impl Default for RblConfig {
+    fn default() -> Self {
+        Self {
+            lists: vec![
+                "zen.spamhaus.org".to_string(),
+                "bl.spamcop.net".to_string(),
+                "b.barracudacentral.org".to_string(),
+                "psbl.surriel.com".to_string(),
+            ],
+        }
+    }
+}
+
+impl RblConfig {
+    /// The lists naming `ip`. Lookups that fail count as not listed.
+    pub async fn listings(&self, ip: IpAddr) -> Vec<BlacklistListing> {
+        let lookups = self.lists.iter().map(|list| async move {
+            let query = format!("{}.{}", reversed(ip), list);
+            let response = tokio::net::lookup_host((query.as_str(), 0)).await.ok()?.next()?.ip();
+            // Spamhaus answers 127.255.255.x to queries it refuses, e.g. from
+            // public resolvers; that says nothing about the address
+            if response.to_string().starts_with("127.255.255.") {
+                debug!("{} refused the query for {}: {}", list, ip, response);
+                return None;
+            }
+            Some(BlacklistListing { list: list.clone(), ip: ip.to_string(), response: response.to_string() })
+        });
+        join_all(lookups).await.into_iter().flatten().collect()
+    }
+}
+
+/// Whether `ip` is routable on the internet, the only kind worth a lookup.
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        // 100.64.0.0/10 is carrier-grade NAT (and Tailscale)
+        IpAddr::V4(v4) => {
+            let cgnat = v4.octets()[0] == 100 && (v4.octets()[1] & 0xc0) == 64;
+            !(v4.is_private() || v4.is_loopback() || v4.is_link_local() || v4.is_unspecified() || cgnat)
+        }
+        IpAddr::V6(v6) => !(v6.is_loopback() || v6.is_unspecified() || (v6.segments()[0] & 0xfe00) == 0xfc00 || (v6.segments()[0] & 0xffc0) == 0xfe80),
+    }
+}
+
+/// Reverses `ip` for a DNSBL query: octets for IPv4, nibbles for IPv6.
+fn reversed(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, c, d] = v4.octets();
+            format!("{}.{}.{}.{}", d, c, b, a)
+        }
+        IpAddr::V6(v6) => v6
+            .octets()
+            .iter()
+            .rev()
+            .flat_map(|byte| [byte & 0x0f, byte >> 4])
+            .map(|nibble| format!("{:x}", nibble))
+            .collect::<Vec<_>>()
+            .join("."),
+    }
+}
@@ -0,0 +1,14 @@
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+/// Boot time of each host as of its previous scan, so a reboot between
+/// scans shows up even when the host has been up a while again.
+pub const STATE_FILE: &str = "boot_times.json";
+
+/// Boot times derived from `/proc/uptime` drift by a few seconds between
+/// scans; a later boot by more than this is a reboot.
+pub const TOLERANCE_SECS: i64 = 120;
+
+/// Boot times by host name.
+pub type BootState = BTreeMap<String, DateTime<Utc>>;
+
@@ -0,0 +1,146 @@
+use crate::models::InventoryReport;
+use crate::rbl;
+use anyhow::{Context, Result};
+use hmac::{Hmac, Mac};
+use regex::{Captures, Regex};
+use serde_json::Value;
+use sha2::Sha256;
+use std::io::{ErrorKind, Read, Write};
+use std::net::IpAddr;
+use std::os::unix::fs::OpenOptionsExt;
+
+/// Secret the pseudonyms are keyed with, generated on first use. Without it
+/// a pseudonym can't be mapped back by hashing every candidate value.
+const KEY_FILE: &str = "~/.config/securepenguin/redact.key";
+
+/// A copy of the report fit for sharing outside the team: public IPs, public
+/// keys, SSH usernames and identity file paths become pseudonyms keyed with
+/// this installation's secret, so they stay the same across hosts, formats
+/// and scans but can't be reversed by whoever reads the report. Private
+/// addresses are kept, since they say little and help reading the report.
+pub fn redact(report: &InventoryReport) -> Result<InventoryReport> {
+    let key = load_key()?;
+    let pseudonym = |kind: &str, value: &str| pseudonym(&key, kind, value);
+    let mut literals: Vec<(String, String)> = Vec::new();
+    for vm in &report.vms {
+        // root is the same everywhere; replacing it would garble messages
+        if vm.host.user != "root" {
+            literals.push((vm.host.user.clone(), pseudonym("user", &vm.host.user)));
+        }
+        let expanded = shellexpand::tilde(&vm.host.identity_file).to_string();
+        for path in [&vm.host.identity_file, &expanded] {
+            literals.push((path.clone(), format!("~/.ssh/{}", pseudonym("key", &vm.host.identity_file))));
+        }
+    }
+    literals.retain(|(value, _)| !value.is_empty());
+    // Paths before the usernames they may contain
+    literals.sort_by_key(|(value, _)| std::cmp::Reverse(value.len()));
+    literals.dedup();
+
+    let redactor = Redactor {
+        key,
+        literals: literals
+            .into_iter()
+            .map(|(value, replacement)| {
+                let word = value.starts_with(char::is_alphanumeric) && value.ends_with(char::is_alphanumeric);
+                let pattern = word.then(|| Regex::new(&format!(r"\b{}\b", regex::escape(&value))).ok()).flatten();
+                (pattern, value, replacement)
+            })
+            .collect(),
+        ssh_key: Regex::new(r"\b((?:ssh-(?:ed25519|rsa|dss)|ecdsa-sha2-nistp\d+) )AAAA[0-9A-Za-z+/]+=*").unwrap(),
+        // Curve25519 keys as wg prints them: 32 bytes of base64
+        wireguard_key: Regex::new(r"\b[A-Za-z0-9+/]{42}[AEIMQUYcgkosw048]=").unwrap(),
+        ip: Regex::new(r"\b(?:\d{1,3}\.){3}\d{1,3}\b|[0-9A-Fa-f]{0,4}(?::[0-9A-Fa-f]{0,4}){2,7}").unwrap(),
+    };
+
+    let mut value = serde_json::to_value(report).context("Failed to serialize report for redaction")?;
+    redactor.walk(&mut value);
+    serde_json::from_value(value).context("Failed to rebuild redacted report")
+}
+
+struct Redactor {
+    key: Vec<u8>,
+    /// Original value and pseudonym, with a pattern matching the value as a
+    /// whole word when it starts and ends with a word character.
+    literals: Vec<(Option<Regex>, String, String)>,
+    ssh_key: Regex,
+    wireguard_key: Regex,
+    ip: Regex,
+}
+
+impl Redactor {
+    fn walk(&self, value: &mut Value) {
+        match value {
+            Value::String(s) => *s = self.text(s),
+            Value::Array(items) => items.iter_mut().for_each(|item| self.walk(item)),
+            // Maps are keyed by host names and paths, which may hold secrets too
+            Value::Object(map) => {
+                let entries = std::mem::take(map);
+                for (key, mut item) in entries {
+                    self.walk(&mut item);
+                    map.insert(self.text(&key), item);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn text(&self, text: &str) -> String {
+        let pseudonym = |kind: &str, value: &str| pseudonym(&self.key, kind, value);
+        let mut text = self.ssh_key.replace_all(text, |c: &Captures| format!("{}{}", &c[1], pseudonym("key", &c[0]))).to_string();
+        text = self.wireguard_key.replace_all(&text, |c: &Captures| pseudonym("key", &c[0])).to_string();
+        text = self
+            .ip
+            .replace_all(&text, |c: &Captures| match c[0].parse::<IpAddr>() {
+                Ok(ip) if rbl::is_public(ip) => pseudonym("ip", &c[0]),
+                _ => c[0].to_string(),
+            })
+            .to_string();
+        for (pattern, value, replacement) in &self.literals {
+            if !text.contains(value.as_str()) {
+                continue;
+            }
+            text = match pattern {
+                Some(pattern) => pattern.replace_all(&text, replacement.as_str()).to_string(),
+                None => text.replace(value.as_str(), replacement),
+            };
+        }
+        text
+    }
+}
+
+/// `<kind>-<8 hex digits>` from an HMAC-SHA256 of the value.
+fn pseudonym(key: &[u8], kind: &str, value: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(value.as_bytes());
+    let digest = mac.finalize().into_bytes();
+    format!("{}-{:02x}{:02x}{:02x}{:02x}", kind, digest[0], digest[1], digest[2], digest[3])
+}
+
+/// Reads the redaction key, creating it from the system's random source the
+/// first time. The file is only readable by the owner.
+fn load_key() -> Result<Vec<u8>> {
+    let path = shellexpand::tilde(KEY_FILE).to_string();
+    match std::fs::read(&path) {
+        Ok(key) if key.len() >= 32 => return Ok(key),
+        Ok(_) => anyhow::bail!("Redaction key {} is too short", path),
+        Err(e) if e.kind() == ErrorKind::NotFound => {}
+        Err(e) => return Err(e).context(format!("Failed to read redaction key: {}", path)),
+    }
+
+    let mut key = vec![0u8; 32];
+    std::fs::File::open("/dev/urandom")
+        .and_then(|mut random| random.read_exact(&mut key))
+        .context("Failed to generate redaction key")?;
+    if let Some(parent) = std::path::Path::new(&path).parent() {
+        std::fs::create_dir_all(parent).context("Failed to create redaction key directory")?;
+    }
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&path)
+        .and_then(|mut file| file.write_all(&key))
+        .context(format!("Failed to write redaction key: {}", path))?;
+    Ok(key)
+}
@@ -0,0 +1,123 @@
+use crate::models::InventoryReport;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::Command;
+use tracing::{debug, info};
+
+/// `[report.git]` section: commit (and optionally push) the generated reports
+/// so every scan leaves a versioned history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GitReportConfig {
+    pub enabled: bool,
+    /// Working tree the reports are written into.
+    pub repo: String,
+    /// Branch to commit on; the current branch when unset.
+    pub branch: Option<String>,
+    /// Commit message; supports {date}, {reachable}, {total}, {critical} and {warnings}.
+    pub message: String,
+    pub push: bool,
+    pub remote: String,
+}
+
+impl Default for GitReportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            repo: "~/SecurePenguin".to_string(),
+            branch: None,
+            message: "Inventario {date}: {reachable}/{total} VMs, {critical} críticos, {warnings} warnings".to_string(),
+            push: false,
+            remote: "origin".to_string(),
+        }
+    }
+}
+
+pub struct ReportRepo {
+    config: GitReportConfig,
+    root: PathBuf,
+}
+
+impl ReportRepo {
+    pub fn new(config: GitReportConfig) -> Self {
+        let root = PathBuf::from(shellexpand::tilde(&config.repo).to_string());
+        Self { config, root }
+    }
+
+    /// Stages the given report files, commits them when they changed and
+    /// pushes if configured. Returns whether a commit was made.
+    pub fn commit(&self, report: &InventoryReport, paths: &[PathBuf]) -> Result<bool> {
+        if let Some(ref branch) = self.config.branch {
+            let current = self.git(&["rev-parse", "--abbrev-ref", "HEAD"])?;
+            if current != *branch {
+                self.git(&["switch", branch])
+                    .with_context(|| format!("Failed to switch report repo to branch {}", branch))?;
+            }
+        }
+
+        let mut add = vec!["add".to_string(), "--".to_string()];
+        add.extend(paths.iter().filter(|p| p.exists()).map(|p| p.to_string_lossy().to_string()));
+        self.git(&add.iter().map(String::as_str).collect::<Vec<_>>())?;
+
+        if self.status(&["diff", "--cached", "--quiet"])? {
+            debug!("Reports unchanged, nothing to commit");
+            return Ok(false);
+        }
+
+        self.git(&["commit", "-m", &self.message(report)])?;
+        info!("Reporte versionado en {}", self.root.display());
+
+        if self.config.push {
+            let mut push = vec!["push", self.config.remote.as_str()];
+            if let Some(ref branch) = self.config.branch {
+                push.push(branch);
+            }
+            self.git(&push)?;
+            info!("Reporte publicado en {}", self.config.remote);
+        }
+
+        Ok(true)
+    }
+
+    fn message(&self, report: &InventoryReport) -> String {
+        self.config
+            .message
+            .replace("{date}", &report.timestamp.format("%Y-%m-%d %H:%M UTC").to_string())
+            .replace("{reachable}", &report.summary.reachable_vms.to_string())
+            .replace("{total}", &report.summary.total_vms.to_string())
+            .replace("{critical}", &report.critical_issues.len().to_string())
+            .replace("{warnings}", &report.warnings.len().to_string())
+    }
+
+    fn git(&self, args: &[&str]) -> Result<String> {
+        let output = self.command(args)?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "git {} failed: {}",
+                args.first().unwrap_or(&""),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Runs a git command whose exit status is the answer (0 = true, 1 = false).
+    fn status(&self, args: &[&str]) -> Result<bool> {
+        let output = self.command(args)?;
+        match output.status.code() {
+            Some(0) => Ok(true),
+            Some(1) => Ok(false),
+            _ => anyhow::bail!("git {} failed: {}", args[0], String::from_utf8_lossy(&output.stderr).trim()),
+        }
+    }
+
+    fn command(&self, args: &[&str]) -> Result<std::process::Output> {
+        Command::new("git")
+            .arg("-C")
+            .arg(&self.root)
+            .args(args)
+            .output()
+            .context("Failed to execute git")
+    }
+}
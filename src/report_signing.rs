@@ -0,0 +1,85 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::info;
+
+/// `[report.signing]` section: an armored detached signature (`<file>.asc`)
+/// next to every generated report, verifiable with `gpg --verify`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SigningConfig {
+    pub enabled: bool,
+    /// Key ID, fingerprint or email to sign with; gpg's default key when unset.
+    pub key: Option<String>,
+    /// GnuPG home with the signing key, e.g. a dedicated one for the scanner.
+    pub homedir: Option<String>,
+    /// Signing program; anything accepting gpg's arguments, like `gpg2` or
+    /// Sequoia's `gpg-sq`.
+    pub program: String,
+}
+
+impl Default for SigningConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            key: None,
+            homedir: None,
+            program: "gpg".to_string(),
+        }
+    }
+}
+
+impl SigningConfig {
+    /// Signs the report files, and the Markdown and JSON files directly in
+    /// the directories among `paths` (the per-host reports). Returns the
+    /// signature files written.
+    pub fn sign(&self, paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        for path in paths {
+            if path.is_dir() {
+                let entries = std::fs::read_dir(path).with_context(|| format!("Failed to read {}", path.display()))?;
+                let mut reports: Vec<PathBuf> = entries
+                    .filter_map(|entry| entry.ok().map(|e| e.path()))
+                    .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "md" || ext == "json"))
+                    .collect();
+                reports.sort();
+                files.extend(reports);
+            } else if path.is_file() {
+                files.push(path.clone());
+            }
+        }
+
+        let signatures = files.iter().map(|file| self.sign_file(file)).collect::<Result<Vec<_>>>()?;
+        info!("Reportes firmados ({} firmas)", signatures.len());
+        Ok(signatures)
+    }
+
+    fn sign_file(&self, file: &Path) -> Result<PathBuf> {
+        let mut signature = file.as_os_str().to_owned();
+        signature.push(".asc");
+        let signature = PathBuf::from(signature);
+
+        let mut command = Command::new(&self.program);
+        if let Some(ref homedir) = self.homedir {
+            command.arg("--homedir").arg(shellexpand::tilde(homedir).to_string());
+        }
+        if let Some(ref key) = self.key {
+            command.args(["--local-user", key]);
+        }
+        let output = command
+            .args(["--batch", "--yes", "--armor", "--detach-sign", "--output"])
+            .arg(&signature)
+            .arg(file)
+            .output()
+            .with_context(|| format!("Failed to execute {}", self.program))?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Signing {} failed: {}",
+                file.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(signature)
+    }
+}
@@ -1,107 +1,584 @@
+use crate::i18n::{Lang, Strings};
 use crate::models::*;
+use crate::os_release;
+use crate::report_git::GitReportConfig;
+use crate::report_signing::SigningConfig;
+use crate::suppressions::ActiveSuppression;
+use crate::timing::TimingProfile;
+use crate::topology::Topology;
 use anyhow::{Context, Result};
-use colored::Colorize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::File;
 use std::io::Write;
+use tracing::info;
+
+/// `[report]` section: extra outputs written next to the combined report.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportConfig {
+    /// Directory for one file per VM plus an index; disabled when unset.
+    pub per_host_dir: Option<String>,
+    /// Formats written for each VM (markdown by default).
+    pub per_host_formats: Vec<HostReportFormat>,
+    /// Graphviz file with the network topology; not written when unset.
+    pub topology_dot: Option<String>,
+    pub signing: SigningConfig,
+    pub git: GitReportConfig,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HostReportFormat {
+    Markdown,
+    Json,
+}
 
 pub struct MarkdownReporter;
 
 impl MarkdownReporter {
-    pub fn generate_report(report: &InventoryReport) -> Result<String> {
+    pub fn generate_report(report: &InventoryReport, lang: Lang) -> Result<String> {
+        let t = lang.strings();
         let mut output = String::new();
 
-        output.push_str(&Self::header(report));
-        output.push_str(&Self::summary(&report.summary));
-        output.push_str("\n## ESTADO POR VM\n\n");
+        output.push_str(&Self::header(report, t));
+        output.push_str(&Self::summary(&report.summary, t));
+        output.push_str(&format!("\n## {}\n\n", t.status_per_vm));
 
-        for vm in &report.vms {
-            output.push_str(&Self::vm_status(vm));
-            output.push_str("\n");
+        if report.vms.iter().any(|vm| vm.host.group.is_some()) {
+            // Ungrouped hosts go last
+            let mut groups: BTreeMap<(bool, &str), Vec<&VmStatus>> = BTreeMap::new();
+            for vm in &report.vms {
+                let key = match vm.host.group {
+                    Some(ref group) => (false, group.as_str()),
+                    None => (true, t.ungrouped),
+                };
+                groups.entry(key).or_default().push(vm);
+            }
+            for ((_, group), vms) in groups {
+                output.push_str(&format!("### 📁 {}\n\n", group));
+                for vm in vms {
+                    output.push_str(&Self::vm_status(vm, t, "####"));
+                    output.push('\n');
+                }
+            }
+        } else {
+            for vm in &report.vms {
+                output.push_str(&Self::vm_status(vm, t, "###"));
+                output.push('\n');
+            }
+        }
+
+        let topology = Topology::from_report(report);
+        if !topology.is_empty() {
+            output.push_str(&format!("## {}\n\n```mermaid\n{}```\n\n", t.topology, topology.mermaid()));
+        }
+
+        if !report.bandwidth.is_empty() {
+            output.push_str(&format!("## {}\n\n", t.bandwidth));
+            output.push_str(&Self::bandwidth_matrix(&report.bandwidth, t));
+            output.push('\n');
+        }
+
+        output.push_str(&format!("## {}\n\n", t.external_web_services));
+        output.push_str(&Self::web_services_table(&report.web_services, t));
+        output.push_str(&Self::redirect_chains(&report.web_services, t));
+        output.push_str(&Self::dns_records(&report.web_services, t));
+        output.push_str(&Self::tls_audits(&report.web_services, t));
+        output.push_str(&Self::scenarios(&report.web_services, t));
+        output.push_str(&Self::health_reports(&report.web_services, t));
+
+        if !report.domains.is_empty() {
+            output.push_str(&format!("\n## {}\n\n", t.domains));
+            output.push_str(&Self::domains_table(&report.domains, report.timestamp, t));
+        }
+
+        if !report.ct_certificates.is_empty() {
+            output.push_str(&format!("\n## {}\n\n", t.ct_certificates));
+            output.push_str(&Self::ct_table(&report.ct_certificates, t));
+        }
+
+        if let Some(ref proxmox) = report.proxmox {
+            output.push_str("\n## PROXMOX\n\n");
+            output.push_str(&Self::proxmox_tables(proxmox, t));
+        }
+
+        if let Some(ref terraform) = report.terraform {
+            output.push_str(&format!("\n## {}\n\n", t.terraform));
+            output.push_str(&Self::terraform_table(terraform, t));
+        }
+
+        if !report.cloud_instances.is_empty() {
+            output.push_str(&format!("\n## {}\n\n", t.cloud_instances));
+            output.push_str(&Self::cloud_instances_table(&report.cloud_instances, t));
         }
 
-        output.push_str("## SERVICIOS WEB EXTERNOS\n\n");
-        output.push_str(&Self::web_services_table(&report.web_services));
+        if !report.policy_violations.is_empty() {
+            output.push_str(&format!("\n## {}\n\n", t.policy_violations));
+            output.push_str(&Self::policy_table(&report.policy_violations, t));
+        }
 
-        output.push_str("\n## ISSUES CRÍTICOS\n\n");
+        if !report.impacts.is_empty() {
+            output.push_str(&format!("\n## {}\n\n", t.impact_analysis));
+            for impact in &report.impacts {
+                output.push_str(&format!("- ❌ **{}** → {}\n", impact.root, impact.affected.join(", ")));
+            }
+        }
+
+        output.push_str(&format!("\n## {}\n\n", t.critical_issues));
         if report.critical_issues.is_empty() {
-            output.push_str("✅ No issues críticos encontrados\n");
+            output.push_str(&format!("✅ {}\n", t.no_critical_issues));
         } else {
-            for issue in &report.critical_issues {
-                output.push_str(&format!("- ❌ {}\n", issue));
-            }
+            output.push_str(&Self::issue_list(&report.critical_issues, "❌", t));
         }
 
-        output.push_str("\n## WARNINGS\n\n");
+        output.push_str(&format!("\n## {}\n\n", t.warnings));
         if report.warnings.is_empty() {
-            output.push_str("✅ No warnings\n");
+            output.push_str(&format!("✅ {}\n", t.no_warnings));
         } else {
-            for warning in &report.warnings {
-                output.push_str(&format!("- ⚠️ {}\n", warning));
+            output.push_str(&Self::issue_list(&report.warnings, "⚠️", t));
+        }
+
+        if !report.notices.is_empty() {
+            output.push_str(&format!("\n## {}\n\n", t.notices));
+            for notice in &report.notices {
+                output.push_str(&format!("- ℹ️ {}\n", notice));
             }
         }
 
+        if !report.suppressions.is_empty() {
+            output.push_str(&format!("\n## {}\n\n", t.suppressions));
+            output.push_str(&Self::suppressions_table(&report.suppressions, t));
+        }
+
+        if report.vms.iter().any(|vm| !vm.timings.is_empty()) {
+            output.push_str(&format!("\n## {}\n\n", t.timings));
+            output.push_str(&Self::timing_tables(&TimingProfile::from_report(report), t));
+        }
+
         output.push_str("\n---\n");
-        output.push_str(&format!("*Generado por securepenguin-inventory*\n"));
+        output.push_str(&format!("*{} securepenguin-inventory*\n", t.generated_by));
         output.push_str(&format!(
-            "*Fecha: {}*\n",
+            "*{}: {}*\n",
+            t.date,
             report.timestamp.format("%Y-%m-%d %H:%M UTC")
         ));
 
         Ok(output)
     }
 
-    fn header(report: &InventoryReport) -> String {
-        format!(
-            "# INVENTARIO STATUS SECUREPENGUIN\nFecha: {}\nHora: {}\n",
+    /// Root causes first, then the issues that follow from them.
+    fn issue_list(issues: &[Issue], emoji: &str, t: &Strings) -> String {
+        let (downstream, roots): (Vec<&Issue>, Vec<&Issue>) = issues.iter().partition(|issue| !issue.caused_by.is_empty());
+        let mut output = String::new();
+        for issue in roots {
+            output.push_str(&format!("- {} {} `{}`\n", emoji, issue, issue.fingerprint()));
+        }
+        for issue in downstream {
+            output.push_str(&format!(
+                "- ↳ {} {} ({} {}) `{}`\n",
+                emoji,
+                issue,
+                t.caused_by,
+                issue.caused_by.join(", "),
+                issue.fingerprint()
+            ));
+        }
+        output
+    }
+
+    fn header(report: &InventoryReport, t: &Strings) -> String {
+        let mut header = format!(
+            "# {}\n{}: {}\n{}: {}\n",
+            t.title,
+            t.date,
             report.timestamp.format("%Y-%m-%d"),
+            t.time,
             report.timestamp.format("%H:%M UTC")
-        )
+        );
+        if !report.checks.is_complete(report.profile) {
+            let selection = report.checks.describe().map(|s| format!(" ({})", s)).unwrap_or_default();
+            header.push_str(&format!("{}: {}{}\n", t.profile, report.profile, selection));
+        }
+        if report.partial {
+            header.push_str(&format!("\n> ⚠️ {}\n", t.partial_report));
+        }
+        header
     }
 
-    fn summary(summary: &Summary) -> String {
+    fn summary(summary: &Summary, t: &Strings) -> String {
         format!(
-            "## RESUMEN EJECUTIVO\n\
-            - VMs auditadas: {}/{}\n\
-            - VMs accesibles: {}\n\
-            - Servicios totales: {}\n\
-            - Servicios corriendo: {}\n\
-            - Servicios con problemas: {}\n\
-            - Contenedores totales: {}\n\
-            - Contenedores activos: {}\n",
+            "## {}\n\
+            - {}: {}/{}\n\
+            - {}: {}\n\
+            - {}: {}\n\
+            - {}: {}\n\
+            - {}: {}\n\
+            - {}: {}\n\
+            - {}: {}\n",
+            t.executive_summary,
+            t.vms_audited,
             summary.reachable_vms,
             summary.total_vms,
+            t.vms_reachable,
             summary.reachable_vms,
+            t.services_total,
             summary.total_services,
+            t.services_running,
             summary.running_services,
+            t.services_failing,
             summary.failed_services,
+            t.containers_total,
             summary.total_containers,
+            t.containers_running,
             summary.running_containers,
         )
     }
 
-    fn vm_status(vm: &VmStatus) -> String {
+    fn vm_status(vm: &VmStatus, t: &Strings, heading: &str) -> String {
         let status_emoji = if vm.reachable { "✅" } else { "❌" };
 
         let mut output = format!(
-            "### {} ({}:{})\n\
-            **Estado:** {} {}\n\
-            **Rol:** {}\n\n",
+            "{} {} ({}:{})\n\
+            **{}:** {} {}\n\
+            **{}:** {}\n\n",
+            heading,
             vm.host.name,
             vm.host.ip,
             vm.host.port,
+            t.status,
             status_emoji,
             if vm.reachable {
-                "Operativa"
+                t.operational
             } else {
-                "Inaccesible"
+                t.unreachable
             },
-            vm.host.name
+            t.role,
+            if vm.host.roles.is_empty() {
+                vm.host.name.clone()
+            } else {
+                vm.host.roles.join(", ")
+            }
         );
+        if !vm.host.tags.is_empty() {
+            // Replace the blank line closing the header so tags stay part of it
+            output.pop();
+            output.push_str(&format!("**{}:** {}\n\n", t.tags, vm.host.tags.join(", ")));
+        }
+
+        if let Some(ref reason) = vm.maintenance {
+            output.push_str(&format!("**🔧 {}:** {}\n\n", t.maintenance, reason));
+        }
+
+        if !vm.incomplete_checks.is_empty() {
+            output.push_str(&format!("**⏱️ {}:**\n", t.incomplete_checks));
+            for check in &vm.incomplete_checks {
+                output.push_str(&format!("- ⚠️ {}\n", check));
+            }
+            output.push('\n');
+        }
+
+        if let Some(load) = vm.load {
+            output.push_str(&format!(
+                "**{}:** {:.2} / {:.2} / {:.2} ({} {})\n\n",
+                t.load, load.one, load.five, load.fifteen, load.cores, t.cores
+            ));
+        }
+
+        if let Some(ref memory) = vm.memory {
+            output.push_str(&format!(
+                "**{}:** {:.1} / {:.1} GiB ({}%)\n\n",
+                t.memory_usage,
+                memory.total_bytes.saturating_sub(memory.available_bytes) as f64 / 1024.0 / 1024.0 / 1024.0,
+                memory.total_bytes as f64 / 1024.0 / 1024.0 / 1024.0,
+                memory.used_percent()
+            ));
+        }
+
+        if let Some(ref os) = vm.os {
+            output.push_str(&format!("**{}:** {}, kernel {}", t.os, os.pretty_name, os.kernel));
+            if let Some(eol) = os_release::end_of_life(os) {
+                output.push_str(&format!(" ({} {})", t.end_of_life, eol));
+            }
+            output.push_str("\n\n");
+        }
+
+        if let Some(ref hardware) = vm.hardware {
+            let mut parts = Vec::new();
+            match (&hardware.cpu_model, hardware.cores) {
+                (Some(model), Some(cores)) => parts.push(format!("{} ({} {})", model, cores, t.cores)),
+                (Some(model), None) => parts.push(model.clone()),
+                (None, Some(cores)) => parts.push(format!("{} {}", cores, t.cores)),
+                (None, None) => {}
+            }
+            if let Some(bytes) = hardware.memory_bytes {
+                parts.push(format!("{:.1} GiB RAM", bytes as f64 / 1024.0 / 1024.0 / 1024.0));
+            }
+            match hardware.virtualization.as_deref() {
+                Some("none") => parts.push(t.bare_metal.to_string()),
+                Some(virtualization) => parts.push(virtualization.to_string()),
+                None => {}
+            }
+            output.push_str(&format!("**{}:** {}\n\n", t.hardware, parts.join(", ")));
+
+            if !hardware.disks.is_empty() {
+                let disks: Vec<String> = hardware
+                    .disks
+                    .iter()
+                    .map(|disk| {
+                        let size = disk.size_bytes as f64 / 1024.0 / 1024.0 / 1024.0;
+                        let kind = if disk.rotational { "HDD" } else { "SSD" };
+                        match disk.model {
+                            Some(ref model) => format!("{} {:.1} GiB ({}, {})", disk.name, size, kind, model),
+                            None => format!("{} {:.1} GiB ({})", disk.name, size, kind),
+                        }
+                    })
+                    .collect();
+                output.push_str(&format!("**{}:** {}\n\n", t.disks, disks.join(", ")));
+            }
+        }
+
+        if !vm.gpus.is_empty() {
+            output.push_str(&format!("**🎮 {}:**\n\n", t.gpus));
+            output.push_str(&Self::table_header(&t.gpu_columns));
+            let gib = |bytes: u64| bytes as f64 / 1024.0 / 1024.0 / 1024.0;
+            for gpu in &vm.gpus {
+                let memory = match (gpu.memory_used_bytes, gpu.memory_total_bytes) {
+                    (Some(used), Some(total)) => format!("{:.1} / {:.1} GiB", gib(used), gib(total)),
+                    (None, Some(total)) => format!("{:.1} GiB", gib(total)),
+                    _ => "-".to_string(),
+                };
+                output.push_str(&format!(
+                    "| {} | {} | {} | {} |\n",
+                    gpu.model,
+                    memory,
+                    gpu.utilization_percent.map(|p| format!("{}%", p)).unwrap_or_else(|| "-".to_string()),
+                    gpu.temperature_celsius.map(|c| format!("{:.0} °C", c)).unwrap_or_else(|| "-".to_string())
+                ));
+            }
+            output.push('\n');
+        }
+
+        if !vm.blacklistings.is_empty() {
+            let listings: Vec<String> = vm
+                .blacklistings
+                .iter()
+                .map(|listing| format!("{} ({}, {})", listing.ip, listing.list, listing.response))
+                .collect();
+            output.push_str(&format!("**🚫 {}:** {}\n\n", t.blacklisted, listings.join(", ")));
+        }
+
+        if let (Some(booted_at), Some(secs)) = (vm.booted_at, vm.uptime_secs) {
+            output.push_str(&format!(
+                "**{}:** {} ({}d {}h {}m)\n\n",
+                t.uptime,
+                booted_at.format("%Y-%m-%d %H:%M UTC"),
+                secs / 86400,
+                secs % 86400 / 3600,
+                secs % 3600 / 60
+            ));
+        }
+
+        if !vm.filesystems.is_empty() {
+            output.push_str(&format!("**💾 {}:**\n\n", t.filesystems));
+            output.push_str(&Self::table_header(&t.filesystem_columns));
+            for fs in &vm.filesystems {
+                output.push_str(&format!(
+                    "| {} | {} | {:.1} GiB | {}% | {} |\n",
+                    fs.mountpoint,
+                    fs.device,
+                    fs.size_bytes as f64 / 1024.0 / 1024.0 / 1024.0,
+                    fs.used_percent,
+                    fs.inodes_used_percent.map(|p| format!("{}%", p)).unwrap_or_else(|| "-".to_string())
+                ));
+            }
+            output.push('\n');
+        }
+
+        if let Some(swap) = vm.swap.as_ref().filter(|swap| swap.total_bytes > 0) {
+            output.push_str(&format!(
+                "**{}:** {}% ({:.1} / {:.1} GiB){}\n\n",
+                t.swap,
+                swap.used_percent().unwrap_or(0),
+                swap.used_bytes as f64 / 1024.0 / 1024.0 / 1024.0,
+                swap.total_bytes as f64 / 1024.0 / 1024.0 / 1024.0,
+                swap.pages_per_sec.map(|pages| format!(", {} pages/s", pages)).unwrap_or_default()
+            ));
+        }
+
+        if let Some(ref thermal) = vm.thermal {
+            let mut readings: Vec<_> = thermal.readings.iter().collect();
+            readings.sort_by(|a, b| b.celsius.total_cmp(&a.celsius));
+            let mut parts: Vec<String> =
+                readings.iter().take(5).map(|r| format!("{} {:.1} °C", r.name, r.celsius)).collect();
+            let [now, since_boot, events] = t.throttling;
+            if !thermal.throttling.is_empty() {
+                parts.push(format!("⚠️ {}: {}", now, thermal.throttling.join(", ")));
+            }
+            if !thermal.throttled_since_boot.is_empty() {
+                parts.push(format!("{}: {}", since_boot, thermal.throttled_since_boot.join(", ")));
+            }
+            if let Some(count) = thermal.throttle_count.filter(|count| *count > 0) {
+                parts.push(format!("{} {}", count, events));
+            }
+            output.push_str(&format!("**🌡️ {}:** {}\n\n", t.temperatures, parts.join(", ")));
+        }
+
+        if !vm.open_ports.is_empty() {
+            let classes = [SocketExposure::Loopback, SocketExposure::Vpn, SocketExposure::Private, SocketExposure::World];
+            let counts: Vec<String> = classes
+                .iter()
+                .zip(t.listener_classes)
+                .map(|(class, label)| (vm.open_ports.iter().filter(|p| p.exposure == Some(*class)).count(), label))
+                .filter(|(count, _)| *count > 0)
+                .map(|(count, label)| format!("{} {}", count, label))
+                .collect();
+            output.push_str(&format!("**{}:** {}\n\n", t.listeners, counts.join(", ")));
+        }
+
+        if !vm.ping.is_empty() {
+            output.push_str("**Ping:**\n");
+            for result in &vm.ping {
+                output.push_str(&format!(
+                    "- {} {}: {}/{} {} ({:.0}% {}), {} {}\n",
+                    if result.received > 0 { "✅" } else { "❌" },
+                    result.target,
+                    result.received,
+                    result.transmitted,
+                    t.received,
+                    result.loss_percent,
+                    t.loss,
+                    t.avg_rtt,
+                    result.avg_ms.map(|t| format!("{:.1}ms", t)).unwrap_or_else(|| "N/A".to_string())
+                ));
+            }
+            output.push('\n');
+        }
+
+        if !vm.paths.is_empty() {
+            output.push_str(&format!("**{}:**\n", t.network_paths));
+            for path in &vm.paths {
+                let ms = |ms: Option<f64>| ms.map(|t| format!("{:.1}ms", t)).unwrap_or_else(|| "N/A".to_string());
+                output.push_str(&format!(
+                    "- {} {} {}: SSH {}, ping {}{}\n",
+                    if path.ssh_connect_ms.is_some() { "✅" } else { "❌" },
+                    match path.kind {
+                        PathKind::Public => t.public_ip,
+                        PathKind::Vpn => "VPN",
+                    },
+                    path.address,
+                    ms(path.ssh_connect_ms),
+                    ms(path.ping_avg_ms),
+                    path.ping_loss_percent.map(|loss| format!(" ({:.0}% {})", loss, t.loss)).unwrap_or_default()
+                ));
+            }
+            output.push('\n');
+        }
+
+        if !vm.traceroute.is_empty() {
+            output.push_str("**Traceroute:**\n```\n");
+            for hop in &vm.traceroute {
+                output.push_str(&format!(
+                    "{:>2}  {:<15}  {}\n",
+                    hop.ttl,
+                    hop.address.as_deref().unwrap_or("*"),
+                    hop.rtt_ms.map(|t| format!("{:.1}ms", t)).unwrap_or_default()
+                ));
+            }
+            output.push_str("```\n\n");
+        }
+
+        if !vm.custom_checks.is_empty() {
+            output.push_str(&format!("**{}:**\n", t.custom_checks));
+            for check in &vm.custom_checks {
+                let emoji = match check.status {
+                    CustomCheckStatus::Ok => "✅",
+                    CustomCheckStatus::Warning => "⚠️",
+                    CustomCheckStatus::Critical => "❌",
+                    CustomCheckStatus::Unknown => "❓",
+                };
+                output.push_str(&format!("- {} {}: {}", emoji, check.name, check.message));
+                if !check.metrics.is_empty() {
+                    let metrics: Vec<String> = check.metrics.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+                    output.push_str(&format!(" ({})", metrics.join(", ")));
+                }
+                output.push('\n');
+            }
+            output.push('\n');
+        }
+
+        if let Some(jobs) = vm.scheduled_jobs.as_ref().filter(|jobs| !jobs.is_empty()) {
+            output.push_str(&format!("**⏰ {}:**\n", t.scheduled_jobs));
+            for job in jobs {
+                output.push_str(&format!("- `{}` {}", job.schedule, job.command));
+                match job.user {
+                    Some(ref user) => output.push_str(&format!(" ({}, {})\n", job.source, user)),
+                    None => output.push_str(&format!(" ({})\n", job.source)),
+                }
+            }
+            output.push('\n');
+        }
+
+        if !vm.archived_files.is_empty() {
+            output.push_str(&format!("**🗄️ {}:**\n", t.archived_files));
+            for file in &vm.archived_files {
+                output.push_str(&format!(
+                    "- `{}` ({} bytes{})\n",
+                    file.path,
+                    file.bytes,
+                    if file.via_sudo { ", sudo" } else { "" }
+                ));
+            }
+            output.push('\n');
+        }
+
+        if !vm.exposed_ports.is_empty() {
+            output.push_str(&format!("**{}:**\n", t.exposed_ports));
+            for exposed in &vm.exposed_ports {
+                let bound_to = [SocketExposure::Loopback, SocketExposure::Vpn, SocketExposure::Private]
+                    .iter()
+                    .zip(t.listener_classes)
+                    .find(|(class, _)| exposed.exposure == Some(**class))
+                    .map(|(_, label)| format!(" ({})", label))
+                    .unwrap_or_default();
+                output.push_str(&format!(
+                    "- {} {}/tcp {}{}\n",
+                    if exposed.expected_public && !exposed.is_forwarded() { "✅" } else { "❌" },
+                    exposed.port,
+                    exposed.process.as_deref().unwrap_or(""),
+                    bound_to
+                ));
+            }
+            output.push('\n');
+        }
+
+        if let Some(ref ext) = vm.host.external {
+            output.push_str(&format!(
+                "**{}:** {}{}\n",
+                t.provider,
+                ext.provider,
+                ext.region.as_ref().map(|r| format!(" ({})", r)).unwrap_or_default()
+            ));
+            if !ext.alternate_ips.is_empty() {
+                output.push_str(&format!("**{}:** {}\n", t.alternate_ips, ext.alternate_ips.join(", ")));
+            }
+            if !ext.expect_reachable {
+                output.push_str(&format!("**{}:** {}\n", t.expected_reachability, t.not_reachable_from_scanner));
+            }
+            output.push('\n');
+        }
+
+        if let Some(ref ip) = vm.connected_ip {
+            if *ip != vm.host.ip {
+                output.push_str(&format!("**{}:** {}\n\n", t.connected_via, ip));
+            }
+        }
 
         if vm.reachable {
-            output.push_str("**Servicios:**\n");
+            output.push_str(&format!("**{}:**\n", t.services));
             if vm.services.is_empty() {
-                output.push_str("- Ninguno detectado\n");
+                output.push_str(&format!("- {}\n", t.none_detected));
             } else {
                 for service in &vm.services {
                     let status_icon = match service.status {
@@ -111,14 +588,14 @@ impl MarkdownReporter {
                         ServiceStatus::NotFound => "❓",
                     };
                     output.push_str(&format!(
-                        "- {} {} (puertos: {:?})\n",
-                        status_icon, service.name, service.ports
+                        "- {} {} ({}: {:?})\n",
+                        status_icon, service.name, t.ports, service.ports
                     ));
                 }
             }
 
             if !vm.containers.is_empty() {
-                output.push_str("\n**Contenedores:**\n");
+                output.push_str(&format!("\n**{}:**\n", t.containers));
                 for container in &vm.containers {
                     let status_emoji = if container.status.contains("Up") {
                         "✅"
@@ -132,26 +609,225 @@ impl MarkdownReporter {
                 }
             }
 
+            if let Some(ref storage) = vm.container_storage {
+                let [images, unused, volumes, orphan, reclaimable] = t.container_storage_parts;
+                output.push_str(&format!(
+                    "\n**{}:** {} {} ({} {}), {} {} ({} {}), {:.1} GB {}\n",
+                    t.container_storage,
+                    storage.images.len(),
+                    images,
+                    storage.unused_images().count(),
+                    unused,
+                    storage.volumes.len(),
+                    volumes,
+                    storage.orphan_volumes().count(),
+                    orphan,
+                    storage.reclaimable_bytes() as f64 / 1e9,
+                    reclaimable
+                ));
+            }
+
             if let Some(ref wg) = vm.wireguard {
                 output.push_str(&format!(
                     "\n**WireGuard:**\n\
-                    - Interface: {}\n\
-                    - Public Key: {}\n\
-                    - Listening Port: {}\n\
-                    - Peers conectados: {}\n",
+                    - {}: {}\n\
+                    - {}: {}\n\
+                    - {}: {}\n\
+                    - {}: {}\n",
+                    t.interface,
                     wg.interface,
+                    t.public_key,
                     wg.public_key,
+                    t.listening_port,
                     wg.listening_port,
+                    t.connected_peers,
                     wg.peers.len()
                 ));
             }
 
+            if !vm.storage_arrays.is_empty() {
+                output.push_str(&format!("\n**{}:**\n", t.storage));
+                for array in &vm.storage_arrays {
+                    let status_emoji = if array.degraded { "❌" } else { "✅" };
+                    output.push_str(&format!(
+                        "- {} {} {} {} - {}",
+                        status_emoji,
+                        array.kind,
+                        array.name,
+                        array.level.as_deref().unwrap_or(""),
+                        array.state
+                    ));
+                    if !array.failed_devices.is_empty() {
+                        output.push_str(&format!(" ({}: {})", t.affected, array.failed_devices.join(", ")));
+                    }
+                    output.push('\n');
+                }
+            }
+
+            if !vm.certificates.is_empty() {
+                output.push_str(&format!("\n**{}:**\n", t.certificates));
+                for cert in &vm.certificates {
+                    let renew = if cert.auto_renew { String::new() } else { format!(" ⚠️ {}", t.no_auto_renew) };
+                    output.push_str(&format!(
+                        "- {} ({}) - {}: {}{}\n",
+                        cert.name,
+                        cert.source,
+                        t.expires,
+                        cert.expires
+                            .map(|e| e.format("%Y-%m-%d").to_string())
+                            .unwrap_or_else(|| t.unknown.to_string()),
+                        renew
+                    ));
+                }
+            }
+
+            if let Some(ref traefik) = vm.traefik {
+                output.push_str(&format!(
+                    "\n**Traefik:** {} routers, {} {}\n",
+                    traefik.routers.len(),
+                    traefik.services.len(),
+                    t.services_lower
+                ));
+                for service in traefik.services.iter().filter(|s| !s.servers.is_empty()) {
+                    let down = service
+                        .servers
+                        .iter()
+                        .filter(|s| s.status.as_deref() != Some("UP"))
+                        .count();
+                    let status_emoji = if down == 0 { "✅" } else { "❌" };
+                    output.push_str(&format!(
+                        "- {} {} ({}/{} {})\n",
+                        status_emoji,
+                        service.name,
+                        service.servers.len() - down,
+                        service.servers.len(),
+                        t.backends_up
+                    ));
+                }
+            }
+
+            if !vm.databases.is_empty() {
+                output.push_str(&format!("\n**{}:**\n", t.databases));
+                for db in &vm.databases {
+                    if !db.connected {
+                        output.push_str(&format!(
+                            "- ❌ {} - {}\n",
+                            db.engine,
+                            db.error.as_deref().unwrap_or(t.no_connection)
+                        ));
+                        continue;
+                    }
+                    output.push_str(&format!(
+                        "- ✅ {}{} - {}: {}, {}: {}",
+                        db.engine,
+                        if db.is_replica { format!(" ({})", t.replica) } else { String::new() },
+                        t.connections,
+                        db.connections.map(|c| c.to_string()).unwrap_or_else(|| "N/A".to_string()),
+                        t.oldest_transaction,
+                        db.oldest_transaction_secs
+                            .map(|t| format!("{:.0}s", t))
+                            .unwrap_or_else(|| "N/A".to_string())
+                    ));
+                    if db.is_replica {
+                        output.push_str(&format!(
+                            ", {}: {}",
+                            t.lag,
+                            db.replication_lag_secs
+                                .filter(|_| db.replicating != Some(false))
+                                .map(|l| format!("{:.0}s", l))
+                                .unwrap_or_else(|| t.stopped.to_string())
+                        ));
+                    }
+                    output.push('\n');
+                }
+            }
+
+            if !vm.data_stores.is_empty() {
+                output.push_str(&format!("\n**{}:**\n", t.data_stores));
+                for store in &vm.data_stores {
+                    if !store.healthy {
+                        output.push_str(&format!(
+                            "- ❌ {} - {}\n",
+                            store.engine,
+                            store.error.as_deref().unwrap_or(t.no_response)
+                        ));
+                        continue;
+                    }
+                    let mut details = Vec::new();
+                    if let Some(ref memory) = store.memory_used {
+                        details.push(format!("{}: {}", t.memory, memory));
+                    }
+                    if let Some(keys) = store.keys {
+                        details.push(format!("{}: {}", t.keys, keys));
+                    }
+                    if let Some(ref replication) = store.replication {
+                        details.push(format!("{}: {}", t.replication, replication));
+                    }
+                    output.push_str(&format!("- ✅ {}", store.engine));
+                    if !details.is_empty() {
+                        output.push_str(&format!(" - {}", details.join(", ")));
+                    }
+                    output.push('\n');
+                }
+            }
+
+            if let Some(ref samba) = vm.samba {
+                output.push_str("\n**Samba:**\n");
+                output.push_str(&format!(
+                    "- {}: {}{}\n",
+                    t.configured_interfaces,
+                    if samba.interfaces.is_empty() { t.all.to_string() } else { samba.interfaces.join(", ") },
+                    if samba.bind_interfaces_only { " (bind interfaces only)" } else { "" }
+                ));
+                output.push_str(&format!("- smbd {}: {}\n", t.listening_on, samba.smbd_bindings.join(", ")));
+                output.push_str(&format!("- nmbd {}: {}\n", t.listening_on, samba.nmbd_bindings.join(", ")));
+                if !samba.unbound_interfaces.is_empty() {
+                    output.push_str(&format!("- ❌ {}: {}\n", t.unbound_interfaces, samba.unbound_interfaces.join(", ")));
+                }
+                for share in &samba.shares {
+                    output.push_str(&format!(
+                        "- Share `{}` → {}\n",
+                        share.name,
+                        share.path.as_deref().unwrap_or("-")
+                    ));
+                }
+                if let Some(ref listing) = samba.anonymous_listing {
+                    let status_emoji = if listing.reachable { "✅" } else { "❌" };
+                    output.push_str(&format!(
+                        "- {} {}: {}\n",
+                        status_emoji,
+                        t.anonymous_listing,
+                        listing.error.clone().unwrap_or_else(|| format!("{} {}", listing.shares.len(), t.shares_visible))
+                    ));
+                }
+            }
+
+            if !vm.kernel_events.is_empty() {
+                output.push_str(&format!("\n**🐧 {}:**\n", t.kernel_events));
+                for event in vm.kernel_events.iter().rev().take(10) {
+                    output.push_str(&format!("- {} {}\n", event.timestamp.format("%Y-%m-%d %H:%M:%S"), event.message));
+                }
+            }
+
             if !vm.recent_errors.is_empty() {
-                output.push_str("\n**Logs recientes (últimas 24h):**\n");
-                for error in vm.recent_errors.iter().take(10) {
+                let mut by_service: Vec<(&str, usize)> = Vec::new();
+                for error in &vm.recent_errors {
+                    match by_service.iter_mut().find(|(service, _)| *service == error.service) {
+                        Some((_, count)) => *count += 1,
+                        None => by_service.push((&error.service, 1)),
+                    }
+                }
+                by_service.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+                let counts: Vec<String> = by_service.iter().map(|(service, count)| format!("{} ({})", service, count)).collect();
+                output.push_str(&format!("\n**{}:** {}\n", t.recent_logs, counts.join(", ")));
+                // Newest first
+                for error in vm.recent_errors.iter().rev().take(10) {
                     output.push_str(&format!(
-                        "```\n{} {} {}\n```\n",
-                        error.timestamp, error.service, error.message
+                        "```\n{} {} [{}] {}\n```\n",
+                        error.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                        error.service,
+                        error.level(),
+                        error.message
                     ));
                 }
             }
@@ -160,13 +836,16 @@ impl MarkdownReporter {
         output
     }
 
-    fn web_services_table(services: &[WebService]) -> String {
-        let mut table = String::from("| Servicio | URL | HTTP Status | Tiempo response |\n");
-        table.push_str("|----------|-----|-------------|----------------|\n");
+    fn web_services_table(services: &[WebService], t: &Strings) -> String {
+        let mut table = Self::table_header(&t.web_services_columns);
 
         for service in services {
             let status = if let Some(status) = service.http_status {
-                if (200..300).contains(&status) {
+                if service.content_error.is_some() {
+                    format!("{} {} ({})", "❌", status, t.content)
+                } else if service.unexpected_status {
+                    format!("{} {}", "❌", status)
+                } else if (200..300).contains(&status) {
                     format!("{} {}", "✅", status)
                 } else if (300..400).contains(&status) {
                     format!("{} {}", "⚠️", status)
@@ -179,29 +858,487 @@ impl MarkdownReporter {
                 "?".to_string()
             };
 
-            let time = service
-                .response_time
-                .map(|t| format!("{:.3}s", t))
-                .unwrap_or_else(|| "N/A".to_string());
+            let format_time = |t: Option<f64>| t.map(|t| format!("{:.3}s", t)).unwrap_or_else(|| "N/A".to_string());
+            let (time, error_rate) = match service.latency {
+                Some(ref latency) => (
+                    format!(
+                        "{} / {} / {}",
+                        format_time(latency.min),
+                        format_time(latency.median),
+                        format_time(latency.p95)
+                    ),
+                    format!("{:.0}%", latency.error_rate * 100.0),
+                ),
+                None => (format_time(service.response_time), "N/A".to_string()),
+            };
+
+            let backend = service
+                .route
+                .as_ref()
+                .map(|r| format!("{} → {}", r.vm, r.service))
+                .unwrap_or_else(|| "-".to_string());
+
+            let https = match service.https_enforced {
+                Some(true) => "✅",
+                Some(false) => "❌",
+                None => "-",
+            };
+
+            table.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} | {} |\n",
+                service.name, service.url, status, time, error_rate, https, backend
+            ));
+        }
+
+        table
+    }
+
+    /// Rows are the iperf3 clients, columns the servers; failed tests show ❌.
+    fn bandwidth_matrix(results: &[BandwidthResult], t: &Strings) -> String {
+        let hosts: BTreeSet<&str> = results.iter().flat_map(|r| [r.from.as_str(), r.to.as_str()]).collect();
+        let mut columns = vec![t.bandwidth_corner];
+        columns.extend(hosts.iter().copied());
+
+        let mut output = Self::table_header(&columns);
+        for from in &hosts {
+            let cells: Vec<String> = hosts
+                .iter()
+                .map(|to| match results.iter().find(|r| r.from == *from && r.to == *to) {
+                    _ if from == to => "-".to_string(),
+                    Some(BandwidthResult { mbps: Some(mbps), .. }) => format!("{:.0}", mbps),
+                    Some(_) => "❌".to_string(),
+                    None => String::new(),
+                })
+                .collect();
+            output.push_str(&format!("| **{}** | {} |\n", from, cells.join(" | ")));
+        }
+        output
+    }
+
+    fn proxmox_tables(proxmox: &ProxmoxInventory, t: &Strings) -> String {
+        let gib = |bytes: Option<u64>| {
+            bytes
+                .map(|b| format!("{:.1} GiB", b as f64 / 1024.0 / 1024.0 / 1024.0))
+                .unwrap_or_else(|| "-".to_string())
+        };
+
+        let mut output = Self::table_header(&t.proxmox_node_columns);
+        for node in &proxmox.nodes {
+            output.push_str(&format!(
+                "| {} | {} | {} | {} / {} |\n",
+                node.name,
+                if node.status == "online" { "✅ online".to_string() } else { format!("❌ {}", node.status) },
+                match (node.cpu_usage, node.max_cpu) {
+                    (Some(usage), Some(max)) => format!("{:.0}% {} {} {}", usage * 100.0, t.cores_of, max, t.cores),
+                    _ => "-".to_string(),
+                },
+                gib(node.mem_used),
+                gib(node.max_mem)
+            ));
+        }
+
+        output.push('\n');
+        output.push_str(&Self::table_header(&t.proxmox_guest_columns));
+        for guest in &proxmox.guests {
+            output.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} | {} | {} | {} |\n",
+                guest.vmid,
+                guest.name,
+                guest.kind,
+                guest.node,
+                guest.status,
+                guest.max_cpu.map(|c| c.to_string()).unwrap_or_else(|| "-".to_string()),
+                gib(guest.max_mem),
+                gib(guest.max_disk),
+                guest.matched_host.as_ref().map(|h| format!("✅ {}", h)).unwrap_or_else(|| "-".to_string())
+            ));
+        }
+
+        output
+    }
+
+    fn terraform_table(terraform: &TerraformComparison, t: &Strings) -> String {
+        let mut output = Self::table_header(&t.terraform_columns);
+
+        for resource in &terraform.resources {
+            let status = match (&resource.matched_host, resource.reachable) {
+                (Some(_), true) => format!("✅ {}", t.responds),
+                (Some(_), false) => format!("❌ {}", t.not_responding),
+                (None, _) => format!("❓ {}", t.not_in_inventory),
+            };
+            output.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                resource.address,
+                resource.name,
+                resource.ips.join(", "),
+                resource.matched_host.as_deref().unwrap_or("-"),
+                status
+            ));
+        }
+
+        if !terraform.unmanaged_hosts.is_empty() {
+            output.push_str(&format!(
+                "\n**{}:** {}\n",
+                t.unmanaged_hosts,
+                terraform.unmanaged_hosts.join(", ")
+            ));
+        }
+
+        output
+    }
+
+    fn suppressions_table(suppressions: &[ActiveSuppression], t: &Strings) -> String {
+        let mut table = Self::table_header(&t.suppression_columns);
+
+        for active in suppressions {
+            let suppression = &active.suppression;
+            table.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                suppression.criteria(),
+                suppression.reason.as_deref().unwrap_or("-"),
+                suppression.expires.map(|d| d.to_string()).unwrap_or_else(|| "-".to_string()),
+                active.suppressed.len()
+            ));
+        }
+
+        table
+    }
+
+    fn timing_tables(profile: &TimingProfile, t: &Strings) -> String {
+        let mut tables = format!("{}: {:.1}s\n\n", t.scan_duration, profile.duration_secs);
+
+        tables.push_str(&Self::table_header(&t.timing_host_columns));
+        for host in &profile.hosts {
+            let mut checks: Vec<(&String, &f64)> = host.checks.iter().collect();
+            checks.sort_by(|a, b| b.1.total_cmp(a.1));
+            let slowest: Vec<String> = checks.iter().take(3).map(|(check, secs)| format!("{} {:.1}s", check, secs)).collect();
+            tables.push_str(&format!("| {} | {:.1} | {} |\n", host.host, host.duration_secs, slowest.join(", ")));
+        }
+
+        tables.push('\n');
+        tables.push_str(&Self::table_header(&t.timing_check_columns));
+        for check in &profile.checks {
+            tables.push_str(&format!(
+                "| {} | {:.1} | {:.1} | {} |\n",
+                check.check, check.total_secs, check.max_secs, check.slowest_host
+            ));
+        }
+        for (check, secs) in &profile.scan_checks {
+            tables.push_str(&format!("| {} | {:.1} | {:.1} | - |\n", check, secs, secs));
+        }
+
+        tables
+    }
+
+    fn policy_table(violations: &[PolicyViolation], t: &Strings) -> String {
+        let mut table = Self::table_header(&t.policy_columns);
+
+        for violation in violations {
+            let severity = match violation.severity {
+                Severity::Critical => "❌",
+                Severity::Warning => "⚠️",
+                Severity::Info => "ℹ️",
+            };
+            table.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                violation.rule_id,
+                severity,
+                violation.host,
+                violation.description.as_deref().unwrap_or("-"),
+                violation.message
+            ));
+        }
+
+        table
+    }
 
+    fn cloud_instances_table(instances: &[CloudInstance], t: &Strings) -> String {
+        let mut table = Self::table_header(&t.cloud_columns);
+
+        for instance in instances {
+            table.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} | {} | {} |\n",
+                instance.provider,
+                instance.name,
+                instance.location.as_deref().unwrap_or("-"),
+                instance.machine_type.as_deref().unwrap_or("-"),
+                instance.status,
+                instance.public_ips.join(", "),
+                instance.private_ips.join(", "),
+                instance
+                    .matched_host
+                    .as_ref()
+                    .map(|h| format!("✅ {}", h))
+                    .unwrap_or_else(|| format!("❌ {}", t.not_inventoried))
+            ));
+        }
+
+        table
+    }
+
+    fn domains_table(domains: &[DomainRegistration], now: DateTime<Utc>, t: &Strings) -> String {
+        let mut table = Self::table_header(&t.domain_columns);
+
+        for registration in domains {
+            let (expires, days_left) = match (registration.expires, &registration.error) {
+                (Some(expires), _) => (
+                    expires.format("%Y-%m-%d").to_string(),
+                    (expires - now).num_days().to_string(),
+                ),
+                (None, Some(error)) => (format!("❌ {}", error), "-".to_string()),
+                (None, None) => ("-".to_string(), "-".to_string()),
+            };
             table.push_str(&format!(
                 "| {} | {} | {} | {} |\n",
-                service.name, service.url, status, time
+                registration.domain,
+                registration.registrar.as_deref().unwrap_or("-"),
+                expires,
+                days_left
+            ));
+        }
+
+        table
+    }
+
+    fn ct_table(certificates: &[CtCertificate], t: &Strings) -> String {
+        let mut table = Self::table_header(&t.ct_columns);
+
+        for certificate in certificates {
+            // Unknown names and issuers are what the certificate is listed for
+            let names: Vec<String> = certificate
+                .names
+                .iter()
+                .map(|name| if certificate.unknown_names.contains(name) { format!("**{}**", name) } else { name.clone() })
+                .collect();
+            table.push_str(&format!(
+                "| [{}](https://crt.sh/?id={}) | {} | {}{} | {} → {} |\n",
+                certificate.id,
+                certificate.id,
+                names.join(", "),
+                if certificate.unknown_issuer { "❌ " } else { "" },
+                certificate.issuer,
+                certificate.not_before.format("%Y-%m-%d"),
+                certificate.not_after.format("%Y-%m-%d")
             ));
         }
 
         table
     }
 
-    pub fn save_report(report: &InventoryReport, output_path: &str) -> Result<()> {
-        let markdown = Self::generate_report(report)?;
+    fn redirect_chains(services: &[WebService], t: &Strings) -> String {
+        let mut output = String::new();
+
+        for service in services.iter().filter(|s| !s.redirect_chain.is_empty()) {
+            if output.is_empty() {
+                output.push_str(&format!("\n**{}:**\n", t.redirect_chains));
+            }
+            let status_emoji = if service.redirect_error.is_some() { "❌" } else { "↪️" };
+            output.push_str(&format!(
+                "- {} {}: {} → {}\n",
+                status_emoji,
+                service.name,
+                service.url,
+                service.redirect_chain.join(" → ")
+            ));
+        }
+
+        output
+    }
+
+    fn dns_records(services: &[WebService], t: &Strings) -> String {
+        let mut output = String::new();
+
+        for service in services {
+            let Some(ref dns) = service.dns else { continue };
+            if output.is_empty() {
+                output.push_str(&format!("\n**{}:**\n", t.dns_records));
+            }
+            let domain = service.url.split("://").nth(1).and_then(|rest| rest.split(['/', ':']).next()).unwrap_or_default();
+            let mut chain = vec![domain.to_string()];
+            chain.extend(dns.cnames.iter().cloned());
+            chain.push(match dns.error {
+                Some(ref error) => error.clone(),
+                None => dns.addresses.join(", "),
+            });
+            let status_emoji = if dns.error.is_some() || service.dns_error.is_some() { "❌" } else { "✅" };
+            output.push_str(&format!("- {} {}: {}\n", status_emoji, service.name, chain.join(" → ")));
+        }
+
+        output
+    }
+
+    fn tls_audits(services: &[WebService], t: &Strings) -> String {
+        let mut output = String::new();
+
+        for service in services {
+            let Some(ref tls) = service.tls else { continue };
+            if output.is_empty() {
+                output.push_str(&format!("\n**{}:**\n", t.tls_protocols));
+            }
+            let mut line = match (&tls.protocol, &tls.cipher, &tls.error) {
+                (Some(protocol), Some(cipher), _) => format!("{}, {}", protocol, cipher),
+                (_, _, Some(error)) => error.clone(),
+                _ => "?".to_string(),
+            };
+            let mut weak = tls.legacy_protocols.clone();
+            weak.extend(tls.weak_cipher.iter().cloned());
+            if !weak.is_empty() {
+                line.push_str(&format!(" ({} {})", t.accepts, weak.join(", ")));
+            }
+            let status_emoji = if weak.is_empty() && tls.error.is_none() { "🔒" } else { "❌" };
+            output.push_str(&format!("- {} {}: {}\n", status_emoji, service.name, line));
+        }
+
+        output
+    }
+
+    fn scenarios(services: &[WebService], t: &Strings) -> String {
+        let mut output = String::new();
+
+        for service in services.iter().filter(|s| !s.scenario.is_empty()) {
+            if output.is_empty() {
+                output.push_str(&format!("\n**{}:**\n", t.scenarios));
+            }
+            let steps: Vec<String> = service
+                .scenario
+                .iter()
+                .map(|step| {
+                    let status = step.http_status.map(|s| s.to_string()).unwrap_or_else(|| "ERROR".to_string());
+                    match step.error {
+                        Some(ref error) => format!("❌ {} ({}, {})", step.name, status, error),
+                        None => format!("{} ({}, {:.3}s)", step.name, status, step.duration),
+                    }
+                })
+                .collect();
+            let status_emoji = if service.scenario.iter().any(|step| step.error.is_some()) { "❌" } else { "✅" };
+            output.push_str(&format!("- {} {}: {}\n", status_emoji, service.name, steps.join(" → ")));
+        }
+
+        output
+    }
+
+    fn health_reports(services: &[WebService], t: &Strings) -> String {
+        let mut output = String::new();
+
+        for service in services {
+            let Some(ref health) = service.health else { continue };
+            if output.is_empty() {
+                output.push_str(&format!("\n**{}:**\n", t.health_endpoints));
+            }
+            let healthy = health.error.is_none() && health.fields.iter().all(|field| field.ok);
+            output.push_str(&format!(
+                "- {} {}: {} ({})\n",
+                if healthy { "✅" } else { "❌" },
+                service.name,
+                health.url,
+                match health.error {
+                    Some(ref error) => error.clone(),
+                    None => health.http_status.map(|s| s.to_string()).unwrap_or_default(),
+                }
+            ));
+            for field in &health.fields {
+                let value = field.value.as_deref().unwrap_or(t.health_field_parts[1]);
+                match field.expected {
+                    Some(ref expected) if !field.ok => output.push_str(&format!(
+                        "  - ❌ {}: {} ({} {})\n",
+                        field.path, value, t.health_field_parts[0], expected
+                    )),
+                    Some(_) => output.push_str(&format!("  - ✅ {}: {}\n", field.path, value)),
+                    None => output.push_str(&format!("  - {}: {}\n", field.path, value)),
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Standalone page for a single VM, linkable from wikis or Obsidian.
+    pub fn host_page(vm: &VmStatus, report: &InventoryReport, lang: Lang) -> String {
+        let t = lang.strings();
+        let mut output = format!(
+            "# {}\n{}: {}\n",
+            vm.host.name,
+            t.date,
+            report.timestamp.format("%Y-%m-%d %H:%M UTC")
+        );
+        if report.partial {
+            output.push_str(&format!("\n> ⚠️ {}\n", t.partial_report));
+        }
+        output.push('\n');
+        output.push_str(&Self::vm_status(vm, t, "###"));
+
+        if !vm.checks.is_empty() {
+            output.push_str(&format!("\n**{}:**\n", t.checks));
+            for check in &vm.checks {
+                output.push_str(&format!("- {} {}\n", Self::check_emoji(check.result), check.name));
+                for message in &check.messages {
+                    output.push_str(&format!("  - {}\n", message));
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Index of the per-host pages; `link` maps a VM to its page's file name.
+    pub fn host_index(report: &InventoryReport, lang: Lang, link: impl Fn(&VmStatus) -> String) -> String {
+        let t = lang.strings();
+        let mut output = format!(
+            "# {}\n{}: {}\n\n",
+            t.host_index,
+            t.date,
+            report.timestamp.format("%Y-%m-%d %H:%M UTC")
+        );
+        if report.partial {
+            output.push_str(&format!("> ⚠️ {}\n\n", t.partial_report));
+        }
+        output.push_str(&Self::table_header(&t.host_index_columns));
+
+        for vm in &report.vms {
+            let problems: Vec<&str> = vm
+                .checks
+                .iter()
+                .filter(|c| matches!(c.result, CheckResult::Failed | CheckResult::Warning))
+                .map(|c| c.name.as_str())
+                .collect();
+            output.push_str(&format!(
+                "| [{}]({}) | {} | {} | {} |\n",
+                vm.host.name,
+                link(vm),
+                vm.host.ip,
+                if vm.reachable { format!("✅ {}", t.operational) } else { format!("❌ {}", t.unreachable) },
+                if problems.is_empty() { "-".to_string() } else { problems.join(", ") }
+            ));
+        }
+
+        output
+    }
+
+    fn check_emoji(result: CheckResult) -> &'static str {
+        match result {
+            CheckResult::Passed => "✅",
+            CheckResult::Warning => "⚠️",
+            CheckResult::Failed => "❌",
+            CheckResult::Skipped => "⏭️",
+        }
+    }
+
+    /// Header row plus separator for a markdown table with these columns.
+    fn table_header(columns: &[&str]) -> String {
+        let header: Vec<String> = columns.iter().map(|c| format!(" {} ", c)).collect();
+        let separator: Vec<String> = header.iter().map(|c| "-".repeat(c.chars().count())).collect();
+        format!("|{}|\n|{}|\n", header.join("|"), separator.join("|"))
+    }
+
+    pub fn save_report(report: &InventoryReport, output_path: &str, lang: Lang) -> Result<()> {
+        let markdown = Self::generate_report(report, lang)?;
         let mut file = File::create(output_path)
             .context(format!("Failed to create report file: {}", output_path))?;
 
         file.write_all(markdown.as_bytes())
             .context("Failed to write report")?;
 
-        println!("\n✅ Reporte guardado en: {}", output_path.green().bold());
+        info!("Reporte guardado en: {}", output_path);
         Ok(())
     }
 }
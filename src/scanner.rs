@@ -1,93 +1,1217 @@
+use crate::capabilities::{self, CapabilityCache};
+use crate::config::Config;
+use crate::custom_checks;
+use crate::dependencies;
+use crate::events::ScanEvent;
 use crate::models::*;
-use crate::ssh_client::SshClient;
+use crate::file_audit;
+use crate::integrity;
+use crate::kernel_events;
+use crate::log_scan::LogScanner;
+use crate::maintenance;
+use crate::pinger::Pinger;
+use crate::policy;
+use crate::rbl;
+use crate::reboots;
+use crate::port_scanner::PortScanner;
+use crate::progress::{HostProgress, ScanProgress};
+use crate::scheduled_jobs;
+use crate::scripting;
+use crate::state;
+use crate::ssh_config;
+use crate::suppressions;
+use crate::providers;
+use crate::proxmox::ProxmoxClient;
+use crate::telemetry::Metrics;
+use crate::ssh_client::{CheckCancelled, CheckLimits, CheckTimedOut, HostKeyChanged, SshClient};
+use crate::terraform;
+use crate::traceroute::Tracer;
 use crate::web_scanner::WebScanner;
 use anyhow::Result;
-use chrono::Utc;
-use colored::Colorize;
+use futures::future::join_all;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Mutex;
+use std::time::Instant;
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error_span, info, info_span, warn, Instrument};
+
+const CERT_EXPIRY_WARNING_DAYS: i64 = 21;
+const REPLICATION_LAG_WARNING_SECS: f64 = 300.0;
+const OLD_TRANSACTION_WARNING_SECS: f64 = 3600.0;
 
 pub struct InventoryScanner {
     hosts: Vec<VmHost>,
+    config: Config,
+    cancel: CancellationToken,
+    events: broadcast::Sender<ScanEvent>,
+    metrics: Metrics,
+    /// When each open issue was first raised, by [`Issue::key`].
+    first_seen: Mutex<HashMap<String, DateTime<Utc>>>,
+    /// Seconds spent on each check of the hosts being scanned, by host name.
+    timings: Mutex<HashMap<String, BTreeMap<String, f64>>>,
+    /// What each host can run, from this or earlier scans.
+    capabilities: Mutex<CapabilityCache>,
+    port_scanner: PortScanner,
+    log_scanner: LogScanner,
+    pinger: Pinger,
+    tracer: Tracer,
+}
+
+/// Issues raised while scanning, by severity.
+#[derive(Default)]
+struct Findings {
+    critical_issues: Vec<Issue>,
+    warnings: Vec<Issue>,
+    notices: Vec<Issue>,
+}
+
+impl Findings {
+    fn push(&mut self, severity: Severity, host: Option<&str>, check: &str, message: String) {
+        let issue = Issue::new(severity, host, check, message);
+        match severity {
+            Severity::Critical => self.critical_issues.push(issue),
+            Severity::Warning => self.warnings.push(issue),
+            Severity::Info => self.notices.push(issue),
+        }
+    }
+
+    /// Runs one evaluation step and records the critical issues and warnings
+    /// it raised under `host` and `check`. Returns their messages.
+    fn evaluate(
+        &mut self,
+        host: Option<&str>,
+        check: &str,
+        evaluate: impl FnOnce(&mut Vec<String>, &mut Vec<String>),
+    ) -> (Vec<String>, Vec<String>) {
+        let (mut critical, mut warnings) = (Vec::new(), Vec::new());
+        evaluate(&mut critical, &mut warnings);
+        for message in &critical {
+            self.push(Severity::Critical, host, check, message.clone());
+        }
+        for message in &warnings {
+            self.push(Severity::Warning, host, check, message.clone());
+        }
+        (critical, warnings)
+    }
+
+    /// Evaluates one check of `host` and records its verdict in `checks`.
+    fn check(
+        &mut self,
+        checks: &mut Vec<CheckOutcome>,
+        host: &str,
+        name: &str,
+        evaluate: impl FnOnce(&mut Vec<String>, &mut Vec<String>),
+    ) {
+        let (critical, warnings) = self.evaluate(Some(host), name, evaluate);
+        checks.push(CheckOutcome::from_issues(name, critical, warnings));
+    }
+}
+
+/// A collection step started by [`InventoryScanner::spawn`].
+enum Pending<'scope, T> {
+    Done(T),
+    Running(std::thread::ScopedJoinHandle<'scope, T>),
+}
+
+impl<T> Pending<'_, T> {
+    fn join(self) -> T {
+        match self {
+            Pending::Done(value) => value,
+            Pending::Running(handle) => handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)),
+        }
+    }
+}
+
+/// What the parallel collection stage gathered from a host; a check that
+/// was skipped or failed leaves its default.
+struct Collected {
+    services: Vec<Service>,
+    containers: Vec<Container>,
+    wireguard: Option<WireGuardStatus>,
+    open_ports: Vec<Port>,
+    load: Option<LoadAverage>,
+    processes: Vec<Process>,
+    recent_errors: Vec<LogEntry>,
+    storage_arrays: Vec<StorageArray>,
+    certificates: Vec<ManagedCertificate>,
+    scheduled_jobs: Option<Vec<ScheduledJob>>,
+    container_storage: Option<ContainerStorage>,
+    kernel_events: Vec<KernelEvent>,
+    thermal: Option<Thermal>,
+    filesystems: Vec<Filesystem>,
+    swap: Option<Swap>,
+    uptime: Option<std::time::Duration>,
+    os: Option<OsRelease>,
+    hardware: Option<Hardware>,
+    gpus: Vec<Gpu>,
 }
 
 impl InventoryScanner {
-    pub fn new(hosts: Vec<VmHost>) -> Self {
-        Self { hosts }
+    pub fn new(hosts: Vec<VmHost>, config: Config) -> Self {
+        Self {
+            port_scanner: PortScanner::new(config.exposure.clone()),
+            log_scanner: LogScanner::new(config.logs.clone()),
+            pinger: Pinger::new(config.ping.clone()),
+            tracer: Tracer::new(config.traceroute.clone()),
+            capabilities: Mutex::new(state::load(&config.state.path(&config.capabilities.cache_file), "capability cache").unwrap_or_else(|e| {
+                warn!("Capability cache not loaded, probing every host: {:#}", e);
+                CapabilityCache::new()
+            })),
+            hosts,
+            config,
+            cancel: CancellationToken::new(),
+            events: broadcast::channel(256).0,
+            metrics: Metrics::new(),
+            first_seen: Mutex::new(HashMap::new()),
+            timings: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Token that stops the scan early; the report is still produced from
+    /// what was collected so far and marked as partial.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancel.clone()
+    }
+
+    /// Live progress of every scan run by this scanner.
+    pub fn events(&self) -> broadcast::Sender<ScanEvent> {
+        self.events.clone()
+    }
+
+    fn emit(&self, event: ScanEvent) {
+        // No subscribers is the common case, not an error
+        let _ = self.events.send(event);
     }
 
     pub async fn scan(&self) -> Result<InventoryReport> {
-        let web_scanner = WebScanner::new();
-        let web_services = web_scanner.scan_all().await?;
+        let started = Instant::now();
+        let mut report = self
+            .run_scan()
+            .instrument(info_span!("scan", hosts = self.hosts.len()))
+            .await?;
+        report.duration_secs = started.elapsed().as_secs_f64();
+        self.metrics.scan(report.duration_secs, report.partial);
+        Ok(report)
+    }
+
+    async fn run_scan(&self) -> Result<InventoryReport> {
+        let deadline = self.config.timeouts.scan_secs.map(|secs| Instant::now() + std::time::Duration::from_secs(secs));
+        let mut timings = BTreeMap::new();
+        let web_started = Instant::now();
+        let web_services = if self.runs("web") { self.config.web_services.clone() } else { Vec::new() };
+        let web_scanner = WebScanner::new(web_services, self.config.tls.clone());
+        let mut web_services = tokio::select! {
+            result = web_scanner.scan_all() => result?,
+            _ = self.cancel.cancelled() => Vec::new(),
+        };
+        if self.runs("web") {
+            timings.insert("web".to_string(), web_started.elapsed().as_secs_f64());
+        }
 
         let mut vms = Vec::new();
-        let mut critical_issues = Vec::new();
-        let mut warnings = Vec::new();
+        let mut findings = Findings::default();
 
-        println!("{} Scanning VMs...", "[*]".blue().bold());
+        info!("Scanning VMs...");
+        self.emit(ScanEvent::ScanStarted { hosts: self.hosts.len() });
+        let progress = ScanProgress::new(self.hosts.len());
 
         for host in &self.hosts {
-            println!("  Checking {}...", host.name.cyan());
-            
-            match SshClient::connect(host.clone()).await {
-                Ok(ssh_client) => {
-                    let reachable = ssh_client.is_reachable();
-                    
-                    if !reachable {
-                        warnings.push(format!("{} is not reachable", host.name));
-                    }
+            if self.cancel.is_cancelled() {
+                vms.push(VmStatus::not_scanned(host.clone(), "scan interrupted"));
+                continue;
+            }
 
-                    let services = ssh_client.list_running_services().unwrap_or_default();
-                    let containers = ssh_client.list_containers().unwrap_or_default();
-                    let wireguard = ssh_client.get_wireguard_status().unwrap_or(None);
-                    let open_ports = ssh_client.get_open_ports().unwrap_or_default();
-                    let recent_errors = ssh_client.get_recent_errors().unwrap_or_default();
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                warn!("Skipping {}: scan deadline reached", host.name);
+                findings.push(
+                    Severity::Warning,
+                    Some(&host.name),
+                    "connectivity",
+                    format!("{}: not scanned, scan deadline reached", host.name),
+                );
+                vms.push(VmStatus::not_scanned(host.clone(), "scan deadline reached"));
+                continue;
+            }
 
-                    // Check for critical issues
-                    self.check_critical_issues(&host, &services, &recent_errors, &mut critical_issues);
-                    
-                    vms.push(VmStatus {
-                        host: host.clone(),
-                        reachable,
-                        services,
-                        containers,
-                        wireguard,
-                        open_ports,
-                        recent_errors,
-                    });
+            // Error level so the span stays attached to warnings under --quiet
+            let span = error_span!("host", host = %host.name);
+            let host_progress = progress.host(&host.name);
+            vms.push(self.scan_host(host, deadline, &host_progress, &mut findings).instrument(span).await);
+        }
+        progress.finish();
+        let cache_path = self.config.state.path(&self.config.capabilities.cache_file);
+        if let Err(e) = state::save(&cache_path, "capability cache", &*self.capabilities.lock().unwrap()) {
+            warn!("Capability cache not saved: {:#}", e);
+        }
+
+        if self.runs("integrity") {
+            let started = Instant::now();
+            self.check_integrity(&mut vms, &mut findings);
+            timings.insert("integrity".to_string(), started.elapsed().as_secs_f64());
+        }
+        if self.runs("suid") {
+            let started = Instant::now();
+            self.check_special_files(&mut vms, &mut findings);
+            timings.insert("suid".to_string(), started.elapsed().as_secs_f64());
+        }
+        if self.runs("uptime") {
+            let started = Instant::now();
+            self.check_reboots(&mut vms, &mut findings);
+            timings.insert("uptime".to_string(), started.elapsed().as_secs_f64());
+        }
+        if self.runs("rbl") {
+            let started = Instant::now();
+            tokio::select! {
+                _ = self.check_blacklists(&mut vms, &mut findings) => {}
+                _ = self.cancel.cancelled() => {}
+            }
+            timings.insert("rbl".to_string(), started.elapsed().as_secs_f64());
+        }
+        let mut bandwidth = Vec::new();
+        if self.config.bandwidth.enabled && self.runs("bandwidth") {
+            let started = Instant::now();
+            bandwidth = self.measure_bandwidth(&vms, deadline, &mut findings).await;
+            timings.insert("bandwidth".to_string(), started.elapsed().as_secs_f64());
+        }
+        if self.runs("cron") {
+            let started = Instant::now();
+            self.check_scheduled_jobs(&mut vms, &mut findings);
+            timings.insert("cron".to_string(), started.elapsed().as_secs_f64());
+        }
+        let mut policy_violations = Vec::new();
+        if self.runs("policy") {
+            let started = Instant::now();
+            policy_violations = self.check_policy(&mut vms, &mut findings);
+            timings.insert("policy".to_string(), started.elapsed().as_secs_f64());
+        }
+        if self.runs("web") {
+            let host_addresses = Self::resolve_host_addresses(&vms).await;
+            findings.evaluate(None, "web", |critical, warnings| {
+                self.check_web_content(&web_services, critical, warnings);
+                self.attribute_web_routes(&mut web_services, &vms, warnings);
+                self.check_web_dns(&mut web_services, &vms, &host_addresses, warnings);
+            });
+        }
+        let mut domains = Vec::new();
+        if self.runs("domains") {
+            let started = Instant::now();
+            domains = tokio::select! {
+                domains = self.config.domains.registrations() => domains,
+                _ = self.cancel.cancelled() => Vec::new(),
+            };
+            findings.evaluate(None, "domains", |critical, warnings| {
+                for registration in &domains {
+                    if let Some(ref error) = registration.error {
+                        warnings.push(format!("{}: registration lookup failed - {}", registration.domain, error));
+                    }
+                    match self.config.domains.issue(registration) {
+                        Some((Severity::Critical, issue)) => critical.push(issue),
+                        Some((_, issue)) => warnings.push(issue),
+                        None => {}
+                    }
                 }
-                Err(e) => {
-                    println!("    {} Failed: {}", "✗".red(), e);
-                    critical_issues.push(format!("{}: {}", host.name, e));
-                    
-                    vms.push(VmStatus {
-                        host: host.clone(),
-                        reachable: false,
-                        services: Vec::new(),
-                        containers: Vec::new(),
-                        wireguard: None,
-                        open_ports: Vec::new(),
-                        recent_errors: Vec::new(),
-                    });
+            });
+            timings.insert("domains".to_string(), started.elapsed().as_secs_f64());
+        }
+        let mut ct_certificates = Vec::new();
+        if self.runs("ct") {
+            let started = Instant::now();
+            let known: Vec<String> = self
+                .config
+                .web_services
+                .iter()
+                .filter_map(|service| reqwest::Url::parse(&service.url).ok()?.host_str().map(|host| host.to_string()))
+                .collect();
+            let result = tokio::select! {
+                result = self.config.certificate_transparency.unexpected(&known) => Some(result),
+                _ = self.cancel.cancelled() => None,
+            };
+            findings.evaluate(None, "ct", |critical, warnings| match result {
+                Some(Ok(certificates)) => {
+                    for certificate in &certificates {
+                        for (severity, issue) in self.config.certificate_transparency.issues(certificate) {
+                            match severity {
+                                Severity::Critical => critical.push(issue),
+                                _ => warnings.push(issue),
+                            }
+                        }
+                    }
+                    ct_certificates = certificates;
                 }
-            }
+                Some(Err(e)) => warnings.push(format!("Certificate Transparency not checked: {:#}", e)),
+                None => {}
+            });
+            timings.insert("ct".to_string(), started.elapsed().as_secs_f64());
         }
 
-        let summary = self.generate_summary(&vms);
+        // Cross-checks against other inventories would flag every host we
+        // never got to as missing, so an interrupted scan stops here
+        let partial = self.cancel.is_cancelled();
+        let (cloud_instances, proxmox, terraform) = if partial {
+            findings.push(Severity::Warning, None, "scan", "Scan interrupted, report is partial".to_string());
+            (Vec::new(), None, None)
+        } else {
+            let mut cloud_instances = Vec::new();
+            if self.runs("cloud") {
+                let started = Instant::now();
+                let mut cloud_warnings = Vec::new();
+                cloud_instances = self.discover_cloud_instances(&mut cloud_warnings).await;
+                findings.evaluate(None, "cloud", |_, warnings| warnings.append(&mut cloud_warnings));
+                timings.insert("cloud".to_string(), started.elapsed().as_secs_f64());
+            }
+            let mut proxmox = None;
+            if self.runs("proxmox") {
+                let started = Instant::now();
+                let mut proxmox_warnings = Vec::new();
+                proxmox = self.proxmox_inventory(&vms, &mut proxmox_warnings).await;
+                findings.evaluate(None, "proxmox", |_, warnings| warnings.append(&mut proxmox_warnings));
+                timings.insert("proxmox".to_string(), started.elapsed().as_secs_f64());
+            }
+            let mut terraform = None;
+            if self.runs("terraform") {
+                let started = Instant::now();
+                findings.evaluate(None, "terraform", |critical, warnings| {
+                    terraform = self.compare_terraform_state(&vms, critical, warnings);
+                });
+                timings.insert("terraform".to_string(), started.elapsed().as_secs_f64());
+            }
+            (cloud_instances, proxmox, terraform)
+        };
 
-        Ok(InventoryReport {
+        let Findings { critical_issues, warnings, notices } = findings;
+        let summary = self.generate_summary(&vms);
+        let mut report = InventoryReport {
             timestamp: Utc::now(),
             vms,
             web_services,
+            cloud_instances,
+            proxmox,
+            terraform,
             summary,
             critical_issues,
             warnings,
+            notices,
+            partial,
+            profile: self.config.profile,
+            checks: self.config.checks.clone(),
+            duration_secs: 0.0,
+            timings,
+            policy_violations,
+            domains,
+            ct_certificates,
+            suppressions: Vec::new(),
+            impacts: Vec::new(),
+            bandwidth,
+        };
+        dependencies::apply(&self.config.dependencies, &mut report);
+        maintenance::apply(&self.config.maintenance, &mut report);
+        scripting::run(&self.config.scripting, &mut report);
+        suppressions::apply(&self.config.suppressions, &mut report);
+        self.stamp_first_seen(&mut report);
+
+        self.emit(ScanEvent::ScanFinished {
+            partial,
+            critical_issues: report.critical_issues.len(),
+            warnings: report.warnings.len(),
+        });
+
+        Ok(report)
+    }
+
+    /// Carries `first_seen` over from earlier scans for issues still open.
+    /// A partial scan, or one that left checks out, forgets nothing, since
+    /// hosts and checks it skipped raised nothing.
+    fn stamp_first_seen(&self, report: &mut InventoryReport) {
+        let mut first_seen = self.first_seen.lock().unwrap();
+        let mut seen = HashMap::new();
+        for issue in report
+            .critical_issues
+            .iter_mut()
+            .chain(report.warnings.iter_mut())
+            .chain(report.notices.iter_mut())
+        {
+            let key = issue.key();
+            if let Some(since) = first_seen.get(&key) {
+                issue.first_seen = *since;
+            }
+            seen.insert(key, issue.first_seen);
+        }
+
+        if report.partial || !report.checks.is_complete(report.profile) {
+            first_seen.extend(seen);
+        } else {
+            *first_seen = seen;
+        }
+    }
+
+    pub fn hosts(&self) -> &[VmHost] {
+        &self.hosts
+    }
+
+    /// Scans a single host outside a full run (e.g. a rescan from the TUI).
+    /// Its findings are only kept in the host's check outcomes.
+    pub async fn scan_one(&self, host: &VmHost) -> VmStatus {
+        let deadline = self.config.timeouts.scan_secs.map(|secs| Instant::now() + std::time::Duration::from_secs(secs));
+        let progress = ScanProgress::new(1);
+        let host_progress = progress.host(&host.name);
+        let span = error_span!("host", host = %host.name);
+        let status = self
+            .scan_host(host, deadline, &host_progress, &mut Findings::default())
+            .instrument(span)
+            .await;
+        drop(host_progress);
+        progress.finish();
+        status
+    }
+
+    /// Collects everything about one host and records its findings.
+    async fn scan_host(
+        &self,
+        host: &VmHost,
+        deadline: Option<Instant>,
+        progress: &HostProgress,
+        findings: &mut Findings,
+    ) -> VmStatus {
+        debug!("Checking {}...", host.name);
+        let started = Instant::now();
+        progress.stage("ping");
+        let (ping, paths) = if self.runs("ping") {
+            let started = Instant::now();
+            let ping = self.ping_host(&self.pinger, host).await;
+            let paths = self.probe_paths(host, &ping).await;
+            self.record(host, "ping", started.elapsed().as_secs_f64());
+            (ping, paths)
+        } else {
+            (Vec::new(), Vec::new())
+        };
+
+        // Metrics node_exporter provides are not collected again over SSH
+        let exporter = if self.runs("load") || self.runs("disk") {
+            progress.stage("node_exporter");
+            self.config.node_exporter.scrape(host).await
+        } else {
+            None
+        };
+        let exporter_load = exporter.as_ref().and_then(|metrics| metrics.load);
+        let exporter_filesystems = exporter.as_ref().map(|metrics| metrics.filesystems.clone()).filter(|fs| !fs.is_empty());
+        let from_exporter = |check: &str| match check {
+            "load" => exporter_load.is_some(),
+            "disk" => exporter_filesystems.is_some(),
+            _ => false,
+        };
+
+        progress.stage("connect");
+        self.emit(ScanEvent::Connecting { host: host.name.clone() });
+        let connect_started = Instant::now();
+        let connected = self.connect(host, deadline).await;
+        self.record(host, "connect", connect_started.elapsed().as_secs_f64());
+        let mut status = match connected {
+            Ok((ssh_client, connected_ip)) => {
+                progress.stage("capabilities");
+                let host_capabilities = self.capabilities(host, &ssh_client);
+                let missing = |check: &str| host_capabilities.as_ref().and_then(|c| capabilities::missing(c, check));
+                if self.config.ssh.batch_commands {
+                    progress.stage("collect");
+                    let wanted = |check: &str| self.runs(check) && !from_exporter(check) && missing(check).is_none();
+                    if let Err(e) = self.collect(host, "collect", || ssh_client.prefetch(wanted)) {
+                        warn!("Batched collection failed, running checks one by one: {}", e);
+                    }
+                }
+
+                let reachable = ssh_client.is_reachable();
+
+                let mut checks = Vec::new();
+                findings.check(&mut checks, &host.name, "connectivity", |_, warnings| {
+                    if !reachable {
+                        warnings.push(format!("{} is not reachable", host.name));
+                    }
+
+                    if connected_ip != host.ip {
+                        warnings.push(format!(
+                            "{}: {} unreachable, connected via alternate IP {}",
+                            host.name, host.ip, connected_ip
+                        ));
+                    }
+                });
+
+                progress.stage("checks");
+                let mut incomplete_checks = Vec::new();
+                let collected = std::thread::scope(|scope| {
+                    let services = self.gather(scope, host, progress, "services", || ssh_client.list_running_services());
+                    let containers = self.gather(scope, host, progress, "containers", || ssh_client.list_containers());
+                    let wireguard = self.gather(scope, host, progress, "wireguard", || ssh_client.get_wireguard_status());
+                    let open_ports = self.gather(scope, host, progress, "ports", || ssh_client.get_open_ports());
+                    let load = self.gather(scope, host, progress, "load", || match exporter_load {
+                        Some(load) => Ok(Some(load)),
+                        None => ssh_client.get_load_average(),
+                    });
+                    let processes = self.gather(scope, host, progress, "processes", || ssh_client.get_processes());
+                    let recent_errors = self.gather(scope, host, progress, "logs", || ssh_client.get_recent_errors());
+                    let storage_arrays = self.gather(scope, host, progress, "storage", || ssh_client.get_storage_arrays());
+                    let certificates = self.gather(scope, host, progress, "certificates", || ssh_client.get_certificates());
+                    let os = self.gather(scope, host, progress, "os", || ssh_client.get_os_release());
+                    let hardware = self.gather(scope, host, progress, "hardware", || ssh_client.get_hardware());
+                    let gpus = self.gather(scope, host, progress, "gpu", || ssh_client.get_gpus());
+                    let uptime = self.gather(scope, host, progress, "uptime", || ssh_client.get_uptime());
+                    let filesystems = self.gather(scope, host, progress, "disk", || match exporter_filesystems {
+                        Some(ref filesystems) => Ok(filesystems.clone()),
+                        None => ssh_client.get_filesystems(),
+                    });
+                    let swap = self.gather(scope, host, progress, "swap", || ssh_client.get_swap());
+                    let thermal = self.gather(scope, host, progress, "sensors", || ssh_client.get_thermal());
+                    let kernel_events = self.gather(scope, host, progress, "kernel", || ssh_client.get_kernel_events());
+                    let container_storage = self.gather(scope, host, progress, "volumes", || ssh_client.get_container_storage());
+                    let scheduled_jobs = self.gather(scope, host, progress, "cron", || ssh_client.get_scheduled_jobs().map(Some));
+
+                    Collected {
+                        services: Self::complete(services.join(), &mut incomplete_checks),
+                        containers: Self::complete(containers.join(), &mut incomplete_checks),
+                        wireguard: Self::complete(wireguard.join(), &mut incomplete_checks),
+                        open_ports: Self::complete(open_ports.join(), &mut incomplete_checks),
+                        load: Self::complete(load.join(), &mut incomplete_checks),
+                        processes: Self::complete(processes.join(), &mut incomplete_checks),
+                        recent_errors: Self::complete(recent_errors.join(), &mut incomplete_checks),
+                        storage_arrays: Self::complete(storage_arrays.join(), &mut incomplete_checks),
+                        certificates: Self::complete(certificates.join(), &mut incomplete_checks),
+                        scheduled_jobs: Self::complete(scheduled_jobs.join(), &mut incomplete_checks),
+                        container_storage: Self::complete(container_storage.join(), &mut incomplete_checks),
+                        kernel_events: Self::complete(kernel_events.join(), &mut incomplete_checks),
+                        thermal: Self::complete(thermal.join(), &mut incomplete_checks),
+                        filesystems: Self::complete(filesystems.join(), &mut incomplete_checks),
+                        swap: Self::complete(swap.join(), &mut incomplete_checks),
+                        uptime: Self::complete(uptime.join(), &mut incomplete_checks),
+                        os: Self::complete(os.join(), &mut incomplete_checks),
+                        hardware: Self::complete(hardware.join(), &mut incomplete_checks),
+                        gpus: Self::complete(gpus.join(), &mut incomplete_checks),
+                    }
+                });
+                let Collected {
+                    services,
+                    containers,
+                    wireguard,
+                    mut open_ports,
+                    load,
+                    processes,
+                    recent_errors,
+                    storage_arrays,
+                    certificates,
+                    scheduled_jobs,
+                    container_storage,
+                    kernel_events,
+                    thermal,
+                    filesystems,
+                    swap,
+                    uptime,
+                    os,
+                    hardware,
+                    gpus,
+                } = collected;
+                for port in &mut open_ports {
+                    port.exposure = Some(self.port_scanner.classify(port));
+                }
+                let booted_at = uptime.and_then(|uptime| chrono::Duration::from_std(uptime).ok()).map(|uptime| Utc::now() - uptime);
+                for check in &incomplete_checks {
+                    findings.push(
+                        Severity::Warning,
+                        Some(&host.name),
+                        check.split(' ').next().unwrap_or(check),
+                        format!("{}: {} - results incomplete", host.name, check),
+                    );
+                }
+                self.emit(ScanEvent::Collected {
+                    host: host.name.clone(),
+                    services: services.len(),
+                    containers: containers.len(),
+                });
+                progress.stage("apps");
+                let apps_started = Instant::now();
+                let apps = self.runs("apps");
+                let traefik = if apps && Self::runs_traefik(&services, &containers) {
+                    match ssh_client.get_traefik_inventory(self.config.traefik.api_url(&host.name)) {
+                        Ok(inventory) => Some(inventory),
+                        Err(e) => {
+                            findings.push(Severity::Warning, Some(&host.name), "traefik", format!("{}: {}", host.name, e));
+                            None
+                        }
+                    }
+                } else {
+                    None
+                };
+                let databases = Self::detect_databases(&services)
+                    .into_iter()
+                    .filter(|_| apps)
+                    .map(|engine| match engine {
+                        "postgresql" => ssh_client.get_postgres_status(),
+                        _ => ssh_client.get_mysql_status(),
+                    })
+                    .collect::<Vec<_>>();
+                let data_stores = if apps { self.probe_data_stores(&ssh_client, &services, &containers) } else { Vec::new() };
+                let samba = if apps && services.iter().any(|s| s.name.to_lowercase().contains("samba") || s.name.contains("smbd")) {
+                    match ssh_client.get_samba_status() {
+                        Ok(mut samba) => {
+                            samba.anonymous_listing = Some(ssh_client.list_samba_shares_anonymously());
+                            Some(samba)
+                        }
+                        Err(e) => {
+                            findings.push(Severity::Warning, Some(&host.name), "samba", format!("{}: {:#}", host.name, e));
+                            None
+                        }
+                    }
+                } else {
+                    None
+                };
+                if apps {
+                    self.record(host, "apps", apps_started.elapsed().as_secs_f64());
+                }
+
+                // Check for critical issues
+                progress.stage("exposure");
+                let exposed_ports = if self.runs("exposure") {
+                    let started = Instant::now();
+                    let exposed_ports = self.port_scanner.scan(&host.ip, &open_ports).await;
+                    self.record(host, "exposure", started.elapsed().as_secs_f64());
+                    exposed_ports
+                } else {
+                    Vec::new()
+                };
+
+                checks.extend(incomplete_checks.iter().map(|c| CheckOutcome::skipped(c.split(' ').next().unwrap_or(c), c)));
+                for check in ["services", "containers", "ports", "logs"] {
+                    if let Some(reason) = missing(check).filter(|_| self.runs(check)) {
+                        checks.push(CheckOutcome::skipped(check, reason));
+                    }
+                }
+                if self.runs("services") && missing("services").is_none() {
+                    findings.check(&mut checks, &host.name, "services", |critical, _| {
+                        self.check_critical_issues(host, &services, critical)
+                    });
+                }
+                if self.runs("logs") && missing("logs").is_none() {
+                    findings.check(&mut checks, &host.name, "logs", |critical, warnings| {
+                        self.check_log_patterns(host, &recent_errors, critical, warnings)
+                    });
+                }
+                if !host.roles.is_empty() && self.runs("services") && self.runs("containers") {
+                    if incomplete_checks.iter().any(|c| c.starts_with("services ") || c.starts_with("containers ")) {
+                        checks.push(CheckOutcome::skipped("roles", "service or container list incomplete"));
+                    } else if let Some(reason) = missing("services") {
+                        checks.push(CheckOutcome::skipped("roles", reason));
+                    } else {
+                        findings.check(&mut checks, &host.name, "roles", |critical, warnings| {
+                            self.check_roles(host, &services, &containers, critical, warnings)
+                        });
+                    }
+                }
+                if !host.expected_containers.is_empty() && self.runs("containers") {
+                    if incomplete_checks.iter().any(|c| c.starts_with("containers ")) {
+                        checks.push(CheckOutcome::skipped("expected_containers", "container list incomplete"));
+                    } else {
+                        findings.check(&mut checks, &host.name, "expected_containers", |critical, warnings| {
+                            self.check_expected_containers(host, &containers, critical, warnings)
+                        });
+                    }
+                }
+                if self.runs("exposure") {
+                    findings.check(&mut checks, &host.name, "exposure", |critical, warnings| {
+                        self.check_exposed_ports(host, &exposed_ports, critical, warnings)
+                    });
+                }
+                if self.runs("ports") && missing("ports").is_none() {
+                    findings.check(&mut checks, &host.name, "listeners", |_, warnings| {
+                        self.check_listeners(host, &open_ports, warnings)
+                    });
+                }
+                if self.runs("containers") && missing("containers").is_none() {
+                    findings.check(&mut checks, &host.name, "container_exposure", |_, warnings| {
+                        for container in &containers {
+                            warnings.extend(
+                                self.config.container_audit.exposures(container).into_iter().map(|issue| format!("{}: {}", host.name, issue)),
+                            );
+                        }
+                    });
+                    findings.check(&mut checks, &host.name, "container_secrets", |_, warnings| {
+                        for container in &containers {
+                            if let Some(issue) = self.config.container_audit.plaintext_secrets(container) {
+                                warnings.push(format!("{}: {}", host.name, issue));
+                            }
+                        }
+                    });
+                }
+                if let Some(ref os) = os {
+                    findings.check(&mut checks, &host.name, "os", |critical, warnings| {
+                        if let Some((severity, issue)) = self.config.os.eol_issue(os) {
+                            let issue = format!("{}: {}", host.name, issue);
+                            match severity {
+                                Severity::Critical => critical.push(issue),
+                                _ => warnings.push(issue),
+                            }
+                        }
+                    });
+                }
+                if self.runs("disk") {
+                    findings.check(&mut checks, &host.name, "disk", |critical, warnings| {
+                        for (severity, issue) in self.config.disk.filesystem_issues(&filesystems) {
+                            let issue = format!("{}: {}", host.name, issue);
+                            match severity {
+                                Severity::Critical => critical.push(issue),
+                                _ => warnings.push(issue),
+                            }
+                        }
+                    });
+                }
+                if let Some(ref swap) = swap {
+                    findings.check(&mut checks, &host.name, "swap", |_, warnings| {
+                        warnings.extend(self.config.disk.swap_issues(swap).into_iter().map(|issue| format!("{}: {}", host.name, issue)))
+                    });
+                }
+                if let Some(ref thermal) = thermal {
+                    findings.check(&mut checks, &host.name, "sensors", |critical, warnings| {
+                        for (severity, issue) in self.config.sensors.issues(thermal) {
+                            let issue = format!("{}: {}", host.name, issue);
+                            match severity {
+                                Severity::Critical => critical.push(issue),
+                                _ => warnings.push(issue),
+                            }
+                        }
+                    });
+                }
+                if self.runs("kernel") {
+                    findings.check(&mut checks, &host.name, "kernel", |critical, _| {
+                        critical.extend(kernel_events::issues(&kernel_events).into_iter().map(|issue| format!("{}: {}", host.name, issue)))
+                    });
+                }
+                if let Some(ref storage) = container_storage {
+                    findings.check(&mut checks, &host.name, "volumes", |_, warnings| {
+                        warnings.extend(self.config.container_storage.issue(storage).map(|issue| format!("{}: {}", host.name, issue)))
+                    });
+                }
+                if self.runs("processes") {
+                    findings.check(&mut checks, &host.name, "processes", |critical, warnings| {
+                        self.check_processes(host, &processes, critical, warnings)
+                    });
+                }
+                if self.runs("ping") {
+                    findings.check(&mut checks, &host.name, "ping", |_, warnings| self.check_packet_loss(host, &ping, warnings));
+                }
+                if !paths.is_empty() {
+                    findings.check(&mut checks, &host.name, "paths", |_, warnings| self.check_paths(host, &paths, warnings));
+                }
+                if self.runs("storage") {
+                    findings.check(&mut checks, &host.name, "storage", |critical, _| {
+                        self.check_storage_arrays(host, &storage_arrays, critical)
+                    });
+                }
+                if self.runs("certificates") {
+                    findings.check(&mut checks, &host.name, "certificates", |critical, warnings| {
+                        self.check_certificates(host, &certificates, critical, warnings)
+                    });
+                }
+                if apps {
+                    findings.check(&mut checks, &host.name, "databases", |critical, warnings| {
+                        self.check_databases(host, &databases, critical, warnings)
+                    });
+                    findings.check(&mut checks, &host.name, "data_stores", |critical, warnings| {
+                        self.check_data_stores(host, &data_stores, critical, warnings)
+                    });
+                }
+                if let Some(ref samba) = samba {
+                    findings.check(&mut checks, &host.name, "samba", |critical, warnings| self.check_samba(host, samba, critical, warnings));
+                }
+
+                progress.stage("archive");
+                let mut archived_files = Vec::new();
+                if self.runs("archive") && self.config.archive.applies_to(host) {
+                    findings.check(&mut checks, &host.name, "archive", |_, warnings| {
+                        archived_files = self.archive_files(host, &ssh_client, warnings);
+                    });
+                }
+
+                progress.stage("integrity");
+                let file_integrity = if self.runs("integrity") && self.config.integrity.applies_to(host) {
+                    match self.collect(host, "integrity", || ssh_client.hash_files(&self.config.integrity.files)) {
+                        Ok(files) => Some(files),
+                        Err(e) => {
+                            let issue = format!("{}: monitored files not hashed - {}", host.name, e);
+                            checks.push(CheckOutcome::from_issues("integrity", Vec::new(), vec![issue.clone()]));
+                            findings.push(Severity::Warning, Some(&host.name), "integrity", issue);
+                            None
+                        }
+                    }
+                } else {
+                    None
+                };
+
+                progress.stage("suid");
+                let special_files = if self.runs("suid") && self.config.file_audit.applies_to(host) {
+                    let audit = &self.config.file_audit;
+                    match self.collect(host, "suid", || ssh_client.find_special_files(&audit.setuid_paths, &audit.writable_paths)) {
+                        Ok(files) => Some(files),
+                        Err(e) => {
+                            let issue = format!("{}: file audit failed - {}", host.name, e);
+                            checks.push(CheckOutcome::from_issues("suid", Vec::new(), vec![issue.clone()]));
+                            findings.push(Severity::Warning, Some(&host.name), "suid", issue);
+                            None
+                        }
+                    }
+                } else {
+                    None
+                };
+
+                progress.stage("custom");
+                let mut custom_checks = Vec::new();
+                for custom in self.config.custom_checks.iter().filter(|c| self.runs("custom") && c.applies_to(host)) {
+                    let output = custom
+                        .source()
+                        .and_then(|script| self.collect(host, "custom", || ssh_client.run_script(&custom.name, &script, custom.sudo)));
+                    let check = match output {
+                        Ok(output) => custom_checks::parse(&custom.name, &output),
+                        Err(e) => custom_checks::unknown(&custom.name, &e.to_string()),
+                    };
+                    findings.check(&mut checks, &host.name, &format!("custom:{}", custom.name), |critical, warnings| {
+                        let issue = format!("{}: {} - {}", host.name, check.name, check.message);
+                        match check.status {
+                            CustomCheckStatus::Ok => {}
+                            CustomCheckStatus::Warning | CustomCheckStatus::Unknown => warnings.push(issue),
+                            CustomCheckStatus::Critical => critical.push(issue),
+                        }
+                    });
+                    custom_checks.push(check);
+                }
+
+                VmStatus {
+                    host: host.clone(),
+                    reachable,
+                    connected_ip: Some(connected_ip),
+                    services,
+                    containers,
+                    wireguard,
+                    open_ports,
+                    blacklistings: Vec::new(),
+                    load,
+                    memory: exporter.as_ref().and_then(|metrics| metrics.memory.clone()),
+                    os,
+                    hardware,
+                    gpus,
+                    uptime_secs: uptime.map(|uptime| uptime.as_secs()),
+                    booted_at,
+                    filesystems,
+                    swap,
+                    thermal,
+                    recent_errors,
+                    kernel_events,
+                    storage_arrays,
+                    certificates,
+                    traefik,
+                    databases,
+                    data_stores,
+                    samba,
+                    exposed_ports,
+                    ping,
+                    paths,
+                    traceroute: Vec::new(),
+                    incomplete_checks,
+                    checks,
+                    custom_checks,
+                    maintenance: None,
+                    archived_files,
+                    processes,
+                    file_integrity,
+                    special_files,
+                    container_storage,
+                    scheduled_jobs,
+                    capabilities: host_capabilities,
+                    timings: BTreeMap::new(),
+                    duration_secs: 0.0,
+                }
+            }
+            Err(e) => {
+                warn!("Connection failed: {}", e);
+                self.emit(ScanEvent::Failed { host: host.name.clone(), error: e.to_string() });
+                progress.stage("exposure");
+                let mut exposed_ports = Vec::new();
+                if self.runs("exposure") {
+                    let started = Instant::now();
+                    exposed_ports = self.port_scanner.scan(&host.ip, &[]).await;
+                    self.record(host, "exposure", started.elapsed().as_secs_f64());
+                }
+                let mut traceroute = Vec::new();
+                if self.runs("traceroute") {
+                    let started = Instant::now();
+                    traceroute = self.tracer.trace(&host.ip).await;
+                    self.record(host, "traceroute", started.elapsed().as_secs_f64());
+                }
+                let mut checks = Vec::new();
+                if self.runs("exposure") {
+                    findings.check(&mut checks, &host.name, "exposure", |critical, warnings| {
+                        self.check_exposed_ports(host, &exposed_ports, critical, warnings)
+                    });
+                }
+                if !paths.is_empty() {
+                    findings.check(&mut checks, &host.name, "paths", |_, warnings| self.check_paths(host, &paths, warnings));
+                }
+                if let Some(changed) = e.downcast_ref::<HostKeyChanged>() {
+                    let issue = format!(
+                        "🔐 {} ({}): {} - possible MITM or reinstalled host, verify before updating known_hosts",
+                        host.name, host.ip, changed
+                    );
+                    checks.insert(0, CheckOutcome::from_issues("connectivity", vec![issue.clone()], Vec::new()));
+                    findings.push(Severity::Critical, Some(&host.name), "connectivity", issue);
+                } else {
+                    let issue = match host.external {
+                        Some(ref ext) if !ext.expect_reachable => format!(
+                            "{} ({}): unreachable as expected{}",
+                            host.name,
+                            ext.provider,
+                            ext.note.as_ref().map(|n| format!(" - {}", n)).unwrap_or_default()
+                        ),
+                        Some(ref ext) => format!("{} ({}): {}{}", host.name, ext.provider, e, Self::diagnose(&ping)),
+                        None => format!("{}: {}{}", host.name, e, Self::diagnose(&ping)),
+                    };
+                    let connectivity = match host.external.as_ref().map(|ext| ext.unreachable_severity) {
+                        Some(Severity::Info) => CheckOutcome::skipped("connectivity", &issue),
+                        Some(Severity::Warning) => CheckOutcome::from_issues("connectivity", Vec::new(), vec![issue.clone()]),
+                        Some(Severity::Critical) | None => CheckOutcome::from_issues("connectivity", vec![issue.clone()], Vec::new()),
+                    };
+                    let severity = match connectivity.result {
+                        CheckResult::Skipped => Severity::Info,
+                        CheckResult::Warning => Severity::Warning,
+                        _ => Severity::Critical,
+                    };
+                    findings.push(severity, Some(&host.name), "connectivity", issue);
+                    checks.insert(0, connectivity);
+                }
+                checks.extend(
+                    [("services", "services"), ("storage", "storage"), ("certificates", "certificates"), ("databases", "apps"), ("data_stores", "apps")]
+                        .iter()
+                        .filter(|(_, collected_by)| self.runs(collected_by))
+                        .map(|(name, _)| CheckOutcome::skipped(name, "host unreachable")),
+                );
+
+                VmStatus {
+                    host: host.clone(),
+                    reachable: false,
+                    connected_ip: None,
+                    services: Vec::new(),
+                    containers: Vec::new(),
+                    wireguard: None,
+                    open_ports: Vec::new(),
+                    blacklistings: Vec::new(),
+                    load: None,
+                    memory: None,
+                    os: None,
+                    hardware: None,
+                    gpus: Vec::new(),
+                    uptime_secs: None,
+                    booted_at: None,
+                    filesystems: Vec::new(),
+                    swap: None,
+                    thermal: None,
+                    recent_errors: Vec::new(),
+                    kernel_events: Vec::new(),
+                    storage_arrays: Vec::new(),
+                    certificates: Vec::new(),
+                    traefik: None,
+                    databases: Vec::new(),
+                    data_stores: Vec::new(),
+                    samba: None,
+                    exposed_ports,
+                    ping,
+                    paths,
+                    traceroute,
+                    incomplete_checks: Vec::new(),
+                    checks,
+                    custom_checks: Vec::new(),
+                    maintenance: None,
+                    archived_files: Vec::new(),
+                    processes: Vec::new(),
+                    file_integrity: None,
+                    special_files: None,
+                    container_storage: None,
+                    scheduled_jobs: None,
+                    capabilities: self.capabilities.lock().unwrap().get(&host.name).cloned(),
+                    timings: BTreeMap::new(),
+                    duration_secs: 0.0,
+                }
+            }
+        };
+
+        status.timings = self.timings.lock().unwrap().remove(&host.name).unwrap_or_default();
+        status.duration_secs = started.elapsed().as_secs_f64();
+        self.metrics.host(&host.name, status.duration_secs, status.reachable);
+        for check in status.checks.iter().filter(|c| c.result == CheckResult::Failed) {
+            self.metrics.check_failed(&host.name, &check.name);
+        }
+        self.emit(ScanEvent::Finished {
+            host: host.name.clone(),
+            reachable: status.reachable,
+            failed_checks: status.checks.iter().filter(|c| c.result == CheckResult::Failed).count(),
+        });
+        status
+    }
+
+    /// Copies the configured files of `host` into today's archive; files
+    /// that could not be copied become warnings.
+    fn archive_files(&self, host: &VmHost, ssh_client: &SshClient, warnings: &mut Vec<String>) -> Vec<ArchivedFile> {
+        let archive = &self.config.archive;
+        let paths = match self.collect(host, "archive", || ssh_client.list_files(&archive.files)) {
+            Ok(paths) => paths,
+            Err(e) => {
+                warnings.push(format!("{}: config archive skipped - {}", host.name, e));
+                return Vec::new();
+            }
+        };
+
+        let mut archived = Vec::new();
+        for path in paths {
+            let local = archive.local_path(&host.name, &path);
+            match self.collect(host, "archive", || ssh_client.fetch_file(&path, &local, archive.sudo_fallback)) {
+                Ok((bytes, via_sudo)) => archived.push(ArchivedFile { path, bytes, via_sudo }),
+                Err(e) => warnings.push(format!("{}: could not archive {} - {}", host.name, path, e)),
+            }
+        }
+        debug!("Archived {} files from {}", archived.len(), host.name);
+        archived
+    }
+
+    /// Whether the scan profile and `--only`/`--skip` include `check`.
+    fn runs(&self, check: &str) -> bool {
+        self.config.checks.includes(self.config.profile, check)
+    }
+
+    /// Collects `check` of `host` on `scope` (see [`Self::spawn`]) when the
+    /// scan includes it; a check left out of the profile yields an empty
+    /// value. The host's bar gets a step now and advances once it's done.
+    fn gather<'scope, T: Default + Send + 'scope>(
+        &'scope self,
+        scope: &'scope std::thread::Scope<'scope, '_>,
+        host: &'scope VmHost,
+        progress: &'scope HostProgress,
+        check: &'static str,
+        f: impl FnOnce() -> Result<T> + Send + 'scope,
+    ) -> Pending<'scope, Result<T>> {
+        progress.add_check();
+        self.spawn(scope, move || {
+            let result = if !self.runs(check) || self.lacks(host, check) { Ok(T::default()) } else { self.collect(host, check, f) };
+            progress.check_done(check);
+            result
         })
     }
 
+    /// Whether `host` is known to lack what `check` needs.
+    fn lacks(&self, host: &VmHost, check: &str) -> bool {
+        self.capabilities.lock().unwrap().get(&host.name).is_some_and(|c| capabilities::missing(c, check).is_some())
+    }
+
+    /// What `host` can run, probed when the cached entry is missing or stale.
+    /// A failed probe falls back to the stale entry, or to running every
+    /// check as if all tools were there.
+    fn capabilities(&self, host: &VmHost, ssh_client: &SshClient) -> Option<Capabilities> {
+        let cached = self.capabilities.lock().unwrap().get(&host.name).cloned();
+        if let Some(cached) = cached.as_ref().filter(|c| self.config.capabilities.is_fresh(c)) {
+            return Some(cached.clone());
+        }
+        match self.collect(host, "capabilities", || ssh_client.probe_capabilities()) {
+            Ok(probed) => {
+                debug!("Capabilities: {:?}", probed);
+                self.capabilities.lock().unwrap().insert(host.name.clone(), probed.clone());
+                Some(probed)
+            }
+            Err(e) => {
+                warn!("Capability probe failed: {:#}", e);
+                cached
+            }
+        }
+    }
+
+    /// Starts a collection step on its own thread when `parallel_checks` is
+    /// on, keeping the host span so its logs stay attributed; otherwise runs it
+    /// right away.
+    fn spawn<'scope, T: Send + 'scope>(
+        &self,
+        scope: &'scope std::thread::Scope<'scope, '_>,
+        f: impl FnOnce() -> T + Send + 'scope,
+    ) -> Pending<'scope, T> {
+        if self.config.ssh.parallel_checks {
+            let span = tracing::Span::current();
+            Pending::Running(scope.spawn(move || span.in_scope(f)))
+        } else {
+            Pending::Done(f())
+        }
+    }
+
+    /// Runs one collection step in its own span and records how long it took.
+    fn collect<T>(&self, host: &VmHost, check: &'static str, f: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let result = info_span!("check", check).in_scope(f);
+        let secs = started.elapsed().as_secs_f64();
+        self.metrics.check(&host.name, check, secs);
+        self.record(host, check, secs);
+        result
+    }
+
+    /// Adds `secs` to the time spent on `check` of `host`; a check run
+    /// several times (e.g. one per custom script) accumulates.
+    fn record(&self, host: &VmHost, check: &str, secs: f64) {
+        *self
+            .timings
+            .lock()
+            .unwrap()
+            .entry(host.name.clone())
+            .or_default()
+            .entry(check.to_string())
+            .or_default() += secs;
+    }
+
+    /// Unwraps a check result, recording checks cut short by a timeout or an
+    /// interruption so the report shows them as incomplete rather than empty.
+    fn complete<T: Default>(result: Result<T>, incomplete_checks: &mut Vec<String>) -> T {
+        match result {
+            Ok(value) => value,
+            Err(e) => {
+                if let Some(timeout) = e.downcast_ref::<CheckTimedOut>() {
+                    incomplete_checks.push(timeout.to_string());
+                } else if let Some(cancelled) = e.downcast_ref::<CheckCancelled>() {
+                    incomplete_checks.push(cancelled.to_string());
+                }
+                T::default()
+            }
+        }
+    }
+
+    /// Connects to the host's primary IP, falling back to the alternate IPs
+    /// of externally hosted VMs. Returns the client and the address used.
+    async fn connect(&self, host: &VmHost, deadline: Option<Instant>) -> Result<(SshClient, String)> {
+        let limits = CheckLimits {
+            timeouts: self.config.timeouts.clone(),
+            deadline,
+            cancel: self.cancel.clone(),
+        };
+        let mut candidates = vec![host.ip.clone()];
+        if let Some(ref ext) = host.external {
+            candidates.extend(ext.alternate_ips.iter().cloned());
+        }
+
+        let mut last_error = None;
+        for ip in candidates {
+            let mut target = host.clone();
+            target.ip = ip.clone();
+            match SshClient::connect(target, self.config.ssh.clone(), limits.clone(), self.log_scanner.query(host)).await {
+                Ok(client) => return Ok((client, ip)),
+                // A changed host key is a security event, not a routing problem
+                Err(e) if e.is::<HostKeyChanged>() => return Err(e),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No address to connect to")))
+    }
+
     fn check_critical_issues(
         &self,
         host: &VmHost,
         services: &[Service],
-        errors: &[LogEntry],
         critical_issues: &mut Vec<String>,
     ) {
         // Check for port conflicts
@@ -96,7 +1220,7 @@ impl InventoryScanner {
         for service in services {
             if matches!(service.status, ServiceStatus::Running) {
                 for port in &service.ports {
-                    port_usage.entry(*port).or_insert_with(Vec::new).push(service);
+                    port_usage.entry(*port).or_default().push(service);
                 }
             }
         }
@@ -110,19 +1234,1081 @@ impl InventoryScanner {
                 ));
             }
         }
+    }
+
+    /// Reports the `[logs]` patterns matched by recent log entries, once per
+    /// pattern with the latest matching entry.
+    fn check_log_patterns(&self, host: &VmHost, entries: &[LogEntry], critical: &mut Vec<String>, warnings: &mut Vec<String>) {
+        for (pattern, matched) in self.log_scanner.matches(host, entries) {
+            let Some(latest) = matched.iter().max_by_key(|entry| entry.timestamp) else {
+                continue;
+            };
+            let mut issue = format!("{}: {} - {}: {}", host.name, pattern.description, latest.service, latest.message);
+            if matched.len() > 1 {
+                issue.push_str(&format!(" ({} times)", matched.len()));
+            }
+            match pattern.severity {
+                Severity::Critical => critical.push(issue),
+                _ => warnings.push(issue),
+            }
+        }
+    }
+
+    /// Asserts that every service the host's roles require is running, either
+    /// as a systemd unit or as a container.
+    fn check_roles(
+        &self,
+        host: &VmHost,
+        services: &[Service],
+        containers: &[Container],
+        critical_issues: &mut Vec<String>,
+        warnings: &mut Vec<String>,
+    ) {
+        for role in &host.roles {
+            let Some(expected) = self.config.roles.get(role) else {
+                warnings.push(format!("{}: role '{}' is not defined in [roles]", host.name, role));
+                continue;
+            };
+
+            for name in expected {
+                let needle = name.to_lowercase();
+                let service = services.iter().find(|s| s.name.to_lowercase().contains(&needle));
+                let container = containers.iter().find(|c| c.name.to_lowercase().contains(&needle));
+
+                let running = service.is_some_and(|s| s.status == ServiceStatus::Running)
+                    || container.is_some_and(|c| c.status.contains("Up"));
+                if running {
+                    continue;
+                }
+
+                match (service, container) {
+                    (None, None) => critical_issues.push(format!(
+                        "{}: {} expected by role '{}' not found",
+                        host.name, name, role
+                    )),
+                    _ => critical_issues.push(format!(
+                        "{}: {} expected by role '{}' is not running",
+                        host.name, name, role
+                    )),
+                }
+            }
+        }
+    }
+
+    /// Compares the containers of `host` with the ones its config expects,
+    /// matched by name or image. A host without docker or podman finds all
+    /// of them missing.
+    fn check_expected_containers(
+        &self,
+        host: &VmHost,
+        containers: &[Container],
+        critical_issues: &mut Vec<String>,
+        warnings: &mut Vec<String>,
+    ) {
+        let matches = |pattern: &str, container: &Container| {
+            ssh_config::wildcard_match(pattern, &container.name) || ssh_config::wildcard_match(pattern, &container.image)
+        };
 
-        // Check for specific errors
-        for error in errors {
-            if error.message.contains("NT_STATUS_ADDRESS_ALREADY_ASSOCIATED") 
-                || error.message.contains("Failed to bind")
-                || error.message.contains("port.*already")
-            {
+        for pattern in &host.expected_containers {
+            let found: Vec<&Container> = containers.iter().filter(|c| matches(pattern, c)).collect();
+            if found.is_empty() {
+                critical_issues.push(format!("{}: expected container {} not found", host.name, pattern));
+            } else if !found.iter().any(|c| c.status.contains("Up")) {
+                let stopped: Vec<String> = found.iter().map(|c| format!("{}: {}", c.name, c.status)).collect();
                 critical_issues.push(format!(
-                    "{}: Port binding error - {}",
-                    host.name, error.message
+                    "{}: expected container {} is not running ({})",
+                    host.name,
+                    pattern,
+                    stopped.join(", ")
                 ));
             }
         }
+
+        for container in containers.iter().filter(|c| !host.expected_containers.iter().any(|p| matches(p, c))) {
+            warnings.push(format!(
+                "{}: unexpected container {} ({}, {})",
+                host.name, container.name, container.image, container.status
+            ));
+        }
+    }
+
+    /// Compares the monitored files of each host with the baseline and
+    /// records the new state: files seen for the first time always, changes
+    /// only once accepted.
+    fn check_integrity(&self, vms: &mut [VmStatus], findings: &mut Findings) {
+        let config = &self.config.integrity;
+        if !config.enabled {
+            return;
+        }
+
+        let baseline_path = self.config.state.path(&config.baseline_file);
+        let mut baseline: integrity::Baseline = match state::load(&baseline_path, "integrity baseline") {
+            Ok(baseline) => baseline,
+            Err(e) => {
+                findings.push(Severity::Warning, None, "integrity", format!("Integrity not checked: {:#}", e));
+                return;
+            }
+        };
+
+        for vm in vms.iter_mut() {
+            let Some(ref files) = vm.file_integrity else {
+                continue;
+            };
+            let known = baseline.entry(vm.host.name.clone()).or_default();
+            let first_scan = known.is_empty();
+
+            let host = &vm.host.name;
+            let (changed, _) = findings.evaluate(Some(host), "integrity", |critical, _| {
+                critical.extend(integrity::changes(known, files).into_iter().map(|c| format!("{}: {}", host, c)));
+                critical.extend(integrity::exposed_private_keys(files).into_iter().map(|e| format!("{}: {}", host, e)));
+            });
+            if first_scan && !files.is_empty() {
+                findings.push(
+                    Severity::Info,
+                    Some(host),
+                    "integrity",
+                    format!("{}: integrity baseline recorded for {} files", host, files.len()),
+                );
+            }
+
+            if config.accept_changes || changed.is_empty() {
+                *known = files.iter().map(|f| (f.path.clone(), f.clone())).collect();
+            } else {
+                for file in files {
+                    known.entry(file.path.clone()).or_insert_with(|| file.clone());
+                }
+            }
+            vm.checks.push(CheckOutcome::from_issues("integrity", changed, Vec::new()));
+        }
+
+        if let Err(e) = state::save(&baseline_path, "integrity baseline", &baseline) {
+            findings.push(Severity::Warning, None, "integrity", format!("{:#}", e));
+        }
+    }
+
+    /// Reports SUID/SGID binaries and world-writable files that were not
+    /// there on the previous scan of the host, then remembers the current set.
+    fn check_special_files(&self, vms: &mut [VmStatus], findings: &mut Findings) {
+        let config = &self.config.file_audit;
+        if !config.enabled {
+            return;
+        }
+
+        let state_path = self.config.state.path(&config.state_file);
+        let mut state: file_audit::AuditState = match state::load(&state_path, "file audit state") {
+            Ok(state) => state,
+            Err(e) => {
+                findings.push(Severity::Warning, None, "suid", format!("File audit not compared: {:#}", e));
+                return;
+            }
+        };
+
+        for vm in vms.iter_mut() {
+            let Some(ref files) = vm.special_files else {
+                continue;
+            };
+            let host = &vm.host.name;
+            let current: BTreeSet<String> = files.iter().map(file_audit::key).collect();
+
+            let Some(previous) = state.insert(host.clone(), current) else {
+                findings.push(
+                    Severity::Info,
+                    Some(host),
+                    "suid",
+                    format!("{}: file audit baseline recorded ({} files)", host, files.len()),
+                );
+                vm.checks.push(CheckOutcome::from_issues("suid", Vec::new(), Vec::new()));
+                continue;
+            };
+            let (critical, warnings) = findings.evaluate(Some(host), "suid", |critical, warnings| {
+                for file in files.iter().filter(|f| !previous.contains(&file_audit::key(f))) {
+                    let issue = format!("{}: {}", host, file_audit::describe(file));
+                    match file.kind {
+                        SpecialFileKind::WorldWritable => warnings.push(issue),
+                        SpecialFileKind::Setuid | SpecialFileKind::Setgid => critical.push(issue),
+                    }
+                }
+            });
+            vm.checks.push(CheckOutcome::from_issues("suid", critical, warnings));
+        }
+
+        if let Err(e) = state::save(&state_path, "file audit state", &state) {
+            findings.push(Severity::Warning, None, "suid", format!("{:#}", e));
+        }
+    }
+
+    /// Warns about hosts that booted after their previous scan, then
+    /// remembers the current boot times.
+    fn check_reboots(&self, vms: &mut [VmStatus], findings: &mut Findings) {
+        let state_path = self.config.state.path(reboots::STATE_FILE);
+        let mut state: reboots::BootState = match state::load(&state_path, "boot times") {
+            Ok(state) => state,
+            Err(e) => {
+                findings.push(Severity::Warning, None, "uptime", format!("Reboots not detected: {:#}", e));
+                return;
+            }
+        };
+
+        for vm in vms.iter_mut() {
+            let Some(booted_at) = vm.booted_at else {
+                continue;
+            };
+            let host = &vm.host.name;
+            let Some(previous) = state.insert(host.clone(), booted_at) else {
+                continue;
+            };
+            let (critical, warnings) = findings.evaluate(Some(host), "uptime", |_, warnings| {
+                if (booted_at - previous).num_seconds() > reboots::TOLERANCE_SECS {
+                    warnings.push(format!(
+                        "{}: unexpected reboot at {} (previous boot {})",
+                        host,
+                        booted_at.format("%Y-%m-%d %H:%M UTC"),
+                        previous.format("%Y-%m-%d %H:%M UTC")
+                    ));
+                }
+            });
+            vm.checks.push(CheckOutcome::from_issues("uptime", critical, warnings));
+        }
+
+        if let Err(e) = state::save(&state_path, "boot times", &state) {
+            findings.push(Severity::Warning, None, "uptime", format!("{:#}", e));
+        }
+    }
+
+    /// Looks up the public addresses of every host, reachable or not, in the
+    /// configured DNS blacklists.
+    async fn check_blacklists(&self, vms: &mut [VmStatus], findings: &mut Findings) {
+        let lookups = vms.iter().map(|vm| async move {
+            let addresses: BTreeSet<std::net::IpAddr> =
+                vm.host.addresses().iter().filter_map(|ip| ip.parse().ok()).filter(|ip| rbl::is_public(*ip)).collect();
+            join_all(addresses.into_iter().map(|ip| self.config.rbl.listings(ip))).await.concat()
+        });
+        let listings = join_all(lookups).await;
+
+        for (vm, listings) in vms.iter_mut().zip(listings) {
+            let host = &vm.host.name;
+            let (critical, warnings) = findings.evaluate(Some(host), "rbl", |_, warnings| {
+                for listing in &listings {
+                    warnings.push(format!("{}: {} listed on {} ({})", host, listing.ip, listing.list, listing.response));
+                }
+            });
+            vm.checks.push(CheckOutcome::from_issues("rbl", critical, warnings));
+            vm.blacklistings = listings;
+        }
+    }
+
+    /// Runs iperf3 from every participating host to every other one, a pair
+    /// at a time so the tests don't compete for the same links.
+    async fn measure_bandwidth(&self, vms: &[VmStatus], deadline: Option<Instant>, findings: &mut Findings) -> Vec<BandwidthResult> {
+        let config = &self.config.bandwidth;
+        let mut clients = Vec::new();
+        for (vm, address) in config.participants(vms) {
+            match self.connect(&vm.host, deadline).await {
+                Ok((client, _)) => clients.push((vm.host.name.as_str(), address, client)),
+                Err(e) => findings.push(
+                    Severity::Warning,
+                    Some(&vm.host.name),
+                    "bandwidth",
+                    format!("{}: bandwidth not tested - {:#}", vm.host.name, e),
+                ),
+            }
+        }
+
+        // Long enough for the client to connect and finish the test
+        let lifetime = std::time::Duration::from_secs(config.duration_secs + 30);
+        let mut results = Vec::new();
+        for (server, address, server_ssh) in &clients {
+            for (client, _, client_ssh) in clients.iter().filter(|(name, ..)| name != server) {
+                if self.cancel.is_cancelled() {
+                    return results;
+                }
+                let measured = server_ssh
+                    .start_iperf_server(config.port, lifetime)
+                    .and_then(|()| client_ssh.iperf_throughput(address, config.port, config.duration_secs));
+                results.push(BandwidthResult {
+                    from: client.to_string(),
+                    to: server.to_string(),
+                    mbps: measured.as_ref().ok().map(|bits| bits / 1_000_000.0),
+                    error: measured.err().map(|e| format!("{:#}", e)),
+                });
+            }
+        }
+
+        findings.evaluate(None, "bandwidth", |_, warnings| {
+            warnings.extend(results.iter().filter_map(|result| config.issue(result)));
+        });
+        results
+    }
+
+    /// Reports cron entries and timers that were not there on the previous
+    /// scan of the host, then remembers the current set.
+    fn check_scheduled_jobs(&self, vms: &mut [VmStatus], findings: &mut Findings) {
+        let state_path = self.config.state.path(scheduled_jobs::STATE_FILE);
+        let mut state: scheduled_jobs::JobState = match state::load(&state_path, "scheduled job state") {
+            Ok(state) => state,
+            Err(e) => {
+                findings.push(Severity::Warning, None, "cron", format!("Scheduled jobs not compared: {:#}", e));
+                return;
+            }
+        };
+
+        for vm in vms.iter_mut() {
+            let Some(ref jobs) = vm.scheduled_jobs else {
+                continue;
+            };
+            let host = &vm.host.name;
+            let current: BTreeSet<String> = jobs.iter().map(scheduled_jobs::key).collect();
+
+            let Some(previous) = state.insert(host.clone(), current) else {
+                findings.push(
+                    Severity::Info,
+                    Some(host),
+                    "cron",
+                    format!("{}: scheduled jobs recorded ({} jobs)", host, jobs.len()),
+                );
+                vm.checks.push(CheckOutcome::from_issues("cron", Vec::new(), Vec::new()));
+                continue;
+            };
+            let (critical, warnings) = findings.evaluate(Some(host), "cron", |_, warnings| {
+                for job in jobs.iter().filter(|j| !previous.contains(&scheduled_jobs::key(j))) {
+                    warnings.push(format!("{}: new scheduled job: {}", host, scheduled_jobs::describe(job)));
+                }
+            });
+            vm.checks.push(CheckOutcome::from_issues("cron", critical, warnings));
+        }
+
+        if let Err(e) = state::save(&state_path, "scheduled job state", &state) {
+            findings.push(Severity::Warning, None, "cron", format!("{:#}", e));
+        }
+    }
+
+    /// Evaluates the policy rules file against the scanned hosts. Violations
+    /// join the issue lists by severity and fail each host's "policy" check.
+    fn check_policy(&self, vms: &mut [VmStatus], findings: &mut Findings) -> Vec<PolicyViolation> {
+        if !self.config.policy.enabled {
+            return Vec::new();
+        }
+
+        let rules = match policy::load(&self.config.policy) {
+            Ok(rules) => rules,
+            Err(e) => {
+                findings.push(Severity::Warning, None, "policy", format!("Policy rules not evaluated: {:#}", e));
+                return Vec::new();
+            }
+        };
+
+        let violations = policy::evaluate(&rules, vms);
+        for vm in vms.iter_mut().filter(|vm| vm.reachable) {
+            let mut checks = Vec::new();
+            findings.check(&mut checks, &vm.host.name, "policy", |critical, warnings| {
+                for violation in violations.iter().filter(|v| v.host == vm.host.name) {
+                    match violation.severity {
+                        Severity::Critical => critical.push(violation.issue()),
+                        Severity::Warning => warnings.push(violation.issue()),
+                        Severity::Info => {}
+                    }
+                }
+            });
+            vm.checks.extend(checks);
+        }
+        for violation in violations.iter().filter(|v| v.severity == Severity::Info) {
+            findings.push(Severity::Info, Some(&violation.host), "policy", violation.issue());
+        }
+
+        violations
+    }
+
+    /// Lists instances from the enabled cloud providers and flags the ones
+    /// that exist in the cloud but are missing from the inventory.
+    async fn discover_cloud_instances(&self, warnings: &mut Vec<String>) -> Vec<CloudInstance> {
+        let mut instances = Vec::new();
+
+        for provider in providers::enabled_providers(&self.config) {
+            match provider.discover().await {
+                Ok(found) => instances.extend(found),
+                Err(e) => warnings.push(format!("{} discovery failed: {}", provider.name(), e)),
+            }
+        }
+
+        for instance in instances.iter_mut() {
+            instance.matched_host = self
+                .hosts
+                .iter()
+                .find(|host| instance.matches(host))
+                .map(|host| host.name.clone());
+
+            if instance.matched_host.is_none() {
+                warnings.push(format!(
+                    "{} instance {} ({}, {}) is not in the inventory",
+                    instance.provider,
+                    instance.name,
+                    instance.location.as_deref().unwrap_or("unknown location"),
+                    instance.status
+                ));
+            }
+        }
+
+        instances
+    }
+
+    async fn proxmox_inventory(&self, vms: &[VmStatus], warnings: &mut Vec<String>) -> Option<ProxmoxInventory> {
+        if !self.config.proxmox.enabled {
+            return None;
+        }
+
+        let client = ProxmoxClient::new(self.config.proxmox.clone());
+        let result = match client {
+            Ok(client) => client.inventory().await,
+            Err(e) => Err(e),
+        };
+
+        let mut inventory = match result {
+            Ok(inventory) => inventory,
+            Err(e) => {
+                warnings.push(format!("Proxmox inventory failed: {}", e));
+                return None;
+            }
+        };
+
+        for node in inventory.nodes.iter().filter(|n| n.status != "online") {
+            warnings.push(format!("Proxmox node {} is {}", node.name, node.status));
+        }
+
+        for guest in inventory.guests.iter_mut() {
+            guest.matched_host = vms
+                .iter()
+                .find(|vm| vm.host.name == guest.name)
+                .map(|vm| vm.host.name.clone());
+
+            // A running guest whose SSH scan failed points at the guest, not the hypervisor
+            if let Some(vm) = vms.iter().find(|vm| vm.host.name == guest.name) {
+                if !vm.reachable && guest.status == "running" {
+                    warnings.push(format!(
+                        "{}: Proxmox reports {} {} running on {} but it is not reachable",
+                        vm.host.name, guest.kind, guest.vmid, guest.node
+                    ));
+                }
+            }
+        }
+
+        Some(inventory)
+    }
+
+    /// Compares the instances Terraform expects with what the scan found.
+    fn compare_terraform_state(
+        &self,
+        vms: &[VmStatus],
+        critical_issues: &mut Vec<String>,
+        warnings: &mut Vec<String>,
+    ) -> Option<TerraformComparison> {
+        let state_file = self.config.terraform.state_file.as_ref()?;
+
+        let mut resources = match terraform::load_resources(state_file) {
+            Ok(resources) => resources,
+            Err(e) => {
+                warnings.push(format!("Terraform state ingestion failed: {}", e));
+                return None;
+            }
+        };
+
+        for resource in resources.iter_mut() {
+            let vm = vms.iter().find(|vm| {
+                vm.host.name == resource.name
+                    || vm.host.addresses().iter().any(|ip| resource.ips.contains(ip))
+            });
+
+            resource.matched_host = vm.map(|vm| vm.host.name.clone());
+            resource.reachable = vm.map(|vm| vm.reachable).unwrap_or(false);
+
+            match vm {
+                Some(vm) if !vm.reachable => critical_issues.push(format!(
+                    "{}: managed by Terraform as {} but not responding",
+                    vm.host.name, resource.address
+                )),
+                None => warnings.push(format!(
+                    "Terraform resource {} ({}) is not in the inventory",
+                    resource.address,
+                    if resource.ips.is_empty() { "no IP".to_string() } else { resource.ips.join(", ") }
+                )),
+                _ => {}
+            }
+        }
+
+        let unmanaged_hosts = vms
+            .iter()
+            .map(|vm| vm.host.name.clone())
+            .filter(|name| !resources.iter().any(|r| r.matched_host.as_ref() == Some(name)))
+            .collect();
+
+        Some(TerraformComparison { resources, unmanaged_hosts })
+    }
+
+    async fn ping_host(&self, pinger: &Pinger, host: &VmHost) -> Vec<PingResult> {
+        if !pinger.enabled() {
+            return Vec::new();
+        }
+
+        let targets = std::iter::once(&host.ip).chain(host.vpn_ip.as_ref());
+        join_all(targets.map(|target| pinger.ping(target))).await
+    }
+
+    /// Times an SSH connection over the public and the VPN address of hosts
+    /// that have both, next to the ping results for each.
+    async fn probe_paths(&self, host: &VmHost, ping: &[PingResult]) -> Vec<NetworkPath> {
+        let Some(ref vpn_ip) = host.vpn_ip else { return Vec::new() };
+        if !self.pinger.enabled() || *vpn_ip == host.ip {
+            return Vec::new();
+        }
+
+        let paths = [(PathKind::Public, &host.ip), (PathKind::Vpn, vpn_ip)];
+        let connects = join_all(paths.iter().map(|(_, address)| self.pinger.connect(address, host.port))).await;
+        paths
+            .into_iter()
+            .zip(connects)
+            .map(|((kind, address), ssh_connect_ms)| {
+                let ping = ping.iter().find(|p| p.target == *address);
+                NetworkPath {
+                    kind,
+                    address: address.clone(),
+                    ssh_connect_ms,
+                    ping_avg_ms: ping.and_then(|p| p.avg_ms),
+                    ping_loss_percent: ping.map(|p| p.loss_percent),
+                }
+            })
+            .collect()
+    }
+
+    /// Flags hosts SSH reaches over one path only; both down is left to the
+    /// connectivity check.
+    fn check_paths(&self, host: &VmHost, paths: &[NetworkPath], warnings: &mut Vec<String>) {
+        let path = |kind| paths.iter().find(|p| p.kind == kind);
+        let (Some(public), Some(vpn)) = (path(PathKind::Public), path(PathKind::Vpn)) else { return };
+        match (public.ssh_connect_ms.is_some(), vpn.ssh_connect_ms.is_some()) {
+            (false, true) => warnings.push(format!(
+                "{}: SSH answers over the VPN ({}) but not over the public IP ({}) - check the public firewall",
+                host.name, vpn.address, public.address
+            )),
+            (true, false) => warnings.push(format!(
+                "{}: SSH answers over the public IP ({}) but not over the VPN ({}) - check the VPN tunnel",
+                host.name, public.address, vpn.address
+            )),
+            _ => {}
+        }
+    }
+
+    /// Tells apart a broken SSH daemon from a host that is down altogether.
+    fn diagnose(ping: &[PingResult]) -> String {
+        if ping.is_empty() {
+            String::new()
+        } else if ping.iter().any(|p| p.received > 0) {
+            " (host answers ping: SSH broken)".to_string()
+        } else {
+            " (no ping reply: host down or unreachable)".to_string()
+        }
+    }
+
+    fn check_packet_loss(&self, host: &VmHost, ping: &[PingResult], warnings: &mut Vec<String>) {
+        for result in ping {
+            if result.loss_percent >= 100.0 {
+                warnings.push(format!("{}: {} does not answer ping", host.name, result.target));
+            } else if result.loss_percent > 0.0 {
+                warnings.push(format!(
+                    "{}: {:.0}% packet loss to {}",
+                    host.name, result.loss_percent, result.target
+                ));
+            }
+        }
+    }
+
+    fn check_exposed_ports(
+        &self,
+        host: &VmHost,
+        exposed_ports: &[ExposedPort],
+        critical_issues: &mut Vec<String>,
+        warnings: &mut Vec<String>,
+    ) {
+        for exposed in exposed_ports {
+            let process = exposed.process.as_deref().unwrap_or("unknown process");
+            if exposed.is_forwarded() {
+                let issue = format!(
+                    "{}: Port {} ({}) is bound to {} but reachable from outside on {} (forwarded by docker-proxy or a DNAT rule?)",
+                    host.name,
+                    exposed.port,
+                    process,
+                    match exposed.exposure {
+                        Some(SocketExposure::Loopback) => "loopback",
+                        Some(SocketExposure::Vpn) => "a VPN",
+                        _ => "the LAN",
+                    },
+                    host.ip
+                );
+                if exposed.expected_public {
+                    warnings.push(issue);
+                } else {
+                    critical_issues.push(issue);
+                }
+            } else if !exposed.expected_public {
+                critical_issues.push(format!(
+                    "{}: Port {} ({}) reachable from outside on {}",
+                    host.name, exposed.port, process, host.ip
+                ));
+            }
+        }
+    }
+
+    /// Flags suspicious processes once per executable and user, leaving
+    /// pids out so the issue stays the same across restarts.
+    fn check_processes(&self, host: &VmHost, processes: &[Process], critical: &mut Vec<String>, warnings: &mut Vec<String>) {
+        let mut reported = Vec::new();
+        for process in processes {
+            if reported.contains(&(&process.exe, &process.user)) {
+                continue;
+            }
+            if let Some((severity, reason)) = self.config.processes.suspicion(process) {
+                reported.push((&process.exe, &process.user));
+                let issue = format!("{}: suspicious process {} run by {} ({})", host.name, process.exe, process.user, reason);
+                match severity {
+                    Severity::Critical => critical.push(issue),
+                    _ => warnings.push(issue),
+                }
+            }
+        }
+    }
+
+    /// Warns about sockets listening on every interface or a public address
+    /// on ports not meant to be public. IPv4 and IPv6 listeners of the same
+    /// port are reported once.
+    fn check_listeners(&self, host: &VmHost, open_ports: &[Port], warnings: &mut Vec<String>) {
+        let mut reported = Vec::new();
+        for port in open_ports.iter().filter(|p| p.exposure == Some(SocketExposure::World)) {
+            if self.port_scanner.is_expected_public(port.port) || reported.contains(&(port.port, &port.protocol)) {
+                continue;
+            }
+            reported.push((port.port, &port.protocol));
+            warnings.push(format!(
+                "{}: {} listens on {}:{}/{} (world-exposed, not in exposure.public_ports)",
+                host.name, port.process, url_host(&port.address), port.port, port.protocol
+            ));
+        }
+    }
+
+    fn check_storage_arrays(
+        &self,
+        host: &VmHost,
+        arrays: &[StorageArray],
+        critical_issues: &mut Vec<String>,
+    ) {
+        for array in arrays.iter().filter(|a| a.degraded) {
+            let devices = if array.failed_devices.is_empty() {
+                "unknown devices".to_string()
+            } else {
+                array.failed_devices.join(", ")
+            };
+            critical_issues.push(format!(
+                "{}: {} array {} degraded ({}) - affected: {}",
+                host.name, array.kind, array.name, array.state, devices
+            ));
+        }
+    }
+
+    fn check_certificates(
+        &self,
+        host: &VmHost,
+        certificates: &[ManagedCertificate],
+        critical_issues: &mut Vec<String>,
+        warnings: &mut Vec<String>,
+    ) {
+        let now = Utc::now();
+
+        for cert in certificates {
+            match cert.expires {
+                Some(expires) if expires <= now => critical_issues.push(format!(
+                    "{}: Certificate {} ({}) expired on {}",
+                    host.name, cert.name, cert.source, expires.format("%Y-%m-%d")
+                )),
+                Some(expires) if expires - now <= Duration::days(CERT_EXPIRY_WARNING_DAYS) => warnings.push(format!(
+                    "{}: Certificate {} ({}) expires in {} days",
+                    host.name, cert.name, cert.source, (expires - now).num_days()
+                )),
+                Some(_) => {}
+                None => warnings.push(format!(
+                    "{}: Could not read expiry of certificate {} ({})",
+                    host.name, cert.name, cert.source
+                )),
+            }
+
+            if !cert.auto_renew {
+                warnings.push(format!(
+                    "{}: Certificate {} ({}) will not renew automatically",
+                    host.name, cert.name, cert.source
+                ));
+            }
+        }
+    }
+
+    fn runs_traefik(services: &[Service], containers: &[Container]) -> bool {
+        services.iter().any(|s| s.name.contains("traefik"))
+            || containers
+                .iter()
+                .any(|c| c.name.contains("traefik") || c.name.contains("coolify-proxy"))
+    }
+
+    fn detect_databases(services: &[Service]) -> Vec<&'static str> {
+        let mut engines = Vec::new();
+        if services.iter().any(|s| s.name.contains("postgres")) {
+            engines.push("postgresql");
+        }
+        if services.iter().any(|s| s.name.contains("mysql") || s.name.contains("mariadb")) {
+            engines.push("mysql");
+        }
+        engines
+    }
+
+    fn check_databases(
+        &self,
+        host: &VmHost,
+        databases: &[DatabaseStatus],
+        critical_issues: &mut Vec<String>,
+        warnings: &mut Vec<String>,
+    ) {
+        for db in databases {
+            if !db.connected {
+                critical_issues.push(format!(
+                    "{}: {} connection failed - {}",
+                    host.name,
+                    db.engine,
+                    db.error.as_deref().unwrap_or("unknown error")
+                ));
+                continue;
+            }
+
+            if db.replicating == Some(false) {
+                critical_issues.push(format!(
+                    "{}: {} replica is not replicating",
+                    host.name, db.engine
+                ));
+            } else if let Some(lag) = db.replication_lag_secs.filter(|l| *l > REPLICATION_LAG_WARNING_SECS) {
+                warnings.push(format!(
+                    "{}: {} replication lag {:.0}s",
+                    host.name, db.engine, lag
+                ));
+            }
+
+            if let Some(age) = db.oldest_transaction_secs.filter(|a| *a > OLD_TRANSACTION_WARNING_SECS) {
+                warnings.push(format!(
+                    "{}: {} has a transaction open for {:.0}s",
+                    host.name, db.engine, age
+                ));
+            }
+        }
+    }
+
+    fn probe_data_stores(
+        &self,
+        ssh_client: &SshClient,
+        services: &[Service],
+        containers: &[Container],
+    ) -> Vec<DataStoreStatus> {
+        let mut data_stores = Vec::new();
+
+        if services.iter().any(|s| s.name.contains("redis")) {
+            data_stores.push(ssh_client.get_redis_status(None));
+        } else if let Some(container) = containers
+            .iter()
+            .find(|c| c.name.contains("redis") && c.status.contains("Up"))
+        {
+            data_stores.push(ssh_client.get_redis_status(Some(&container.name)));
+        }
+
+        if services.iter().any(|s| s.name.contains("couchdb"))
+            || containers.iter().any(|c| c.name.contains("couchdb"))
+        {
+            data_stores.push(ssh_client.get_couchdb_status());
+        }
+
+        data_stores
+    }
+
+    fn check_data_stores(
+        &self,
+        host: &VmHost,
+        data_stores: &[DataStoreStatus],
+        critical_issues: &mut Vec<String>,
+        warnings: &mut Vec<String>,
+    ) {
+        for store in data_stores {
+            if !store.healthy {
+                critical_issues.push(format!(
+                    "{}: {} is not healthy - {}",
+                    host.name,
+                    store.engine,
+                    store.error.as_deref().unwrap_or("unknown error")
+                ));
+            } else if let Some(ref replication) = store.replication {
+                if replication.contains("link down") {
+                    warnings.push(format!("{}: {} {}", host.name, store.engine, replication));
+                }
+            }
+        }
+    }
+
+    fn check_samba(
+        &self,
+        host: &VmHost,
+        samba: &SambaStatus,
+        critical_issues: &mut Vec<String>,
+        warnings: &mut Vec<String>,
+    ) {
+        if samba.smbd_bindings.is_empty() {
+            critical_issues.push(format!("{}: smbd is not listening on any address", host.name));
+        }
+
+        if !samba.unbound_interfaces.is_empty() {
+            critical_issues.push(format!(
+                "{}: smbd not bound to configured interfaces {:?}",
+                host.name, samba.unbound_interfaces
+            ));
+        }
+
+        if samba.nmbd_bindings.is_empty() {
+            warnings.push(format!("{}: nmbd is not listening (NetBIOS browsing disabled)", host.name));
+        }
+
+        if let Some(ref listing) = samba.anonymous_listing {
+            if !listing.reachable {
+                warnings.push(format!(
+                    "{}: Samba not reachable from scanner - {}",
+                    host.name,
+                    listing.error.as_deref().unwrap_or("unknown error")
+                ));
+            }
+        }
+    }
+
+    fn check_web_content(
+        &self,
+        web_services: &[WebService],
+        critical_issues: &mut Vec<String>,
+        warnings: &mut Vec<String>,
+    ) {
+        for web_service in web_services {
+            if let Some(ref reason) = web_service.redirect_error {
+                warnings.push(format!("{}: {}", web_service.name, reason));
+            }
+
+            if let Some(ref latency) = web_service.latency {
+                if latency.error_rate > 0.0 && latency.error_rate < 1.0 {
+                    warnings.push(format!(
+                        "{}: intermittent failures ({:.0}% of probes failed)",
+                        web_service.name,
+                        latency.error_rate * 100.0
+                    ));
+                }
+            }
+
+            if let Some(ref tls) = web_service.tls {
+                for issue in self.config.tls.issues(tls) {
+                    warnings.push(format!("{}: {}", web_service.name, issue));
+                }
+            }
+
+            if web_service.https_enforced == Some(false) {
+                warnings.push(format!("{}: plain HTTP does not redirect to HTTPS", web_service.name));
+            }
+
+            if web_service.unexpected_status {
+                warnings.push(format!(
+                    "{}: unexpected HTTP status {}",
+                    web_service.name,
+                    web_service.http_status.map(|s| s.to_string()).unwrap_or_default()
+                ));
+            }
+
+            if let Some(ref health) = web_service.health {
+                if let Some(ref error) = health.error {
+                    critical_issues.push(format!("{}: health endpoint {} failed - {}", web_service.name, health.url, error));
+                }
+                for field in health.fields.iter().filter(|field| !field.ok) {
+                    critical_issues.push(format!(
+                        "{}: health {} is {} (expected {})",
+                        web_service.name,
+                        field.path,
+                        field.value.as_deref().unwrap_or("missing"),
+                        field.expected.as_deref().unwrap_or_default()
+                    ));
+                }
+            }
+
+            if let Some(step) = web_service.scenario.iter().find(|step| step.error.is_some()) {
+                critical_issues.push(format!(
+                    "{}: scenario step '{}' failed - {}",
+                    web_service.name,
+                    step.name,
+                    step.error.as_deref().unwrap_or_default()
+                ));
+            }
+
+            if let Some(ref reason) = web_service.content_error {
+                critical_issues.push(format!(
+                    "{}: HTTP {} but content check failed - {}",
+                    web_service.name,
+                    web_service.http_status.map(|s| s.to_string()).unwrap_or_default(),
+                    reason
+                ));
+            }
+        }
+    }
+
+    /// Matches each probed web service to the Traefik router serving its
+    /// domain, so failing services can be blamed on the right backend.
+    fn attribute_web_routes(
+        &self,
+        web_services: &mut [WebService],
+        vms: &[VmStatus],
+        warnings: &mut Vec<String>,
+    ) {
+        for web_service in web_services.iter_mut() {
+            let domain = web_service
+                .url
+                .split("://")
+                .nth(1)
+                .and_then(|rest| rest.split(['/', ':']).next())
+                .unwrap_or_default()
+                .to_string();
+
+            for vm in vms {
+                let Some(ref traefik) = vm.traefik else { continue };
+                let Some(router) = traefik.routers.iter().find(|r| r.domains.contains(&domain)) else {
+                    continue;
+                };
+
+                // Router services may omit the provider suffix (`n8n` vs `n8n@docker`)
+                let service = traefik.services.iter().find(|s| {
+                    s.name == router.service || s.name.split('@').next() == Some(router.service.as_str())
+                });
+
+                web_service.route = Some(RouteAttribution {
+                    vm: vm.host.name.clone(),
+                    router: router.name.clone(),
+                    service: service.map(|s| s.name.clone()).unwrap_or_else(|| router.service.clone()),
+                    servers: service.map(|s| s.servers.clone()).unwrap_or_default(),
+                });
+                break;
+            }
+
+            let failing = web_service.error.is_some()
+                || web_service.content_error.is_some()
+                || web_service.unexpected_status;
+
+            if let (true, Some(ref route)) = (failing, &web_service.route) {
+                let down: Vec<&str> = route
+                    .servers
+                    .iter()
+                    .filter(|s| s.status.as_deref() != Some("UP"))
+                    .map(|s| s.url.as_str())
+                    .collect();
+                warnings.push(format!(
+                    "{} failing: routed by {} on {} to service {}{}",
+                    web_service.name,
+                    route.router,
+                    route.vm,
+                    route.service,
+                    if down.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" (backends down: {})", down.join(", "))
+                    }
+                ));
+            }
+        }
+    }
+
+    /// The IP addresses of every host, by host name. Hosts from the SSH
+    /// config may be known by a DNS name, which is resolved to its A and AAAA
+    /// records; names that don't resolve are left out.
+    async fn resolve_host_addresses(vms: &[VmStatus]) -> HashMap<String, Vec<std::net::IpAddr>> {
+        let lookups = vms.iter().map(|vm| async move {
+            let mut ips = Vec::new();
+            for address in vm.host.addresses() {
+                match address.parse::<std::net::IpAddr>() {
+                    Ok(ip) => ips.push(ip),
+                    Err(_) => match tokio::net::lookup_host((address.as_str(), 0)).await {
+                        Ok(resolved) => ips.extend(resolved.map(|a| a.ip())),
+                        Err(e) => debug!("{}: {} not resolved: {}", vm.host.name, address, e),
+                    },
+                }
+            }
+            (vm.host.name.clone(), ips)
+        });
+        join_all(lookups).await.into_iter().collect()
+    }
+
+    /// Checks that each web service's domain resolves to the host serving
+    /// it, so a record left behind after an IP change is caught before users
+    /// end up on the old address.
+    fn check_web_dns(
+        &self,
+        web_services: &mut [WebService],
+        vms: &[VmStatus],
+        host_addresses: &HashMap<String, Vec<std::net::IpAddr>>,
+        warnings: &mut Vec<String>,
+    ) {
+        for web_service in web_services.iter_mut() {
+            let Some(ref dns) = web_service.dns else { continue };
+            let domain = web_service.url.split("://").nth(1).and_then(|rest| rest.split(['/', ':']).next()).unwrap_or_default();
+            if let Some(ref error) = dns.error {
+                warnings.push(format!("{}: DNS lookup of {} failed - {}", web_service.name, domain, error));
+                continue;
+            }
+
+            let config = self.config.web_services.iter().find(|c| c.name == web_service.name);
+            let expected_ips = config.map(|c| c.expected_ips.as_slice()).unwrap_or_default();
+            let (expected, serving): (Vec<ipnet::IpNet>, String) = if !expected_ips.is_empty() {
+                let networks = expected_ips
+                    .iter()
+                    .filter_map(|ip| {
+                        ip.parse()
+                            .or_else(|_| ip.parse::<std::net::IpAddr>().map(ipnet::IpNet::from))
+                            .map_err(|_| warn!("Ignoring expected IP {} of {}", ip, web_service.name))
+                            .ok()
+                    })
+                    .collect();
+                (networks, expected_ips.join(", "))
+            } else {
+                let host = config
+                    .and_then(|c| c.host.clone())
+                    .or_else(|| web_service.route.as_ref().map(|route| route.vm.clone()));
+                let Some(vm) = host.and_then(|host| vms.iter().find(|vm| vm.host.name == host)) else { continue };
+                let addresses = vm.host.addresses();
+                let networks = host_addresses
+                    .get(&vm.host.name)
+                    .into_iter()
+                    .flatten()
+                    .map(|ip| ipnet::IpNet::from(ip.to_canonical()))
+                    .collect();
+                let listed: Vec<&str> = addresses.iter().map(|ip| ip.as_str()).collect();
+                (networks, format!("{} ({})", vm.host.name, listed.join(", ")))
+            };
+            if expected.is_empty() {
+                continue;
+            }
+
+            let stale: Vec<&str> = dns
+                .addresses
+                .iter()
+                .filter(|address| {
+                    !address
+                        .parse::<std::net::IpAddr>()
+                        .is_ok_and(|ip| expected.iter().any(|network| network.contains(&ip.to_canonical())))
+                })
+                .map(|address| address.as_str())
+                .collect();
+            if !stale.is_empty() {
+                let error = format!("{} resolves to {}, not {}", domain, stale.join(", "), serving);
+                warnings.push(format!("{}: {}", web_service.name, error));
+                web_service.dns_error = Some(error);
+            }
+        }
     }
 
     fn generate_summary(&self, vms: &[VmStatus]) -> Summary {
@@ -0,0 +1,58 @@
+use crate::models::{ScheduledJob, ScheduledJobKind};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Scheduled jobs seen by the previous scan of each host, so new ones stand
+/// out: cron entries and timers are a common persistence mechanism.
+pub const STATE_FILE: &str = "scheduled_jobs.json";
+
+/// Jobs by host name, as [`key`]s.
+pub type JobState = BTreeMap<String, BTreeSet<String>>;
+
+pub fn key(job: &ScheduledJob) -> String {
+    format!("{} {} {} {}", job.source, job.user.as_deref().unwrap_or("-"), job.schedule, job.command)
+}
+
+pub fn describe(job: &ScheduledJob) -> String {
+    let kind = match job.kind {
+        ScheduledJobKind::Cron => "cron",
+        ScheduledJobKind::Timer => "timer",
+    };
+    match job.user {
+        Some(ref user) => format!("{} {} [{}] as {}: {}", kind, job.source, job.schedule, user, job.command),
+        None => format!("{} {} [{}]: {}", kind, job.source, job.schedule, job.command),
+    }
+}
+
+/// Parses one crontab line. System crontabs (`/etc/crontab`, `/etc/cron.d`)
+/// carry the user as a sixth field; user crontabs are named after theirs.
+pub fn parse_cron_line(source: &str, line: &str) -> Option<ScheduledJob> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') || is_variable(line) {
+        return None;
+    }
+
+    let system = source.starts_with("/etc/");
+    let mut fields = line.split_whitespace();
+    let schedule = if line.starts_with('@') {
+        fields.next()?.to_string()
+    } else {
+        fields.by_ref().take(5).collect::<Vec<_>>().join(" ")
+    };
+    let user = if system {
+        Some(fields.next()?.to_string())
+    } else {
+        source.rsplit('/').next().map(str::to_string)
+    };
+    let command = fields.collect::<Vec<_>>().join(" ");
+    if command.is_empty() {
+        return None;
+    }
+
+    Some(ScheduledJob { kind: ScheduledJobKind::Cron, source: source.to_string(), user, schedule, command })
+}
+
+/// `NAME=value` lines set the environment of the jobs below them.
+fn is_variable(line: &str) -> bool {
+    line.split_once('=')
+        .is_some_and(|(name, _)| !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+}
@@ -0,0 +1,173 @@
+use crate::models::{InventoryReport, Issue, Severity};
+use anyhow::{Context, Result};
+use rhai::{Array, Dynamic, Engine, Scope};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+use tracing::{debug, warn};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScriptingConfig {
+    pub enabled: bool,
+    /// Rhai scripts run after each scan, in order. Glob patterns are expanded.
+    pub scripts: Vec<String>,
+}
+
+/// The issue lists of a report, shared with the functions scripts call.
+#[derive(Default)]
+struct Issues {
+    critical: Vec<Issue>,
+    warnings: Vec<Issue>,
+    notices: Vec<Issue>,
+}
+
+fn severity(name: &str) -> Result<Severity, Box<rhai::EvalAltResult>> {
+    match name {
+        "critical" => Ok(Severity::Critical),
+        "warning" => Ok(Severity::Warning),
+        "notice" | "info" => Ok(Severity::Info),
+        other => Err(format!("unknown severity '{}', use critical, warning or notice", other).into()),
+    }
+}
+
+impl Issues {
+    fn list(&mut self, severity: Severity) -> &mut Vec<Issue> {
+        match severity {
+            Severity::Critical => &mut self.critical,
+            Severity::Warning => &mut self.warnings,
+            Severity::Info => &mut self.notices,
+        }
+    }
+
+    /// Takes every issue whose message contains `pattern` out of all three lists.
+    fn take(&mut self, pattern: &str) -> Vec<Issue> {
+        let mut taken = Vec::new();
+        for list in [&mut self.critical, &mut self.warnings, &mut self.notices] {
+            let (matching, kept) = list.drain(..).partition(|issue: &Issue| issue.message.contains(pattern));
+            *list = kept;
+            taken.extend(matching);
+        }
+        taken
+    }
+}
+
+/// Post-scan analysis hooks. Each script sees the report as `report` and can
+/// change its findings through:
+///
+/// - `add_issue(severity, message)` and `add_issue(severity, host, message)`
+/// - `suppress(pattern)`: drops issues whose message contains `pattern`, returns how many
+/// - `set_severity(pattern, severity)`: re-ranks matching issues, returns how many
+/// - `issues(severity)`: the current list, as objects with `host`, `check` and `message`
+///
+/// Severities are `"critical"`, `"warning"` and `"notice"`. A failing script
+/// adds a warning and leaves the report as the previous scripts left it.
+pub fn run(config: &ScriptingConfig, report: &mut InventoryReport) {
+    if !config.enabled {
+        return;
+    }
+
+    let issues = Rc::new(RefCell::new(Issues {
+        critical: std::mem::take(&mut report.critical_issues),
+        warnings: std::mem::take(&mut report.warnings),
+        notices: std::mem::take(&mut report.notices),
+    }));
+    let engine = engine(&issues);
+
+    let mut failures = Vec::new();
+    match rhai::serde::to_dynamic(&*report) {
+        Ok(snapshot) => {
+            for path in script_paths(&config.scripts) {
+                debug!("Running script {}", path.display());
+                if let Err(e) = run_script(&engine, &path, snapshot.clone()) {
+                    warn!("Script {} failed: {:#}", path.display(), e);
+                    failures.push(format!("Script {} failed: {:#}", path.display(), e));
+                }
+            }
+        }
+        Err(e) => failures.push(format!("Scripts not run, report not convertible: {}", e)),
+    }
+
+    let issues = std::mem::take(&mut *issues.borrow_mut());
+    report.critical_issues = issues.critical;
+    report.warnings = issues.warnings;
+    report.notices = issues.notices;
+    report
+        .warnings
+        .extend(failures.into_iter().map(|f| Issue::new(Severity::Warning, None, "scripting", f)));
+}
+
+fn engine(issues: &Rc<RefCell<Issues>>) -> Engine {
+    let mut engine = Engine::new();
+
+    let shared = issues.clone();
+    engine.register_fn("add_issue", move |name: &str, message: &str| {
+        let severity = severity(name)?;
+        shared.borrow_mut().list(severity).push(Issue::new(severity, None, "script", message.to_string()));
+        Ok::<_, Box<rhai::EvalAltResult>>(())
+    });
+
+    let shared = issues.clone();
+    engine.register_fn("add_issue", move |name: &str, host: &str, message: &str| {
+        let severity = severity(name)?;
+        shared.borrow_mut().list(severity).push(Issue::new(severity, Some(host), "script", message.to_string()));
+        Ok::<_, Box<rhai::EvalAltResult>>(())
+    });
+
+    let shared = issues.clone();
+    engine.register_fn("suppress", move |pattern: &str| shared.borrow_mut().take(pattern).len() as i64);
+
+    let shared = issues.clone();
+    engine.register_fn("set_severity", move |pattern: &str, name: &str| {
+        let severity = severity(name)?;
+        let mut issues = shared.borrow_mut();
+        let moved = issues.take(pattern);
+        let count = moved.len() as i64;
+        issues
+            .list(severity)
+            .extend(moved.into_iter().map(|issue| Issue { severity, ..issue }));
+        Ok::<_, Box<rhai::EvalAltResult>>(count)
+    });
+
+    let shared = issues.clone();
+    engine.register_fn("issues", move |name: &str| {
+        let severity = severity(name)?;
+        shared
+            .borrow_mut()
+            .list(severity)
+            .iter()
+            .map(rhai::serde::to_dynamic)
+            .collect::<Result<Array, _>>()
+    });
+
+    engine
+}
+
+fn run_script(engine: &Engine, path: &PathBuf, report: Dynamic) -> Result<()> {
+    let source = std::fs::read_to_string(path).context("Failed to read script")?;
+    let mut scope = Scope::new();
+    scope.push("report", report);
+    engine
+        .run_with_scope(&mut scope, &source)
+        .map_err(|e| anyhow::anyhow!("{}", e))
+}
+
+fn script_paths(patterns: &[String]) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for pattern in patterns {
+        let expanded = shellexpand::tilde(pattern).to_string();
+        match glob::glob(&expanded) {
+            Ok(matches) => {
+                let mut matched: Vec<PathBuf> = matches.flatten().collect();
+                matched.sort();
+                if matched.is_empty() {
+                    warn!("No script matches {}", pattern);
+                }
+                paths.extend(matched);
+            }
+            Err(e) => warn!("Invalid script pattern {}: {}", pattern, e),
+        }
+    }
+    paths
+}
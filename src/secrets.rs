@@ -0,0 +1,81 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::process::Command;
+use toml::Value;
+
+/// Replaces secret references in the config with their values, so tokens
+/// and passwords don't have to sit in the file in plain text. A string is a
+/// reference when it is exactly one of:
+///
+/// - `env:NAME`, an environment variable
+/// - `pass:path/in/store`, the first line of `pass show`
+/// - `vault:mount/path#field`, `vault kv get -field=<field>` (`value` when
+///   no field is given), using the usual `VAULT_ADDR` and `VAULT_TOKEN`
+///
+/// Sections with `enabled = false` are left alone, so a secret store that
+/// is down only breaks the integrations in use.
+pub fn resolve(config: &mut Value) -> Result<()> {
+    let mut cache = HashMap::new();
+    walk(config, "", &mut cache)
+}
+
+fn walk(value: &mut Value, path: &str, cache: &mut HashMap<String, String>) -> Result<()> {
+    match value {
+        Value::String(s) => {
+            if let Some(resolved) = lookup(s, cache) {
+                *s = resolved.with_context(|| format!("Failed to resolve secret for {}", path))?;
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                walk(item, &format!("{}[{}]", path, i), cache)?;
+            }
+        }
+        Value::Table(table) => {
+            if table.get("enabled").and_then(Value::as_bool) == Some(false) {
+                return Ok(());
+            }
+            for (key, item) in table.iter_mut() {
+                let path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                walk(item, &path, cache)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// The secret `reference` points to, or `None` when it is a plain value.
+fn lookup(reference: &str, cache: &mut HashMap<String, String>) -> Option<Result<String>> {
+    let (scheme, target) = reference.split_once(':')?;
+    if !matches!(scheme, "env" | "pass" | "vault") || target.is_empty() {
+        return None;
+    }
+    if let Some(secret) = cache.get(reference) {
+        return Some(Ok(secret.clone()));
+    }
+
+    let secret = match scheme {
+        "env" => std::env::var(target).with_context(|| format!("Environment variable {} is not set", target)),
+        "pass" => run("pass", &["show", target]).map(|out| out.lines().next().unwrap_or_default().to_string()),
+        _ => {
+            let (path, field) = target.split_once('#').unwrap_or((target, "value"));
+            run("vault", &["kv", "get", &format!("-field={}", field), path])
+        }
+    };
+    if let Ok(ref secret) = secret {
+        cache.insert(reference.to_string(), secret.clone());
+    }
+    Some(secret)
+}
+
+fn run(program: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to execute {}", program))?;
+    if !output.status.success() {
+        anyhow::bail!("{} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end_matches(['\n', '\r']).to_string())
+}
@@ -0,0 +1,59 @@
+use crate::models::{Severity, Thermal};
+use serde::{Deserialize, Serialize};
+
+/// Temperatures that deserve attention. Hardware limits vary; these suit
+/// most CPUs and Raspberry Pis, which throttle at 80-85 °C.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SensorConfig {
+    pub warning_celsius: f64,
+    pub critical_celsius: f64,
+}
+
+impl Default for SensorConfig {
+    fn default() -> Self {
+        Self { warning_celsius: 80.0, critical_celsius: 90.0 }
+    }
+}
+
+impl SensorConfig {
+    /// Readings above the thresholds and throttling in effect, as issue
+    /// messages without the host.
+    pub fn issues(&self, thermal: &Thermal) -> Vec<(Severity, String)> {
+        let mut issues = Vec::new();
+        if let Some(hottest) = thermal.hottest() {
+            let severity = if hottest.celsius >= self.critical_celsius {
+                Some(Severity::Critical)
+            } else if hottest.celsius >= self.warning_celsius {
+                Some(Severity::Warning)
+            } else {
+                None
+            };
+            if let Some(severity) = severity {
+                issues.push((severity, format!("{} at {:.1} °C", hottest.name, hottest.celsius)));
+            }
+        }
+        if !thermal.throttling.is_empty() {
+            issues.push((Severity::Warning, format!("throttling now: {}", thermal.throttling.join(", "))));
+        }
+        issues
+    }
+}
+
+/// Raspberry Pi `vcgencmd get_throttled` bits: the low ones hold now, the
+/// same bits shifted by 16 have happened since boot.
+const THROTTLED_FLAGS: [(u32, &str); 4] =
+    [(0, "under-voltage"), (1, "frequency capped"), (2, "throttled"), (3, "soft temperature limit")];
+
+/// Splits a `get_throttled` value such as `0x50005` into the conditions in
+/// effect now and those seen since boot.
+pub fn throttled_flags(value: u32) -> (Vec<String>, Vec<String>) {
+    let set = |offset: u32| {
+        THROTTLED_FLAGS
+            .iter()
+            .filter(|(bit, _)| value & (1 << (bit + offset)) != 0)
+            .map(|(_, name)| name.to_string())
+            .collect()
+    };
+    (set(0), set(16))
+}
@@ -0,0 +1,152 @@
+use crate::ssh_client::SshSettings;
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use tracing::warn;
+
+/// Environment variable the askpass helper reads the passphrase from. It is
+/// only set on the `ssh-add` child, so the passphrase never touches disk.
+const ASKPASS_SECRET_VAR: &str = "SP_ASKPASS_SECRET";
+
+/// A private `ssh-agent` started for the duration of the scan and loaded with
+/// the hosts' (possibly passphrase-protected) identity files.
+pub struct AgentSession {
+    socket: String,
+    pid: String,
+}
+
+impl AgentSession {
+    /// Starts an agent when the settings ask for keys to be unlocked, either
+    /// from a passphrase source or interactively. Returns `None` when scans
+    /// should just use the user's agent (or no agent) as configured.
+    pub fn start(settings: &SshSettings, identity_files: &[String]) -> Result<Option<Self>> {
+        let passphrase = settings.passphrase()?;
+        if passphrase.is_none() && !settings.interactive {
+            return Ok(None);
+        }
+
+        let mut keys: Vec<&String> = identity_files.iter().filter(|f| !f.is_empty()).collect();
+        keys.sort();
+        keys.dedup();
+        if keys.is_empty() {
+            return Ok(None);
+        }
+
+        let output = Command::new("ssh-agent")
+            .arg("-s")
+            .output()
+            .context("Failed to start ssh-agent")?;
+        if !output.status.success() {
+            anyhow::bail!("ssh-agent failed: {}", String::from_utf8_lossy(&output.stderr));
+        }
+
+        let env = String::from_utf8_lossy(&output.stdout);
+        let session = Self {
+            socket: agent_var(&env, "SSH_AUTH_SOCK").context("ssh-agent did not report SSH_AUTH_SOCK")?,
+            pid: agent_var(&env, "SSH_AGENT_PID").context("ssh-agent did not report SSH_AGENT_PID")?,
+        };
+
+        match passphrase {
+            Some(passphrase) => session.add_with_passphrase(&keys, &passphrase)?,
+            None => session.add_interactively(&keys)?,
+        }
+
+        Ok(Some(session))
+    }
+
+    pub fn socket(&self) -> &str {
+        &self.socket
+    }
+
+    fn add_with_passphrase(&self, keys: &[&String], passphrase: &str) -> Result<()> {
+        let askpass = AskpassScript::create()?;
+
+        for key in keys {
+            let output = Command::new("ssh-add")
+                .arg(key.as_str())
+                .env("SSH_AUTH_SOCK", &self.socket)
+                .env("SSH_ASKPASS", &askpass.path)
+                .env("SSH_ASKPASS_REQUIRE", "force")
+                .env("DISPLAY", std::env::var("DISPLAY").unwrap_or_else(|_| ":0".to_string()))
+                .env(ASKPASS_SECRET_VAR, passphrase)
+                .stdin(Stdio::null())
+                .output()
+                .context("Failed to execute ssh-add")?;
+
+            if !output.status.success() {
+                warn!("Could not load {} into ssh-agent: {}",
+                    key, String::from_utf8_lossy(&output.stderr).trim());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_interactively(&self, keys: &[&String]) -> Result<()> {
+        for key in keys {
+            let status = Command::new("ssh-add")
+                .arg(key.as_str())
+                .env("SSH_AUTH_SOCK", &self.socket)
+                .status()
+                .context("Failed to execute ssh-add")?;
+
+            if !status.success() {
+                warn!("Could not load {} into ssh-agent", key);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for AgentSession {
+    fn drop(&mut self) {
+        let _ = Command::new("ssh-agent")
+            .arg("-k")
+            .env("SSH_AGENT_PID", &self.pid)
+            .env("SSH_AUTH_SOCK", &self.socket)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+}
+
+/// Extracts `NAME=value;` from the Bourne-shell output of `ssh-agent -s`.
+fn agent_var(env: &str, name: &str) -> Option<String> {
+    let prefix = format!("{}=", name);
+    env.split([';', '\n'])
+        .map(str::trim)
+        .find_map(|part| part.strip_prefix(&prefix))
+        .map(str::to_string)
+}
+
+/// Temporary askpass helper that echoes the passphrase from its environment.
+struct AskpassScript {
+    path: PathBuf,
+}
+
+impl AskpassScript {
+    fn create() -> Result<Self> {
+        // create_new refuses an existing path or symlink, so nobody else can
+        // plant or swap the helper that ssh-add runs with the passphrase
+        let path = std::env::temp_dir().join(format!("sp-askpass-{}", std::process::id()));
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o700)
+            .open(&path)
+            .context(format!("Failed to create askpass helper: {}", path.display()))?;
+        let script = Self { path };
+        file.write_all(format!("#!/bin/sh\nprintf '%s\\n' \"${}\"\n", ASKPASS_SECRET_VAR).as_bytes())
+            .context("Failed to write askpass helper")?;
+        Ok(script)
+    }
+}
+
+impl Drop for AskpassScript {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
@@ -1,53 +1,955 @@
-use crate::models::{VmHost, Service, ServiceStatus, Container, WireGuardStatus, WireGuardPeer, Port, LogEntry};
-use anyhow::Result;
-use std::process::Command;
+use crate::models::{url_host, VmHost, Capabilities, InitSystem, FileFingerprint, Process, ScheduledJob, ScheduledJobKind, SpecialFile, SpecialFileKind, Service, ServiceStatus, Container, ContainerImage, ContainerMount, ContainerRuntime, ContainerStorage, ContainerVolume, WireGuardStatus, WireGuardPeer, Port, LoadAverage, KernelEvent, Filesystem, LogEntry, OsRelease, Hardware, DiskDevice, Gpu, StorageArray, Swap, TemperatureReading, Thermal, ManagedCertificate, TraefikInventory, TraefikRouter, TraefikService, TraefikServer, DatabaseStatus, DataStoreStatus, SambaStatus, SambaShare, SambaListing};
+use crate::kernel_events;
+use crate::log_scan::LogQuery;
+use crate::scheduled_jobs;
+use crate::sensors;
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDateTime, Utc};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt, PermissionsExt};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+/// Traefik's API with `api.insecure=true` on its default port.
+const TRAEFIK_API_URL: &str = "http://localhost:8080/api";
+
+const DEFAULT_TOFU_KNOWN_HOSTS: &str = "~/.config/securepenguin/known_hosts";
+
+const DEFAULT_CONTROL_DIR: &str = "~/.cache/securepenguin/ssh";
+
+const COUCHDB_URL: &str = "http://localhost:5984";
+
+/// `docker ps`/`podman ps` template: name, status, image and ports.
+const CONTAINER_FORMAT: &str = "{{.Names}}|{{.Status}}|{{.Image}}|{{.Ports}}";
+
+/// Lines of a plain syslog file taken as errors, for hosts without journald.
+const SYSLOG_ERROR_KEYWORDS: &str = "error|fail|crit|emerg|alert|panic|denied";
+
+const ACME_JSON_PATHS: &[&str] = &[
+    "/etc/traefik/acme.json",
+    "/etc/traefik/acme/acme.json",
+    "/opt/traefik/acme.json",
+    "/data/coolify/proxy/acme.json",
+    "/letsencrypt/acme.json",
+];
+
+/// How host keys are verified before running any command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HostKeyPolicy {
+    /// Only connect to hosts already present in known_hosts.
+    Strict,
+    /// Trust on first use: record unknown keys, refuse changed ones.
+    Tofu,
+    /// Accept any key (legacy behaviour).
+    Off,
+}
+
+/// The remote host presented a key different from the one on record.
+#[derive(Debug)]
+pub struct HostKeyChanged {
+    pub fingerprint: Option<String>,
+}
+
+impl std::fmt::Display for HostKeyChanged {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.fingerprint {
+            Some(ref fp) => write!(f, "host key changed (now {})", fp),
+            None => write!(f, "host key changed"),
+        }
+    }
+}
+
+impl std::error::Error for HostKeyChanged {}
+
+/// Time limits for remote collection, per check and for the whole scan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeoutConfig {
+    /// Deadline for the whole scan; hosts not started by then are skipped.
+    pub scan_secs: Option<u64>,
+    pub connect_secs: u64,
+    /// Default limit for a single remote check.
+    pub check_secs: u64,
+    /// Limit for the batched collection of all baseline checks.
+    pub batch_secs: u64,
+    /// Per-check overrides (services, containers, wireguard, ports, logs, storage, certificates, ...).
+    pub checks: HashMap<String, u64>,
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        Self {
+            scan_secs: None,
+            connect_secs: 10,
+            check_secs: 60,
+            batch_secs: 180,
+            checks: HashMap::new(),
+        }
+    }
+}
+
+impl TimeoutConfig {
+    fn for_check(&self, check: &str) -> Duration {
+        Duration::from_secs(self.checks.get(check).copied().unwrap_or(self.check_secs))
+    }
+}
+
+/// A remote check did not finish within its time limit.
+#[derive(Debug)]
+pub struct CheckTimedOut {
+    pub check: String,
+    pub after: Duration,
+}
+
+impl std::fmt::Display for CheckTimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} timed out after {}s", self.check, self.after.as_secs())
+    }
+}
+
+impl std::error::Error for CheckTimedOut {}
+
+/// A remote check was aborted because the scan is shutting down.
+#[derive(Debug)]
+pub struct CheckCancelled {
+    pub check: String,
+}
+
+impl std::fmt::Display for CheckCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} interrupted", self.check)
+    }
+}
+
+impl std::error::Error for CheckCancelled {}
+
+/// Bounds shared by every remote command of a scan.
+#[derive(Debug, Clone)]
+pub struct CheckLimits {
+    pub timeouts: TimeoutConfig,
+    pub deadline: Option<Instant>,
+    pub cancel: CancellationToken,
+}
+
+/// How privileged commands are run on a host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SudoMode {
+    /// Never escalate; run every check as the SSH user.
+    None,
+    /// `sudo -n`, failing instead of prompting when a password is needed.
+    Passwordless,
+    /// `sudo -A`, with the password supplied by a remote askpass helper.
+    Askpass,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SudoSettings {
+    pub mode: SudoMode,
+    /// Remote path of the askpass helper used in `askpass` mode.
+    pub askpass_path: Option<String>,
+}
+
+impl Default for SudoSettings {
+    fn default() -> Self {
+        Self {
+            mode: SudoMode::Passwordless,
+            askpass_path: None,
+        }
+    }
+}
+
+/// Where the Traefik API answers, as seen from the host running Traefik.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TraefikConfig {
+    pub api_url: String,
+    /// Per-host overrides, keyed by host name.
+    pub host_api_url: HashMap<String, String>,
+}
+
+impl Default for TraefikConfig {
+    fn default() -> Self {
+        Self {
+            api_url: TRAEFIK_API_URL.to_string(),
+            host_api_url: HashMap::new(),
+        }
+    }
+}
+
+impl TraefikConfig {
+    pub fn api_url(&self, host: &str) -> &str {
+        self.host_api_url.get(host).unwrap_or(&self.api_url)
+    }
+}
+
+/// How the scanner authenticates to hosts over SSH.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SshSettings {
+    /// Offer keys held by ssh-agent in addition to the host's identity file.
+    pub use_agent: bool,
+    /// Agent socket to use instead of the inherited `SSH_AUTH_SOCK`.
+    pub agent_socket: Option<String>,
+    /// Environment variable holding the passphrase of encrypted identity files.
+    pub passphrase_env: Option<String>,
+    /// Local command printing the passphrase (e.g. a `secret-tool` or `pass` lookup).
+    pub passphrase_command: Option<String>,
+    /// Prompt for passphrases on the terminal before the scan starts.
+    pub interactive: bool,
+    pub host_key_policy: HostKeyPolicy,
+    /// known_hosts file to verify against; TOFU fingerprints are persisted here.
+    pub known_hosts_file: Option<String>,
+    pub sudo: SudoSettings,
+    /// Per-host sudo overrides, keyed by host name.
+    pub host_sudo: HashMap<String, SudoSettings>,
+    /// Collect the baseline checks of a host in a single SSH round-trip.
+    pub batch_commands: bool,
+    /// Run the independent checks of a host concurrently, each over its own
+    /// SSH channel (multiplexed over the ControlMaster connection when enabled).
+    pub parallel_checks: bool,
+    /// Reuse one TCP connection per host through an ssh ControlMaster.
+    pub control_master: bool,
+    /// Directory holding the ControlMaster sockets.
+    pub control_dir: String,
+    /// How long an idle master connection stays open (ssh `ControlPersist`).
+    pub control_persist: String,
+}
+
+impl Default for SshSettings {
+    fn default() -> Self {
+        Self {
+            use_agent: true,
+            agent_socket: None,
+            passphrase_env: None,
+            passphrase_command: None,
+            interactive: false,
+            host_key_policy: HostKeyPolicy::Strict,
+            known_hosts_file: None,
+            sudo: SudoSettings::default(),
+            host_sudo: HashMap::new(),
+            batch_commands: true,
+            parallel_checks: true,
+            control_master: true,
+            control_dir: DEFAULT_CONTROL_DIR.to_string(),
+            control_persist: "60s".to_string(),
+        }
+    }
+}
+
+impl SshSettings {
+    /// Resolves the configured passphrase source, if any.
+    pub fn passphrase(&self) -> Result<Option<String>> {
+        if let Some(ref var) = self.passphrase_env {
+            if let Ok(value) = std::env::var(var) {
+                return Ok(Some(value));
+            }
+        }
+
+        if let Some(ref command) = self.passphrase_command {
+            let output = Command::new("sh")
+                .args(["-c", command])
+                .output()
+                .context("Failed to execute passphrase_command")?;
+            if !output.status.success() {
+                anyhow::bail!("passphrase_command failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+            }
+            let passphrase = String::from_utf8_lossy(&output.stdout).trim_end_matches('\n').to_string();
+            return Ok(Some(passphrase));
+        }
+
+        Ok(None)
+    }
+
+    /// Socket directory for connection multiplexing, created private on demand.
+    fn control_dir(&self) -> Option<String> {
+        if !self.control_master {
+            return None;
+        }
+        let dir = shellexpand::tilde(&self.control_dir).to_string();
+        std::fs::create_dir_all(&dir).ok()?;
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700)).ok()?;
+        Some(dir)
+    }
+
+    /// known_hosts file passed to ssh. TOFU keeps its own file by default so
+    /// first-use fingerprints don't end up in the user's known_hosts.
+    fn known_hosts_path(&self) -> Option<String> {
+        let file = match (&self.known_hosts_file, self.host_key_policy) {
+            (Some(file), _) => file.as_str(),
+            (None, HostKeyPolicy::Tofu) => DEFAULT_TOFU_KNOWN_HOSTS,
+            (None, _) => return None,
+        };
+        let path = shellexpand::tilde(file).to_string();
+        if let Some(dir) = std::path::Path::new(&path).parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        Some(path)
+    }
+}
+
+const SECTION_BEGIN: &str = "@@SP_BEGIN ";
+
+const SECTION_END: &str = "@@SP_END ";
+
+/// Collection commands that can be bundled into one SSH session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Section {
+    Hostname,
+    Services,
+    DockerCheck,
+    Docker,
+    Podman,
+    ContainerInspect,
+    ContainerStorage,
+    WireGuard,
+    OpenPorts,
+    RecentErrors,
+    KernelLog,
+    Mdstat,
+    Zpool,
+    LetsEncrypt,
+    AcmeJson,
+    Load,
+    OsRelease,
+    Hardware,
+    Gpu,
+    Uptime,
+    Filesystems,
+    Swap,
+    Sensors,
+    Processes,
+    ScheduledJobs,
+}
+
+impl Section {
+    const ALL: [Section; 25] = [
+        Section::Hostname,
+        Section::Services,
+        Section::DockerCheck,
+        Section::Docker,
+        Section::Podman,
+        Section::ContainerInspect,
+        Section::ContainerStorage,
+        Section::WireGuard,
+        Section::OpenPorts,
+        Section::RecentErrors,
+        Section::KernelLog,
+        Section::Mdstat,
+        Section::Zpool,
+        Section::LetsEncrypt,
+        Section::AcmeJson,
+        Section::Load,
+        Section::OsRelease,
+        Section::Hardware,
+        Section::Gpu,
+        Section::Uptime,
+        Section::Filesystems,
+        Section::Swap,
+        Section::Sensors,
+        Section::Processes,
+        Section::ScheduledJobs,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Section::Hostname => "hostname",
+            Section::Services => "services",
+            Section::DockerCheck => "docker_check",
+            Section::Docker => "docker",
+            Section::Podman => "podman",
+            Section::ContainerInspect => "container_inspect",
+            Section::ContainerStorage => "container_storage",
+            Section::WireGuard => "wireguard",
+            Section::OpenPorts => "open_ports",
+            Section::RecentErrors => "recent_errors",
+            Section::KernelLog => "kernel_log",
+            Section::Mdstat => "mdstat",
+            Section::Zpool => "zpool",
+            Section::LetsEncrypt => "letsencrypt",
+            Section::AcmeJson => "acme_json",
+            Section::Load => "load",
+            Section::OsRelease => "os_release",
+            Section::Hardware => "hardware",
+            Section::Gpu => "gpu",
+            Section::Uptime => "uptime",
+            Section::Filesystems => "filesystems",
+            Section::Swap => "swap",
+            Section::Sensors => "sensors",
+            Section::Processes => "processes",
+            Section::ScheduledJobs => "scheduled_jobs",
+        }
+    }
+
+    /// Check whose timeout applies to this section.
+    fn check(&self) -> &'static str {
+        match self {
+            Section::Hostname => "hostname",
+            Section::Services => "services",
+            Section::DockerCheck | Section::Docker | Section::Podman | Section::ContainerInspect => "containers",
+            Section::ContainerStorage => "volumes",
+            Section::WireGuard => "wireguard",
+            Section::OpenPorts => "ports",
+            Section::RecentErrors => "logs",
+            Section::KernelLog => "kernel",
+            Section::Mdstat | Section::Zpool => "storage",
+            Section::LetsEncrypt | Section::AcmeJson => "certificates",
+            Section::Load => "load",
+            Section::OsRelease => "os",
+            Section::Hardware => "hardware",
+            Section::Gpu => "gpu",
+            Section::Uptime => "uptime",
+            Section::Filesystems => "disk",
+            Section::Swap => "swap",
+            Section::Sensors => "sensors",
+            Section::Processes => "processes",
+            Section::ScheduledJobs => "cron",
+        }
+    }
+}
 
 pub struct SshClient {
     host: VmHost,
+    settings: SshSettings,
+    limits: CheckLimits,
+    logs: LogQuery,
+    batch: Mutex<HashMap<Section, (String, i32)>>,
 }
 
 impl SshClient {
-    pub async fn connect(host: VmHost) -> Result<Self> {
-        let result = Command::new("ssh")
-            .args([
-                "-o", "StrictHostKeyChecking=no",
-                "-o", "ConnectTimeout=10",
-                "-o", "ServerAliveInterval=60",
-                "-o", "ServerAliveCountMax=3",
-                "-i", &host.identity_file,
-                "-p", &host.port.to_string(),
-                &format!("{}@{}", host.user, host.ip),
-                "true"
-            ])
-            .output();
+    pub async fn connect(
+        host: VmHost,
+        settings: SshSettings,
+        limits: CheckLimits,
+        logs: LogQuery,
+    ) -> Result<Self> {
+        let client = Self {
+            host,
+            settings,
+            limits,
+            logs,
+            batch: Mutex::new(HashMap::new()),
+        };
+        let mut command = client.ssh_command(client.limits.timeouts.connect_secs);
+        command.args(["-o", "ServerAliveCountMax=3", "true"]);
+        let output = client.output(&mut command, "connect", Duration::from_secs(client.limits.timeouts.connect_secs * 2))?;
 
-        match result {
-            Ok(output) => {
-                if output.status.success() {
-                    return Ok(Self { host });
+        if output.status.success() {
+            return Ok(client);
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("REMOTE HOST IDENTIFICATION HAS CHANGED") {
+            return Err(HostKeyChanged {
+                fingerprint: stderr
+                    .split_whitespace()
+                    .find(|w| w.starts_with("SHA256:"))
+                    .map(|w| w.trim_end_matches('.').to_string()),
+            }
+            .into());
+        }
+        anyhow::bail!("SSH authentication failed: {}", stderr.trim())
+    }
+
+    /// Base `ssh` invocation for this host. Runs in batch mode so a locked
+    /// key fails fast instead of hanging on a prompt mid-scan; encrypted keys
+    /// are unlocked up front through ssh-agent.
+    fn ssh_command(&self, connect_timeout: u64) -> Command {
+        let mut command = self.openssh_command("ssh", connect_timeout);
+        command.args(["-p", &self.host.port.to_string(), &self.destination()]);
+        command
+    }
+
+    /// `sftp` fetching `remote` into `local`, with the same options as [`Self::ssh_command`].
+    fn sftp_command(&self, remote: &str, local: &Path) -> Command {
+        let mut command = self.openssh_command("sftp", 30);
+        // An IPv6 address needs brackets to be told apart from the path
+        let ip = url_host(&self.host.ip);
+        let host = if self.host.user.is_empty() { ip } else { format!("{}@{}", self.host.user, ip) };
+        command.args(["-q", "-P", &self.host.port.to_string()]);
+        command.arg(format!("{}:{}", host, remote));
+        command.arg(local);
+        command
+    }
+
+    fn destination(&self) -> String {
+        if self.host.user.is_empty() {
+            self.host.ip.clone()
+        } else {
+            format!("{}@{}", self.host.user, self.host.ip)
+        }
+    }
+
+    /// An OpenSSH client (`ssh` or `sftp`) with the host key policy,
+    /// multiplexing, identity and agent options applied.
+    fn openssh_command(&self, program: &str, connect_timeout: u64) -> Command {
+        let mut command = Command::new(program);
+        let strict = match self.settings.host_key_policy {
+            HostKeyPolicy::Strict => "yes",
+            HostKeyPolicy::Tofu => "accept-new",
+            HostKeyPolicy::Off => "no",
+        };
+        command.args([
+            "-o", &format!("StrictHostKeyChecking={}", strict),
+            "-o", "BatchMode=yes",
+            "-o", &format!("ConnectTimeout={}", connect_timeout),
+            "-o", "ServerAliveInterval=60",
+        ]);
+
+        if let Some(dir) = self.settings.control_dir() {
+            command.args([
+                "-o", "ControlMaster=auto",
+                // %C hashes host, port and user, keeping the path under the socket length limit
+                "-o", &format!("ControlPath={}/%C", dir),
+                "-o", &format!("ControlPersist={}", self.settings.control_persist),
+            ]);
+        }
+
+        if let Some(file) = self.settings.known_hosts_path() {
+            command.args(["-o", &format!("UserKnownHostsFile={}", file)]);
+        }
+
+        if !self.host.identity_file.is_empty() {
+            command.args(["-i", &self.host.identity_file]);
+        }
+
+        if !self.settings.use_agent {
+            command.args(["-o", "IdentityAgent=none"]);
+        } else if let Some(ref socket) = self.settings.agent_socket {
+            command.env("SSH_AUTH_SOCK", shellexpand::tilde(socket).to_string());
+        }
+        command
+    }
+
+    /// Wraps `command` according to the host's sudo strategy. When escalation
+    /// fails (password required, not in sudoers) the command is retried
+    /// unprivileged, which still works for e.g. users in the docker group.
+    fn sudo(&self, command: &str) -> String {
+        self.sudo_as(None, command)
+    }
+
+    fn sudo_as(&self, user: Option<&str>, command: &str) -> String {
+        match self.privileged(user, command) {
+            Some(privileged) => format!("{{ {} || {}; }}", privileged, command),
+            None => command.to_string(),
+        }
+    }
+
+    /// `command` run through sudo as configured for the host, without the
+    /// unprivileged fallback; `None` when sudo is disabled.
+    fn privileged(&self, user: Option<&str>, command: &str) -> Option<String> {
+        let settings = self.settings.host_sudo.get(&self.host.name).unwrap_or(&self.settings.sudo);
+        let target = user.map(|u| format!("-u {} ", u)).unwrap_or_default();
+
+        match settings.mode {
+            SudoMode::None => None,
+            SudoMode::Passwordless => Some(format!("sudo -n {}{}", target, command)),
+            SudoMode::Askpass => match settings.askpass_path {
+                Some(ref path) => Some(format!("SUDO_ASKPASS={} sudo -A {}{}", path, target, command)),
+                None => Some(format!("sudo -A {}{}", target, command)),
+            },
+        }
+    }
+
+    /// Remote command collecting one batchable section.
+    fn section_command(&self, section: Section) -> String {
+        match section {
+            Section::Hostname => "hostname".to_string(),
+            // systemd, OpenRC (Alpine, Gentoo) or FreeBSD rc.d; the latter two
+            // only print the service name
+            Section::Services => "if [ -d /run/systemd/system ]; then \
+                systemctl list-units --type=service --state=running --no-legend --plain; \
+                elif command -v rc-status >/dev/null 2>&1; then rc-status -a -C 2>/dev/null | awk '/\\[ *started/ {print $1}'; \
+                elif [ \"$(uname -s)\" = FreeBSD ]; then \
+                for s in $(service -e); do n=${s##*/}; service $n onestatus >/dev/null 2>&1 && echo $n; done; fi"
+                .to_string(),
+            Section::DockerCheck => "command -v docker >/dev/null 2>&1 && echo 'DOCKER_FOUND'".to_string(),
+            Section::Docker => format!("{} || echo 'DOCKER_ERROR'", self.sudo(&format!("docker ps -a --format '{}' 2>/dev/null", CONTAINER_FORMAT))),
+            Section::Podman => format!("{} || echo 'PODMAN_ERROR'", self.sudo(&format!("podman ps -a --format '{}' 2>/dev/null", CONTAINER_FORMAT))),
+            Section::ContainerInspect => {
+                let script = "for c in docker podman; do command -v $c >/dev/null 2>&1 || continue; \
+                    ids=$($c ps -q); [ -z \"$ids\" ] || $c inspect $ids; break; done";
+                format!("{} || echo 'INSPECT_ERROR'", self.sudo(&format!("sh -c '{}' 2>/dev/null", script)))
+            }
+            Section::ContainerStorage => {
+                // Nothing is printed when no runtime answers. Volume sizes come
+                // from du on their mountpoints, as the runtime only tracks some drivers
+                let script = r#"c=$(command -v docker || command -v podman) || exit 0; \
+                    r=$($c info -f "{{.DockerRootDir}}" 2>/dev/null || $c info -f "{{.Store.GraphRoot}}") || exit 0; \
+                    $c ps -aq | xargs -r $c inspect -f "USED {{.Image}}"; \
+                    $c images -q --no-trunc | sort -u | xargs -r $c image inspect -f "IMAGE {{.Id}} {{.Size}} {{.RepoTags}}"; \
+                    $c volume ls -q -f dangling=true | sed "s/^/ORPHAN /"; \
+                    for v in $($c volume ls -q); do \
+                        printf "VOLUME %s %s\n" "$v" "$(du -sk "$($c volume inspect -f "{{.Mountpoint}}" $v)" 2>/dev/null | cut -f1)"; \
+                    done; \
+                    echo "ROOT $r $(df -P "$r" | tail -1 | awk "{print \$5}")""#;
+                format!("{} || echo 'STORAGE_ERROR'", self.sudo(&format!("sh -c '{}' 2>/dev/null", script)))
+            }
+            Section::WireGuard => format!("{} || echo 'WG_ERROR'", self.sudo("wg show 2>/dev/null")),
+            // iproute2, then FreeBSD's sockstat and busybox/net-tools netstat
+            // Every TCP listener and bound UDP socket, each one is classified
+            Section::OpenPorts => "if command -v ss >/dev/null 2>&1; then ss -Htulpn; \
+                elif command -v sockstat >/dev/null 2>&1; then sockstat -46l -P tcp,udp | tail -n +2 | sed 's/^/SOCKSTAT /'; \
+                else netstat -tulnp 2>/dev/null | grep -E '^(tcp|udp)' | sed 's/^/NETSTAT /'; fi"
+                .to_string(),
+            // Without journald, syslog files have no priority to filter on, so
+            // lines are picked by keyword instead
+            Section::RecentErrors => format!(
+                "if command -v journalctl >/dev/null 2>&1; then \
+                (journalctl --since=-{0}s --priority={1} --no-pager -o json 2>/dev/null || echo 'JOURNALCTL_ERROR') | tail -n {2}; \
+                else echo \"SYSLOG_TZ $(date +%z)\"; {3} | grep -iE '{4}' | tail -n {2} | sed 's/^/SYSLOG /'; fi",
+                self.logs.window.as_secs(),
+                self.logs.priority,
+                self.logs.max_entries,
+                self.sudo("sh -c 'tail -q -n 5000 /var/log/messages /var/log/syslog 2>/dev/null; true'"),
+                SYSLOG_ERROR_KEYWORDS
+            ),
+            Section::KernelLog => {
+                // dmesg covers hosts whose journal does not keep kernel messages
+                let script = format!(
+                    "(journalctl -k --since=-{}s --priority=warning --no-pager -o json 2>/dev/null | grep -E \"{}\") || \
+                    (dmesg --time-format iso 2>/dev/null | grep -E \"{}\" | sed \"s/^/DMESG /\"); true",
+                    self.logs.window.as_secs(),
+                    kernel_events::PREFILTER,
+                    kernel_events::PREFILTER
+                );
+                self.sudo(&format!("sh -c '{}'", script))
+            }
+            Section::Mdstat => "cat /proc/mdstat 2>/dev/null || echo 'MDSTAT_ERROR'".to_string(),
+            Section::Zpool => format!(
+                "command -v zpool >/dev/null 2>&1 && {} || echo 'ZPOOL_ERROR'",
+                self.sudo("zpool status 2>/dev/null")
+            ),
+            Section::LetsEncrypt => {
+                // Only a missing openssl fails the script; a certificate
+                // without a renewal conf is a finding, not an error
+                let script = "[ -d /etc/letsencrypt/live ] || exit 0; \
+                    command -v openssl >/dev/null 2>&1 || exit 1; \
+                    (systemctl is-active --quiet certbot.timer || systemctl is-active --quiet snap.certbot.renew.timer || [ -f /etc/cron.d/certbot ]) && echo RENEWER_OK; \
+                    for d in /etc/letsencrypt/live/*/; do \
+                        n=$(basename $d); echo CERT $n; \
+                        openssl x509 -noout -subject -enddate -in $d/cert.pem 2>/dev/null; \
+                        if [ -f /etc/letsencrypt/renewal/$n.conf ]; then echo RENEWAL_OK; fi; \
+                    done; true";
+                format!("{} || echo 'LE_ERROR'", self.sudo(&format!("sh -c '{}' 2>/dev/null", script)))
+            }
+            Section::AcmeJson => {
+                let script = format!(
+                    r#"for p in {}; do \
+                        [ -f $p ] || continue; echo ACME $p; \
+                        tr , "\n" < $p | grep -w certificate | cut -d\" -f4 | \
+                        while read c; do echo $c | base64 -d 2>/dev/null | openssl x509 -noout -subject -enddate 2>/dev/null; done; \
+                    done"#,
+                    ACME_JSON_PATHS.join(" ")
+                );
+                format!("{} || echo 'ACME_ERROR'", self.sudo(&format!("sh -c '{}' 2>/dev/null", script)))
+            }
+            // FreeBSD has no /proc; vm.loadavg prints `{ 0.10 0.20 0.30 }`
+            Section::Load => "if [ -r /proc/loadavg ]; then cat /proc/loadavg && nproc; \
+                else sysctl -n vm.loadavg | tr -d '{}' && sysctl -n hw.ncpu; fi"
+                .to_string(),
+            Section::OsRelease => "cat /etc/os-release 2>/dev/null; echo \"KERNEL=$(uname -r)\"".to_string(),
+            // FreeBSD has no /proc/cpuinfo or lsblk; sysctl covers CPU and memory
+            Section::Hardware => "if [ -r /proc/cpuinfo ]; then \
+                    echo \"CPU $(grep -m1 -E '^(model name|Model|Hardware)' /proc/cpuinfo | cut -d: -f2-)\"; \
+                    echo \"CORES $(nproc)\"; grep '^MemTotal:' /proc/meminfo; \
+                    lsblk -d -b -n -P -o NAME,SIZE,ROTA,TYPE,MODEL 2>/dev/null | sed 's/^/DISK /'; \
+                else \
+                    echo \"CPU $(sysctl -n hw.model)\"; echo \"CORES $(sysctl -n hw.ncpu)\"; \
+                    echo \"MemTotal: $(($(sysctl -n hw.physmem) / 1024)) kB\"; \
+                fi; echo \"VIRT $(systemd-detect-virt 2>/dev/null || sysctl -n kern.vm_guest 2>/dev/null)\"; true"
+                .to_string(),
+            Section::Gpu => "command -v nvidia-smi >/dev/null 2>&1 && \
+                    nvidia-smi --query-gpu=name,memory.total,memory.used,utilization.gpu,temperature.gpu \
+                    --format=csv,noheader,nounits 2>&1 | sed 's/^/NVIDIA /'; \
+                command -v rocm-smi >/dev/null 2>&1 && { printf 'ROCM '; \
+                    rocm-smi --showproductname --showmeminfo vram --showuse --showtemp --json 2>/dev/null | tr -d '\\n'; echo; }; true"
+                .to_string(),
+            Section::Uptime => "cat /proc/uptime".to_string(),
+            Section::Filesystems => {
+                // Pseudo and image filesystems are always full
+                let local = "-x tmpfs -x devtmpfs -x overlay -x squashfs -x efivarfs";
+                format!(
+                    "df -P -k {0} 2>/dev/null | tail -n +2 | sed 's/^/SPACE /'; df -P -i {0} 2>/dev/null | tail -n +2 | sed 's/^/INODES /'",
+                    local
+                )
+            }
+            // The one-second sample of swap activity is only taken with swap to sample
+            Section::Swap => "grep -E '^Swap(Total|Free):' /proc/meminfo; \
+                grep -q '^SwapTotal: *0 ' /proc/meminfo || { grep -E '^pswp(in|out) ' /proc/vmstat; sleep 1; grep -E '^pswp(in|out) ' /proc/vmstat; }"
+                .to_string(),
+            Section::Sensors => "for z in /sys/class/thermal/thermal_zone*; do \
+                [ -r $z/temp ] && echo \"ZONE $(cat $z/type) $(cat $z/temp)\"; done; \
+                command -v sensors >/dev/null 2>&1 && echo \"LMSENSORS $(sensors -j 2>/dev/null | tr -d '\\n')\"; \
+                command -v vcgencmd >/dev/null 2>&1 && echo \"VCGENCMD $(vcgencmd measure_temp) $(vcgencmd get_throttled)\"; \
+                cat /sys/devices/system/cpu/cpu*/thermal_throttle/core_throttle_count 2>/dev/null | \
+                awk '{ n += $1 } END { if (NR) print \"THROTTLE_COUNT\", n }'; true"
+                .to_string(),
+            Section::Processes => {
+                // Kernel threads have no executable and are skipped
+                let script = "for p in /proc/[0-9]*; do e=$(readlink $p/exe 2>/dev/null) || continue; \
+                    printf \"%s\\t%s\\t%s\\t%s\\n\" \"${p#/proc/}\" \"$(stat -c %U $p)\" \"$e\" \
+                    \"$(tr \"\\000\\n\" \"  \" < $p/cmdline | cut -c1-200)\"; done";
+                format!("{} || echo 'PS_ERROR'", self.sudo(&format!("sh -c '{}' 2>/dev/null", script)))
+            }
+            Section::ScheduledJobs => {
+                // Crontabs are prefixed with their path; timers come with their
+                // schedule and the command of the unit they activate
+                let script = r#"for f in /etc/crontab /etc/cron.d/* /var/spool/cron/crontabs/* /var/spool/cron/*; do \
+                    [ -f "$f" ] && sed "s|^|CRON $f\t|" "$f"; done; \
+                    systemctl list-units --type=timer --all --no-legend --plain 2>/dev/null | while read t rest; do \
+                        u=$(systemctl show -p Unit --value $t); \
+                        printf "TIMER %s\t%s\t%s\t%s\n" "$t" \
+                        "$(systemctl show -p TimersCalendar -p TimersMonotonic --value $t | sed -n "s/^{ \([^;]*\) ;.*/\1/p" | paste -sd ,)" \
+                        "$u" "$(systemctl show -p ExecStart --value $u | sed -n "s/.*argv\[\]=\([^;]*\) ;.*/\1/p" | head -1)"; \
+                    done; true"#;
+                format!("{} || echo 'CRON_ERROR'", self.sudo(&format!("sh -c '{}' 2>/dev/null", script)))
+            }
+        }
+    }
+
+    /// Runs the batchable sections of the `wanted` checks in a single SSH
+    /// session and caches the outputs, so the getters below cost no extra
+    /// round-trips. Sections are delimited with markers carrying each
+    /// command's exit status.
+    pub fn prefetch(&self, wanted: impl Fn(&str) -> bool) -> Result<()> {
+        let script = Section::ALL
+            .iter()
+            .filter(|section| wanted(section.check()))
+            .map(|section| {
+                format!(
+                    "echo '{}{}'; ( {} ) </dev/null; echo \"{}{} $?\"",
+                    SECTION_BEGIN, section.name(), self.section_command(*section), SECTION_END, section.name()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let output = self.run_command_within(&script, "batch", Duration::from_secs(self.limits.timeouts.batch_secs))?;
+
+        let mut cache = self.batch.lock().unwrap();
+        let mut current: Option<(Section, String)> = None;
+        for line in output.lines() {
+            if let Some(name) = line.strip_prefix(SECTION_BEGIN) {
+                current = Section::ALL.iter().find(|s| s.name() == name).map(|s| (*s, String::new()));
+                continue;
+            }
+            let Some((section, body)) = current.as_mut() else { continue };
+            match end_marker(line, *section) {
+                Some((pos, status)) => {
+                    // A command without a trailing newline leaves output before the marker
+                    body.push_str(&line[..pos]);
+                    cache.insert(*section, (std::mem::take(body), status));
+                    current = None;
+                }
+                None => {
+                    body.push_str(line);
+                    body.push('\n');
                 }
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                anyhow::bail!("SSH authentication failed: {}", stderr)
             }
-            Err(e) => anyhow::bail!("Failed to execute SSH: {}", e),
         }
+
+        Ok(())
+    }
+
+    /// Output of `section`, from the prefetched batch when available.
+    fn section(&self, section: Section) -> Result<String> {
+        if let Some((output, status)) = self.batch.lock().unwrap().get(&section) {
+            if *status != 0 {
+                anyhow::bail!("Command failed with exit status {}", status);
+            }
+            return Ok(output.clone());
+        }
+
+        self.run_command_within(&self.section_command(section), section.check(), self.limits.timeouts.for_check(section.check()))
     }
 
     pub fn hostname(&self) -> Result<String> {
-        self.run_command("hostname")
+        self.section(Section::Hostname)
+    }
+
+    /// Distribution and kernel; `None` without `/etc/os-release`.
+    /// Finds out the init system, which of the tools checks rely on are
+    /// installed and whether sudo works, in one round trip.
+    pub fn probe_capabilities(&self) -> Result<Capabilities> {
+        let sudo = self
+            .privileged(None, "true")
+            .map(|command| format!("{} >/dev/null 2>&1 && echo sudo", command))
+            .unwrap_or_default();
+        let script = format!(
+            "if [ -d /run/systemd/system ]; then echo init=systemd; \
+            elif command -v rc-status >/dev/null 2>&1; then echo init=openrc; \
+            elif [ \"$(uname -s)\" = FreeBSD ]; then echo init=bsdrc; else echo init=other; fi; \
+            for t in docker podman ss netstat sockstat journalctl; do command -v $t >/dev/null 2>&1 && echo $t; done; \
+            for f in /var/log/messages /var/log/syslog; do [ -f $f ] && echo syslog; done; \
+            {} </dev/null; true",
+            sudo
+        );
+        let output = self.run_command_within(&script, "capabilities", self.limits.timeouts.for_check("capabilities"))?;
+        let found: Vec<&str> = output.lines().map(str::trim).collect();
+        let init = match found.iter().find_map(|line| line.strip_prefix("init=")) {
+            Some("systemd") => InitSystem::Systemd,
+            Some("openrc") => InitSystem::OpenRc,
+            Some("bsdrc") => InitSystem::BsdRc,
+            _ => InitSystem::Other,
+        };
+        let has = |tool: &str| found.contains(&tool);
+
+        Ok(Capabilities {
+            init,
+            docker: has("docker"),
+            podman: has("podman"),
+            ss: has("ss"),
+            netstat: has("netstat"),
+            sockstat: has("sockstat"),
+            journald: has("journalctl"),
+            syslog: has("syslog"),
+            sudo: has("sudo"),
+            probed_at: Utc::now(),
+        })
+    }
+
+    pub fn get_os_release(&self) -> Result<Option<OsRelease>> {
+        let output = self.section(Section::OsRelease)?;
+        // KEY=value, with the value optionally quoted
+        let field = |key: &str| {
+            output.lines().find_map(|line| {
+                let (k, value) = line.split_once('=')?;
+                (k == key).then(|| value.trim().trim_matches('"').trim_matches('\'').to_string())
+            })
+        };
+
+        let Some(id) = field("ID") else {
+            return Ok(None);
+        };
+        Ok(Some(OsRelease {
+            pretty_name: field("PRETTY_NAME").unwrap_or_else(|| id.clone()),
+            version_id: field("VERSION_ID").unwrap_or_default(),
+            kernel: field("KERNEL").unwrap_or_default(),
+            id,
+        }))
+    }
+
+    /// CPU, memory and disks from `/proc` and `lsblk`, and the
+    /// virtualization type from `systemd-detect-virt`.
+    pub fn get_hardware(&self) -> Result<Option<Hardware>> {
+        let output = self.section(Section::Hardware)?;
+        let mut hardware = Hardware { cpu_model: None, cores: None, memory_bytes: None, disks: Vec::new(), virtualization: None };
+        let non_empty = |value: &str| Some(value.trim().to_string()).filter(|v| !v.is_empty());
+
+        for line in output.lines() {
+            if let Some(model) = line.strip_prefix("CPU ") {
+                hardware.cpu_model = non_empty(model);
+            } else if let Some(cores) = line.strip_prefix("CORES ") {
+                hardware.cores = cores.trim().parse().ok();
+            } else if let Some(memory) = line.strip_prefix("MemTotal:") {
+                let kb = memory.split_whitespace().next().and_then(|kb| kb.parse::<u64>().ok());
+                hardware.memory_bytes = kb.map(|kb| kb * 1024);
+            } else if let Some(virtualization) = line.strip_prefix("VIRT ") {
+                hardware.virtualization = non_empty(virtualization);
+            } else if let Some(disk) = line.strip_prefix("DISK ") {
+                // KEY="value" pairs, values possibly with spaces (the model)
+                let mut fields = HashMap::new();
+                for pair in disk.split("\" ") {
+                    if let Some((key, value)) = pair.split_once("=\"") {
+                        fields.insert(key.trim(), value.trim_end_matches('"'));
+                    }
+                }
+                // zram swap devices are disks to lsblk, but hold no data
+                let Some(name) = fields.get("NAME").filter(|name| !name.starts_with("zram")) else { continue };
+                if fields.get("TYPE") != Some(&"disk") {
+                    continue;
+                }
+                hardware.disks.push(DiskDevice {
+                    name: name.to_string(),
+                    size_bytes: fields.get("SIZE").and_then(|size| size.parse().ok()).unwrap_or(0),
+                    model: fields.get("MODEL").and_then(|model| non_empty(model)),
+                    rotational: fields.get("ROTA") == Some(&"1"),
+                });
+            }
+        }
+
+        if hardware.cpu_model.is_none() && hardware.cores.is_none() && hardware.memory_bytes.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(hardware))
+    }
+
+    /// GPUs from `nvidia-smi` and `rocm-smi`; empty when neither is installed.
+    /// An `nvidia-smi` that can't reach its driver fails the check.
+    pub fn get_gpus(&self) -> Result<Vec<Gpu>> {
+        let output = self.section(Section::Gpu)?;
+        let mut gpus = Vec::new();
+
+        for line in output.lines() {
+            if let Some(line) = line.strip_prefix("NVIDIA ") {
+                let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+                let [name, total, used, utilization, temperature] = fields[..] else {
+                    anyhow::bail!("nvidia-smi failed: {}", line);
+                };
+                let mib = |value: &str| value.parse::<u64>().ok().map(|mib| mib * 1024 * 1024);
+                gpus.push(Gpu {
+                    vendor: "nvidia".to_string(),
+                    model: name.to_string(),
+                    memory_total_bytes: mib(total),
+                    memory_used_bytes: mib(used),
+                    utilization_percent: utilization.parse().ok(),
+                    temperature_celsius: temperature.parse().ok(),
+                });
+            } else if let Some(json) = line.strip_prefix("ROCM ") {
+                let Ok(serde_json::Value::Object(cards)) = serde_json::from_str(json) else {
+                    continue;
+                };
+                for (card, fields) in cards.iter().filter(|(name, _)| name.starts_with("card")) {
+                    // Key names and case vary between ROCm releases
+                    let field = |matches: &dyn Fn(&str) -> bool| {
+                        fields.as_object()?.iter().find(|(key, _)| matches(&key.to_lowercase())).and_then(|(_, v)| v.as_str())
+                    };
+                    let bytes = |value: Option<&str>| value.and_then(|v| v.trim().parse::<u64>().ok());
+                    gpus.push(Gpu {
+                        vendor: "amd".to_string(),
+                        model: field(&|k| k == "card series")
+                            .or_else(|| field(&|k| k == "card model"))
+                            .unwrap_or(card)
+                            .to_string(),
+                        memory_total_bytes: bytes(field(&|k| k.starts_with("vram total memory"))),
+                        memory_used_bytes: bytes(field(&|k| k.starts_with("vram total used memory"))),
+                        utilization_percent: field(&|k| k.starts_with("gpu use")).and_then(|v| v.trim().parse().ok()),
+                        temperature_celsius: field(&|k| k.starts_with("temperature") && k.contains("edge"))
+                            .or_else(|| field(&|k| k.starts_with("temperature")))
+                            .and_then(|v| v.trim().parse().ok()),
+                    });
+                }
+            }
+        }
+
+        Ok(gpus)
     }
 
-    pub fn uptime(&self) -> Result<String> {
-        self.run_command("uptime")
+    /// Time since boot, from `/proc/uptime` rather than the locale- and
+    /// version-dependent output of `uptime`.
+    pub fn get_uptime(&self) -> Result<Option<Duration>> {
+        let output = self.section(Section::Uptime)?;
+        Ok(output
+            .split_whitespace()
+            .next()
+            .and_then(|secs| secs.parse::<f64>().ok())
+            .map(Duration::from_secs_f64))
     }
 
     pub fn list_running_services(&self) -> Result<Vec<Service>> {
-        let output = self.run_command("systemctl list-units --type=service --state=running --no-legend --plain")?;
+        let output = self.section(Section::Services)?;
         
         let mut services = Vec::new();
         let service_patterns = vec![
             "docker", "podman", "wireguard", "samba", "guacamole",
-            "nginx", "traefik", "apache", "mysql", "postgres", "redis",
+            "nginx", "traefik", "apache", "mysql", "mariadb", "postgres", "redis",
             "pdns", "powerdns", "n8n", "obsidian", "couchdb", "authelia"
         ];
 
@@ -70,61 +972,204 @@ impl SshClient {
     }
 
     pub fn list_containers(&self) -> Result<Vec<Container>> {
-        if let Ok(output) = self.run_command("command -v docker >/dev/null 2>&1 && echo 'DOCKER_FOUND'") {
-            if output.contains("DOCKER_FOUND") {
-                return self.list_docker_containers();
+        let mut containers = match self.section(Section::DockerCheck) {
+            Ok(output) if output.contains("DOCKER_FOUND") => self.list_docker_containers()?,
+            Err(e) if e.is::<CheckTimedOut>() => return Err(e),
+            _ => self.list_podman_containers()?,
+        };
+
+        // The listing stands on its own when inspecting is not allowed
+        match self.inspect_containers() {
+            Ok(mut runtimes) => {
+                for container in &mut containers {
+                    container.runtime = runtimes.remove(&container.name);
+                }
             }
+            Err(e) if e.is::<CheckTimedOut>() => return Err(e),
+            Err(_) => {}
+        }
+
+        Ok(containers)
+    }
+
+    /// Privileges, network mode and mounts of the running containers, by name.
+    fn inspect_containers(&self) -> Result<HashMap<String, ContainerRuntime>> {
+        let output = self.section(Section::ContainerInspect)?;
+        if output.contains("INSPECT_ERROR") {
+            anyhow::bail!("Containers not inspectable");
+        }
+        if output.trim().is_empty() {
+            return Ok(HashMap::new());
         }
 
-        self.list_podman_containers()
+        let inspected: Vec<serde_json::Value> = serde_json::from_str(&output).context("Failed to parse container inspection")?;
+        Ok(inspected
+            .iter()
+            .map(|container| {
+                let host_config = &container["HostConfig"];
+                let mounts = container["Mounts"]
+                    .as_array()
+                    .map(|mounts| {
+                        mounts
+                            .iter()
+                            .map(|mount| ContainerMount {
+                                source: json_str(mount, "Source"),
+                                destination: json_str(mount, "Destination"),
+                                read_only: mount["RW"].as_bool() == Some(false),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let runtime = ContainerRuntime {
+                    image: json_str(&container["Config"], "Image"),
+                    privileged: host_config["Privileged"].as_bool().unwrap_or(false),
+                    network_mode: json_str(host_config, "NetworkMode"),
+                    mounts,
+                    env: container["Config"]["Env"]
+                        .as_array()
+                        .map(|env| {
+                            env.iter()
+                                .filter_map(|var| var.as_str()?.split_once('='))
+                                .filter(|(_, value)| !value.is_empty())
+                                .map(|(name, _)| name.to_string())
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                };
+                (json_str(container, "Name").trim_start_matches('/').to_string(), runtime)
+            })
+            .collect())
     }
 
     fn list_docker_containers(&self) -> Result<Vec<Container>> {
-        let output = self.run_command("sudo docker ps -a --format table name,status,ports 2>/dev/null || echo 'DOCKER_ERROR'")?;
+        let output = self.section(Section::Docker)?;
         
         if output.contains("DOCKER_ERROR") || output.trim().is_empty() {
             return Ok(Vec::new());
         }
 
-        let mut containers = Vec::new();
-        for line in output.lines().skip(1) {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 3 {
-                containers.push(Container {
-                    name: parts[0].to_string(),
-                    status: parts[1].to_string(),
-                    ports: parts[2].to_string(),
-                });
-            }
-        }
-
-        Ok(containers)
+        Ok(output.lines().filter_map(parse_container_line).collect())
     }
 
     fn list_podman_containers(&self) -> Result<Vec<Container>> {
-        let output = self.run_command("sudo podman ps -a --format table name,status,ports 2>/dev/null || echo 'PODMAN_ERROR'")?;
+        let output = self.section(Section::Podman)?;
         
         if output.contains("PODMAN_ERROR") || output.trim().is_empty() {
             return Ok(Vec::new());
         }
 
-        let mut containers = Vec::new();
-        for line in output.lines().skip(1) {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 3 {
-                containers.push(Container {
-                    name: parts[0].to_string(),
-                    status: parts[1].to_string(),
-                    ports: parts[2].to_string(),
+        Ok(output.lines().filter_map(parse_container_line).collect())
+    }
+
+    /// Images and volumes of the container runtime; `None` without one.
+    pub fn get_container_storage(&self) -> Result<Option<ContainerStorage>> {
+        let output = self.section(Section::ContainerStorage)?;
+        if output.contains("STORAGE_ERROR") {
+            anyhow::bail!("Container images and volumes not readable");
+        }
+        if output.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let used: Vec<&str> = output.lines().filter_map(|line| line.strip_prefix("USED ")).map(str::trim).collect();
+        let orphans: Vec<&str> = output.lines().filter_map(|line| line.strip_prefix("ORPHAN ")).map(str::trim).collect();
+        let mut storage = ContainerStorage { images: Vec::new(), volumes: Vec::new(), data_root: None, data_root_used_percent: None };
+
+        for line in output.lines() {
+            if let Some(image) = line.strip_prefix("IMAGE ") {
+                // IMAGE sha256:… 187654321 [nginx:latest nginx:1.27]
+                let mut fields = image.splitn(3, ' ');
+                let (Some(id), Some(size)) = (fields.next(), fields.next()) else {
+                    continue;
+                };
+                storage.images.push(ContainerImage {
+                    id: id.to_string(),
+                    tags: fields
+                        .next()
+                        .unwrap_or_default()
+                        .trim_matches(|c| c == '[' || c == ']')
+                        .split_whitespace()
+                        .map(str::to_string)
+                        .collect(),
+                    size_bytes: size.parse().unwrap_or(0),
+                    in_use: used.contains(&id),
+                });
+            } else if let Some(volume) = line.strip_prefix("VOLUME ") {
+                let (name, size) = volume.split_once(' ').unwrap_or((volume, ""));
+                storage.volumes.push(ContainerVolume {
+                    name: name.to_string(),
+                    size_bytes: size.trim().parse::<u64>().ok().map(|kib| kib * 1024),
+                    orphan: orphans.contains(&name),
                 });
+            } else if let Some(root) = line.strip_prefix("ROOT ") {
+                let (path, used_percent) = root.rsplit_once(' ').unwrap_or((root, ""));
+                storage.data_root = Some(path.to_string()).filter(|p| !p.is_empty());
+                storage.data_root_used_percent = used_percent.trim().trim_end_matches('%').parse().ok();
             }
         }
 
-        Ok(containers)
+        Ok(Some(storage))
+    }
+
+    /// Every userland process with its executable path, as `/proc` shows it
+    /// (a `(deleted)` suffix marks an executable removed from disk).
+    pub fn get_processes(&self) -> Result<Vec<Process>> {
+        let output = self.section(Section::Processes)?;
+        if output.contains("PS_ERROR") {
+            anyhow::bail!("Process list not readable");
+        }
+
+        Ok(output
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(4, '\t');
+                Some(Process {
+                    pid: fields.next()?.parse().ok()?,
+                    user: fields.next()?.to_string(),
+                    exe: fields.next()?.to_string(),
+                    command: fields.next().unwrap_or_default().trim_end().to_string(),
+                })
+            })
+            .collect())
+    }
+
+    /// Crontab entries (system and per-user) and systemd timers.
+    pub fn get_scheduled_jobs(&self) -> Result<Vec<ScheduledJob>> {
+        let output = self.section(Section::ScheduledJobs)?;
+        if output.contains("CRON_ERROR") {
+            anyhow::bail!("Scheduled jobs not readable");
+        }
+
+        let mut jobs = Vec::new();
+        for line in output.lines() {
+            if let Some(entry) = line.strip_prefix("CRON ") {
+                if let Some((source, line)) = entry.split_once('\t') {
+                    jobs.extend(scheduled_jobs::parse_cron_line(source, line));
+                }
+            } else if let Some(entry) = line.strip_prefix("TIMER ") {
+                let mut fields = entry.splitn(4, '\t');
+                let (Some(timer), Some(schedule), Some(unit)) = (fields.next(), fields.next(), fields.next()) else {
+                    continue;
+                };
+                let command = match fields.next().map(str::trim).filter(|exec| !exec.is_empty()) {
+                    Some(exec) => format!("{} ({})", unit, exec),
+                    None => unit.to_string(),
+                };
+                jobs.push(ScheduledJob {
+                    kind: ScheduledJobKind::Timer,
+                    source: timer.to_string(),
+                    user: None,
+                    schedule: schedule.to_string(),
+                    command,
+                });
+            }
+        }
+
+        Ok(jobs)
     }
 
     pub fn get_wireguard_status(&self) -> Result<Option<WireGuardStatus>> {
-        let output = self.run_command("sudo wg show 2>/dev/null || echo 'WG_ERROR'")?;
+        let output = self.section(Section::WireGuard)?;
 
         if output.contains("WG_ERROR") || output.trim().is_empty() {
             return Ok(None);
@@ -161,19 +1206,19 @@ impl SshClient {
                     latest_handshake: None,
                     transfer: None,
                 });
-            } else if line.starts_with("  endpoint:") {
+            } else if line.starts_with("endpoint:") {
                 if let Some(ref mut peer) = current_peer {
-                    peer.endpoint = Some(line.split(':').nth(1).unwrap_or("unknown").trim().to_string());
+                    peer.endpoint = line.split_once(':').map(|(_, endpoint)| endpoint.trim().to_string());
                 }
-            } else if line.starts_with("  allowed ips:") {
+            } else if line.starts_with("allowed ips:") {
                 if let Some(ref mut peer) = current_peer {
                     peer.allowed_ips = line.split(':').nth(1).unwrap_or("unknown").trim().to_string();
                 }
-            } else if line.starts_with("  latest handshake:") {
+            } else if line.starts_with("latest handshake:") {
                 if let Some(ref mut peer) = current_peer {
                     peer.latest_handshake = Some(line.split(':').nth(1).unwrap_or("unknown").trim().to_string());
                 }
-            } else if line.starts_with("  transfer:") {
+            } else if line.starts_with("transfer:") {
                 if let Some(ref mut peer) = current_peer {
                     peer.transfer = Some(line.split(':').nth(1).unwrap_or("unknown").trim().to_string());
                 }
@@ -194,79 +1239,1066 @@ impl SshClient {
     }
 
     pub fn get_open_ports(&self) -> Result<Vec<Port>> {
-        let output = self.run_command("ss -tulpn | grep LISTEN | head -20")?;
+        let output = self.section(Section::OpenPorts)?;
         
         let mut ports = Vec::new();
         for line in output.lines() {
-            if let Some(port_str) = line.split(':').nth(1) {
-                if let Some(port_part) = port_str.split_whitespace().next() {
-                    if let Ok(port) = port_part.parse::<u16>() {
-                        let protocol = line.split_whitespace().next()
-                            .unwrap_or("unknown")
-                            .to_string();
-                        
-                        let process = line
-                            .split("users:(\"")
-                            .nth(1)
-                            .and_then(|s| s.split('"').next())
-                            .unwrap_or("unknown")
-                            .to_string();
-
-                        ports.push(Port { port, protocol, process });
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            // Protocol, local address and process, by tool:
+            //   ss:       tcp LISTEN 0 128 0.0.0.0:22 0.0.0.0:* users:(("sshd",pid=1,fd=3))
+            //             udp UNCONN 0 0 0.0.0.0:51820 0.0.0.0:*
+            //   sockstat: SOCKSTAT root sshd 812 4 tcp4 *:22 *:*
+            //   netstat:  NETSTAT tcp 0 0 0.0.0.0:22 0.0.0.0:* LISTEN 812/sshd
+            //             NETSTAT udp 0 0 0.0.0.0:53 0.0.0.0:* 640/dnsmasq
+            let (protocol, local, process) = match fields.first() {
+                Some(&"SOCKSTAT") => (fields.get(5), fields.get(6), fields.get(2).map(|p| p.to_string())),
+                // TCP sockets other than listeners are connections, not services
+                Some(&"NETSTAT") if fields.get(1).is_some_and(|p| p.starts_with("tcp")) && fields.get(6) != Some(&"LISTEN") => continue,
+                Some(&"NETSTAT") => (
+                    fields.get(1),
+                    fields.get(4),
+                    fields.last().and_then(|p| p.split_once('/')).map(|(_, name)| name.to_string()),
+                ),
+                _ if !matches!(fields.get(1), Some(&"LISTEN") | Some(&"UNCONN")) => continue,
+                _ => (
+                    fields.first(),
+                    fields.get(4),
+                    line.split("users:(\"").nth(1).and_then(|s| s.split('"').next()).map(|p| p.to_string()),
+                ),
+            };
+            // Local address column, e.g. 0.0.0.0:22, [::]:445 or 127.0.0.1%lo:53
+            let Some((address, port_part)) = local.and_then(|local| local.rsplit_once(':')) else {
+                continue;
+            };
+            if let Ok(port) = port_part.parse::<u16>() {
+                // tcp4/tcp6 from sockstat and netstat
+                let protocol = protocol.map(|p| p.trim_end_matches(['4', '6'])).unwrap_or("unknown").to_string();
+                let process = process.unwrap_or_else(|| "unknown".to_string());
+
+                let (address, interface) = match address.split_once('%') {
+                    Some((address, interface)) => (address, Some(interface.to_string())),
+                    None => (address, None),
+                };
+                let address = address.trim_matches(['[', ']']).to_string();
+                ports.push(Port { port, protocol, process, address, interface, exposure: None });
+            }
+        }
+
+        Ok(ports)
+    }
+
+    pub fn get_load_average(&self) -> Result<Option<LoadAverage>> {
+        let output = self.section(Section::Load)?;
+
+        let mut lines = output.lines();
+        let averages: Vec<f64> = lines
+            .next()
+            .unwrap_or_default()
+            .split_whitespace()
+            .take(3)
+            .filter_map(|v| v.parse().ok())
+            .collect();
+        let cores = lines.next().and_then(|n| n.trim().parse().ok());
+
+        Ok(match (averages.as_slice(), cores) {
+            ([one, five, fifteen], Some(cores)) => Some(LoadAverage {
+                one: *one,
+                five: *five,
+                fifteen: *fifteen,
+                cores,
+            }),
+            _ => None,
+        })
+    }
+
+    /// Local filesystems with their space and inode usage.
+    pub fn get_filesystems(&self) -> Result<Vec<Filesystem>> {
+        let output = self.section(Section::Filesystems)?;
+
+        // SPACE /dev/sda1 41152736 30123456 9116896 77% /
+        let parse = |line: &str| -> Option<(String, String, u64, Option<u8>)> {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let mountpoint = fields.get(5..)?.join(" ");
+            let percent = fields[4].trim_end_matches('%').parse().ok();
+            Some((mountpoint, fields[0].to_string(), fields[1].parse().unwrap_or(0), percent))
+        };
+
+        let mut filesystems: Vec<Filesystem> = Vec::new();
+        for line in output.lines() {
+            if let Some((mountpoint, device, kib, used_percent)) = line.strip_prefix("SPACE ").and_then(parse) {
+                // Bind mounts show the same device twice
+                if mountpoint.is_empty() || filesystems.iter().any(|fs| fs.mountpoint == mountpoint) {
+                    continue;
+                }
+                filesystems.push(Filesystem {
+                    mountpoint,
+                    device,
+                    size_bytes: kib * 1024,
+                    used_percent: used_percent.unwrap_or(0),
+                    inodes_used_percent: None,
+                });
+            } else if let Some((mountpoint, _, inodes, used_percent)) = line.strip_prefix("INODES ").and_then(parse) {
+                if let Some(fs) = filesystems.iter_mut().find(|fs| fs.mountpoint == mountpoint) {
+                    fs.inodes_used_percent = used_percent.filter(|_| inodes > 0);
+                }
+            }
+        }
+
+        Ok(filesystems)
+    }
+
+    /// Swap usage and activity; `None` when unreadable.
+    pub fn get_swap(&self) -> Result<Option<Swap>> {
+        let output = self.section(Section::Swap)?;
+        let kib = |name: &str| {
+            output
+                .lines()
+                .find_map(|line| line.strip_prefix(name))
+                .and_then(|value| value.split_whitespace().next())
+                .and_then(|value| value.parse::<u64>().ok())
+        };
+        let (Some(total), Some(free)) = (kib("SwapTotal:"), kib("SwapFree:")) else {
+            return Ok(None);
+        };
+
+        // pswpin/pswpout before and after the one-second sleep
+        let counters: Vec<u64> = output
+            .lines()
+            .filter(|line| line.starts_with("pswp"))
+            .filter_map(|line| line.split_whitespace().nth(1)?.parse().ok())
+            .collect();
+        let pages_per_sec = match counters.as_slice() {
+            [in_before, out_before, in_after, out_after] => {
+                Some((in_after + out_after).saturating_sub(in_before + out_before))
+            }
+            _ => None,
+        };
+
+        Ok(Some(Swap { total_bytes: total * 1024, used_bytes: total.saturating_sub(free) * 1024, pages_per_sec }))
+    }
+
+    /// Temperatures and throttling; `None` when the host has no sensors.
+    pub fn get_thermal(&self) -> Result<Option<Thermal>> {
+        let output = self.section(Section::Sensors)?;
+        let mut thermal = Thermal { readings: Vec::new(), throttling: Vec::new(), throttled_since_boot: Vec::new(), throttle_count: None };
+
+        for line in output.lines() {
+            if let Some(zone) = line.strip_prefix("ZONE ") {
+                // ZONE x86_pkg_temp 54000 (millidegrees)
+                if let Some((name, millis)) = zone.rsplit_once(' ') {
+                    if let Ok(millis) = millis.trim().parse::<f64>() {
+                        thermal.readings.push(TemperatureReading { name: name.to_string(), celsius: millis / 1000.0 });
+                    }
+                }
+            } else if let Some(json) = line.strip_prefix("LMSENSORS ") {
+                let Ok(serde_json::Value::Object(chips)) = serde_json::from_str(json) else {
+                    continue;
+                };
+                // {"coretemp-isa-0000": {"Adapter": "...", "Core 0": {"temp2_input": 52.0, ...}}}
+                for (chip, features) in &chips {
+                    let chip = chip.split('-').next().unwrap_or(chip);
+                    let Some(features) = features.as_object() else {
+                        continue;
+                    };
+                    for (feature, values) in features {
+                        let input = values.as_object().and_then(|values| {
+                            values.iter().find(|(key, _)| key.starts_with("temp") && key.ends_with("_input"))
+                        });
+                        if let Some(celsius) = input.and_then(|(_, value)| value.as_f64()) {
+                            thermal.readings.push(TemperatureReading { name: format!("{}/{}", chip, feature), celsius });
+                        }
                     }
                 }
+            } else if let Some(vc) = line.strip_prefix("VCGENCMD ") {
+                // VCGENCMD temp=48.3'C throttled=0x50000
+                for field in vc.split_whitespace() {
+                    if let Some(temp) = field.strip_prefix("temp=") {
+                        if let Ok(celsius) = temp.trim_end_matches("'C").parse() {
+                            thermal.readings.push(TemperatureReading { name: "vcgencmd".to_string(), celsius });
+                        }
+                    } else if let Some(value) = field.strip_prefix("throttled=0x") {
+                        if let Ok(value) = u32::from_str_radix(value, 16) {
+                            (thermal.throttling, thermal.throttled_since_boot) = sensors::throttled_flags(value);
+                        }
+                    }
+                }
+            } else if let Some(count) = line.strip_prefix("THROTTLE_COUNT ") {
+                thermal.throttle_count = count.trim().parse().ok();
             }
         }
 
-        Ok(ports)
+        let found = !thermal.readings.is_empty() || !thermal.throttled_since_boot.is_empty() || thermal.throttle_count.is_some();
+        Ok(found.then_some(thermal))
     }
 
     pub fn get_recent_errors(&self) -> Result<Vec<LogEntry>> {
-        let output = self.run_command("journalctl --since '24 hours ago' --priority err --no-pager | tail -50 2>/dev/null || echo 'JOURNALCTL_ERROR'")?;
+        let output = self.section(Section::RecentErrors)?;
 
         if output.contains("JOURNALCTL_ERROR") || output.trim().is_empty() {
             return Ok(Vec::new());
         }
 
-        let mut errors = Vec::new();
+        // Traditional syslog stamps are in the host's local time
+        let offset = output
+            .lines()
+            .find_map(|line| line.strip_prefix("SYSLOG_TZ "))
+            .and_then(|offset| parse_utc_offset(offset.trim()))
+            .unwrap_or(FixedOffset::east_opt(0).unwrap());
+
+        // One JSON object per journal entry, or a prefixed syslog line
+        let since = Utc::now() - self.logs.window;
+        Ok(output
+            .lines()
+            .filter_map(|line| match line.strip_prefix("SYSLOG ") {
+                Some(line) => parse_syslog_line(line, offset).filter(|entry| entry.timestamp >= since),
+                None => parse_journal_entry(&serde_json::from_str::<serde_json::Value>(line).ok()?),
+            })
+            .collect())
+    }
+
+    /// OOM kills, hung tasks and filesystem or I/O errors logged by the
+    /// kernel within the log window.
+    pub fn get_kernel_events(&self) -> Result<Vec<KernelEvent>> {
+        let output = self.section(Section::KernelLog)?;
+        let since = Utc::now() - self.logs.window;
+
+        Ok(output
+            .lines()
+            .filter_map(|line| match line.strip_prefix("DMESG ") {
+                // 2026-10-18T09:12:44,123456+00:00 Out of memory: Killed process ...
+                Some(line) => {
+                    let (timestamp, message) = line.split_once(' ')?;
+                    let timestamp = DateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S,%f%:z").ok()?.to_utc();
+                    kernel_events::classify(timestamp, message)
+                }
+                None => {
+                    let entry = parse_journal_entry(&serde_json::from_str(line).ok()?)?;
+                    kernel_events::classify(entry.timestamp, &entry.message)
+                }
+            })
+            .filter(|event| event.timestamp >= since)
+            .collect())
+    }
+
+    pub fn get_storage_arrays(&self) -> Result<Vec<StorageArray>> {
+        let mut arrays = self.list_md_arrays()?;
+        arrays.extend(self.list_zfs_pools()?);
+        Ok(arrays)
+    }
+
+    fn list_md_arrays(&self) -> Result<Vec<StorageArray>> {
+        let output = self.section(Section::Mdstat)?;
+
+        if output.contains("MDSTAT_ERROR") || output.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut arrays: Vec<StorageArray> = Vec::new();
         for line in output.lines() {
-            let parts: Vec<&str> = line.splitn(4, ' ').collect();
-            if parts.len() >= 4 {
-                errors.push(LogEntry {
-                    timestamp: parts[0].to_string(),
-                    service: parts.get(1).unwrap_or(&"unknown").to_string(),
-                    level: "err".to_string(),
-                    message: parts[2..].join(" "),
+            // md0 : active raid1 sdb1[1](F) sda1[0]
+            if line.starts_with("md") && line.contains(" : ") {
+                let mut parts = line.split_whitespace();
+                let name = parts.next().unwrap_or("unknown").to_string();
+                let rest: Vec<&str> = parts.skip(1).collect();
+                let state = rest.first().unwrap_or(&"unknown").to_string();
+                let level = rest.get(1).filter(|l| l.starts_with("raid")).map(|l| l.to_string());
+
+                let failed_devices = rest
+                    .iter()
+                    .filter(|d| d.ends_with("(F)"))
+                    .map(|d| d.split('[').next().unwrap_or(d).to_string())
+                    .collect::<Vec<_>>();
+
+                arrays.push(StorageArray {
+                    name,
+                    kind: "mdraid".to_string(),
+                    level,
+                    degraded: state != "active" || !failed_devices.is_empty(),
+                    state,
+                    failed_devices,
                 });
+            } else if let Some(array) = arrays.last_mut() {
+                // 1953382464 blocks super 1.2 [2/1] [U_]
+                if let Some(members) = line.split_whitespace().last() {
+                    if members.starts_with('[') && members.contains('_') {
+                        array.degraded = true;
+                    }
+                }
             }
         }
 
-        Ok(errors)
+        for array in arrays.iter_mut().filter(|a| a.degraded) {
+            let detail = self
+                .run_command(&self.sudo(&format!("mdadm --detail /dev/{} 2>/dev/null", array.name)))
+                .unwrap_or_default();
+
+            for line in detail.lines() {
+                let line = line.trim();
+                if let Some(state) = line.strip_prefix("State :") {
+                    array.state = state.trim().to_string();
+                } else if line.contains("faulty") || line.contains("removed") {
+                    if let Some(device) = line.split_whitespace().last() {
+                        let device = device.to_string();
+                        if device.starts_with('/') && !array.failed_devices.iter().any(|d| device.ends_with(d.as_str())) {
+                            array.failed_devices.push(device);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(arrays)
     }
 
-    fn run_command(&self, command: &str) -> Result<String> {
-        let result = Command::new("ssh")
-            .args([
-                "-o", "StrictHostKeyChecking=no",
-                "-o", "ConnectTimeout=30",
-                "-o", "ServerAliveInterval=60",
-                "-i", &self.host.identity_file,
-                "-p", &self.host.port.to_string(),
-                &format!("{}@{}", self.host.user, self.host.ip),
-                command,
-            ])
+    fn list_zfs_pools(&self) -> Result<Vec<StorageArray>> {
+        let output = self.section(Section::Zpool)?;
+
+        if output.contains("ZPOOL_ERROR") || output.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut pools: Vec<StorageArray> = Vec::new();
+        let mut in_config = false;
+        for line in output.lines() {
+            let trimmed = line.trim();
+
+            if let Some(name) = trimmed.strip_prefix("pool:") {
+                in_config = false;
+                pools.push(StorageArray {
+                    name: name.trim().to_string(),
+                    kind: "zfs".to_string(),
+                    level: None,
+                    state: "unknown".to_string(),
+                    degraded: false,
+                    failed_devices: Vec::new(),
+                });
+            } else if let Some(pool) = pools.last_mut() {
+                if let Some(state) = trimmed.strip_prefix("state:") {
+                    pool.state = state.trim().to_string();
+                    pool.degraded = pool.state != "ONLINE";
+                } else if trimmed.starts_with("NAME") && trimmed.contains("STATE") {
+                    in_config = true;
+                } else if trimmed.starts_with("errors:") || trimmed.is_empty() {
+                    in_config = false;
+                } else if in_config {
+                    // <vdev> <state> <read> <write> <cksum>
+                    let parts: Vec<&str> = trimmed.split_whitespace().collect();
+                    if parts.len() >= 2 && parts[0] != pool.name {
+                        if pool.level.is_none() && (parts[0].starts_with("mirror") || parts[0].starts_with("raidz")) {
+                            pool.level = Some(parts[0].split('-').next().unwrap_or(parts[0]).to_string());
+                        }
+                        let is_vdev = parts[0].starts_with("mirror") || parts[0].starts_with("raidz");
+                        if !is_vdev && parts[1] != "ONLINE" {
+                            pool.failed_devices.push(format!("{} ({})", parts[0], parts[1]));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(pools)
+    }
+
+    pub fn get_certificates(&self) -> Result<Vec<ManagedCertificate>> {
+        let mut certificates = self.list_letsencrypt_certificates()?;
+        certificates.extend(self.list_acme_json_certificates()?);
+        Ok(certificates)
+    }
+
+    fn list_letsencrypt_certificates(&self) -> Result<Vec<ManagedCertificate>> {
+        let output = self.section(Section::LetsEncrypt)?;
+
+        if output.contains("LE_ERROR") || output.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let renewer_active = output.lines().any(|l| l.trim() == "RENEWER_OK");
+        let mut certificates: Vec<ManagedCertificate> = Vec::new();
+
+        for line in output.lines() {
+            let line = line.trim();
+            if let Some(name) = line.strip_prefix("CERT ") {
+                certificates.push(ManagedCertificate {
+                    name: name.to_string(),
+                    source: "letsencrypt".to_string(),
+                    subject: String::new(),
+                    expires: None,
+                    auto_renew: false,
+                });
+            } else if let Some(cert) = certificates.last_mut() {
+                if let Some(subject) = line.strip_prefix("subject=") {
+                    cert.subject = subject.trim().to_string();
+                } else if let Some(date) = line.strip_prefix("notAfter=") {
+                    cert.expires = parse_openssl_date(date);
+                } else if line == "RENEWAL_OK" {
+                    cert.auto_renew = renewer_active;
+                }
+            }
+        }
+
+        Ok(certificates)
+    }
+
+    fn list_acme_json_certificates(&self) -> Result<Vec<ManagedCertificate>> {
+        let output = self.section(Section::AcmeJson)?;
+
+        if output.contains("ACME_ERROR") || output.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut certificates: Vec<ManagedCertificate> = Vec::new();
+        let mut source = String::new();
+
+        for line in output.lines() {
+            let line = line.trim();
+            if let Some(path) = line.strip_prefix("ACME ") {
+                source = format!("traefik:{}", path);
+            } else if let Some(subject) = line.strip_prefix("subject=") {
+                let subject = subject.trim().to_string();
+                certificates.push(ManagedCertificate {
+                    name: subject
+                        .rsplit("CN")
+                        .next()
+                        .map(|cn| cn.trim_start_matches([' ', '=']).to_string())
+                        .unwrap_or_else(|| subject.clone()),
+                    source: source.clone(),
+                    subject,
+                    expires: None,
+                    // Traefik renews its own certificates while running
+                    auto_renew: true,
+                });
+            } else if let Some(date) = line.strip_prefix("notAfter=") {
+                if let Some(cert) = certificates.last_mut() {
+                    cert.expires = parse_openssl_date(date);
+                }
+            }
+        }
+
+        Ok(certificates)
+    }
+
+    pub fn get_traefik_inventory(&self, api_url: &str) -> Result<TraefikInventory> {
+        let routers = self.traefik_api(api_url, "http/routers")?;
+        let services = self.traefik_api(api_url, "http/services")?;
+
+        let routers = routers
+            .iter()
+            .map(|r| {
+                let rule = json_str(r, "rule");
+                TraefikRouter {
+                    name: json_str(r, "name"),
+                    domains: parse_rule_domains(&rule),
+                    rule,
+                    service: json_str(r, "service"),
+                    status: json_str(r, "status"),
+                }
+            })
+            .collect();
+
+        let services = services
+            .iter()
+            .map(|s| {
+                let server_status = s.get("serverStatus");
+                let servers = s
+                    .pointer("/loadBalancer/servers")
+                    .and_then(|v| v.as_array())
+                    .map(|servers| {
+                        servers
+                            .iter()
+                            .map(|server| {
+                                let url = json_str(server, "url");
+                                TraefikServer {
+                                    status: server_status
+                                        .and_then(|st| st.get(&url))
+                                        .and_then(|st| st.as_str())
+                                        .map(|st| st.to_string()),
+                                    url,
+                                }
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                TraefikService {
+                    name: json_str(s, "name"),
+                    status: json_str(s, "status"),
+                    servers,
+                }
+            })
+            .collect();
+
+        Ok(TraefikInventory { routers, services })
+    }
+
+    fn traefik_api(&self, api_url: &str, endpoint: &str) -> Result<Vec<serde_json::Value>> {
+        let output = self.run_command(&format!(
+            "curl -sf --max-time 10 '{}/{}' 2>/dev/null || echo 'TRAEFIK_API_ERROR'",
+            api_url.trim_end_matches('/').replace('\'', "'\\''"),
+            endpoint
+        ))?;
+
+        if output.contains("TRAEFIK_API_ERROR") {
+            anyhow::bail!("Traefik API not reachable at {}", api_url);
+        }
+
+        Ok(serde_json::from_str(&output)?)
+    }
+
+    pub fn get_postgres_status(&self) -> DatabaseStatus {
+        let query = "SELECT pg_is_in_recovery(), \
+            (SELECT count(*) FROM pg_stat_activity), \
+            CASE WHEN pg_is_in_recovery() THEN \
+                CASE WHEN pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() THEN 0 \
+                ELSE extract(epoch FROM now() - pg_last_xact_replay_timestamp()) END END, \
+            (SELECT coalesce(max(extract(epoch FROM now() - xact_start)), 0) FROM pg_stat_activity WHERE xact_start IS NOT NULL), \
+            (SELECT status FROM pg_stat_wal_receiver)";
+
+        let mut status = DatabaseStatus {
+            engine: "postgresql".to_string(),
+            connected: false,
+            error: None,
+            is_replica: false,
+            replication_lag_secs: None,
+            replicating: None,
+            connections: None,
+            oldest_transaction_secs: None,
+        };
+
+        match self.run_command(&format!(
+            "cd /tmp && {}",
+            self.sudo_as(Some("postgres"), &format!("psql -tA -c \"{}\"", query))
+        )) {
+            Ok(output) => {
+                // t|12|3.5|0|streaming
+                // The lag is 0 once everything received is replayed, so an idle
+                // primary doesn't make its replica look behind
+                let fields: Vec<&str> = output.trim().split('|').collect();
+                status.connected = true;
+                status.is_replica = fields.first() == Some(&"t");
+                status.connections = fields.get(1).and_then(|v| v.parse().ok());
+                status.replication_lag_secs = fields.get(2).and_then(|v| v.parse().ok());
+                status.oldest_transaction_secs = fields.get(3).and_then(|v| v.parse().ok());
+                if status.is_replica {
+                    status.replicating = Some(fields.get(4) == Some(&"streaming"));
+                }
+            }
+            Err(e) => status.error = Some(e.to_string().trim().to_string()),
+        }
+
+        status
+    }
+
+    pub fn get_mysql_status(&self) -> DatabaseStatus {
+        let query = "SELECT \
+            (SELECT VARIABLE_VALUE FROM performance_schema.global_status WHERE VARIABLE_NAME = 'Threads_connected'), \
+            (SELECT coalesce(max(timestampdiff(SECOND, trx_started, now())), 0) FROM information_schema.innodb_trx)";
+
+        let mut status = DatabaseStatus {
+            engine: "mysql".to_string(),
+            connected: false,
+            error: None,
+            is_replica: false,
+            replication_lag_secs: None,
+            replicating: None,
+            connections: None,
+            oldest_transaction_secs: None,
+        };
+
+        match self.run_command(&self.sudo(&format!("mysql -N -B -e \"{}\"", query))) {
+            Ok(output) => {
+                let fields: Vec<&str> = output.split_whitespace().collect();
+                status.connected = true;
+                status.connections = fields.first().and_then(|v| v.parse().ok());
+                status.oldest_transaction_secs = fields.get(1).and_then(|v| v.parse().ok());
+            }
+            Err(e) => {
+                status.error = Some(e.to_string().trim().to_string());
+                return status;
+            }
+        }
+
+        // SHOW SLAVE STATUS was removed in MySQL 8.4, older MariaDB lacks SHOW REPLICA STATUS
+        let replica = self
+            .run_command(&format!(
+                "{} || {}",
+                self.sudo("mysql -e 'SHOW REPLICA STATUS\\G' 2>/dev/null"),
+                self.sudo("mysql -e 'SHOW SLAVE STATUS\\G' 2>/dev/null")
+            ))
+            .unwrap_or_default();
+
+        for line in replica.lines() {
+            let line = line.trim();
+            if line.starts_with("Seconds_Behind_Source:") || line.starts_with("Seconds_Behind_Master:") {
+                status.is_replica = true;
+                // NULL while the replication threads are stopped
+                status.replication_lag_secs = line.split(':').nth(1).and_then(|v| v.trim().parse().ok());
+                status.replicating = Some(status.replication_lag_secs.is_some());
+            }
+        }
+
+        status
+    }
+
+    /// Probes Redis on the host, or inside `container` when it runs in Docker.
+    pub fn get_redis_status(&self, container: Option<&str>) -> DataStoreStatus {
+        let cli = |args: &str| match container {
+            Some(name) => self.sudo(&format!("docker exec {} redis-cli {}", name, args)),
+            None => format!("redis-cli {}", args),
+        };
+
+        let mut status = DataStoreStatus {
+            engine: "redis".to_string(),
+            healthy: false,
+            error: None,
+            memory_used: None,
+            keys: None,
+            replication: None,
+        };
+
+        match self.run_command(&cli("ping")) {
+            Ok(output) if output.trim() == "PONG" => status.healthy = true,
+            Ok(output) => {
+                status.error = Some(output.trim().to_string());
+                return status;
+            }
+            Err(e) => {
+                status.error = Some(e.to_string().trim().to_string());
+                return status;
+            }
+        }
+
+        let info = self.run_command(&cli("INFO")).unwrap_or_default();
+        let mut role = None;
+        let mut link_status = None;
+        let mut replicas = None;
+
+        for line in info.lines() {
+            let Some((key, value)) = line.trim().split_once(':') else { continue };
+            match key {
+                "used_memory_human" => status.memory_used = Some(value.to_string()),
+                "role" => role = Some(value.to_string()),
+                "master_link_status" => link_status = Some(value.to_string()),
+                "connected_slaves" => replicas = Some(value.to_string()),
+                // db0:keys=12,expires=0,avg_ttl=0
+                _ if key.starts_with("db") => {
+                    let keys = value
+                        .split(',')
+                        .find_map(|kv| kv.strip_prefix("keys="))
+                        .and_then(|k| k.parse::<u64>().ok())
+                        .unwrap_or(0);
+                    status.keys = Some(status.keys.unwrap_or(0) + keys);
+                }
+                _ => {}
+            }
+        }
+
+        status.replication = match role.as_deref() {
+            Some("slave") => Some(format!("replica (link {})", link_status.unwrap_or_else(|| "unknown".to_string()))),
+            Some("master") => Some(format!("master ({} replicas)", replicas.unwrap_or_else(|| "0".to_string()))),
+            _ => None,
+        };
+        if status.keys.is_none() && !info.is_empty() {
+            status.keys = Some(0);
+        }
+
+        status
+    }
+
+    pub fn get_couchdb_status(&self) -> DataStoreStatus {
+        let mut status = DataStoreStatus {
+            engine: "couchdb".to_string(),
+            healthy: false,
+            error: None,
+            memory_used: None,
+            keys: None,
+            replication: None,
+        };
+
+        match self.run_command(&format!("curl -s --max-time 10 {}/_up", COUCHDB_URL)) {
+            Ok(output) => match serde_json::from_str::<serde_json::Value>(&output) {
+                Ok(body) if body.get("status").and_then(|s| s.as_str()) == Some("ok") => status.healthy = true,
+                Ok(body) => status.error = Some(format!("_up returned {}", body)),
+                Err(_) => status.error = Some(format!("unexpected _up response: {}", output.trim())),
+            },
+            Err(e) => status.error = Some(e.to_string().trim().to_string()),
+        }
+
+        status
+    }
+
+    pub fn get_samba_status(&self) -> Result<SambaStatus> {
+        let output = self.run_command(&format!(
+            "testparm -s 2>/dev/null; echo '@@ADDRESSES'; ip -o addr show; \
+            echo '@@SOCKETS'; {} | grep -E 'smbd|nmbd' || true",
+            self.sudo("ss -tulnpH")
+        ))?;
+
+        let mut section = "config";
+        let mut current_share: Option<String> = None;
+        let mut shares: Vec<SambaShare> = Vec::new();
+        let mut interfaces = Vec::new();
+        let mut bind_interfaces_only = false;
+        let mut addresses: Vec<(String, String)> = Vec::new();
+        let mut smbd_bindings = Vec::new();
+        let mut nmbd_bindings = Vec::new();
+
+        for line in output.lines() {
+            let line = line.trim();
+            match line {
+                "@@ADDRESSES" => section = "addresses",
+                "@@SOCKETS" => section = "sockets",
+                _ if section == "config" => {
+                    if line.starts_with('[') && line.ends_with(']') {
+                        let name = line.trim_matches(['[', ']']).to_string();
+                        if name != "global" {
+                            shares.push(SambaShare { name: name.clone(), path: None, comment: None });
+                        }
+                        current_share = Some(name);
+                    } else if let Some((key, value)) = line.split_once('=') {
+                        let (key, value) = (key.trim(), value.trim().to_string());
+                        match (current_share.as_deref(), key) {
+                            (Some("global"), "interfaces") => {
+                                interfaces = value.split_whitespace().map(|i| i.to_string()).collect();
+                            }
+                            (Some("global"), "bind interfaces only") => {
+                                bind_interfaces_only = matches!(value.as_str(), "Yes" | "yes" | "true");
+                            }
+                            (Some(_), "path") => {
+                                if let Some(share) = shares.last_mut() {
+                                    share.path = Some(value);
+                                }
+                            }
+                            (Some(_), "comment") => {
+                                if let Some(share) = shares.last_mut() {
+                                    share.comment = Some(value);
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                // 3: wg0    inet 10.10.10.1/24 scope global wg0
+                _ if section == "addresses" => {
+                    let parts: Vec<&str> = line.split_whitespace().collect();
+                    if parts.len() >= 4 && parts[2].starts_with("inet") {
+                        let ip = parts[3].split('/').next().unwrap_or_default().to_string();
+                        addresses.push((parts[1].to_string(), ip));
+                    }
+                }
+                // udp UNCONN 0 0 10.10.10.1:137 0.0.0.0:* users:(("nmbd",pid=1,fd=1))
+                _ if section == "sockets" => {
+                    let Some(local) = line.split_whitespace().nth(4) else { continue };
+                    let address = local.rsplit_once(':').map(|(a, _)| a).unwrap_or(local).to_string();
+                    let bindings = if line.contains("smbd") { &mut smbd_bindings } else { &mut nmbd_bindings };
+                    if !bindings.contains(&address) {
+                        bindings.push(address);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Interfaces may be given as names (wg0), addresses or CIDRs
+        let wildcard = |b: &String| b == "0.0.0.0" || b == "*" || b == "[::]";
+        let unbound_interfaces = interfaces
+            .iter()
+            .filter(|iface| {
+                let expected: Vec<String> = if let Some(ip) = iface.split('/').next().filter(|ip| ip.parse::<std::net::IpAddr>().is_ok()) {
+                    vec![ip.to_string()]
+                } else {
+                    addresses
+                        .iter()
+                        .filter(|(name, _)| name == *iface)
+                        .map(|(_, ip)| ip.clone())
+                        .collect()
+                };
+                !expected.is_empty()
+                    && !smbd_bindings
+                        .iter()
+                        .any(|b| wildcard(b) || expected.iter().any(|ip| b.trim_matches(['[', ']']) == ip))
+            })
+            .cloned()
+            .collect();
+
+        Ok(SambaStatus {
+            shares,
+            interfaces,
+            bind_interfaces_only,
+            smbd_bindings,
+            nmbd_bindings,
+            unbound_interfaces,
+            anonymous_listing: None,
+        })
+    }
+
+    /// Runs an anonymous `smbclient -L` against the host from the scanner machine.
+    pub fn list_samba_shares_anonymously(&self) -> SambaListing {
+        let result = Command::new("smbclient")
+            .args(["-L", &format!("//{}", self.host.ip), "-N", "-g"])
             .output();
 
         match result {
             Ok(output) => {
-                if output.status.success() {
-                    Ok(String::from_utf8_lossy(&output.stdout).to_string())
-                } else {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    anyhow::bail!("Command failed: {}", stderr)
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                // Disk|share|comment
+                let shares: Vec<String> = stdout
+                    .lines()
+                    .filter(|l| l.starts_with("Disk|") || l.starts_with("IPC|") || l.starts_with("Printer|"))
+                    .filter_map(|l| l.split('|').nth(1).map(|s| s.to_string()))
+                    .collect();
+                let error = stdout
+                    .lines()
+                    .chain(String::from_utf8_lossy(&output.stderr).lines())
+                    .find(|l| l.contains("NT_STATUS_"))
+                    .map(|l| l.trim().to_string());
+
+                SambaListing {
+                    // An access denied answer still proves smbd is reachable
+                    reachable: output.status.success()
+                        || error.as_deref().map(|e| e.contains("NT_STATUS_ACCESS_DENIED") || e.contains("NT_STATUS_LOGON_FAILURE")).unwrap_or(false),
+                    shares,
+                    error,
                 }
             }
-            Err(e) => anyhow::bail!("Failed to execute SSH command: {}", e),
+            Err(e) => SambaListing {
+                reachable: false,
+                shares: Vec::new(),
+                error: Some(format!("Failed to execute smbclient: {}", e)),
+            },
+        }
+    }
+
+    /// Runs a user-supplied script through `sh -c` and returns its stdout
+    /// whatever the exit status, since check scripts report through their
+    /// output. Times out like any other check named `check`.
+    pub fn run_script(&self, check: &str, script: &str, privileged: bool) -> Result<String> {
+        let command = format!("sh -c '{}'", script.replace('\'', "'\\''"));
+        let command = if privileged { self.sudo(&command) } else { command };
+
+        let mut ssh = self.ssh_command(30);
+        ssh.arg(command);
+        let output = self.output(&mut ssh, check, self.limits.timeouts.for_check(check))?;
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Regular files on the host matching `patterns`, globs expanded remotely
+    /// (with sudo when configured, so root-only directories are listed too).
+    pub fn list_files(&self, patterns: &[String]) -> Result<Vec<String>> {
+        let script = format!("for f in {}; do [ -f \"$f\" ] && echo \"$f\"; done; true", patterns.join(" "));
+        let command = self.sudo(&format!("sh -c '{}'", script.replace('\'', "'\\''")));
+        let output = self.run_command_within(&command, "archive", self.limits.timeouts.for_check("archive"))?;
+        Ok(output.lines().map(str::to_string).collect())
+    }
+
+    /// SHA-256, mode and owner of the regular files matching `patterns`
+    /// (read with sudo when configured).
+    pub fn hash_files(&self, patterns: &[String]) -> Result<Vec<FileFingerprint>> {
+        let script = format!(
+            "for f in {}; do [ -f \"$f\" ] || continue; \
+             h=$(sha256sum \"$f\" 2>/dev/null | cut -d\" \" -f1); \
+             printf \"%s %s %s\\n\" \"${{h:--}}\" \"$(stat -c %a:%U:%G \"$f\")\" \"$f\"; done; true",
+            patterns.join(" ")
+        );
+        let command = self.sudo(&format!("sh -c '{}'", script.replace('\'', "'\\''")));
+        let output = self.run_command_within(&command, "integrity", self.limits.timeouts.for_check("integrity"))?;
+
+        Ok(output
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.splitn(3, ' ');
+                let (hash, stat, path) = (parts.next()?, parts.next()?, parts.next()?);
+                let (mode, owner) = stat.split_once(':')?;
+                Some(FileFingerprint {
+                    path: path.to_string(),
+                    sha256: (hash != "-").then(|| hash.to_string()),
+                    mode: mode.to_string(),
+                    owner: owner.to_string(),
+                })
+            })
+            .collect())
+    }
+
+    /// SUID/SGID binaries under `setuid_paths` and world-writable files
+    /// under `writable_paths`, each searched without crossing filesystems.
+    pub fn find_special_files(&self, setuid_paths: &[String], writable_paths: &[String]) -> Result<Vec<SpecialFile>> {
+        let script = format!(
+            "find {} -xdev -type f \\( -perm -4000 -o -perm -2000 \\) -printf \"%m %u:%g %p\\n\" 2>/dev/null; \
+             echo WRITABLE; \
+             find {} -xdev -type f -perm -0002 -printf \"%m %u:%g %p\\n\" 2>/dev/null; true",
+            setuid_paths.join(" "),
+            writable_paths.join(" ")
+        );
+        let command = self.sudo(&format!("sh -c '{}'", script.replace('\'', "'\\''")));
+        let output = self.run_command_within(&command, "suid", self.limits.timeouts.for_check("suid"))?;
+
+        let mut files = Vec::new();
+        let mut writable = false;
+        for line in output.lines() {
+            if line == "WRITABLE" {
+                writable = true;
+                continue;
+            }
+            let mut parts = line.splitn(3, ' ');
+            let (Some(mode), Some(owner), Some(path)) = (parts.next(), parts.next(), parts.next()) else {
+                continue;
+            };
+            let kind = if writable {
+                SpecialFileKind::WorldWritable
+            } else if u32::from_str_radix(mode, 8).is_ok_and(|m| m & 0o4000 != 0) {
+                SpecialFileKind::Setuid
+            } else {
+                SpecialFileKind::Setgid
+            };
+            files.push(SpecialFile { path: path.to_string(), kind, mode: mode.to_string(), owner: owner.to_string() });
+        }
+        Ok(files)
+    }
+
+    /// Starts an iperf3 server on `port` that exits after serving one client,
+    /// or after `lifetime` if no client comes.
+    pub fn start_iperf_server(&self, port: u16, lifetime: Duration) -> Result<()> {
+        let command = format!(
+            "command -v iperf3 >/dev/null 2>&1 || {{ echo 'iperf3 not installed' >&2; exit 1; }}; \
+            nohup timeout {} iperf3 -s -1 -p {} >/dev/null 2>&1 </dev/null & sleep 1",
+            lifetime.as_secs(),
+            port
+        );
+        self.run_command_within(&command, "bandwidth", self.limits.timeouts.for_check("bandwidth"))?;
+        Ok(())
+    }
+
+    /// Throughput in bits per second from this host to the iperf3 server at
+    /// `server`, over `secs` seconds.
+    pub fn iperf_throughput(&self, server: &str, port: u16, secs: u64) -> Result<f64> {
+        // iperf3 reports its own failures in the JSON, with a non-zero status
+        let command = format!("iperf3 -c {} -p {} -t {} -J 2>&1; true", server, port, secs);
+        let timeout = self.limits.timeouts.for_check("bandwidth") + Duration::from_secs(secs);
+        let output = self.run_command_within(&command, "bandwidth", timeout)?;
+
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(&output) else {
+            anyhow::bail!("{}", output.trim());
+        };
+        if let Some(error) = json["error"].as_str() {
+            anyhow::bail!("{}", error);
+        }
+        json["end"]["sum_received"]["bits_per_second"].as_f64().context("iperf3 reported no throughput")
+    }
+
+    /// Copies `remote` to `local` over SFTP. When SFTP fails (typically a
+    /// root-only file) and `sudo_fallback` is set, the file is read with
+    /// `sudo cat` instead. Returns the size and whether sudo was needed.
+    pub fn fetch_file(&self, remote: &str, local: &Path, sudo_fallback: bool) -> Result<(u64, bool)> {
+        // Archived files include private keys (WireGuard configs), so they
+        // are never readable by other users, not even while downloading
+        if let Some(parent) = local.parent() {
+            std::fs::DirBuilder::new()
+                .recursive(true)
+                .mode(0o700)
+                .create(parent)
+                .context(format!("Failed to create {}", parent.display()))?;
+        }
+        let open_private = || -> Result<std::fs::File> {
+            let file = std::fs::OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .mode(0o600)
+                .open(local)
+                .context(format!("Failed to write {}", local.display()))?;
+            // The mode only applies when the file is created
+            file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+            Ok(file)
+        };
+        // sftp keeps the mode of a file that already exists
+        open_private()?;
+
+        let timeout = self.limits.timeouts.for_check("archive");
+        let output = self.output(&mut self.sftp_command(remote, local), "archive", timeout)?;
+        if output.status.success() {
+            return Ok((std::fs::metadata(local)?.len(), false));
+        }
+        let sftp_error = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if !sudo_fallback {
+            anyhow::bail!("SFTP failed: {}", sftp_error);
+        }
+
+        let mut ssh = self.ssh_command(30);
+        ssh.arg(self.sudo(&format!("cat '{}'", remote.replace('\'', "'\\''"))));
+        let output = self.output(&mut ssh, "archive", timeout)?;
+        if !output.status.success() {
+            anyhow::bail!("SFTP failed ({}), sudo cat failed: {}", sftp_error, String::from_utf8_lossy(&output.stderr).trim());
+        }
+        open_private()?.write_all(&output.stdout)?;
+        Ok((output.stdout.len() as u64, true))
+    }
+
+    fn run_command(&self, command: &str) -> Result<String> {
+        self.run_command_within(command, "command", Duration::from_secs(self.limits.timeouts.check_secs))
+    }
+
+    /// Runs `command` on the host, failing with [`CheckTimedOut`] when it
+    /// exceeds `timeout` or the scan deadline.
+    fn run_command_within(&self, command: &str, check: &str, timeout: Duration) -> Result<String> {
+        let mut ssh = self.ssh_command(30);
+        ssh.arg(command);
+
+        let output = self.output(&mut ssh, check, timeout)?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("Command failed: {}", stderr)
+        }
+    }
+
+    /// Runs a local command to completion within `timeout` (capped by the scan
+    /// deadline), killing it and failing with [`CheckTimedOut`] on expiry or
+    /// [`CheckCancelled`] when the scan is interrupted.
+    fn output(&self, command: &mut Command, check: &str, timeout: Duration) -> Result<Output> {
+        let timeout = match self.limits.deadline {
+            Some(deadline) => timeout.min(deadline.saturating_duration_since(Instant::now())),
+            None => timeout,
+        };
+        let timed_out = || CheckTimedOut { check: check.to_string(), after: timeout };
+        if timeout.is_zero() {
+            return Err(timed_out().into());
+        }
+
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to execute SSH command")?;
+
+        // Drain the pipes on threads so a chatty command can't block on a full pipe
+        let stdout = child.stdout.take().map(read_to_end_in_background);
+        let stderr = child.stderr.take().map(read_to_end_in_background);
+
+        let started = Instant::now();
+        loop {
+            if let Some(status) = child.try_wait()? {
+                let collect = |reader: Option<std::thread::JoinHandle<Vec<u8>>>| {
+                    reader.and_then(|r| r.join().ok()).unwrap_or_default()
+                };
+                return Ok(Output {
+                    status,
+                    stdout: collect(stdout),
+                    stderr: collect(stderr),
+                });
+            }
+            if self.limits.cancel.is_cancelled() {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(CheckCancelled { check: check.to_string() }.into());
+            }
+            if started.elapsed() >= timeout {
+                let _ = child.kill();
+                let _ = child.wait();
+                // Readers are left detached: a backgrounded ControlMaster may still hold the pipes
+                return Err(timed_out().into());
+            }
+            std::thread::sleep(Duration::from_millis(50));
         }
     }
 
@@ -274,3 +2306,135 @@ impl SshClient {
         self.hostname().is_ok()
     }
 }
+
+/// Position and exit status of `section`'s end marker in `line`. Output that
+/// merely mentions a marker, like the batch script in a process listing, has
+/// no numeric status and doesn't count.
+fn end_marker(line: &str, section: Section) -> Option<(usize, i32)> {
+    let marker = format!("{}{} ", SECTION_END, section.name());
+    let pos = line.rfind(&marker)?;
+    let status = line[pos + marker.len()..].trim().parse().ok()?;
+    Some((pos, status))
+}
+
+fn read_to_end_in_background<R: Read + Send + 'static>(mut reader: R) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = reader.read_to_end(&mut buf);
+        buf
+    })
+}
+
+/// Parses dates as printed by `openssl x509 -enddate`, e.g. `Mar  1 12:00:00 2025 GMT`.
+fn parse_openssl_date(date: &str) -> Option<DateTime<Utc>> {
+    let normalized = date.split_whitespace().collect::<Vec<_>>().join(" ");
+    NaiveDateTime::parse_from_str(&normalized, "%b %d %H:%M:%S %Y GMT")
+        .ok()
+        .map(|dt| dt.and_utc())
+}
+
+/// Builds a log entry from a `journalctl -o json` record, whose fields are
+/// all strings except for non-UTF-8 messages, sent as byte arrays.
+fn parse_journal_entry(entry: &serde_json::Value) -> Option<LogEntry> {
+    let micros: i64 = entry["__REALTIME_TIMESTAMP"].as_str()?.parse().ok()?;
+    let message = match &entry["MESSAGE"] {
+        serde_json::Value::String(message) => message.clone(),
+        serde_json::Value::Array(bytes) => {
+            let bytes: Vec<u8> = bytes.iter().filter_map(|b| b.as_u64()).map(|b| b as u8).collect();
+            String::from_utf8_lossy(&bytes).into_owned()
+        }
+        _ => return None,
+    };
+    let service = entry["_SYSTEMD_UNIT"]
+        .as_str()
+        .or(entry["SYSLOG_IDENTIFIER"].as_str())
+        .or(entry["_COMM"].as_str())
+        .unwrap_or("unknown");
+
+    Some(LogEntry {
+        timestamp: DateTime::from_timestamp_micros(micros)?,
+        service: service.to_string(),
+        priority: entry["PRIORITY"].as_str().and_then(|p| p.parse().ok()).unwrap_or(3),
+        message: message.trim_end().to_string(),
+    })
+}
+
+/// Builds a log entry from a syslog file line, either traditional
+/// (`Oct 18 05:39:54 host sshd[812]: message`, without a year) or RFC 3339
+/// (`2026-10-18T05:39:54.123+00:00 host sshd[812]: message`). Times without
+/// an offset are in the host's local time, `offset` from UTC.
+fn parse_syslog_line(line: &str, offset: FixedOffset) -> Option<LogEntry> {
+    let (timestamp, rest) = match line.split_once(' ') {
+        Some((first, rest)) if first.contains('T') => (DateTime::parse_from_rfc3339(first).ok()?.to_utc(), rest),
+        _ => {
+            // "Oct  18 05:39:54" pads single-digit days with a space
+            let stamp = line.get(..15)?.split_whitespace().collect::<Vec<_>>().join(" ");
+            let now = Utc::now();
+            let parse = |year: i32| {
+                NaiveDateTime::parse_from_str(&format!("{} {}", year, stamp), "%Y %b %d %H:%M:%S")
+                    .ok()
+                    .and_then(|t| t.and_local_timezone(offset).single())
+                    .map(|t| t.to_utc())
+            };
+            // Lines from late December read in January belong to last year
+            let timestamp = parse(now.year())
+                .filter(|t| *t <= now + chrono::Duration::days(1))
+                .or_else(|| parse(now.year() - 1))?;
+            (timestamp, line.get(15..)?.trim_start())
+        }
+    };
+
+    // host program[pid]: message
+    let (_, rest) = rest.split_once(' ')?;
+    let (program, message) = rest.split_once(": ").unwrap_or(("unknown", rest));
+    let service = program.split('[').next().unwrap_or(program);
+
+    Some(LogEntry {
+        timestamp,
+        service: service.to_string(),
+        priority: 3,
+        message: message.trim_end().to_string(),
+    })
+}
+
+/// Offset from UTC as printed by `date +%z`, e.g. `-0300`.
+fn parse_utc_offset(offset: &str) -> Option<FixedOffset> {
+    let sign = match offset.get(..1)? {
+        "+" => 1,
+        "-" => -1,
+        _ => return None,
+    };
+    let hours: i32 = offset.get(1..3)?.parse().ok()?;
+    let minutes: i32 = offset.get(3..5)?.parse().ok()?;
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// A container from a [`CONTAINER_FORMAT`] line, e.g.
+/// `traefik|Up 3 hours|traefik:v3.0|0.0.0.0:443->443/tcp`.
+fn parse_container_line(line: &str) -> Option<Container> {
+    let mut fields = line.splitn(4, '|');
+    let name = fields.next().filter(|name| !name.trim().is_empty())?;
+    let status = fields.next()?;
+    Some(Container {
+        name: name.trim().to_string(),
+        status: status.to_string(),
+        image: fields.next().unwrap_or_default().to_string(),
+        ports: fields.next().unwrap_or_default().trim().to_string(),
+        runtime: None,
+    })
+}
+
+fn json_str(value: &serde_json::Value, key: &str) -> String {
+    value.get(key).and_then(|v| v.as_str()).unwrap_or("unknown").to_string()
+}
+
+/// Extracts the domains from a Traefik rule such as ``Host(`a.com`) || Host(`b.com`)``.
+fn parse_rule_domains(rule: &str) -> Vec<String> {
+    rule.split("Host(")
+        .skip(1)
+        .flat_map(|part| part.split(')').next())
+        .flat_map(|hosts| hosts.split(','))
+        .map(|host| host.trim().trim_matches('`').to_string())
+        .filter(|host| !host.is_empty())
+        .collect()
+}
@@ -0,0 +1,223 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// A parsed OpenSSH client config, with `Include` directives expanded inline.
+///
+/// Options are resolved like ssh does: blocks are evaluated top to bottom
+/// and the first value obtained for each keyword wins.
+pub struct SshConfig {
+    blocks: Vec<Block>,
+}
+
+struct Block {
+    criteria: Criteria,
+    options: Vec<(String, String)>,
+}
+
+enum Criteria {
+    /// Options before the first Host/Match line apply to every host.
+    Global,
+    Host(Vec<String>),
+    Match(Vec<(bool, String, Vec<String>)>),
+}
+
+impl SshConfig {
+    pub fn load(path: &str) -> Result<Self> {
+        let path = PathBuf::from(shellexpand::tilde(path).to_string());
+        let base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+        let mut lines = Vec::new();
+        read_with_includes(&path, &base_dir, 0, &mut lines)?;
+
+        let mut blocks = vec![Block { criteria: Criteria::Global, options: Vec::new() }];
+        for line in lines {
+            let Some((key, value)) = split_directive(&line) else { continue };
+
+            match key.as_str() {
+                "host" => blocks.push(Block {
+                    criteria: Criteria::Host(value.split_whitespace().map(|p| p.to_string()).collect()),
+                    options: Vec::new(),
+                }),
+                "match" => blocks.push(Block {
+                    criteria: Criteria::Match(parse_match(&value)),
+                    options: Vec::new(),
+                }),
+                _ => {
+                    if let Some(block) = blocks.last_mut() {
+                        block.options.push((key, value));
+                    }
+                }
+            }
+        }
+
+        Ok(Self { blocks })
+    }
+
+    /// Concrete host aliases declared in `Host` lines, skipping wildcard and
+    /// negated patterns, in declaration order.
+    pub fn host_aliases(&self) -> Vec<String> {
+        let mut aliases: Vec<String> = Vec::new();
+        for block in &self.blocks {
+            if let Criteria::Host(ref patterns) = block.criteria {
+                for pattern in patterns {
+                    let concrete = !pattern.contains(['*', '?']) && !pattern.starts_with('!');
+                    if concrete && !aliases.contains(pattern) {
+                        aliases.push(pattern.clone());
+                    }
+                }
+            }
+        }
+        aliases
+    }
+
+    /// Resolves every option for `alias`, keyed by lowercase keyword.
+    pub fn resolve(&self, alias: &str) -> HashMap<String, String> {
+        let mut options: HashMap<String, String> = HashMap::new();
+
+        for block in &self.blocks {
+            let applies = match block.criteria {
+                Criteria::Global => true,
+                Criteria::Host(ref patterns) => host_matches(alias, patterns),
+                Criteria::Match(ref criteria) => match_applies(alias, &options, criteria),
+            };
+
+            if applies {
+                for (key, value) in &block.options {
+                    options.entry(key.clone()).or_insert_with(|| value.clone());
+                }
+            }
+        }
+
+        options
+    }
+}
+
+fn read_with_includes(path: &Path, base_dir: &Path, depth: usize, lines: &mut Vec<String>) -> Result<()> {
+    if depth > MAX_INCLUDE_DEPTH {
+        anyhow::bail!("Include nesting too deep at {}", path.display());
+    }
+
+    let content = std::fs::read_to_string(path)
+        .context(format!("Failed to read SSH config: {}", path.display()))?;
+
+    for line in content.lines() {
+        match split_directive(line) {
+            Some((key, value)) if key == "include" => {
+                for pattern in value.split_whitespace() {
+                    let expanded = shellexpand::tilde(pattern).to_string();
+                    // Relative includes are resolved against ~/.ssh
+                    let full = if Path::new(&expanded).is_absolute() {
+                        PathBuf::from(expanded)
+                    } else {
+                        base_dir.join(expanded)
+                    };
+
+                    let mut matches: Vec<PathBuf> = glob::glob(&full.to_string_lossy())
+                        .map(|paths| paths.filter_map(|p| p.ok()).collect())
+                        .unwrap_or_default();
+                    matches.sort();
+
+                    for included in matches.iter().filter(|p| p.is_file()) {
+                        read_with_includes(included, base_dir, depth + 1, lines)?;
+                    }
+                }
+            }
+            Some(_) => lines.push(line.to_string()),
+            None => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Splits `Keyword value` or `Keyword=value`, ignoring comments and blanks.
+fn split_directive(line: &str) -> Option<(String, String)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let split_at = line.find(|c: char| c.is_whitespace() || c == '=')?;
+    let key = line[..split_at].to_lowercase();
+    let value = line[split_at..]
+        .trim_start_matches(|c: char| c.is_whitespace() || c == '=')
+        .trim()
+        .trim_matches('"')
+        .to_string();
+
+    Some((key, value))
+}
+
+fn parse_match(value: &str) -> Vec<(bool, String, Vec<String>)> {
+    let mut criteria = Vec::new();
+    let mut tokens = value.split_whitespace();
+
+    while let Some(token) = tokens.next() {
+        let (negated, keyword) = match token.strip_prefix('!') {
+            Some(keyword) => (true, keyword.to_lowercase()),
+            None => (false, token.to_lowercase()),
+        };
+
+        let args = match keyword.as_str() {
+            "all" | "canonical" | "final" => Vec::new(),
+            _ => tokens
+                .next()
+                .map(|arg| arg.split(',').map(|a| a.to_string()).collect())
+                .unwrap_or_default(),
+        };
+        criteria.push((negated, keyword, args));
+    }
+
+    criteria
+}
+
+/// Evaluates a `Match` line. Criteria we cannot evaluate offline (`exec`,
+/// `localnetwork`, ...) are treated as not matching.
+fn match_applies(alias: &str, resolved: &HashMap<String, String>, criteria: &[(bool, String, Vec<String>)]) -> bool {
+    criteria.iter().all(|(negated, keyword, args)| {
+        let result = match keyword.as_str() {
+            "all" | "final" => true,
+            "canonical" => false,
+            "originalhost" => host_matches(alias, args),
+            "host" => host_matches(resolved.get("hostname").map(|h| h.as_str()).unwrap_or(alias), args),
+            "user" => resolved.get("user").map(|u| host_matches(u, args)).unwrap_or(false),
+            "localuser" => std::env::var("USER").map(|u| host_matches(&u, args)).unwrap_or(false),
+            _ => false,
+        };
+        result != *negated
+    })
+}
+
+/// ssh pattern-list semantics: any positive match and no negated match.
+fn host_matches(name: &str, patterns: &[String]) -> bool {
+    let mut matched = false;
+    for pattern in patterns {
+        match pattern.strip_prefix('!') {
+            Some(negated) if wildcard_match(negated, name) => return false,
+            Some(_) => {}
+            None => matched |= wildcard_match(pattern, name),
+        }
+    }
+    matched
+}
+
+/// Case-insensitive match of `name` against a pattern with `*` and `?`.
+pub fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    fn matches(p: &[char], n: &[char]) -> bool {
+        match (p.first(), n.first()) {
+            (None, None) => true,
+            (Some('*'), _) => matches(&p[1..], n) || (!n.is_empty() && matches(p, &n[1..])),
+            (Some('?'), Some(_)) => matches(&p[1..], &n[1..]),
+            (Some(pc), Some(nc)) if pc.eq_ignore_ascii_case(nc) => matches(&p[1..], &n[1..]),
+            _ => false,
+        }
+    }
+
+    matches(&pattern, &name)
+}
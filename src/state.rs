@@ -0,0 +1,44 @@
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// `[state]` section: where what one scan remembers for the next one is kept
+/// (integrity baselines, boot times, capability cache, open incidents).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StateConfig {
+    pub dir: String,
+}
+
+impl Default for StateConfig {
+    fn default() -> Self {
+        Self {
+            dir: "~/.cache/securepenguin".to_string(),
+        }
+    }
+}
+
+impl StateConfig {
+    /// `file` in the state directory; an absolute or `~` path is kept as is.
+    pub fn path(&self, file: &str) -> PathBuf {
+        PathBuf::from(shellexpand::tilde(&self.dir).to_string()).join(shellexpand::tilde(file).as_ref())
+    }
+}
+
+/// Reads a JSON state file; a missing file is an empty state, as on the
+/// first scan. `what` names the state in error messages.
+pub fn load<T: DeserializeOwned + Default>(path: &Path, what: &str) -> Result<T> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    let content = std::fs::read_to_string(path).context(format!("Failed to read {}: {}", what, path.display()))?;
+    serde_json::from_str(&content).context(format!("Failed to parse {}: {}", what, path.display()))
+}
+
+pub fn save<T: Serialize>(path: &Path, what: &str, state: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).context(format!("Failed to create {} directory", what))?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(state)?).context(format!("Failed to write {}: {}", what, path.display()))
+}
@@ -0,0 +1,168 @@
+use crate::models::{CheckResult, InventoryReport, Issue, Severity};
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, Utc};
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SuppressionsConfig {
+    pub enabled: bool,
+    /// TOML file with `[[suppressions]]` entries, re-read on every scan.
+    pub file: String,
+}
+
+impl Default for SuppressionsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            file: "~/.config/securepenguin/suppressions.toml".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SuppressionsFile {
+    #[serde(default)]
+    suppressions: Vec<Suppression>,
+}
+
+/// An accepted finding. It matches issues by fingerprint, or by any
+/// combination of host, check and a substring of the message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Suppression {
+    pub fingerprint: Option<String>,
+    pub host: Option<String>,
+    pub check: Option<String>,
+    pub pattern: Option<String>,
+    pub reason: Option<String>,
+    /// Last day the suppression applies, as a TOML date (`expires = 2026-12-31`).
+    #[serde(default, deserialize_with = "toml_date")]
+    pub expires: Option<NaiveDate>,
+}
+
+fn toml_date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<NaiveDate>, D::Error> {
+    match Option::<toml::value::Datetime>::deserialize(deserializer)? {
+        Some(date) => date.to_string().parse().map(Some).map_err(de::Error::custom),
+        None => Ok(None),
+    }
+}
+
+/// A suppression in effect during a scan, with the issues it hid.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveSuppression {
+    #[serde(flatten)]
+    pub suppression: Suppression,
+    pub suppressed: Vec<Issue>,
+}
+
+impl Suppression {
+    fn is_empty(&self) -> bool {
+        self.fingerprint.is_none() && self.host.is_none() && self.check.is_none() && self.pattern.is_none()
+    }
+
+    fn matches(&self, issue: &Issue) -> bool {
+        self.fingerprint.as_ref().is_none_or(|f| *f == issue.fingerprint())
+            && self.host.as_ref().is_none_or(|h| issue.host.as_ref() == Some(h))
+            && self.check.as_ref().is_none_or(|c| *c == issue.check)
+            && self.pattern.as_ref().is_none_or(|p| issue.message.contains(p.as_str()))
+    }
+
+    /// Short description of what the suppression matches.
+    pub fn criteria(&self) -> String {
+        let criteria: Vec<String> = [
+            self.fingerprint.as_ref().map(|f| format!("`{}`", f)),
+            self.host.clone(),
+            self.check.clone(),
+            self.pattern.as_ref().map(|p| format!("\"{}\"", p)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        criteria.join(" / ")
+    }
+}
+
+pub fn load(config: &SuppressionsConfig) -> Result<Vec<Suppression>> {
+    let path = shellexpand::tilde(&config.file).to_string();
+    let content = std::fs::read_to_string(&path).context(format!("Failed to read suppressions: {}", path))?;
+    let file: SuppressionsFile = toml::from_str(&content).context(format!("Failed to parse suppressions: {}", path))?;
+    Ok(file.suppressions)
+}
+
+/// Moves issues matched by an unexpired suppression out of the report's issue
+/// lists and clears them from the host check outcomes, so they neither show
+/// up as findings nor page anyone.
+pub fn apply(config: &SuppressionsConfig, report: &mut InventoryReport) {
+    if !config.enabled {
+        return;
+    }
+
+    let suppressions = match load(config) {
+        Ok(suppressions) => suppressions,
+        Err(e) => {
+            report
+                .warnings
+                .push(Issue::new(Severity::Warning, None, "suppressions", format!("Suppressions not applied: {:#}", e)));
+            return;
+        }
+    };
+
+    let today = Utc::now().date_naive();
+    let mut active = Vec::new();
+    for suppression in suppressions {
+        if suppression.is_empty() {
+            report.warnings.push(Issue::new(
+                Severity::Warning,
+                None,
+                "suppressions",
+                "Suppression without fingerprint, host, check or pattern ignored".to_string(),
+            ));
+            continue;
+        }
+        if let Some(expires) = suppression.expires.filter(|expires| *expires < today) {
+            report.notices.push(Issue::new(
+                Severity::Info,
+                None,
+                "suppressions",
+                format!("Suppression {} expired on {}", suppression.criteria(), expires),
+            ));
+            continue;
+        }
+
+        let mut suppressed = Vec::new();
+        for list in [&mut report.critical_issues, &mut report.warnings, &mut report.notices] {
+            let (matching, kept): (Vec<Issue>, Vec<Issue>) = list.drain(..).partition(|issue| suppression.matches(issue));
+            *list = kept;
+            suppressed.extend(matching);
+        }
+        active.push(ActiveSuppression { suppression, suppressed });
+    }
+
+    for vm in &mut report.vms {
+        for check in &mut vm.checks {
+            if !matches!(check.result, CheckResult::Failed | CheckResult::Warning) {
+                continue;
+            }
+            check.messages.retain(|message| {
+                !active.iter().flat_map(|a| &a.suppressed).any(|issue| {
+                    issue.host.as_ref() == Some(&vm.host.name) && issue.check == check.name && issue.message == *message
+                })
+            });
+            let still_critical = check.messages.iter().any(|message| {
+                report
+                    .critical_issues
+                    .iter()
+                    .any(|issue| issue.host.as_ref() == Some(&vm.host.name) && issue.message == *message)
+            });
+            check.result = if still_critical {
+                CheckResult::Failed
+            } else if !check.messages.is_empty() {
+                CheckResult::Warning
+            } else {
+                CheckResult::Passed
+            };
+        }
+    }
+
+    report.suppressions = active;
+}
@@ -0,0 +1,139 @@
+use anyhow::{Context, Result};
+use opentelemetry::metrics::{Counter, Histogram};
+use opentelemetry::trace::TracerProvider;
+use opentelemetry::{global, KeyValue};
+use opentelemetry_otlp::{MetricExporter, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::metrics::SdkMeterProvider;
+use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+use serde::{Deserialize, Serialize};
+
+/// `[telemetry]` section: export traces and metrics over OTLP/HTTP (Tempo,
+/// Jaeger, an OpenTelemetry Collector...).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    pub enabled: bool,
+    /// Base OTLP/HTTP endpoint; `/v1/traces` and `/v1/metrics` are appended.
+    pub endpoint: String,
+    pub service_name: String,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "http://localhost:4318".to_string(),
+            service_name: "sp-inventory".to_string(),
+        }
+    }
+}
+
+/// Exporters for the lifetime of the process; dropping flushes what is
+/// still buffered, so keep it alive until the scan results are out.
+pub struct Telemetry {
+    tracer_provider: SdkTracerProvider,
+    meter_provider: SdkMeterProvider,
+}
+
+impl Telemetry {
+    pub fn init(config: &TelemetryConfig) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+
+        let endpoint = config.endpoint.trim_end_matches('/');
+        let resource = Resource::builder().with_service_name(config.service_name.clone()).build();
+
+        let spans = SpanExporter::builder()
+            .with_http()
+            .with_endpoint(format!("{}/v1/traces", endpoint))
+            .build()
+            .context("Failed to create OTLP span exporter")?;
+        let tracer_provider = SdkTracerProvider::builder()
+            .with_batch_exporter(spans)
+            .with_resource(resource.clone())
+            .build();
+
+        let metrics = MetricExporter::builder()
+            .with_http()
+            .with_endpoint(format!("{}/v1/metrics", endpoint))
+            .build()
+            .context("Failed to create OTLP metric exporter")?;
+        let meter_provider = SdkMeterProvider::builder()
+            .with_periodic_exporter(metrics)
+            .with_resource(resource)
+            .build();
+        global::set_meter_provider(meter_provider.clone());
+
+        Ok(Some(Self { tracer_provider, meter_provider }))
+    }
+
+    pub fn tracer(&self) -> SdkTracer {
+        self.tracer_provider.tracer(env!("CARGO_CRATE_NAME"))
+    }
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        let _ = self.tracer_provider.shutdown();
+        let _ = self.meter_provider.shutdown();
+    }
+}
+
+/// Scan instruments. Without `[telemetry]` they record into the global no-op
+/// meter, so callers never need to check whether export is enabled.
+pub struct Metrics {
+    scan_duration: Histogram<f64>,
+    host_duration: Histogram<f64>,
+    check_duration: Histogram<f64>,
+    check_failures: Counter<u64>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let meter = global::meter(env!("CARGO_CRATE_NAME"));
+        Self {
+            scan_duration: meter
+                .f64_histogram("sp_inventory.scan.duration")
+                .with_unit("s")
+                .with_description("Duration of a full inventory scan")
+                .build(),
+            host_duration: meter
+                .f64_histogram("sp_inventory.host.duration")
+                .with_unit("s")
+                .with_description("Time spent scanning one host")
+                .build(),
+            check_duration: meter
+                .f64_histogram("sp_inventory.check.duration")
+                .with_unit("s")
+                .with_description("Time spent collecting one check on one host")
+                .build(),
+            check_failures: meter
+                .u64_counter("sp_inventory.check.failures")
+                .with_description("Checks that ended in a failed verdict")
+                .build(),
+        }
+    }
+
+    pub fn scan(&self, secs: f64, partial: bool) {
+        self.scan_duration.record(secs, &[KeyValue::new("partial", partial)]);
+    }
+
+    pub fn host(&self, host: &str, secs: f64, reachable: bool) {
+        self.host_duration.record(
+            secs,
+            &[KeyValue::new("host", host.to_string()), KeyValue::new("reachable", reachable)],
+        );
+    }
+
+    pub fn check(&self, host: &str, check: &'static str, secs: f64) {
+        self.check_duration
+            .record(secs, &[KeyValue::new("host", host.to_string()), KeyValue::new("check", check)]);
+    }
+
+    pub fn check_failed(&self, host: &str, check: &str) {
+        self.check_failures
+            .add(1, &[KeyValue::new("host", host.to_string()), KeyValue::new("check", check.to_string())]);
+    }
+}
@@ -0,0 +1,45 @@
+use crate::models::InventoryReport;
+use anyhow::{Context, Result};
+use std::path::Path;
+use tera::Tera;
+use tracing::info;
+
+/// Entry point looked up in the template directory; other files there can
+/// be pulled in with `{% include %}` or `{% extends %}`.
+const ENTRY_TEMPLATE: &str = "report.md";
+
+/// Renders the report with user-supplied Tera templates, with the
+/// serialized `InventoryReport` as context.
+pub struct TemplateReporter {
+    tera: Tera,
+}
+
+impl TemplateReporter {
+    pub fn new(template_dir: &str) -> Result<Self> {
+        let dir = shellexpand::tilde(template_dir).to_string();
+        let glob = Path::new(&dir).join("**").join("*");
+        let tera = Tera::new(&glob.to_string_lossy())
+            .context(format!("Failed to load templates from {}", dir))?;
+
+        if !tera.get_template_names().any(|name| name == ENTRY_TEMPLATE) {
+            anyhow::bail!("Template directory {} has no {}", dir, ENTRY_TEMPLATE);
+        }
+
+        Ok(Self { tera })
+    }
+
+    pub fn generate_report(&self, report: &InventoryReport) -> Result<String> {
+        let context = tera::Context::from_serialize(report).context("Failed to build template context")?;
+        self.tera
+            .render(ENTRY_TEMPLATE, &context)
+            .map_err(|e| anyhow::anyhow!("Failed to render {}: {:?}", ENTRY_TEMPLATE, e))
+    }
+
+    pub fn save_report(&self, report: &InventoryReport, output_path: &str) -> Result<()> {
+        std::fs::write(output_path, self.generate_report(report)?)
+            .context(format!("Failed to create report file: {}", output_path))?;
+
+        info!("Reporte guardado en: {}", output_path);
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+use crate::models::TerraformResource;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Resource types that describe a machine we expect to find in the inventory.
+const INSTANCE_TYPES: &[&str] = &[
+    "google_compute_instance",
+    "hcloud_server",
+    "oci_core_instance",
+    "aws_instance",
+    "proxmox_vm_qemu",
+    "proxmox_lxc",
+    "digitalocean_droplet",
+];
+
+const IP_KEYS: &[&str] = &[
+    "ipv4_address",
+    "ipv6_address",
+    "public_ip",
+    "private_ip",
+    "network_ip",
+    "nat_ip",
+    "ip",
+    "default_ipv4_address",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TerraformConfig {
+    /// A `terraform.tfstate` file or the output of `terraform show -json`.
+    pub state_file: Option<String>,
+}
+
+/// Extracts the instance resources from a raw state file or `terraform show -json` output.
+pub fn load_resources(path: &str) -> Result<Vec<TerraformResource>> {
+    let path = shellexpand::tilde(path).to_string();
+    let content = std::fs::read_to_string(&path)
+        .context(format!("Failed to read terraform state: {}", path))?;
+    let state: serde_json::Value = serde_json::from_str(&content)
+        .context(format!("Failed to parse terraform state: {}", path))?;
+
+    let mut resources = Vec::new();
+
+    if let Some(root) = state.pointer("/values/root_module") {
+        collect_show_json(root, &mut resources);
+    } else {
+        for resource in state.get("resources").and_then(|r| r.as_array()).into_iter().flatten() {
+            let kind = resource.get("type").and_then(|t| t.as_str()).unwrap_or_default();
+            if resource.get("mode").and_then(|m| m.as_str()) != Some("managed") || !INSTANCE_TYPES.contains(&kind) {
+                continue;
+            }
+            let module = resource.get("module").and_then(|m| m.as_str()).map(|m| format!("{}.", m)).unwrap_or_default();
+            let name = resource.get("name").and_then(|n| n.as_str()).unwrap_or_default();
+
+            for (i, instance) in resource.get("instances").and_then(|i| i.as_array()).into_iter().flatten().enumerate() {
+                let mut address = format!("{}{}.{}", module, kind, name);
+                if let Some(key) = instance.get("index_key") {
+                    address.push_str(&format!("[{}]", key));
+                } else if i > 0 {
+                    address.push_str(&format!("[{}]", i));
+                }
+                if let Some(attributes) = instance.get("attributes") {
+                    resources.push(to_resource(address, kind, attributes));
+                }
+            }
+        }
+    }
+
+    Ok(resources)
+}
+
+fn collect_show_json(module: &serde_json::Value, resources: &mut Vec<TerraformResource>) {
+    for resource in module.get("resources").and_then(|r| r.as_array()).into_iter().flatten() {
+        let kind = resource.get("type").and_then(|t| t.as_str()).unwrap_or_default();
+        if resource.get("mode").and_then(|m| m.as_str()) != Some("managed") || !INSTANCE_TYPES.contains(&kind) {
+            continue;
+        }
+        let address = resource.get("address").and_then(|a| a.as_str()).unwrap_or_default().to_string();
+        if let Some(values) = resource.get("values") {
+            resources.push(to_resource(address, kind, values));
+        }
+    }
+
+    for child in module.get("child_modules").and_then(|c| c.as_array()).into_iter().flatten() {
+        collect_show_json(child, resources);
+    }
+}
+
+fn to_resource(address: String, kind: &str, attributes: &serde_json::Value) -> TerraformResource {
+    let name = ["name", "display_name", "hostname"]
+        .iter()
+        .find_map(|key| attributes.get(*key).and_then(|v| v.as_str()))
+        .or_else(|| attributes.pointer("/tags/Name").and_then(|v| v.as_str()))
+        .unwrap_or_default()
+        .to_string();
+
+    let mut ips = Vec::new();
+    collect_ips(attributes, &mut ips);
+
+    TerraformResource {
+        address,
+        kind: kind.to_string(),
+        name,
+        ips,
+        matched_host: None,
+        reachable: false,
+    }
+}
+
+/// IPs are nested differently per provider (e.g. GCP keeps them in
+/// `network_interface[].access_config[].nat_ip`), so walk the whole tree.
+fn collect_ips(value: &serde_json::Value, ips: &mut Vec<String>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                match value.as_str() {
+                    Some(ip) if IP_KEYS.contains(&key.as_str()) && ip.parse::<std::net::IpAddr>().is_ok() => {
+                        if !ips.iter().any(|known| known == ip) {
+                            ips.push(ip.to_string());
+                        }
+                    }
+                    _ => collect_ips(value, ips),
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter().for_each(|item| collect_ips(item, ips)),
+        _ => {}
+    }
+}
@@ -0,0 +1,310 @@
+use crate::models::{InventoryReport, ServiceStatus};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// `[influxdb]` section: per-scan measurements in line protocol, for
+/// long-term Grafana dashboards. With `database` set the InfluxDB 1.x
+/// `/write` API is used, otherwise the 2.x API with `org` and `bucket`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InfluxConfig {
+    pub enabled: bool,
+    pub url: String,
+    pub token: Option<String>,
+    pub org: String,
+    pub bucket: String,
+    pub database: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+impl Default for InfluxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: "http://localhost:8086".to_string(),
+            token: None,
+            org: "securepenguin".to_string(),
+            bucket: "inventory".to_string(),
+            database: None,
+            username: None,
+            password: None,
+        }
+    }
+}
+
+/// `[timescale]` section: the same measurements as rows of a narrow
+/// hypertable, written through `psql`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimescaleConfig {
+    pub enabled: bool,
+    /// libpq connection string, e.g. `postgresql://inventory@db/metrics`.
+    pub dsn: String,
+    /// Created as a hypertable on first write.
+    pub table: String,
+}
+
+impl Default for TimescaleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dsn: "postgresql://localhost/securepenguin".to_string(),
+            table: "inventory_metrics".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum FieldValue {
+    Float(f64),
+    Integer(i64),
+    Boolean(bool),
+}
+
+impl FieldValue {
+    fn as_f64(&self) -> f64 {
+        match *self {
+            FieldValue::Float(value) => value,
+            FieldValue::Integer(value) => value as f64,
+            FieldValue::Boolean(value) => u8::from(value) as f64,
+        }
+    }
+}
+
+/// One measurement at the time of the scan.
+#[derive(Debug, Clone)]
+pub struct Point {
+    pub measurement: &'static str,
+    pub tags: Vec<(&'static str, String)>,
+    pub fields: Vec<(&'static str, FieldValue)>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Point {
+    fn new(measurement: &'static str, timestamp: DateTime<Utc>) -> Self {
+        Self { measurement, tags: Vec::new(), fields: Vec::new(), timestamp }
+    }
+
+    fn tag(mut self, key: &'static str, value: &str) -> Self {
+        self.tags.push((key, value.to_string()));
+        self
+    }
+
+    fn field(mut self, key: &'static str, value: FieldValue) -> Self {
+        self.fields.push((key, value));
+        self
+    }
+
+    fn field_opt(self, key: &'static str, value: Option<FieldValue>) -> Self {
+        match value {
+            Some(value) => self.field(key, value),
+            None => self,
+        }
+    }
+
+    /// The point in InfluxDB line protocol, with a timestamp in seconds.
+    fn line(&self) -> String {
+        // Commas, spaces and equals signs in tags need a backslash
+        let escape = |s: &str| s.replace(',', "\\,").replace(' ', "\\ ").replace('=', "\\=");
+        let mut line = escape(self.measurement);
+        for (key, value) in &self.tags {
+            if !value.is_empty() {
+                line.push_str(&format!(",{}={}", key, escape(value)));
+            }
+        }
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(key, value)| match value {
+                FieldValue::Float(value) => format!("{}={}", key, value),
+                FieldValue::Integer(value) => format!("{}={}i", key, value),
+                FieldValue::Boolean(value) => format!("{}={}", key, value),
+            })
+            .collect();
+        format!("{} {} {}", line, fields.join(","), self.timestamp.timestamp())
+    }
+}
+
+/// Measurements of a scan: one `scan` point, one `host` point per host,
+/// one `filesystem` point per mountpoint and one `web` point per service.
+pub fn points(report: &InventoryReport) -> Vec<Point> {
+    let now = report.timestamp;
+    let mut points = vec![Point::new("scan", now)
+        .field("duration", FieldValue::Float(report.duration_secs))
+        .field("hosts", FieldValue::Integer(report.summary.total_vms as i64))
+        .field("reachable", FieldValue::Integer(report.summary.reachable_vms as i64))
+        .field("critical", FieldValue::Integer(report.critical_issues.len() as i64))
+        .field("warnings", FieldValue::Integer(report.warnings.len() as i64))];
+
+    for vm in &report.vms {
+        let group = vm.host.group.as_deref().unwrap_or_default();
+        let mut point = Point::new("host", now)
+            .tag("host", &vm.host.name)
+            .tag("group", group)
+            .field("reachable", FieldValue::Boolean(vm.reachable));
+        // An unreachable host has no fresh readings; a gap beats a zero
+        if vm.reachable {
+            point = point
+                .field("containers", FieldValue::Integer(vm.containers.len() as i64))
+                .field(
+                    "containers_up",
+                    FieldValue::Integer(vm.containers.iter().filter(|c| c.status.contains("Up")).count() as i64),
+                )
+                .field(
+                    "services_failed",
+                    FieldValue::Integer(vm.services.iter().filter(|s| s.status == ServiceStatus::Failed).count() as i64),
+                )
+                .field_opt("load1", vm.load.map(|load| FieldValue::Float(load.one)))
+                .field_opt("memory_percent", vm.memory.as_ref().map(|memory| FieldValue::Integer(memory.used_percent() as i64)))
+                .field_opt(
+                    "swap_percent",
+                    vm.swap.as_ref().and_then(|swap| swap.used_percent()).map(|percent| FieldValue::Integer(percent as i64)),
+                )
+                .field_opt("uptime", vm.uptime_secs.map(|secs| FieldValue::Integer(secs as i64)));
+        }
+        points.push(point);
+
+        for fs in &vm.filesystems {
+            points.push(
+                Point::new("filesystem", now)
+                    .tag("host", &vm.host.name)
+                    .tag("mountpoint", &fs.mountpoint)
+                    .field("used_percent", FieldValue::Integer(fs.used_percent as i64))
+                    .field_opt("inodes_percent", fs.inodes_used_percent.map(|p| FieldValue::Integer(p as i64))),
+            );
+        }
+    }
+
+    for service in &report.web_services {
+        points.push(
+            Point::new("web", now)
+                .tag("service", &service.name)
+                .field(
+                    "up",
+                    FieldValue::Boolean(service.error.is_none() && !service.unexpected_status && service.content_error.is_none()),
+                )
+                .field_opt("status", service.http_status.map(|s| FieldValue::Integer(s as i64)))
+                .field_opt("response_time", service.response_time.map(FieldValue::Float))
+                .field_opt("p95", service.latency.as_ref().and_then(|l| l.p95).map(FieldValue::Float))
+                .field_opt("error_rate", service.latency.as_ref().map(|l| FieldValue::Float(l.error_rate))),
+        );
+    }
+
+    points
+}
+
+pub struct InfluxWriter {
+    config: InfluxConfig,
+    client: Client,
+}
+
+impl InfluxWriter {
+    pub fn new(config: InfluxConfig) -> Result<Self> {
+        let client = Client::builder().timeout(Duration::from_secs(30)).build()?;
+        Ok(Self { config, client })
+    }
+
+    /// Writes the report's points and returns how many were sent.
+    pub async fn write(&self, report: &InventoryReport) -> Result<usize> {
+        let points = points(report);
+        let body: Vec<String> = points.iter().map(Point::line).collect();
+
+        let base = self.config.url.trim_end_matches('/');
+        let mut request = match self.config.database {
+            Some(ref database) => self
+                .client
+                .post(format!("{}/write", base))
+                .query(&[("db", database.as_str()), ("precision", "s")]),
+            None => self
+                .client
+                .post(format!("{}/api/v2/write", base))
+                .query(&[("org", self.config.org.as_str()), ("bucket", self.config.bucket.as_str()), ("precision", "s")]),
+        };
+        if let Some(ref token) = self.config.token {
+            request = request.header("Authorization", format!("Token {}", token));
+        } else if let Some(ref username) = self.config.username {
+            request = request.basic_auth(username, self.config.password.as_ref());
+        }
+
+        request
+            .body(body.join("\n"))
+            .send()
+            .await
+            .context(format!("Failed to reach InfluxDB at {}", self.config.url))?
+            .error_for_status()
+            .context("InfluxDB rejected the write")?;
+
+        Ok(points.len())
+    }
+}
+
+pub struct TimescaleWriter {
+    config: TimescaleConfig,
+}
+
+impl TimescaleWriter {
+    pub fn new(config: TimescaleConfig) -> Self {
+        Self { config }
+    }
+
+    /// Inserts one row per field into the hypertable, creating it when
+    /// missing, and returns the number of rows.
+    pub async fn write(&self, report: &InventoryReport) -> Result<usize> {
+        let table = &self.config.table;
+        if !table.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.') {
+            anyhow::bail!("Invalid Timescale table name {}", table);
+        }
+        let quote = |s: &str| format!("'{}'", s.replace('\'', "''"));
+
+        let mut rows = Vec::new();
+        for point in points(report) {
+            let tags = serde_json::Value::Object(
+                point.tags.iter().filter(|(_, v)| !v.is_empty()).map(|(k, v)| (k.to_string(), v.clone().into())).collect(),
+            );
+            for (field, value) in &point.fields {
+                rows.push(format!(
+                    "({}, {}, {}::jsonb, {}, {})",
+                    quote(&point.timestamp.to_rfc3339()),
+                    quote(point.measurement),
+                    quote(&tags.to_string()),
+                    quote(field),
+                    value.as_f64()
+                ));
+            }
+        }
+
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS {0} (time timestamptz NOT NULL, measurement text NOT NULL, tags jsonb NOT NULL, field text NOT NULL, value double precision);\n\
+             SELECT create_hypertable('{0}', 'time', if_not_exists => TRUE);\n\
+             INSERT INTO {0} (time, measurement, tags, field, value) VALUES\n{1};\n",
+            table,
+            rows.join(",\n")
+        );
+
+        let mut child = Command::new("psql")
+            .args([self.config.dsn.as_str(), "-q", "-v", "ON_ERROR_STOP=1", "-o", "/dev/null"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to run psql")?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(sql.as_bytes()).await.context("Failed to send rows to psql")?;
+        }
+        let output = tokio::time::timeout(Duration::from_secs(60), child.wait_with_output())
+            .await
+            .context("Timed out writing to Timescale")??;
+        if !output.status.success() {
+            anyhow::bail!("psql failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+
+        Ok(rows.len())
+    }
+}
@@ -0,0 +1,85 @@
+use crate::models::InventoryReport;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tracing::info;
+
+/// Where the time of a scan went, to find slow hosts and expensive checks.
+/// Written by `--profile-output` and summarized in the report appendix.
+#[derive(Debug, Serialize)]
+pub struct TimingProfile {
+    pub timestamp: DateTime<Utc>,
+    pub duration_secs: f64,
+    /// Scan-wide checks (web, cloud, proxmox, terraform, policy).
+    pub scan_checks: BTreeMap<String, f64>,
+    /// Slowest host first.
+    pub hosts: Vec<HostTiming>,
+    /// Most expensive check first, summed over every host.
+    pub checks: Vec<CheckTiming>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HostTiming {
+    pub host: String,
+    pub duration_secs: f64,
+    pub checks: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CheckTiming {
+    pub check: String,
+    pub total_secs: f64,
+    pub max_secs: f64,
+    pub slowest_host: String,
+}
+
+impl TimingProfile {
+    pub fn from_report(report: &InventoryReport) -> Self {
+        let mut hosts: Vec<HostTiming> = report
+            .vms
+            .iter()
+            .map(|vm| HostTiming {
+                host: vm.host.name.clone(),
+                duration_secs: vm.duration_secs,
+                checks: vm.timings.clone(),
+            })
+            .collect();
+        hosts.sort_by(|a, b| b.duration_secs.total_cmp(&a.duration_secs));
+
+        let mut checks: BTreeMap<&str, CheckTiming> = BTreeMap::new();
+        for host in &hosts {
+            for (check, secs) in &host.checks {
+                let timing = checks.entry(check).or_insert_with(|| CheckTiming {
+                    check: check.clone(),
+                    total_secs: 0.0,
+                    max_secs: 0.0,
+                    slowest_host: host.host.clone(),
+                });
+                timing.total_secs += secs;
+                if *secs > timing.max_secs {
+                    timing.max_secs = *secs;
+                    timing.slowest_host = host.host.clone();
+                }
+            }
+        }
+        let mut checks: Vec<CheckTiming> = checks.into_values().collect();
+        checks.sort_by(|a, b| b.total_secs.total_cmp(&a.total_secs));
+
+        Self {
+            timestamp: report.timestamp,
+            duration_secs: report.duration_secs,
+            scan_checks: report.timings.clone(),
+            hosts,
+            checks,
+        }
+    }
+
+    pub fn save(&self, output_path: &str) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(output_path, json).context(format!("Failed to write timing profile: {}", output_path))?;
+
+        info!("Perfil de tiempos guardado en: {}", output_path);
+        Ok(())
+    }
+}
@@ -0,0 +1,299 @@
+use crate::models::*;
+use anyhow::{Context, Result};
+use ipnet::IpNet;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::net::IpAddr;
+use tracing::info;
+
+/// WireGuard renegotiates keys every two minutes while a tunnel carries
+/// traffic, so an older handshake means the link is idle or down.
+const FRESH_HANDSHAKE_SECS: u64 = 180;
+
+/// Network diagram of a scan: hosts with their containers, WireGuard links
+/// between them and Traefik routes to the backends serving each domain.
+/// Rendered as a Mermaid block for the markdown report and as Graphviz DOT.
+pub struct Topology {
+    hosts: Vec<HostNode>,
+    /// Route and backend nodes outside any host, as (id, label).
+    nodes: Vec<(String, String)>,
+    edges: Vec<Edge>,
+}
+
+struct HostNode {
+    id: String,
+    label: String,
+    containers: Vec<(String, String)>,
+}
+
+struct Edge {
+    from: String,
+    to: String,
+    label: String,
+    kind: EdgeKind,
+}
+
+enum EdgeKind {
+    Tunnel { fresh: bool },
+    Route,
+}
+
+/// Node id made of `[A-Za-z0-9_]` only, which both formats accept unquoted.
+fn node_id(prefix: &str, parts: &[&str]) -> String {
+    let mut id = prefix.to_string();
+    for part in parts {
+        id.push('_');
+        id.extend(part.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }));
+    }
+    id
+}
+
+/// Seconds since a handshake as printed by `wg show`, e.g.
+/// `1 minute, 23 seconds ago`.
+fn handshake_age_secs(handshake: &str) -> Option<u64> {
+    if handshake.eq_ignore_ascii_case("now") {
+        return Some(0);
+    }
+    let mut secs = 0;
+    let words: Vec<&str> = handshake.split([' ', ',']).filter(|w| !w.is_empty()).collect();
+    for pair in words.chunks(2) {
+        let [count, unit] = pair else { break };
+        let Ok(count) = count.parse::<u64>() else { continue };
+        secs += count
+            * match unit.trim_end_matches('s') {
+                "second" => 1,
+                "minute" => 60,
+                "hour" => 3600,
+                "day" => 86400,
+                "year" => 365 * 86400,
+                _ => return None,
+            };
+    }
+    Some(secs)
+}
+
+fn short_age(secs: u64) -> String {
+    match secs {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86400 => format!("{}h", s / 3600),
+        s => format!("{}d", s / 86400),
+    }
+}
+
+fn parse_ip(address: &str) -> Option<IpAddr> {
+    address.trim_matches(['[', ']']).parse::<IpAddr>().ok().map(|ip| ip.to_canonical())
+}
+
+/// Address of a WireGuard endpoint, `ip:port` or `[ipv6]:port`.
+fn endpoint_ip(endpoint: &str) -> Option<IpAddr> {
+    match endpoint.parse::<std::net::SocketAddr>() {
+        Ok(address) => Some(address.ip().to_canonical()),
+        Err(_) => parse_ip(endpoint),
+    }
+}
+
+impl Topology {
+    pub fn from_report(report: &InventoryReport) -> Self {
+        let host_ips: Vec<(&VmStatus, Vec<IpAddr>)> = report
+            .vms
+            .iter()
+            .map(|vm| (vm, vm.host.addresses().into_iter().filter_map(|a| parse_ip(a)).collect()))
+            .collect();
+        let host_at = |ip: IpAddr| host_ips.iter().find(|(_, ips)| ips.contains(&ip)).map(|(vm, _)| *vm);
+
+        let hosts = report
+            .vms
+            .iter()
+            .map(|vm| HostNode {
+                id: node_id("h", &[&vm.host.name]),
+                label: format!("{}\n{}", vm.host.name, vm.host.ip),
+                containers: vm
+                    .containers
+                    .iter()
+                    .map(|c| (node_id("c", &[&vm.host.name, &c.name]), c.name.clone()))
+                    .collect(),
+            })
+            .collect();
+
+        let mut topology = Self { hosts, nodes: Vec::new(), edges: Vec::new() };
+        topology.add_tunnels(report, &host_ips, &host_at);
+        topology.add_routes(report, &host_at);
+        topology
+    }
+
+    /// One link per pair of peers, with the freshest handshake either side
+    /// reported. Peers that are no scanned host get a node of their own.
+    fn add_tunnels<'a>(
+        &mut self,
+        report: &'a InventoryReport,
+        host_ips: &[(&'a VmStatus, Vec<IpAddr>)],
+        host_at: &impl Fn(IpAddr) -> Option<&'a VmStatus>,
+    ) {
+        let mut links: BTreeMap<(String, String), Option<u64>> = BTreeMap::new();
+        for vm in &report.vms {
+            let Some(ref wireguard) = vm.wireguard else { continue };
+            for peer in &wireguard.peers {
+                let allowed: Vec<IpNet> = peer.allowed_ips.split(',').filter_map(|net| net.trim().parse().ok()).collect();
+                let remote = host_ips
+                    .iter()
+                    .find(|(other, ips)| {
+                        other.host.name != vm.host.name && ips.iter().any(|ip| allowed.iter().any(|net| net.contains(ip)))
+                    })
+                    .map(|(other, _)| *other)
+                    .or_else(|| peer.endpoint.as_deref().and_then(endpoint_ip).and_then(host_at));
+
+                let remote_id = match remote {
+                    Some(other) => node_id("h", &[&other.host.name]),
+                    None => {
+                        let id = node_id("p", &[&peer.public_key]);
+                        if !self.nodes.iter().any(|(existing, _)| *existing == id) {
+                            let name = peer.endpoint.clone().unwrap_or_else(|| peer.allowed_ips.clone());
+                            self.nodes.push((id.clone(), format!("peer {}", name)));
+                        }
+                        id
+                    }
+                };
+                let local_id = node_id("h", &[&vm.host.name]);
+                let pair = if local_id < remote_id { (local_id, remote_id) } else { (remote_id, local_id) };
+                let age = peer.latest_handshake.as_deref().and_then(handshake_age_secs);
+                let entry = links.entry(pair).or_insert(age);
+                *entry = match (*entry, age) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+            }
+        }
+
+        for ((from, to), age) in links {
+            self.edges.push(Edge {
+                from,
+                to,
+                label: format!("wg {}", age.map(short_age).unwrap_or_else(|| "never".to_string())),
+                kind: EdgeKind::Tunnel { fresh: age.is_some_and(|secs| secs <= FRESH_HANDSHAKE_SECS) },
+            });
+        }
+    }
+
+    /// A node per routed domain, linked to each backend server: a container
+    /// of the proxy's host by name, a scanned host by IP (or the container
+    /// publishing that port on it), or otherwise a node for the address.
+    fn add_routes<'a>(&mut self, report: &'a InventoryReport, host_at: &impl Fn(IpAddr) -> Option<&'a VmStatus>) {
+        for vm in &report.vms {
+            let Some(ref traefik) = vm.traefik else { continue };
+            for router in &traefik.routers {
+                // Router services may omit the provider suffix (`n8n` vs `n8n@docker`)
+                let Some(service) = traefik.services.iter().find(|s| {
+                    s.name == router.service || s.name.split('@').next() == Some(router.service.as_str())
+                }) else {
+                    continue;
+                };
+                let route_id = node_id("r", &[&vm.host.name, &router.name]);
+                let label = if router.domains.is_empty() { router.name.clone() } else { router.domains.join("\n") };
+                self.nodes.push((route_id.clone(), label));
+
+                for server in &service.servers {
+                    let Ok(url) = reqwest::Url::parse(&server.url) else { continue };
+                    let Some(address) = url.host_str() else { continue };
+                    let port = url.port_or_known_default().unwrap_or_default();
+
+                    let backend = if let Some(container) = vm.containers.iter().find(|c| c.name == address) {
+                        node_id("c", &[&vm.host.name, &container.name])
+                    } else if let Some(target) = parse_ip(address).and_then(host_at) {
+                        let published = format!(":{}->", port);
+                        match target.containers.iter().find(|c| c.ports.contains(&published)) {
+                            Some(container) => node_id("c", &[&target.host.name, &container.name]),
+                            None => node_id("h", &[&target.host.name]),
+                        }
+                    } else {
+                        let id = node_id("b", &[address, &port.to_string()]);
+                        if !self.nodes.iter().any(|(existing, _)| *existing == id) {
+                            self.nodes.push((id.clone(), format!("{}:{}", url_host(address), port)));
+                        }
+                        id
+                    };
+
+                    self.edges.push(Edge {
+                        from: route_id.clone(),
+                        to: backend,
+                        label: server.status.clone().unwrap_or_default(),
+                        kind: EdgeKind::Route,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Nothing worth drawing: no links, routes or containers.
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty() && self.hosts.iter().all(|host| host.containers.is_empty())
+    }
+
+    pub fn mermaid(&self) -> String {
+        let label = |text: &str| text.replace('"', "#quot;").replace('\n', "<br/>");
+        let mut output = String::from("flowchart LR\n");
+        for host in &self.hosts {
+            if host.containers.is_empty() {
+                let _ = writeln!(output, "    {}[\"{}\"]", host.id, label(&host.label));
+            } else {
+                let _ = writeln!(output, "    subgraph {}[\"{}\"]", host.id, label(&host.label));
+                for (id, name) in &host.containers {
+                    let _ = writeln!(output, "        {}([\"{}\"])", id, label(name));
+                }
+                output.push_str("    end\n");
+            }
+        }
+        for (id, name) in &self.nodes {
+            let _ = writeln!(output, "    {}[\"{}\"]", id, label(name));
+        }
+        for edge in &self.edges {
+            let arrow = match edge.kind {
+                EdgeKind::Tunnel { fresh: true } => "---",
+                EdgeKind::Tunnel { fresh: false } => "-.-",
+                EdgeKind::Route => "-->",
+            };
+            if edge.label.is_empty() {
+                let _ = writeln!(output, "    {} {} {}", edge.from, arrow, edge.to);
+            } else {
+                let _ = writeln!(output, "    {} {}|\"{}\"| {}", edge.from, arrow, label(&edge.label), edge.to);
+            }
+        }
+        output
+    }
+
+    pub fn dot(&self) -> String {
+        let label = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+        let mut output = String::from("digraph topology {\n    rankdir=LR;\n    node [shape=box];\n");
+        for host in &self.hosts {
+            if host.containers.is_empty() {
+                let _ = writeln!(output, "    {} [label=\"{}\"];", host.id, label(&host.label));
+            } else {
+                let _ = writeln!(output, "    subgraph cluster_{} {{\n        label=\"{}\";", host.id, label(&host.label));
+                let _ = writeln!(output, "        {} [shape=point];", host.id);
+                for (id, name) in &host.containers {
+                    let _ = writeln!(output, "        {} [label=\"{}\", shape=component];", id, label(name));
+                }
+                output.push_str("    }\n");
+            }
+        }
+        for (id, name) in &self.nodes {
+            let _ = writeln!(output, "    {} [label=\"{}\", shape=ellipse];", id, label(name));
+        }
+        for edge in &self.edges {
+            let style = match edge.kind {
+                EdgeKind::Tunnel { fresh: true } => "dir=none, color=darkgreen",
+                EdgeKind::Tunnel { fresh: false } => "dir=none, style=dashed, color=red",
+                EdgeKind::Route => "color=steelblue",
+            };
+            let _ = writeln!(output, "    {} -> {} [label=\"{}\", {}];", edge.from, edge.to, label(&edge.label), style);
+        }
+        output.push_str("}\n");
+        output
+    }
+
+    pub fn save_dot(&self, path: &str) -> Result<()> {
+        std::fs::write(path, self.dot()).context(format!("Failed to write topology diagram: {}", path))?;
+        info!("Diagrama de topología guardado en: {}", path);
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use crate::models::TraceHop;
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TracerouteConfig {
+    pub enabled: bool,
+    pub max_hops: u8,
+    pub wait_secs: u32,
+}
+
+impl Default for TracerouteConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_hops: 20,
+            wait_secs: 2,
+        }
+    }
+}
+
+pub struct Tracer {
+    config: TracerouteConfig,
+}
+
+impl Tracer {
+    pub fn new(config: TracerouteConfig) -> Self {
+        Self { config }
+    }
+
+    /// Runs `traceroute` towards `target`, falling back to `tracepath` when
+    /// traceroute is not installed.
+    pub async fn trace(&self, target: &str) -> Vec<TraceHop> {
+        if !self.config.enabled {
+            return Vec::new();
+        }
+
+        let max_hops = self.config.max_hops.to_string();
+        let wait = self.config.wait_secs.to_string();
+
+        let output = match Command::new("traceroute")
+            .args(["-n", "-q", "1", "-w", &wait, "-m", &max_hops, target])
+            .output()
+            .await
+        {
+            Ok(output) => output,
+            Err(_) => match Command::new("tracepath")
+                .args(["-n", "-m", &max_hops, target])
+                .output()
+                .await
+            {
+                Ok(output) => output,
+                Err(_) => return Vec::new(),
+            },
+        };
+
+        let mut hops: Vec<TraceHop> = Vec::new();
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            //  3  10.0.0.1  4.512 ms       (traceroute)
+            //  3:  10.0.0.1   4.512ms      (tracepath)
+            let mut parts = line.split_whitespace();
+            let Some(ttl) = parts.next().and_then(|t| t.trim_end_matches(':').parse::<u8>().ok()) else {
+                continue;
+            };
+            // tracepath prints several lines per TTL, keep the first one
+            if hops.last().map(|h| h.ttl) == Some(ttl) {
+                continue;
+            }
+
+            let address = parts.next().filter(|a| *a != "*" && *a != "no").map(|a| a.to_string());
+            let rtt_ms = parts
+                .next()
+                .and_then(|r| r.trim_end_matches("ms").parse::<f64>().ok());
+
+            hops.push(TraceHop { ttl, address, rtt_ms });
+        }
+
+        hops
+    }
+}
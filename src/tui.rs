@@ -0,0 +1,271 @@
+use crate::models::{CheckResult, ServiceStatus, SocketExposure, VmHost, VmStatus};
+use crate::scanner::InventoryScanner;
+use anyhow::Result;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Tabs, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+const PANES: [&str; 5] = ["Servicios", "Contenedores", "Puertos", "Errores", "Chequeos"];
+
+enum HostState {
+    Pending,
+    Scanning,
+    Done(Box<VmStatus>),
+}
+
+struct App {
+    scanner: Arc<InventoryScanner>,
+    hosts: Vec<VmHost>,
+    states: Vec<HostState>,
+    selected: ListState,
+    pane: usize,
+    scroll: u16,
+    updates: UnboundedSender<(usize, HostState)>,
+}
+
+/// Interactive mode: scans every host in the background, showing each one
+/// as soon as it finishes, and lets the user rescan the selected host.
+pub async fn run(scanner: InventoryScanner) -> Result<()> {
+    let scanner = Arc::new(scanner);
+    let hosts = scanner.hosts().to_vec();
+    let (updates, mut rx) = mpsc::unbounded_channel();
+
+    let mut app = App {
+        states: hosts.iter().map(|_| HostState::Pending).collect(),
+        selected: ListState::default().with_selected((!hosts.is_empty()).then_some(0)),
+        scanner,
+        hosts,
+        pane: 0,
+        scroll: 0,
+        updates,
+    };
+    app.scan((0..app.hosts.len()).collect());
+
+    let mut terminal = ratatui::init();
+    let result = tokio::task::block_in_place(|| app.event_loop(&mut terminal, &mut rx));
+    ratatui::restore();
+    result
+}
+
+impl App {
+    /// Scans the given hosts one after another on a background task.
+    fn scan(&mut self, indexes: Vec<usize>) {
+        for &i in &indexes {
+            self.states[i] = HostState::Scanning;
+        }
+        let scanner = self.scanner.clone();
+        let updates = self.updates.clone();
+        tokio::spawn(async move {
+            for i in indexes {
+                let host = scanner.hosts()[i].clone();
+                let status = scanner.scan_one(&host).await;
+                if updates.send((i, HostState::Done(Box::new(status)))).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    fn event_loop(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        rx: &mut UnboundedReceiver<(usize, HostState)>,
+    ) -> Result<()> {
+        loop {
+            while let Ok((i, state)) = rx.try_recv() {
+                self.states[i] = state;
+            }
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if !event::poll(Duration::from_millis(200))? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Down | KeyCode::Char('j') => self.select(1),
+                KeyCode::Up | KeyCode::Char('k') => self.select(-1),
+                KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => self.switch_pane(1),
+                KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => self.switch_pane(PANES.len() - 1),
+                KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+                KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+                KeyCode::Char('r') => {
+                    if let Some(i) = self.selected.selected() {
+                        if !matches!(self.states[i], HostState::Scanning) {
+                            self.scan(vec![i]);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn select(&mut self, delta: isize) {
+        if self.hosts.is_empty() {
+            return;
+        }
+        let current = self.selected.selected().unwrap_or(0) as isize;
+        let next = (current + delta).rem_euclid(self.hosts.len() as isize) as usize;
+        self.selected.select(Some(next));
+        self.scroll = 0;
+    }
+
+    fn switch_pane(&mut self, step: usize) {
+        self.pane = (self.pane + step) % PANES.len();
+        self.scroll = 0;
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, footer] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [list_area, detail_area] =
+            Layout::horizontal([Constraint::Length(28), Constraint::Min(1)]).areas(main);
+
+        let items: Vec<ListItem> = self
+            .hosts
+            .iter()
+            .zip(&self.states)
+            .map(|(host, state)| {
+                let (icon, color) = match state {
+                    HostState::Pending => ("·", Color::DarkGray),
+                    HostState::Scanning => ("…", Color::Yellow),
+                    HostState::Done(vm) if !vm.reachable => ("✗", Color::Red),
+                    HostState::Done(vm) if vm.checks.iter().any(|c| c.result == CheckResult::Failed) => {
+                        ("!", Color::Red)
+                    }
+                    HostState::Done(vm) if vm.checks.iter().any(|c| c.result == CheckResult::Warning) => {
+                        ("!", Color::Yellow)
+                    }
+                    HostState::Done(_) => ("✓", Color::Green),
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{} ", icon), Style::default().fg(color)),
+                    Span::raw(host.name.clone()),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(" Hosts "))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, list_area, &mut self.selected);
+
+        let [tabs_area, body_area] = Layout::vertical([Constraint::Length(3), Constraint::Min(1)]).areas(detail_area);
+        let selected = self.selected.selected();
+        let title = selected
+            .map(|i| format!(" {} ({}) ", self.hosts[i].name, self.hosts[i].ip))
+            .unwrap_or_default();
+        let tabs = Tabs::new(PANES.to_vec())
+            .select(self.pane)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+        frame.render_widget(tabs, tabs_area);
+
+        let lines = match selected.map(|i| &self.states[i]) {
+            Some(HostState::Done(vm)) => self.pane_lines(vm),
+            Some(HostState::Scanning) => vec![Line::from("Escaneando...")],
+            Some(HostState::Pending) => vec![Line::from("En cola")],
+            None => Vec::new(),
+        };
+        let body = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL))
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        frame.render_widget(body, body_area);
+
+        frame.render_widget(
+            Paragraph::new("↑/↓ host  ←/→ panel  PgUp/PgDn desplazar  r reescanear  q salir")
+                .style(Style::default().fg(Color::DarkGray)),
+            footer,
+        );
+    }
+
+    fn pane_lines(&self, vm: &VmStatus) -> Vec<Line<'static>> {
+        if !vm.reachable && self.pane != 4 {
+            return vec![Line::styled("Host inaccesible", Style::default().fg(Color::Red))];
+        }
+
+        let ok = Style::default().fg(Color::Green);
+        let warn = Style::default().fg(Color::Yellow);
+        let bad = Style::default().fg(Color::Red);
+
+        match self.pane {
+            0 => vm
+                .services
+                .iter()
+                .map(|s| {
+                    let style = match s.status {
+                        ServiceStatus::Running => ok,
+                        ServiceStatus::Stopped | ServiceStatus::NotFound => warn,
+                        ServiceStatus::Failed => bad,
+                    };
+                    Line::from(vec![
+                        Span::styled(format!("{:<9}", format!("{:?}", s.status)), style),
+                        Span::raw(format!(" {} {:?}", s.name, s.ports)),
+                    ])
+                })
+                .collect(),
+            1 => vm
+                .containers
+                .iter()
+                .map(|c| {
+                    let style = if c.status.contains("Up") { ok } else { warn };
+                    Line::from(vec![
+                        Span::styled(c.name.clone(), style),
+                        Span::raw(format!("  {}  {}", c.status, c.ports)),
+                    ])
+                })
+                .collect(),
+            2 => vm
+                .open_ports
+                .iter()
+                .map(|p| {
+                    let exposed = vm.exposed_ports.iter().find(|e| e.port == p.port);
+                    let style = match exposed {
+                        Some(e) if !e.expected_public || e.is_forwarded() => bad,
+                        Some(_) => warn,
+                        None => Style::default(),
+                    };
+                    let scope = match p.exposure {
+                        Some(SocketExposure::Loopback) => "loopback",
+                        Some(SocketExposure::Vpn) => "vpn",
+                        Some(SocketExposure::Private) => "lan",
+                        Some(SocketExposure::World) => "world",
+                        None => "",
+                    };
+                    Line::styled(format!("{:>5}/{:<4} {:<8} {}", p.port, p.protocol, scope, p.process), style)
+                })
+                .collect(),
+            3 => vm
+                .recent_errors
+                .iter()
+                .map(|e| Line::from(format!("{} {} {}", e.timestamp.format("%m-%d %H:%M:%S"), e.service, e.message)))
+                .collect(),
+            _ => vm
+                .checks
+                .iter()
+                .flat_map(|c| {
+                    let style = match c.result {
+                        CheckResult::Passed => ok,
+                        CheckResult::Warning => warn,
+                        CheckResult::Failed => bad,
+                        CheckResult::Skipped => Style::default().fg(Color::DarkGray),
+                    };
+                    std::iter::once(Line::styled(c.name.clone(), style))
+                        .chain(c.messages.iter().map(|m| Line::from(format!("  {}", m))))
+                })
+                .collect(),
+        }
+    }
+}
@@ -1,56 +1,258 @@
-use crate::models::WebService;
+use crate::models::{DnsRecords, HealthField, HealthReport, LatencyStats, ScenarioStepResult, TlsAudit, WebService};
 use anyhow::Result;
-use reqwest::Client;
+use regex::Regex;
+use reqwest::header::LOCATION;
+use reqwest::redirect::Policy;
+use reqwest::{Client, Method, Response, Url};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::time::Duration;
+use tokio::process::Command;
 use futures::future::join_all;
+use std::process::Stdio;
+use tracing::{debug, warn};
+
+const MAX_REDIRECTS: usize = 10;
+const DEFAULT_PROBES: u32 = 5;
+/// Fields of a health response kept for the report beyond the asserted ones.
+const MAX_HEALTH_FIELDS: usize = 20;
+
+/// Protocol versions as `openssl s_client` names them, oldest first, with
+/// the flag that restricts a handshake to each.
+const TLS_VERSIONS: [(&str, &str); 4] =
+    [("TLSv1", "-tls1"), ("TLSv1.1", "-tls1_1"), ("TLSv1.2", "-tls1_2"), ("TLSv1.3", "-tls1_3")];
+
+/// Cipher suites no HTTPS endpoint should still accept, at the security
+/// level that lets OpenSSL offer them.
+const WEAK_CIPHERS: &str = "NULL:aNULL:EXPORT:LOW:RC4:3DES:DES:MD5@SECLEVEL=0";
 
 pub struct WebScanner {
     client: Client,
     services: Vec<WebServiceConfig>,
+    tls: TlsConfig,
+}
+
+/// Protocol and cipher audit of HTTPS endpoints, done with `openssl
+/// s_client` since the HTTP client refuses legacy protocols outright.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TlsConfig {
+    pub enabled: bool,
+    /// Oldest acceptable protocol: TLSv1, TLSv1.1, TLSv1.2 or TLSv1.3.
+    pub min_version: String,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        Self { enabled: true, min_version: "TLSv1.2".to_string() }
+    }
 }
 
-#[derive(Debug, Clone)]
+impl TlsConfig {
+    /// Issue messages for `audit`, without the service name.
+    pub fn issues(&self, audit: &TlsAudit) -> Vec<String> {
+        let mut issues = Vec::new();
+        if let Some(ref error) = audit.error {
+            issues.push(format!("TLS audit failed - {}", error));
+        }
+        if !audit.legacy_protocols.is_empty() {
+            issues.push(format!("accepts {} (minimum {})", audit.legacy_protocols.join(", "), self.min_version));
+        }
+        if let Some(ref cipher) = audit.weak_cipher {
+            issues.push(format!("accepts weak cipher {}", cipher));
+        }
+        issues
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebServiceConfig {
     pub name: String,
     pub url: String,
+    /// HTTP method for plain checks; content checks always use GET.
+    #[serde(default = "default_method")]
+    pub method: String,
+    /// Statuses considered healthy. When empty any non-5xx status passes.
+    #[serde(default)]
+    pub expected_status: Vec<u16>,
+    pub timeout_secs: Option<u64>,
+    /// Number of requests used to compute latency percentiles and error rate.
+    pub probes: Option<u32>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Domains besides the service's own parent domain that redirects may lead to.
+    #[serde(default)]
+    pub allowed_redirect_domains: Vec<String>,
+    pub expect: Option<ContentExpectation>,
+    pub auth: Option<WebAuth>,
+    /// Inventory host serving the service, whose addresses the domain must
+    /// resolve to. Defaults to the host whose Traefik routes the domain.
+    pub host: Option<String>,
+    /// Addresses or networks the domain may resolve to instead, e.g. a CDN
+    /// in front of the host.
+    #[serde(default)]
+    pub expected_ips: Vec<String>,
+    /// Requests run in order with a shared cookie jar, e.g. load the login
+    /// page, post credentials and expect the dashboard. Stops at the first
+    /// failing step.
+    #[serde(default)]
+    pub scenario: Vec<ScenarioStep>,
+    pub health: Option<HealthEndpoint>,
+}
+
+/// A JSON status endpoint such as `/health` or `/api/status`, whose fields
+/// tell more about the application than the front page does.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthEndpoint {
+    /// Absolute, or relative to the service URL.
+    pub url: String,
+    /// Expected values keyed by field, as a JSON pointer (`/checks/db/status`)
+    /// or a dotted path (`checks.db.status`).
+    #[serde(default, rename = "assert")]
+    pub assertions: BTreeMap<String, serde_json::Value>,
+}
+
+/// One request of a synthetic transaction. `{{name}}` in the URL, headers,
+/// form or JSON body is replaced with a value captured by an earlier step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioStep {
+    pub name: String,
+    #[serde(default = "default_step_method")]
+    pub method: String,
+    /// Absolute, or relative to the service URL (e.g. `/login`).
+    pub url: String,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Sent as `application/x-www-form-urlencoded`.
+    pub form: Option<BTreeMap<String, String>>,
+    pub json: Option<serde_json::Value>,
+    /// Statuses the final response (after redirects) may have. When empty
+    /// any 2xx passes.
+    #[serde(default)]
+    pub expected_status: Vec<u16>,
+    pub expect: Option<ContentExpectation>,
+    /// Values to capture from the body for later steps, as a regex whose
+    /// first group is the value (e.g. a CSRF token).
+    #[serde(default)]
+    pub capture: BTreeMap<String, String>,
+}
+
+fn default_step_method() -> String {
+    "GET".to_string()
+}
+
+fn default_method() -> String {
+    "HEAD".to_string()
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl WebServiceConfig {
+    pub fn new(name: &str, url: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            url: url.to_string(),
+            method: default_method(),
+            expected_status: Vec::new(),
+            timeout_secs: None,
+            probes: None,
+            enabled: true,
+            allowed_redirect_domains: Vec::new(),
+            expect: None,
+            auth: None,
+            host: None,
+            expected_ips: Vec::new(),
+            scenario: Vec::new(),
+            health: None,
+        }
+    }
+}
+
+/// The services probed when the config file does not list any.
+pub fn default_web_services() -> Vec<WebServiceConfig> {
+    vec![
+        WebServiceConfig::new("Coolify", "https://coolify.secure-penguin.com"),
+        WebServiceConfig::new("Guacamole", "https://guacamole.secure-penguin.com"),
+        WebServiceConfig::new("N8n", "https://n8n.secure-penguin.com"),
+        WebServiceConfig::new("Obsidian", "https://obsidian.secure-penguin.com"),
+        WebServiceConfig::new("S3 Console", "https://s3-console.secure-penguin.com"),
+        WebServiceConfig::new("Traefik", "https://traefik.secure-penguin.com"),
+    ]
+}
+
+/// Credentials used to get past the auth wall so the check reaches the
+/// actual application instead of stopping at a 302/401.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WebAuth {
+    Basic { username: String, password: String },
+    Bearer { token: String },
+    /// Logs in through the Authelia portal's first factor API and reuses the
+    /// session cookie. Only works for `one_factor` access policies.
+    Authelia { portal_url: String, username: String, password: String },
+}
+
+/// What a healthy response body must contain. A service returning 200 with
+/// an error page fails the check even though the status looks fine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentExpectation {
+    Substring(String),
+    Regex(String),
+    /// A JSON pointer (e.g. `/status`) that must exist, optionally with a given value.
+    JsonField {
+        pointer: String,
+        equals: Option<serde_json::Value>,
+    },
+}
+
+impl ContentExpectation {
+    /// Returns `None` when the body satisfies the expectation, or the reason it does not.
+    pub fn verify(&self, body: &str) -> Option<String> {
+        match self {
+            ContentExpectation::Substring(expected) => {
+                if body.contains(expected.as_str()) {
+                    None
+                } else {
+                    Some(format!("body does not contain {:?}", expected))
+                }
+            }
+            ContentExpectation::Regex(pattern) => match Regex::new(pattern) {
+                Ok(re) if re.is_match(body) => None,
+                Ok(_) => Some(format!("body does not match /{}/", pattern)),
+                Err(e) => Some(format!("invalid regex /{}/: {}", pattern, e)),
+            },
+            ContentExpectation::JsonField { pointer, equals } => {
+                let json: serde_json::Value = match serde_json::from_str(body) {
+                    Ok(json) => json,
+                    Err(e) => return Some(format!("body is not JSON: {}", e)),
+                };
+                match (json.pointer(pointer), equals) {
+                    (None, _) => Some(format!("JSON field {} missing", pointer)),
+                    (Some(value), Some(expected)) if value != expected => {
+                        Some(format!("JSON field {} is {} (expected {})", pointer, value, expected))
+                    }
+                    _ => None,
+                }
+            }
+        }
+    }
 }
 
 impl WebScanner {
-    pub fn new() -> Self {
+    pub fn new(services: Vec<WebServiceConfig>, tls: TlsConfig) -> Self {
         let client = Client::builder()
             .timeout(Duration::from_secs(10))
             .connect_timeout(Duration::from_secs(5))
+            .redirect(Policy::none())
             .build()
             .expect("Failed to create HTTP client");
 
-        let services = vec![
-            WebServiceConfig {
-                name: "Coolify".to_string(),
-                url: "https://coolify.secure-penguin.com".to_string(),
-            },
-            WebServiceConfig {
-                name: "Guacamole".to_string(),
-                url: "https://guacamole.secure-penguin.com".to_string(),
-            },
-            WebServiceConfig {
-                name: "N8n".to_string(),
-                url: "https://n8n.secure-penguin.com".to_string(),
-            },
-            WebServiceConfig {
-                name: "Obsidian".to_string(),
-                url: "https://obsidian.secure-penguin.com".to_string(),
-            },
-            WebServiceConfig {
-                name: "S3 Console".to_string(),
-                url: "https://s3-console.secure-penguin.com".to_string(),
-            },
-            WebServiceConfig {
-                name: "Traefik".to_string(),
-                url: "https://traefik.secure-penguin.com".to_string(),
-            },
-        ];
+        let services = services.into_iter().filter(|s| s.enabled).collect();
 
-        Self { client, services }
+        Self { client, services, tls }
     }
 
     pub async fn scan_all(&self) -> Result<Vec<WebService>> {
@@ -67,7 +269,7 @@ impl WebScanner {
             match result {
                 Ok(service) => web_services.push(service),
                 Err(e) => {
-                    eprintln!("Error scanning web service: {}", e);
+                    warn!("Error scanning web service: {}", e);
                 }
             }
         }
@@ -76,30 +278,573 @@ impl WebScanner {
     }
 
     async fn scan_service(&self, config: WebServiceConfig) -> Result<WebService> {
+        let mut web_service = WebService {
+            name: config.name.clone(),
+            url: config.url.clone(),
+            http_status: None,
+            unexpected_status: false,
+            response_time: None,
+            latency: None,
+            error: None,
+            content_error: None,
+            redirect_chain: Vec::new(),
+            redirect_error: None,
+            https_enforced: None,
+            route: None,
+            dns: None,
+            dns_error: None,
+            tls: None,
+            scenario: Vec::new(),
+            health: None,
+        };
+
+        let client = match config.auth {
+            Some(WebAuth::Authelia { ref portal_url, ref username, ref password }) => {
+                match self.authelia_login(portal_url, username, password, &config.url).await {
+                    Ok(client) => client,
+                    Err(e) => {
+                        web_service.error = Some(format!("Authelia login failed: {}", e));
+                        return Ok(web_service);
+                    }
+                }
+            }
+            _ => self.client.clone(),
+        };
+
         let start = std::time::Instant::now();
         
-        let response = self.client
-            .head(&config.url)
-            .send()
-            .await;
+        // Content checks need the body, plain reachability checks don't
+        let method = if config.expect.is_some() {
+            Method::GET
+        } else {
+            Method::from_bytes(config.method.to_uppercase().as_bytes()).unwrap_or(Method::HEAD)
+        };
+        let response = self.follow_redirects(&client, &config, method.clone(), &mut web_service.redirect_chain).await;
 
-        let response_time = start.elapsed().as_secs_f64();
+        web_service.response_time = Some(start.elapsed().as_secs_f64());
+        web_service.latency = Some(
+            self.probe_latency(&client, &config, method, web_service.response_time, &response)
+                .await,
+        );
+        let still_redirecting = response.as_ref().is_ok_and(|r| r.status().is_redirection());
+        web_service.redirect_error = Self::check_redirect_chain(&config, &web_service.redirect_chain, still_redirecting);
+        web_service.https_enforced = self.check_https_enforced(&config.url).await;
+        // URLs with an IP address have nothing to resolve
+        if let Some(domain) = Url::parse(&config.url).ok().and_then(|url| url.domain().map(|d| d.to_string())) {
+            web_service.dns = Some(Self::resolve(&domain).await);
+        }
+        web_service.tls = self.audit_tls(&config.url).await;
+        if !config.scenario.is_empty() {
+            web_service.scenario = self.run_scenario(&config).await;
+        }
+        if let Some(ref health) = config.health {
+            web_service.health = Some(Self::check_health(&client, &config, health).await);
+        }
 
         match response {
-            Ok(resp) => Ok(WebService {
-                name: config.name.clone(),
-                url: config.url.clone(),
-                http_status: Some(resp.status().as_u16()),
-                response_time: Some(response_time),
-                error: None,
-            }),
-            Err(e) => Ok(WebService {
-                name: config.name.clone(),
-                url: config.url.clone(),
-                http_status: None,
-                response_time: Some(response_time),
-                error: Some(e.to_string()),
-            }),
+            Ok(resp) => {
+                let http_status = resp.status().as_u16();
+                web_service.content_error = match config.expect {
+                    Some(ref expect) => match resp.text().await {
+                        Ok(body) => expect.verify(&body),
+                        Err(e) => Some(format!("failed to read body: {}", e)),
+                    },
+                    None => None,
+                };
+
+                web_service.unexpected_status = if config.expected_status.is_empty() {
+                    http_status >= 500
+                } else {
+                    !config.expected_status.contains(&http_status)
+                };
+                web_service.http_status = Some(http_status);
+            }
+            Err(e) => web_service.error = Some(e.to_string()),
+        }
+
+        Ok(web_service)
+    }
+
+    /// Follows redirects by hand so every hop ends up in `chain`. Credentials
+    /// are only sent to the host the service was configured with.
+    async fn follow_redirects(
+        &self,
+        client: &Client,
+        config: &WebServiceConfig,
+        method: Method,
+        chain: &mut Vec<String>,
+    ) -> Result<Response> {
+        let origin = Url::parse(&config.url)?;
+        let mut url = origin.clone();
+
+        loop {
+            let mut request = client.request(method.clone(), url.clone());
+            if let Some(secs) = config.timeout_secs {
+                request = request.timeout(Duration::from_secs(secs));
+            }
+            if url.host_str() == origin.host_str() {
+                request = match config.auth {
+                    Some(WebAuth::Basic { ref username, ref password }) => request.basic_auth(username, Some(password)),
+                    Some(WebAuth::Bearer { ref token }) => request.bearer_auth(token),
+                    _ => request,
+                };
+            }
+
+            let response = request.send().await?;
+            if !response.status().is_redirection() || chain.len() >= MAX_REDIRECTS {
+                return Ok(response);
+            }
+
+            let Some(next) = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|l| l.to_str().ok())
+                .and_then(|l| url.join(l).ok())
+            else {
+                return Ok(response);
+            };
+
+            let looped = next == origin || chain.iter().any(|hop| *hop == next.as_str());
+            chain.push(next.to_string());
+            if looped {
+                return Ok(response);
+            }
+            url = next;
+        }
+    }
+
+    /// Repeats the request to get a latency distribution. The initial probe
+    /// (already sent by the caller) counts as the first sample.
+    async fn probe_latency(
+        &self,
+        client: &Client,
+        config: &WebServiceConfig,
+        method: Method,
+        first_time: Option<f64>,
+        first_response: &Result<Response>,
+    ) -> LatencyStats {
+        let probes = config.probes.unwrap_or(DEFAULT_PROBES).max(1);
+        let is_error = |response: &Result<Response>| match response {
+            Ok(resp) => {
+                let status = resp.status().as_u16();
+                if config.expected_status.is_empty() {
+                    status >= 500
+                } else {
+                    !config.expected_status.contains(&status)
+                }
+            }
+            Err(_) => true,
+        };
+
+        let mut samples = Vec::new();
+        let mut errors = 0;
+        if is_error(first_response) {
+            errors += 1;
+        } else if let Some(time) = first_time {
+            samples.push(time);
+        }
+
+        for _ in 1..probes {
+            let start = std::time::Instant::now();
+            let response = self.follow_redirects(client, config, method.clone(), &mut Vec::new()).await;
+            if is_error(&response) {
+                errors += 1;
+            } else {
+                samples.push(start.elapsed().as_secs_f64());
+            }
+        }
+
+        LatencyStats::from_samples(samples, errors as f64 / probes as f64)
+    }
+
+    fn check_redirect_chain(config: &WebServiceConfig, chain: &[String], still_redirecting: bool) -> Option<String> {
+        let mut seen = vec![config.url.trim_end_matches('/').to_string()];
+        for hop in chain {
+            let hop_normalized = hop.trim_end_matches('/').to_string();
+            if seen.contains(&hop_normalized) {
+                return Some(format!("redirect loop at {}", hop));
+            }
+            seen.push(hop_normalized);
+        }
+
+        // A chain of exactly MAX_REDIRECTS hops may well end in a page
+        if chain.len() >= MAX_REDIRECTS && still_redirecting {
+            return Some(format!("more than {} redirects", MAX_REDIRECTS));
+        }
+
+        // Redirects may stay within the service's parent domain (e.g. to the
+        // Authelia portal) or go to explicitly allowed domains
+        let origin_host = Url::parse(&config.url).ok()?.host_str()?.to_string();
+        let labels: Vec<&str> = origin_host.split('.').collect();
+        let base_domain = labels[labels.len().saturating_sub(2)..].join(".");
+
+        chain
+            .iter()
+            .filter_map(|hop| Url::parse(hop).ok())
+            .filter_map(|hop| hop.host_str().map(|h| h.to_string()))
+            .find(|host| {
+                !(host == &base_domain
+                    || host.ends_with(&format!(".{}", base_domain))
+                    || config.allowed_redirect_domains.iter().any(|d| host == d || host.ends_with(&format!(".{}", d))))
+            })
+            .map(|host| format!("redirects outside {} to {}", base_domain, host))
+    }
+
+    /// Checks that the plain HTTP version of an HTTPS service redirects to
+    /// HTTPS. Returns `None` for plain HTTP services, which never claimed
+    /// HTTPS, and when nothing answers on plain HTTP.
+    async fn check_https_enforced(&self, url: &str) -> Option<bool> {
+        let mut plain = Url::parse(url).ok()?;
+        if plain.scheme() != "https" {
+            return None;
+        }
+        plain.set_scheme("http").ok()?;
+
+        let response = self.client.head(plain).send().await.ok()?;
+        let redirects_to_https = response.status().is_redirection()
+            && response
+                .headers()
+                .get(LOCATION)
+                .and_then(|l| l.to_str().ok())
+                .map(|l| l.starts_with("https://"))
+                .unwrap_or(false);
+
+        Some(redirects_to_https)
+    }
+
+    /// Fetches the service's health endpoint and checks its assertions. The
+    /// report keeps every asserted field and the first other scalar fields.
+    async fn check_health(client: &Client, config: &WebServiceConfig, health: &HealthEndpoint) -> HealthReport {
+        let mut report = HealthReport { url: health.url.clone(), http_status: None, fields: Vec::new(), error: None };
+
+        let body: Result<serde_json::Value> = async {
+            let url = Url::parse(&config.url)?.join(&health.url)?;
+            report.url = url.to_string();
+            let mut request = client.get(url);
+            request = match config.auth {
+                Some(WebAuth::Basic { ref username, ref password }) => request.basic_auth(username, Some(password)),
+                Some(WebAuth::Bearer { ref token }) => request.bearer_auth(token),
+                _ => request,
+            };
+            let response = request.send().await?;
+            report.http_status = Some(response.status().as_u16());
+            // Health endpoints often answer 503 with the JSON telling why
+            Ok(serde_json::from_str(&response.text().await?)?)
+        }
+        .await;
+        let body = match body {
+            Ok(body) => body,
+            Err(e) => {
+                report.error = Some(e.to_string());
+                return report;
+            }
+        };
+
+        for (field, expected) in &health.assertions {
+            let pointer = if field.starts_with('/') { field.clone() } else { format!("/{}", field.replace('.', "/")) };
+            let value = body.pointer(&pointer);
+            report.fields.push(HealthField {
+                path: field.clone(),
+                value: value.map(|v| v.to_string()),
+                expected: Some(expected.to_string()),
+                ok: value == Some(expected),
+            });
+        }
+
+        let mut leaves = Vec::new();
+        flatten_json(&body, String::new(), &mut leaves);
+        for (path, value) in leaves {
+            if report.fields.len() >= health.assertions.len() + MAX_HEALTH_FIELDS {
+                break;
+            }
+            let asserted = health.assertions.keys().any(|field| field.trim_start_matches('/').replace('/', ".") == path);
+            if !asserted {
+                report.fields.push(HealthField { path, value: Some(value.to_string()), expected: None, ok: true });
+            }
+        }
+
+        report
+    }
+
+    /// Runs the service's scenario with a fresh cookie jar, following
+    /// redirects as a browser would.
+    async fn run_scenario(&self, config: &WebServiceConfig) -> Vec<ScenarioStepResult> {
+        let mut results = Vec::new();
+        let client = match Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs.unwrap_or(10)))
+            .connect_timeout(Duration::from_secs(5))
+            .redirect(Policy::limited(MAX_REDIRECTS))
+            .cookie_store(true)
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                warn!("Scenario of {} not run: {}", config.name, e);
+                return results;
+            }
+        };
+        let Ok(base) = Url::parse(&config.url) else {
+            return results;
+        };
+
+        let mut captured: BTreeMap<String, String> = BTreeMap::new();
+        let fill = |text: &str, captured: &BTreeMap<String, String>| {
+            captured.iter().fold(text.to_string(), |text, (name, value)| text.replace(&format!("{{{{{}}}}}", name), value))
+        };
+
+        for step in &config.scenario {
+            let start = std::time::Instant::now();
+            let mut result = ScenarioStepResult { name: step.name.clone(), http_status: None, duration: 0.0, error: None };
+
+            let outcome: Result<()> = async {
+                let url = base.join(&fill(&step.url, &captured))?;
+                let method = Method::from_bytes(step.method.to_uppercase().as_bytes())?;
+                let mut request = client.request(method, url.clone());
+                for (name, value) in &step.headers {
+                    request = request.header(name.as_str(), fill(value, &captured));
+                }
+                if url.host_str() == base.host_str() {
+                    request = match config.auth {
+                        Some(WebAuth::Basic { ref username, ref password }) => request.basic_auth(username, Some(password)),
+                        Some(WebAuth::Bearer { ref token }) => request.bearer_auth(token),
+                        _ => request,
+                    };
+                }
+                if let Some(ref form) = step.form {
+                    let form: BTreeMap<&String, String> = form.iter().map(|(k, v)| (k, fill(v, &captured))).collect();
+                    request = request.form(&form);
+                }
+                if let Some(ref json) = step.json {
+                    request = request.header("Content-Type", "application/json").body(fill(&json.to_string(), &captured));
+                }
+
+                let response = request.send().await?;
+                let status = response.status().as_u16();
+                result.http_status = Some(status);
+                let body = response.text().await?;
+
+                let status_ok = if step.expected_status.is_empty() {
+                    (200..300).contains(&status)
+                } else {
+                    step.expected_status.contains(&status)
+                };
+                if !status_ok {
+                    anyhow::bail!("unexpected HTTP status {}", status);
+                }
+                if let Some(reason) = step.expect.as_ref().and_then(|expect| expect.verify(&body)) {
+                    anyhow::bail!("HTTP {} but {}", status, reason);
+                }
+                for (name, pattern) in &step.capture {
+                    let value = Regex::new(pattern)?
+                        .captures(&body)
+                        .and_then(|captures| captures.get(1))
+                        .map(|value| value.as_str().to_string())
+                        .ok_or_else(|| anyhow::anyhow!("nothing to capture as {} with /{}/", name, pattern))?;
+                    captured.insert(name.clone(), value);
+                }
+                Ok(())
+            }
+            .await;
+
+            result.duration = start.elapsed().as_secs_f64();
+            let failed = outcome.is_err();
+            result.error = outcome.err().map(|e| e.to_string());
+            results.push(result);
+            if failed {
+                break;
+            }
+        }
+
+        results
+    }
+
+    /// Reports the protocol and cipher an HTTPS endpoint negotiates, the
+    /// protocols below the minimum it still accepts and whether it accepts
+    /// weak ciphers. Returns `None` for plain HTTP, or when the audit is
+    /// disabled or openssl is not installed.
+    async fn audit_tls(&self, url: &str) -> Option<TlsAudit> {
+        let url = Url::parse(url).ok()?;
+        if !self.tls.enabled || url.scheme() != "https" {
+            return None;
+        }
+        let host = url.host_str()?;
+        let port = url.port_or_known_default()?;
+
+        let minimum = match TLS_VERSIONS.iter().position(|(name, _)| *name == self.tls.min_version) {
+            Some(minimum) => minimum,
+            None => {
+                warn!("Unknown TLS minimum version {}, using TLSv1.2", self.tls.min_version);
+                2
+            }
+        };
+
+        let negotiated = Self::handshake(host, port, Vec::new());
+        // OpenSSL refuses legacy protocols at its default security level
+        let legacy = join_all(
+            TLS_VERSIONS[..minimum]
+                .iter()
+                .map(|(_, flag)| Self::handshake(host, port, vec![flag, "-cipher", "DEFAULT@SECLEVEL=0"])),
+        );
+        let weak = Self::handshake(host, port, vec!["-no_tls1_3", "-cipher", WEAK_CIPHERS]);
+        let (negotiated, legacy, weak) = futures::join!(negotiated, legacy, weak);
+
+        let negotiated = match negotiated {
+            Ok(negotiated) => negotiated,
+            Err(e) => {
+                debug!("TLS audit of {} skipped: {}", url, e);
+                return None;
+            }
+        };
+        let mut audit = TlsAudit {
+            protocol: None,
+            cipher: None,
+            legacy_protocols: Vec::new(),
+            weak_cipher: None,
+            error: None,
+        };
+        match negotiated {
+            Some((protocol, cipher)) => {
+                audit.protocol = Some(protocol);
+                audit.cipher = Some(cipher);
+            }
+            None => audit.error = Some("handshake failed".to_string()),
+        }
+        audit.legacy_protocols = legacy
+            .into_iter()
+            .filter_map(|accepted| accepted.ok().flatten())
+            .map(|(protocol, _)| protocol)
+            .collect();
+        audit.weak_cipher = weak.ok().flatten().map(|(_, cipher)| cipher);
+        Some(audit)
+    }
+
+    /// Runs a TLS handshake with `openssl s_client` and returns the protocol
+    /// and cipher agreed on, or `None` when the server refused it.
+    async fn handshake(host: &str, port: u16, args: Vec<&str>) -> Result<Option<(String, String)>> {
+        // `host` comes bracketed for IPv6; SNI only carries names
+        let mut command = Command::new("openssl");
+        command.args(["s_client", "-connect", &format!("{}:{}", host, port)]);
+        if host.trim_matches(['[', ']']).parse::<std::net::IpAddr>().is_err() {
+            command.args(["-servername", host]);
+        }
+        let output = tokio::time::timeout(
+            Duration::from_secs(10),
+            command
+                .args(args)
+                .stdin(Stdio::null())
+                .output(),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("openssl timed out"))??;
+
+        // New, TLSv1.0, Cipher is ECDHE-RSA-AES256-SHA
+        //     Protocol  : TLSv1.1
+        // The protocol on the first line is the cipher's oldest, so the
+        // session's own comes from the second
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let cipher = stdout
+            .lines()
+            .find_map(|line| line.strip_prefix("New, ")?.split_once(", Cipher is ").map(|(_, cipher)| cipher.trim()))
+            .filter(|cipher| *cipher != "(NONE)");
+        let protocol = stdout.lines().find_map(|line| {
+            let (field, value) = line.split_once(':')?;
+            (field.trim() == "Protocol").then(|| value.trim())
+        });
+        Ok(cipher.zip(protocol).map(|(cipher, protocol)| (protocol.to_string(), cipher.to_string())))
+    }
+
+    /// Resolves the A and AAAA records of `domain` with `dig`, which also
+    /// shows the CNAME chain, falling back to the system resolver when dig
+    /// is not installed.
+    async fn resolve(domain: &str) -> DnsRecords {
+        let mut records = DnsRecords { cnames: Vec::new(), addresses: Vec::new(), error: None };
+
+        match Command::new("dig")
+            .args(["+noall", "+answer", "+time=2", "+tries=1", domain, "A", domain, "AAAA"])
+            .output()
+            .await
+        {
+            Ok(output) => {
+                // n8n.secure-penguin.com. 300 IN CNAME proxy.secure-penguin.com.
+                for line in String::from_utf8_lossy(&output.stdout).lines() {
+                    let fields: Vec<&str> = line.split_whitespace().collect();
+                    let (Some(kind), Some(data)) = (fields.get(3), fields.get(4)) else { continue };
+                    let list = match *kind {
+                        "CNAME" => &mut records.cnames,
+                        "A" | "AAAA" => &mut records.addresses,
+                        _ => continue,
+                    };
+                    let data = data.trim_end_matches('.').to_string();
+                    if !list.contains(&data) {
+                        list.push(data);
+                    }
+                }
+                if !output.status.success() {
+                    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+                    records.error = Some(if stderr.is_empty() { format!("dig exited with {}", output.status) } else { stderr });
+                }
+            }
+            Err(_) => match tokio::net::lookup_host((domain, 443)).await {
+                Ok(addresses) => {
+                    for address in addresses {
+                        let ip = address.ip().to_string();
+                        if !records.addresses.contains(&ip) {
+                            records.addresses.push(ip);
+                        }
+                    }
+                }
+                Err(e) => records.error = Some(e.to_string()),
+            },
+        }
+
+        if records.error.is_none() && records.addresses.is_empty() {
+            records.error = Some("no A or AAAA records".to_string());
+        }
+        records
+    }
+
+    /// Returns a client holding an Authelia session cookie for `target_url`.
+    async fn authelia_login(&self, portal_url: &str, username: &str, password: &str, target_url: &str) -> Result<Client> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .connect_timeout(Duration::from_secs(5))
+            .redirect(Policy::none())
+            .cookie_store(true)
+            .build()?;
+
+        let response = client
+            .post(format!("{}/api/firstfactor", portal_url.trim_end_matches('/')))
+            .json(&serde_json::json!({
+                "username": username,
+                "password": password,
+                "keepMeLoggedIn": false,
+                "targetURL": target_url,
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("portal returned {}", response.status());
+        }
+
+        Ok(client)
+    }
+}
+
+/// Collects the scalar fields of `value` with their dotted paths.
+fn flatten_json(value: &serde_json::Value, path: String, leaves: &mut Vec<(String, serde_json::Value)>) {
+    let child = |key: &str| if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) };
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                flatten_json(value, child(key), leaves);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for (index, value) in items.iter().enumerate() {
+                flatten_json(value, child(&index.to_string()), leaves);
+            }
         }
+        scalar => leaves.push((path, scalar.clone())),
     }
 }